@@ -1,3 +1,4 @@
+use anathema_values::hashmap::HashMap;
 use anathema_values::ValueExpr;
 pub(crate) use storage::Storage;
 
@@ -20,6 +21,8 @@ pub mod views;
 pub struct Constants {
     strings: Strings,
     values: Values,
+    // Constants declared in the template, `const pad = 2`
+    declared: HashMap<StringId, ValueExpr>,
 }
 
 impl Constants {
@@ -27,6 +30,7 @@ impl Constants {
         Self {
             strings: Strings::empty(),
             values: Values::empty(),
+            declared: HashMap::new(),
         }
     }
 
@@ -42,6 +46,14 @@ impl Constants {
         self.values.push(value)
     }
 
+    pub(crate) fn declare(&mut self, name: StringId, value: ValueExpr) {
+        self.declared.insert(name, value);
+    }
+
+    pub(crate) fn lookup_declared(&self, name: StringId) -> Option<&ValueExpr> {
+        self.declared.get(&name)
+    }
+
     pub fn lookup_string(&self, index: StringId) -> &str {
         self.strings.get(index).map(String::as_str).expect(
             "consts have been modified, this is a bug with Anathema, file a bug report please",
@@ -43,6 +43,26 @@ impl Error {
         }
     }
 
+    pub(crate) fn unterminated_comment(range: Range<usize>, src: &str) -> Self {
+        let (line, col) = src_line_no(range.start, src);
+        Self {
+            line,
+            col,
+            src: src.to_string(),
+            kind: ErrorKind::UnterminatedComment,
+        }
+    }
+
+    pub(crate) fn invalid_escape(range: Range<usize>, src: &str) -> Self {
+        let (line, col) = src_line_no(range.start, src);
+        Self {
+            line,
+            col,
+            src: src.to_string(),
+            kind: ErrorKind::InvalidEscape,
+        }
+    }
+
     pub(crate) fn invalid_number(range: Range<usize>, src: &str) -> Self {
         let (line, col) = src_line_no(range.end, src);
         Self {
@@ -76,19 +96,28 @@ impl Display for Error {
 
         let msg = match &self.kind {
             ErrorKind::UnterminatedString => "unterminated string".into(),
+            ErrorKind::UnterminatedComment => "unterminated comment (missing `*/`)".into(),
             ErrorKind::UnterminatedAttributes => "unterminated attributes (missing `]`)".into(),
             ErrorKind::UnterminatedElement => "unterminated element".into(),
             ErrorKind::InvalidToken { expected } => {
                 format!("invalid token (expected: \"{expected}\")")
             }
             ErrorKind::InvalidNumber => "invalid number".into(),
+            ErrorKind::InvalidEscape => "invalid unicode escape (expected `\\u{...}`)".into(),
             ErrorKind::InvalidIndex => "invalid index".into(),
             ErrorKind::InvalidPath => "invalid path".into(),
             ErrorKind::InvalidHexValue => "invalid hex value".into(),
             ErrorKind::UnexpectedEof => "unexpected end of file".into(),
             ErrorKind::TrailingPipe => "trailing pipe character".into(),
             ErrorKind::InvalidDedent => "dedent does not match previous indentation levels".into(),
+            ErrorKind::NestedConst => "constants can only be declared at the top level".into(),
+            ErrorKind::ShadowedConst => "the loop binding has the name of a constant".into(),
             ErrorKind::InvalidOperator(_op) => "invalid operator: {op}".into(),
+            ErrorKind::InvalidFunction => "invalid function (expected a function name)".into(),
+            ErrorKind::UnknownFunction => "unknown function".into(),
+            ErrorKind::InvalidMessageKey => {
+                "invalid message key (expected names separated by dots)".into()
+            }
             ErrorKind::UnexpectedToken(_msg) => "unexpected token: {msg}".into(),
         };
 
@@ -110,16 +139,23 @@ impl Display for Error {
 #[derive(Debug, Clone, PartialEq)]
 pub enum ErrorKind {
     UnterminatedString,
+    UnterminatedComment,
     UnterminatedElement,
     UnterminatedAttributes,
     InvalidToken { expected: &'static str },
     InvalidNumber,
+    InvalidEscape,
     InvalidIndex,
     InvalidHexValue,
     UnexpectedEof,
     TrailingPipe,
     InvalidDedent,
+    NestedConst,
+    ShadowedConst,
     InvalidPath,
     InvalidOperator(Operator),
+    InvalidFunction,
+    UnknownFunction,
+    InvalidMessageKey,
     UnexpectedToken(String),
 }
@@ -18,10 +18,14 @@ impl<'src, 'consts> Iterator for Lexer<'src, 'consts> {
     }
 }
 
+/// The number of columns between tab stops, when indenting with tabs
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
 pub struct Lexer<'src, 'consts> {
     pub(super) src: &'src str,
     pub(crate) consts: &'consts mut Constants,
     chars: Peekable<CharIndices<'src>>,
+    tab_width: usize,
 }
 
 impl<'src, 'consts> Lexer<'src, 'consts> {
@@ -30,9 +34,17 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
             chars: src.char_indices().peekable(),
             consts,
             src,
+            tab_width: DEFAULT_TAB_WIDTH,
         }
     }
 
+    /// A tab advances the indent to the next multiple of the tab width,
+    /// so tabs and spaces can be mixed.
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width.max(1);
+        self
+    }
+
     fn next_token(&mut self) -> Result<Token> {
         let (index, c) = match self.chars.next() {
             None => return self.eof(),
@@ -43,19 +55,27 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
 
         match (c, next) {
             // -----------------------------------------------------------------------------
-            //     - Double tokens -
+            //     - Comments -
+            //     The new line after a line comment is kept,
+            //     so a comment after a node doesn't join the lines.
             // -----------------------------------------------------------------------------
             ('/', Some('/')) => {
-                self.chars.next(); // consume the second slash
-                loop {
-                    if let Some((_, '\n')) | None = self.chars.peek() {
-                        self.chars.next();
+                while let Some((_, c)) = self.chars.peek() {
+                    if *c == '\n' {
                         break;
                     }
                     self.chars.next();
                 }
                 self.next_token()
             }
+            ('/', Some('*')) => {
+                self.take_block_comment(index)?;
+                self.next_token()
+            }
+
+            // -----------------------------------------------------------------------------
+            //     - Double tokens -
+            // -----------------------------------------------------------------------------
             ('{', Some('{')) => {
                 let _ = self.chars.next();
                 Ok(Kind::Op(Operator::LDoubleCurly).to_token(index))
@@ -72,6 +92,14 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
                 let _ = self.chars.next();
                 Ok(Kind::Op(Operator::Or).to_token(index))
             }
+            ('?', Some('?')) => {
+                let _ = self.chars.next();
+                Ok(Kind::Op(Operator::Coalesce).to_token(index))
+            }
+            ('?', Some('.')) => {
+                let _ = self.chars.next();
+                Ok(Kind::Op(Operator::OptionalDot).to_token(index))
+            }
             ('=', Some('=')) => {
                 let _ = self.chars.next();
                 Ok(Kind::Op(Operator::EqualEqual).to_token(index))
@@ -105,6 +133,7 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
             ('%', _) => Ok(Kind::Op(Operator::Mod).to_token(index)),
             ('>', _) => Ok(Kind::Op(Operator::GreaterThan).to_token(index)),
             ('<', _) => Ok(Kind::Op(Operator::LessThan).to_token(index)),
+            ('=', _) => Ok(Kind::Op(Operator::Equal).to_token(index)),
             ('\n', _) => Ok(Kind::Newline.to_token(index)),
             ('@', _) => Ok(Kind::View.to_token(index)),
 
@@ -129,7 +158,7 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
             // -----------------------------------------------------------------------------
             //     - Indents / Whitespace -
             // -----------------------------------------------------------------------------
-            _ if c.is_whitespace() && c != '\n' => Ok(self.take_whitespace().to_token(index)),
+            _ if c.is_whitespace() && c != '\n' => Ok(self.take_whitespace(c).to_token(index)),
 
             // -----------------------------------------------------------------------------
             //     - Hex values -
@@ -148,20 +177,26 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
     }
 
     fn take_string(&mut self, start_char: char, start_index: usize) -> Result<Token> {
+        let mut escaped = false;
+
         loop {
             let n = self.chars.next();
             match n {
                 Some((end, nc)) if nc == start_char => {
-                    let string = self.consts.store_string(&self.src[start_index + 1..end]);
+                    let raw = &self.src[start_index + 1..end];
+                    let string = match escaped {
+                        true => match unescape(raw) {
+                            Some(string) => self.consts.store_string(&string),
+                            None => break Err(Error::invalid_escape(start_index..end, self.src)),
+                        },
+                        false => self.consts.store_string(raw),
+                    };
                     break Ok(Kind::Value(Value::String(string)).to_token(start_index));
                 }
                 Some((_, '\\')) => {
-                    // escaping string terminator
-                    if let Some((_, next)) = self.chars.peek() {
-                        if *next == start_char {
-                            self.chars.next();
-                        }
-                    }
+                    // The escaped char can't terminate the string
+                    escaped = true;
+                    self.chars.next();
                 }
                 None => {
                     break Err(Error::unterminated_string(
@@ -174,6 +209,36 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
         }
     }
 
+    // Block comments can be nested, so a block containing
+    // another block comment can be commented out.
+    fn take_block_comment(&mut self, start_index: usize) -> Result<()> {
+        self.chars.next(); // consume the `*`
+        let mut depth = 1;
+
+        while depth > 0 {
+            let Some((_, c)) = self.chars.next() else {
+                return Err(Error::unterminated_comment(
+                    start_index..self.src.len(),
+                    self.src,
+                ));
+            };
+
+            match (c, self.chars.peek().map(|(_, c)| *c)) {
+                ('/', Some('*')) => {
+                    self.chars.next();
+                    depth += 1;
+                }
+                ('*', Some('/')) => {
+                    self.chars.next();
+                    depth -= 1;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
     fn take_number(&mut self, index: usize) -> Result<Token> {
         let mut end = index;
         let mut parse_float = &self.src[index..=index] == ".";
@@ -219,6 +284,7 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
             "in" => Kind::In,
             "if" => Kind::If,
             "else" => Kind::Else,
+            "const" => Kind::Const,
             "true" => Kind::Value(Value::Bool(true)),
             "false" => Kind::Value(Value::Bool(false)),
             s => {
@@ -228,13 +294,13 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
         }
     }
 
-    fn take_whitespace(&mut self) -> Kind {
-        let mut count = 1;
+    fn take_whitespace(&mut self, first: char) -> Kind {
+        let mut count = self.advance_indent(0, first);
 
         loop {
             match self.chars.peek() {
-                Some((_, next)) if next.is_whitespace() && *next != '\n' => {
-                    count += 1;
+                Some(&(_, next)) if next.is_whitespace() && next != '\n' => {
+                    count = self.advance_indent(count, next);
                     self.chars.next();
                 }
                 Some(_) | None => break,
@@ -244,6 +310,13 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
         Kind::Indent(count)
     }
 
+    fn advance_indent(&self, count: usize, c: char) -> usize {
+        match c {
+            '\t' => (count / self.tab_width + 1) * self.tab_width,
+            _ => count + 1,
+        }
+    }
+
     fn take_hex_values(&mut self, index: usize) -> Result<Token> {
         let index = index + 1; // consume #
         const SHORT: usize = 3;
@@ -284,6 +357,47 @@ impl<'src, 'consts> Lexer<'src, 'consts> {
     }
 }
 
+// Replace the escape sequences in a string:
+// `\n`, `\t`, `\r`, `\0`, `\\`, `\"`, `\'` and `\u{1F600}`.
+// Any other escaped char is kept as is, backslash included.
+// Returns `None` if a unicode escape is invalid.
+fn unescape(raw: &str) -> Option<String> {
+    let mut output = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('t') => output.push('\t'),
+            Some('r') => output.push('\r'),
+            Some('0') => output.push('\0'),
+            Some(c @ ('\\' | '"' | '\'')) => output.push(c),
+            Some('u') => {
+                if chars.next() != Some('{') {
+                    return None;
+                }
+                let hex = chars.by_ref().take_while(|c| *c != '}').collect::<String>();
+                let c = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)?;
+                output.push(c);
+            }
+            Some(c) => {
+                output.push('\\');
+                output.push(c);
+            }
+            None => output.push('\\'),
+        }
+    }
+
+    Some(output)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -318,6 +432,39 @@ mod test {
         let input = "// hello world";
         let mut lexer = Lexer::new(input, &mut consts);
         assert!(lexer.next().is_none());
+
+        let input = "a // hello world\nb";
+        let kinds = Lexer::new(input, &mut consts)
+            .map(|token| token.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(kinds.len(), 4);
+        assert_eq!(kinds[2], Kind::Newline);
+    }
+
+    #[test]
+    fn block_comment() {
+        let mut consts = Constants::new();
+        let input = "/* hello\n /* nested */ world */";
+        let mut lexer = Lexer::new(input, &mut consts);
+        assert!(lexer.next().is_none());
+
+        let input = "[/* hello */]";
+        let kinds = Lexer::new(input, &mut consts)
+            .map(|token| token.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [Kind::Op(Operator::LBracket), Kind::Op(Operator::RBracket)]
+        );
+
+        assert_eq!(error_kind("/* hello"), ErrorKind::UnterminatedComment);
+    }
+
+    #[test]
+    fn invalid_unicode_escape() {
+        assert_eq!(error_kind(r#""\u{zz}""#), ErrorKind::InvalidEscape);
+        assert_eq!(error_kind(r#""\u{110000}""#), ErrorKind::InvalidEscape);
+        assert_eq!(error_kind(r#""\u1F600""#), ErrorKind::InvalidEscape);
     }
 
     #[test]
@@ -341,6 +488,8 @@ mod test {
         let inputs = [
             ("{{", Operator::LDoubleCurly),
             ("}}", Operator::RDoubleCurly),
+            ("??", Operator::Coalesce),
+            ("?.", Operator::OptionalDot),
         ];
 
         for (input, expected) in inputs {
@@ -382,7 +531,11 @@ mod test {
             ("\"double quote string\"", "double quote string"),
             ("\"double 'single inside'\"", "double 'single inside'"),
             ("'single \"double inside\"'", "single \"double inside\""),
-            (r#""escape \"double\"""#, r#"escape \"double\""#),
+            (r#""escape \"double\"""#, r#"escape "double""#),
+            (r#""new\nline\ttab""#, "new\nline\ttab"),
+            (r#""\u{1F600} \u{e9}""#, "\u{1F600} \u{e9}"),
+            (r#""back\\""#, "back\\"),
+            (r#""C:\path""#, r#"C:\path"#),
             ("''", ""), // empty string
         ];
 
@@ -407,6 +560,26 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn mixed_tabs_and_spaces() {
+        let inputs = [
+            ("\t", 4),
+            ("  \t", 4),
+            ("\t  ", 6),
+            ("\t\t", 8),
+            ("    \t", 8),
+        ];
+
+        for (input, expected) in inputs {
+            let actual = token_kind(input);
+            assert_eq!(Kind::Indent(expected), actual);
+        }
+
+        let mut consts = Constants::new();
+        let mut lexer = Lexer::new("  \t", &mut consts).with_tab_width(2);
+        assert_eq!(lexer.next().unwrap().unwrap().0, Kind::Indent(4));
+    }
+
     #[test]
     fn color() {
         let inputs = [
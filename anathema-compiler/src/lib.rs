@@ -8,14 +8,25 @@ pub(crate) mod token;
 
 pub use compiler::Instruction;
 pub use constants::{StringId, ValueId, ViewId, ViewIds};
+pub use lexer::DEFAULT_TAB_WIDTH;
 
 use self::token::Tokens;
 pub use crate::constants::Constants;
 
 /// Compile source into instructions and constants.
 pub fn compile(src: &str, view_ids: &mut ViewIds) -> error::Result<(Vec<Instruction>, Constants)> {
+    compile_with_tab_width(src, view_ids, DEFAULT_TAB_WIDTH)
+}
+
+/// Compile source into instructions and constants,
+/// where a tab advances the indent to the next multiple of `tab_width`.
+pub fn compile_with_tab_width(
+    src: &str,
+    view_ids: &mut ViewIds,
+    tab_width: usize,
+) -> error::Result<(Vec<Instruction>, Constants)> {
     let mut constants = Constants::new();
-    let lexer = lexer::Lexer::new(src, &mut constants);
+    let lexer = lexer::Lexer::new(src, &mut constants).with_tab_width(tab_width);
     let tokens = Tokens::new(lexer.collect::<error::Result<_>>()?, src.len());
     let parser = parsing::parser::Parser::new(tokens, &mut constants, src, view_ids);
     let expressions = parser.collect::<error::Result<Vec<_>>>()?;
@@ -24,3 +35,17 @@ pub fn compile(src: &str, view_ids: &mut ViewIds) -> error::Result<(Vec<Instruct
     let compiler = compiler::Compiler::new(expressions);
     Ok((compiler.compile()?, constants))
 }
+
+/// Parse the source and return all the errors,
+/// rather than stopping at the first error.
+pub fn check(src: &str, tab_width: usize) -> Vec<error::Error> {
+    let mut constants = Constants::new();
+    let lexer = lexer::Lexer::new(src, &mut constants).with_tab_width(tab_width);
+    let tokens = match lexer.collect::<error::Result<_>>() {
+        Ok(tokens) => Tokens::new(tokens, src.len()),
+        Err(err) => return vec![err],
+    };
+    let mut view_ids = ViewIds::new();
+    let parser = parsing::parser::Parser::new(tokens, &mut constants, src, &mut view_ids);
+    parser.filter_map(Result::err).collect()
+}
@@ -1,4 +1,4 @@
-use anathema_values::ValueExpr;
+use anathema_values::{ValueExpr, SPREAD_ATTRIBUTE};
 
 use super::pratt::{eval, expr};
 use crate::error::{src_line_no, Error, ErrorKind, Result};
@@ -23,6 +23,7 @@ pub enum Expression {
 enum State {
     EnterScope,
     ExitScope,
+    ParseConst,
     ParseFor,
     ParseIf,
     ParseView,
@@ -46,6 +47,8 @@ pub struct Parser<'src, 'consts, 'view> {
     closed_scopes: Vec<usize>,
     base_indent: usize,
     done: bool,
+    // An error the parser recovered from, reported by the next call to `next`
+    recovered: Option<Error>,
 }
 
 impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
@@ -71,6 +74,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
             closed_scopes: Vec::new(),
             base_indent,
             done: false,
+            recovered: None,
         }
     }
 
@@ -107,6 +111,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
                 State::ParseIf => self.parse_if(),
                 State::ParseView => self.parse_view(),
                 State::ExitScope => self.exit_scope(),
+                State::ParseConst => self.parse_const(),
                 State::ParseIdent => self.parse_ident(),
                 State::ParseAttributes => {
                     if !self.parse_attributes()? {
@@ -129,7 +134,8 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
     fn next_state(&mut self) {
         match self.state {
             State::EnterScope => self.state = State::ExitScope,
-            State::ExitScope => self.state = State::ParseFor,
+            State::ExitScope => self.state = State::ParseConst,
+            State::ParseConst => self.state = State::ParseFor,
             State::ParseFor => self.state = State::ParseIf,
             State::ParseIf => self.state = State::ParseView,
             State::ParseView => self.state = State::ParseIdent,
@@ -156,8 +162,10 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
         }
 
         let indent = match indent {
+            // Recover by treating the line as not indented
             Some(indent) if indent < self.base_indent => {
-                return Err(self.error(ErrorKind::InvalidDedent))
+                self.recovered = Some(self.error(ErrorKind::InvalidDedent));
+                None
             }
             Some(indent) => Some(indent - self.base_indent),
             None => None,
@@ -180,9 +188,20 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
                 }
                 // Indent is smaller than previous: close larger scopes
                 Some(&last) if indent < last => {
-                    if indent > 0 && !self.open_scopes.iter().any(|s| indent.eq(s)) {
-                        return Err(self.error(ErrorKind::InvalidDedent));
-                    }
+                    // Recover from a dedent that doesn't match any of the open scopes
+                    // by using the closest scope outside of it
+                    let indent = match indent > 0 && !self.open_scopes.contains(&indent) {
+                        true => {
+                            self.recovered = Some(self.error(ErrorKind::InvalidDedent));
+                            self.open_scopes
+                                .iter()
+                                .copied()
+                                .filter(|&s| s < indent)
+                                .max()
+                                .unwrap_or(0)
+                        }
+                        false => indent,
+                    };
 
                     self.open_scopes.retain(|&s| {
                         if indent < s {
@@ -219,7 +238,43 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
     }
 
     // -----------------------------------------------------------------------------
-    //     - Stage 2: Parse ident, For and If -
+    //     - Stage 2: Parse constants -
+    //     `const accent = #ff8800`
+    //     The value is stored in the constants and replaces the name
+    //     in the expressions that follow, unless it's the field of a path
+    //     (`user.accent`). A loop binding can't have the name of a constant.
+    // -----------------------------------------------------------------------------
+    fn parse_const(&mut self) -> Result<Option<Expression>> {
+        if Kind::Const != self.tokens.peek_skip_indent() {
+            self.next_state();
+            return Ok(None);
+        }
+
+        self.tokens.consume();
+
+        if !self.open_scopes.is_empty() {
+            return Err(self.error(ErrorKind::NestedConst));
+        }
+
+        let name = self.read_ident()?;
+
+        if Kind::Op(Operator::Equal) != self.tokens.peek_skip_indent() {
+            return Err(self.error(ErrorKind::InvalidToken { expected: "=" }));
+        }
+
+        self.tokens.consume();
+
+        let expr = expr(&mut self.tokens);
+        let value_expr = eval(expr, self.consts).map_err(|kind| self.error(kind))?;
+        self.consts.declare(name, value_expr);
+
+        // There is nothing else to parse on this line
+        self.state = State::Done;
+        Ok(None)
+    }
+
+    // -----------------------------------------------------------------------------
+    //     - Stage 3: Parse ident, For and If -
     // -----------------------------------------------------------------------------
     fn parse_ident(&mut self) -> Result<Option<Expression>> {
         if Kind::Eof == self.tokens.peek() {
@@ -252,6 +307,11 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
 
         let binding = self.read_ident()?;
 
+        // The constant would replace every use of the binding
+        if self.consts.lookup_declared(binding).is_some() {
+            return Err(self.error(ErrorKind::ShadowedConst));
+        }
+
         if Kind::In != self.tokens.peek_skip_indent() {
             return Err(self.error(ErrorKind::InvalidToken { expected: "in" }));
         }
@@ -260,7 +320,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
         self.tokens.consume();
 
         let expr = expr(&mut self.tokens);
-        let value_expr = eval(expr, self.consts);
+        let value_expr = eval(expr, self.consts).map_err(|kind| self.error(kind))?;
 
         // let data = ValueParser::new(&mut self.lexer).parse()?;
         let data = self.consts.store_value(value_expr);
@@ -281,7 +341,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
         } else if Kind::If == self.tokens.peek_skip_indent() {
             self.tokens.consume();
             let expr = expr(&mut self.tokens);
-            let value_expr = eval(expr, self.consts);
+            let value_expr = eval(expr, self.consts).map_err(|kind| self.error(kind))?;
             let value_id = self.consts.store_value(value_expr);
 
             self.next_state();
@@ -312,7 +372,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
     }
 
     // -----------------------------------------------------------------------------
-    //     - Stage 3: Parse attributes -
+    //     - Stage 4: Parse attributes -
     // -----------------------------------------------------------------------------
     fn parse_attributes(&mut self) -> Result<bool> {
         if Kind::Op(Operator::LBracket) == self.tokens.peek_skip_indent() {
@@ -325,9 +385,12 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
     }
 
     // -----------------------------------------------------------------------------
-    //     - Stage 4: Parse single attribute -
+    //     - Stage 5: Parse single attribute -
     // -----------------------------------------------------------------------------
     fn parse_attribute(&mut self) -> Result<Option<Expression>> {
+        // Attributes can span multiple lines
+        self.tokens.consume_all_whitespace();
+
         // Check for the closing bracket
         if Kind::Op(Operator::RBracket) == self.tokens.peek_skip_indent() {
             self.tokens.consume();
@@ -335,19 +398,32 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
             return Ok(None);
         }
 
-        let key = self.read_ident()?;
+        let key = match self.tokens.peek_skip_indent() {
+            // Spread a map into the attributes: `..panel_style`
+            Kind::Op(Operator::Dot) => {
+                self.tokens.consume();
+                if Kind::Op(Operator::Dot) != self.tokens.next() {
+                    return Err(self.error(ErrorKind::InvalidToken { expected: ".." }));
+                }
+                self.consts.store_string(SPREAD_ATTRIBUTE)
+            }
+            _ => {
+                let key = self.read_ident()?;
 
-        self.tokens.consume_all_whitespace();
+                self.tokens.consume_all_whitespace();
 
-        if Kind::Op(Operator::Colon) != self.tokens.peek_skip_indent() {
-            return Err(self.error(ErrorKind::InvalidToken { expected: ":" }));
-        }
+                if Kind::Op(Operator::Colon) != self.tokens.peek_skip_indent() {
+                    return Err(self.error(ErrorKind::InvalidToken { expected: ":" }));
+                }
 
-        self.tokens.consume();
-        self.tokens.consume_all_whitespace();
+                self.tokens.consume();
+                self.tokens.consume_all_whitespace();
+                key
+            }
+        };
 
         let expr = expr(&mut self.tokens);
-        let value_expr = eval(expr, self.consts);
+        let value_expr = eval(expr, self.consts).map_err(|kind| self.error(kind))?;
         let value = self.consts.store_value(value_expr);
 
         self.tokens.consume_all_whitespace();
@@ -367,7 +443,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
     }
 
     // -----------------------------------------------------------------------------
-    //     - Stage 5: Node value -
+    //     - Stage 6: Node value -
     // -----------------------------------------------------------------------------
     fn parse_value(&mut self) -> Result<Option<Expression>> {
         self.tokens.consume_indent();
@@ -406,7 +482,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
                 break;
             }
             let expression = expr(&mut self.tokens);
-            let value_expr = eval(expression, self.consts);
+            let value_expr = eval(expression, self.consts).map_err(|kind| self.error(kind))?;
             values.push(value_expr);
         }
 
@@ -421,7 +497,7 @@ impl<'src, 'consts, 'view> Parser<'src, 'consts, 'view> {
     }
 
     // -----------------------------------------------------------------------------
-    //     - Stage 6: Done -
+    //     - Stage 7: Done -
     //     Clear empty spaces, ready for next instructions,
     //     or deal with EOF
     // -----------------------------------------------------------------------------
@@ -455,6 +531,10 @@ impl Iterator for Parser<'_, '_, '_> {
     type Item = Result<Expression>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.recovered.take() {
+            return Some(Err(error));
+        }
+
         if self.done {
             return None;
         }
@@ -465,6 +545,9 @@ impl Iterator for Parser<'_, '_, '_> {
                 Some(Ok(Expression::Eof))
             }
             Err(e) => {
+                // Skip the rest of the line so one error
+                // doesn't cause another error on the same line
+                self.tokens.skip_line();
                 self.state = State::Done;
                 Some(Err(e))
             }
@@ -492,6 +575,190 @@ mod test {
         parse(src).into_iter().map(Result::unwrap).collect()
     }
 
+    #[test]
+    fn recover_from_invalid_dedent() {
+        let src = "
+a
+    b
+        c
+      d
+    e
+";
+        let actual = parse(src);
+        let errors = actual.iter().filter(|res| res.is_err()).count();
+        assert_eq!(errors, 1);
+
+        // `d` is a sibling of `b`, and `e` still parses
+        let expected = vec![
+            Expression::Node(0.into()),
+            Expression::ScopeStart,
+            Expression::Node(1.into()),
+            Expression::ScopeStart,
+            Expression::Node(2.into()),
+            Expression::ScopeEnd,
+            Expression::Node(3.into()),
+            Expression::Node(4.into()),
+            Expression::ScopeEnd,
+            Expression::Eof,
+        ];
+        let actual = actual
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_multi_line_attributes() {
+        let src = "
+a [
+    // the first attribute
+    a: a,
+    b: 1, // the second attribute
+
+    c: 2,
+]
+    b
+";
+        let expected = vec![
+            Expression::Node(0.into()),
+            Expression::LoadAttribute {
+                key: 0.into(),
+                value: 0.into(),
+            },
+            Expression::LoadAttribute {
+                key: 1.into(),
+                value: 1.into(),
+            },
+            Expression::LoadAttribute {
+                key: 2.into(),
+                value: 2.into(),
+            },
+            Expression::ScopeStart,
+            Expression::Node(1.into()),
+            Expression::ScopeEnd,
+            Expression::Eof,
+        ];
+
+        let actual = parse_ok(src);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_comments() {
+        let src = "
+a // a comment after a node
+    b
+/*
+c
+    d
+*/
+e /* inline */ 'text'
+";
+        let expected = vec![
+            Expression::Node(0.into()),
+            Expression::ScopeStart,
+            Expression::Node(1.into()),
+            Expression::ScopeEnd,
+            Expression::Node(2.into()),
+            Expression::LoadValue(0.into()),
+            Expression::Eof,
+        ];
+
+        let actual = parse_ok(src);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_constants() {
+        let src = "
+const pad = 2
+const accent = #ff8800
+a [padding: pad, foreground: accent]
+    const nested = 1
+";
+        let mut consts = Constants::new();
+        let mut view_ids = ViewIds::new();
+        let lexer = Lexer::new(src, &mut consts);
+        let tokens = Tokens::new(lexer.collect::<Result<Vec<_>>>().unwrap(), src.len());
+        let parser = Parser::new(tokens, &mut consts, src, &mut view_ids);
+        let mut expressions = parser.collect::<Vec<_>>().into_iter();
+
+        assert!(matches!(expressions.next(), Some(Ok(Expression::Node(_)))));
+        let Some(Ok(Expression::LoadAttribute { value, .. })) = expressions.next() else {
+            panic!("expected an attribute")
+        };
+        let pad = value;
+        let Some(Ok(Expression::LoadAttribute { value, .. })) = expressions.next() else {
+            panic!("expected an attribute")
+        };
+        let accent = value;
+
+        assert!(matches!(
+            expressions.next(),
+            Some(Ok(Expression::ScopeStart))
+        ));
+        let err = expressions.next().unwrap().unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NestedConst);
+
+        assert_eq!(consts.lookup_value(pad), ValueExpr::from(2u64));
+        assert_eq!(
+            consts.lookup_value(accent),
+            ValueExpr::from(anathema_render::Color::from((255, 136, 0)))
+        );
+    }
+
+    #[test]
+    fn constants_in_paths_and_loops() {
+        let src = "
+const name = 1
+a [title: user.name]
+";
+        let mut consts = Constants::new();
+        let mut view_ids = ViewIds::new();
+        let lexer = Lexer::new(src, &mut consts);
+        let tokens = Tokens::new(lexer.collect::<Result<Vec<_>>>().unwrap(), src.len());
+        let parser = Parser::new(tokens, &mut consts, src, &mut view_ids);
+        let mut expressions = parser.collect::<Vec<_>>().into_iter();
+
+        assert!(matches!(expressions.next(), Some(Ok(Expression::Node(_)))));
+        let Some(Ok(Expression::LoadAttribute { value, .. })) = expressions.next() else {
+            panic!("expected an attribute")
+        };
+        assert_eq!(consts.lookup_value(value).to_string(), "user.name");
+
+        let src = "
+const pad = 2
+for pad in items
+    a
+";
+        let err = parse(src).into_iter().find_map(Result::err).unwrap();
+        assert_eq!(err.kind, ErrorKind::ShadowedConst);
+    }
+
+    #[test]
+    fn parse_spread_attributes() {
+        let src = "a [..style, a: 1]";
+        let expected = vec![
+            Expression::Node(0.into()),
+            Expression::LoadAttribute {
+                key: 2.into(),
+                value: 0.into(),
+            },
+            Expression::LoadAttribute {
+                key: 0.into(),
+                value: 1.into(),
+            },
+            Expression::Eof,
+        ];
+
+        let actual = parse_ok(src);
+        assert_eq!(expected, actual);
+
+        let src = "a [.style]";
+        assert!(parse(src)[1].is_err());
+    }
+
     #[test]
     fn parse_single_instruction() {
         let src = "a";
@@ -737,4 +1004,46 @@ mod test {
         assert_eq!(expressions.remove(0), Expression::Node(0.into()));
         assert_eq!(expressions.remove(0), Expression::LoadValue(0.into()));
     }
+
+    #[test]
+    fn parse_text_with_message() {
+        let src = "text @greeting(name)\nview";
+        let expected = vec![
+            Expression::Node(0.into()),
+            Expression::LoadValue(0.into()),
+            Expression::Node(3.into()),
+            Expression::Eof,
+        ];
+
+        let actual = parse_ok(src);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_invalid_function_and_message_key() {
+        let err = parse("text\ntext a.b(c)")
+            .into_iter()
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(err.kind, ErrorKind::InvalidFunction);
+        assert_eq!(err.line, 2);
+
+        let err = parse("text @a[0]")
+            .into_iter()
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(err.kind, ErrorKind::InvalidMessageKey);
+    }
+
+    #[test]
+    fn parse_unknown_function() {
+        let err = parse("text shout(name)")
+            .into_iter()
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(err.kind, ErrorKind::UnknownFunction);
+
+        let expressions = parse_ok("text colorize(name, red)");
+        assert_eq!(expressions.len(), 3);
+    }
 }
@@ -1,41 +1,57 @@
 use std::rc::Rc;
 
 use anathema_values::hashmap::HashMap;
-use anathema_values::{Num, Owned, ValueExpr};
+use anathema_values::{functions, Num, Owned, ValueExpr};
 
 use super::Expr;
+use crate::error::ErrorKind;
 use crate::token::Operator;
 use crate::Constants;
 
-pub fn eval(expr: Expr, consts: &Constants) -> ValueExpr {
-    match expr {
+pub fn eval(expr: Expr, consts: &Constants) -> Result<ValueExpr, ErrorKind> {
+    let value_expr = match expr {
         Expr::Bool(b) => ValueExpr::from(b),
         Expr::Color(color) => ValueExpr::from(color),
-        Expr::Ident(string_id) => {
-            let string = consts.lookup_string(string_id);
-            ValueExpr::Ident(string.into())
-        }
+        Expr::Ident(string_id) => match consts.lookup_declared(string_id) {
+            // Constants declared in the template are replaced by their value
+            Some(value) => value.clone(),
+            None => {
+                let string = consts.lookup_string(string_id);
+                ValueExpr::Ident(string.into())
+            }
+        },
         Expr::Str(string_id) => {
             let string = consts.lookup_string(string_id);
             ValueExpr::String(Rc::from(string))
         }
         Expr::Num(num) => ValueExpr::Owned(Owned::Num(num.into())),
         Expr::Array { lhs, index } => {
-            let lhs = eval(*lhs, consts);
-            let index = eval(*index, consts);
+            let lhs = eval(*lhs, consts)?;
+            let index = eval(*index, consts)?;
             ValueExpr::Index(lhs.into(), index.into())
         }
         Expr::Binary { op, lhs, rhs } => match op {
-            Operator::Dot => ValueExpr::Dot(eval(*lhs, consts).into(), eval(*rhs, consts).into()),
+            // Every lookup is optional: a missing value is empty rather than an error,
+            // `?.` is there to make that explicit in the template.
+            // Constants only replace a bare name, never a field of a path (`user.name`)
+            Operator::Dot | Operator::OptionalDot => {
+                let rhs = match *rhs {
+                    Expr::Ident(string_id) => {
+                        ValueExpr::Ident(consts.lookup_string(string_id).into())
+                    }
+                    rhs => eval(rhs, consts)?,
+                };
+                ValueExpr::Dot(eval(*lhs, consts)?.into(), rhs.into())
+            }
             Operator::Mul | Operator::Plus | Operator::Minus | Operator::Div | Operator::Mod => {
-                let (lhs, rhs) = match (eval(*lhs, consts), eval(*rhs, consts)) {
+                let (lhs, rhs) = match (eval(*lhs, consts)?, eval(*rhs, consts)?) {
                     (ValueExpr::Owned(Owned::Num(lhs)), ValueExpr::Owned(Owned::Num(rhs))) => {
                         match op {
-                            Operator::Mul => return ValueExpr::Owned(Owned::Num(lhs * rhs)),
-                            Operator::Plus => return ValueExpr::Owned(Owned::Num(lhs + rhs)),
-                            Operator::Minus => return ValueExpr::Owned(Owned::Num(lhs - rhs)),
-                            Operator::Div => return ValueExpr::Owned(Owned::Num(lhs / rhs)),
-                            Operator::Mod => return ValueExpr::Owned(Owned::Num(lhs % rhs)),
+                            Operator::Mul => return Ok(ValueExpr::Owned(Owned::Num(lhs * rhs))),
+                            Operator::Plus => return Ok(ValueExpr::Owned(Owned::Num(lhs + rhs))),
+                            Operator::Minus => return Ok(ValueExpr::Owned(Owned::Num(lhs - rhs))),
+                            Operator::Div => return Ok(ValueExpr::Owned(Owned::Num(lhs / rhs))),
+                            Operator::Mod => return Ok(ValueExpr::Owned(Owned::Num(lhs % rhs))),
                             _ => unreachable!(),
                         }
                     }
@@ -52,33 +68,39 @@ pub fn eval(expr: Expr, consts: &Constants) -> ValueExpr {
                 }
             }
             Operator::EqualEqual => {
-                ValueExpr::Equality(eval(*lhs, consts).into(), eval(*rhs, consts).into())
+                ValueExpr::Equality(eval(*lhs, consts)?.into(), eval(*rhs, consts)?.into())
             }
             Operator::GreaterThan => {
-                ValueExpr::Greater(eval(*lhs, consts).into(), eval(*rhs, consts).into())
+                ValueExpr::Greater(eval(*lhs, consts)?.into(), eval(*rhs, consts)?.into())
             }
             Operator::GreaterThanOrEqual => {
-                ValueExpr::GreaterEqual(eval(*lhs, consts).into(), eval(*rhs, consts).into())
+                ValueExpr::GreaterEqual(eval(*lhs, consts)?.into(), eval(*rhs, consts)?.into())
             }
             Operator::LessThan => {
-                ValueExpr::Less(eval(*lhs, consts).into(), eval(*rhs, consts).into())
+                ValueExpr::Less(eval(*lhs, consts)?.into(), eval(*rhs, consts)?.into())
             }
             Operator::LessThanOrEqual => {
-                ValueExpr::LessEqual(eval(*lhs, consts).into(), eval(*rhs, consts).into())
+                ValueExpr::LessEqual(eval(*lhs, consts)?.into(), eval(*rhs, consts)?.into())
             }
             Operator::Or | Operator::And => {
-                let lhs = eval(*lhs, consts);
-                let rhs = eval(*rhs, consts);
+                let lhs = eval(*lhs, consts)?;
+                let rhs = eval(*rhs, consts)?;
                 match op {
                     Operator::Or => ValueExpr::Or(lhs.into(), rhs.into()),
                     Operator::And => ValueExpr::And(lhs.into(), rhs.into()),
                     _ => unreachable!(),
                 }
             }
-            e => panic!("here is a panic: {e:#?}"),
+            Operator::Coalesce => {
+                ValueExpr::Coalesce(eval(*lhs, consts)?.into(), eval(*rhs, consts)?.into())
+            }
+            Operator::Default => {
+                ValueExpr::Default(eval(*lhs, consts)?.into(), eval(*rhs, consts)?.into())
+            }
+            op => return Err(ErrorKind::InvalidOperator(op)),
         },
         Expr::Unary { op, expr } => {
-            let expr = eval(*expr, consts);
+            let expr = eval(*expr, consts)?;
 
             match op {
                 Operator::Not => match expr {
@@ -91,19 +113,84 @@ pub fn eval(expr: Expr, consts: &Constants) -> ValueExpr {
                     }
                     _ => ValueExpr::Negative(expr.into()),
                 },
-                _ => panic!("operator: {op:#?}"),
+                _ => return Err(ErrorKind::InvalidOperator(op)),
             }
         }
         Expr::List(list) => {
-            ValueExpr::List(list.into_iter().map(|expr| eval(expr, consts)).collect())
+            let list = list
+                .into_iter()
+                .map(|expr| eval(expr, consts))
+                .collect::<Result<_, _>>()?;
+            ValueExpr::List(list)
         }
         Expr::Map(map) => ValueExpr::Map(
             map.into_iter()
-                .map(|(key, value)| (eval(key, consts).to_string(), eval(value, consts)))
-                .collect::<HashMap<_, _>>()
+                .map(|(key, value)| Ok((eval(key, consts)?.to_string(), eval(value, consts)?)))
+                .collect::<Result<HashMap<_, _>, _>>()?
                 .into(),
         ),
-        Expr::Call { .. } => unimplemented!(),
+        Expr::Call { fun, args } => {
+            let fun = match *fun {
+                Expr::Ident(string_id) => consts.lookup_string(string_id),
+                _ => return Err(ErrorKind::InvalidFunction),
+            };
+
+            let args = args
+                .into_iter()
+                .map(|arg| eval(arg, consts).map(Box::new))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut args = args.into_iter();
+            let arity = args.len();
+            let mut arg = || args.next().expect("the arity has been checked");
+
+            match (fun, arity) {
+                ("filter", 2) => ValueExpr::Filter(arg(), arg()),
+                ("sort_by", 2) => ValueExpr::SortBy(arg(), arg()),
+                ("paginate", 3) => ValueExpr::Paginate(arg(), arg(), arg()),
+                ("page_count", 2) => ValueExpr::PageCount(arg(), arg()),
+                ("fuzzy", 2) => ValueExpr::Fuzzy(arg(), arg()),
+                ("plural", 3..) => {
+                    let count = arg();
+                    ValueExpr::Plural(count, args.map(|form| *form).collect())
+                }
+                (fun, _) if functions::BUILTIN.contains(&fun) => {
+                    ValueExpr::Call(fun.into(), args.map(|arg| *arg).collect())
+                }
+                _ => return Err(ErrorKind::UnknownFunction),
+            }
+        }
+        Expr::Message(message) => {
+            let (key, args) = match *message {
+                Expr::Call { fun, args } => (*fun, args),
+                key => (key, vec![]),
+            };
+
+            let key = message_key(key, consts)?;
+            let args = args
+                .into_iter()
+                .map(|arg| eval(arg, consts))
+                .collect::<Result<_, _>>()?;
+            ValueExpr::Message(key.into(), args)
+        }
+    };
+
+    Ok(value_expr)
+}
+
+// Message keys are identifiers separated by dots, `@menu.file.open`
+fn message_key(key: Expr, consts: &Constants) -> Result<String, ErrorKind> {
+    match key {
+        Expr::Ident(string_id) => Ok(consts.lookup_string(string_id).to_string()),
+        Expr::Binary {
+            op: Operator::Dot,
+            lhs,
+            rhs,
+        } => Ok(format!(
+            "{}.{}",
+            message_key(*lhs, consts)?,
+            message_key(*rhs, consts)?
+        )),
+        _ => Err(ErrorKind::InvalidMessageKey),
     }
 }
 
@@ -122,7 +209,7 @@ mod test {
         let mut tokens = Tokens::new(tokens, input.len());
 
         let expression = expr(&mut tokens);
-        eval(expression, &consts)
+        eval(expression, &consts).unwrap()
     }
 
     #[test]
@@ -245,6 +332,42 @@ mod test {
         assert_eq!(expr.to_string(), "-a / b");
     }
 
+    #[test]
+    fn transforms() {
+        let expr = eval_str("filter(items, item.active)");
+        assert_eq!(expr.to_string(), "filter(items, item.active)");
+
+        let expr = eval_str("sort_by(filter(items, item.active), item.name)");
+        assert_eq!(
+            expr.to_string(),
+            "sort_by(filter(items, item.active), item.name)"
+        );
+
+        let expr = eval_str("paginate(items, page, 10)");
+        assert_eq!(expr.to_string(), "paginate(items, page, 10)");
+
+        let expr = eval_str("page_count(items, 10)");
+        assert_eq!(expr.to_string(), "page_count(items, 10)");
+
+        let expr = eval_str("fuzzy(items, query)");
+        assert_eq!(expr.to_string(), "fuzzy(items, query)");
+
+        let expr = eval_str("plural(count, \"item\", \"items\")");
+        assert_eq!(expr.to_string(), "plural(count, item, items)");
+    }
+
+    #[test]
+    fn messages() {
+        let expr = eval_str("@greeting");
+        assert_eq!(expr.to_string(), "@greeting");
+
+        let expr = eval_str("@greeting(user.name, 2)");
+        assert_eq!(expr.to_string(), "@greeting(user.name, 2)");
+
+        let expr = eval_str("@menu.file.open(count)");
+        assert_eq!(expr.to_string(), "@menu.file.open(count)");
+    }
+
     #[test]
     fn modulo() {
         let expr = eval_str("5 % 4");
@@ -11,10 +11,11 @@ mod eval;
 pub mod prec {
     pub const INITIAL: u8 = 0;
     pub const ASSIGNMENT: u8 = 1;
-    pub const CONDITIONAL: u8 = 2;
-    pub const LOGICAL: u8 = 3;
-    pub const SUM: u8 = 4;
-    pub const PRODUCT: u8 = 5;
+    pub const COALESCE: u8 = 2;
+    pub const CONDITIONAL: u8 = 3;
+    pub const LOGICAL: u8 = 4;
+    pub const SUM: u8 = 5;
+    pub const PRODUCT: u8 = 6;
     pub const PREFIX: u8 = 7;
     pub const CALL: u8 = 9;
     pub const SUBCRIPT: u8 = 10;
@@ -27,11 +28,12 @@ fn get_precedence(op: Operator) -> u8 {
         | Operator::GreaterThanOrEqual
         | Operator::LessThan
         | Operator::LessThanOrEqual => prec::LOGICAL,
+        Operator::Coalesce | Operator::Default => prec::COALESCE,
         Operator::Or | Operator::And | Operator::EqualEqual => prec::CONDITIONAL,
         Operator::Plus | Operator::Minus => prec::SUM,
         Operator::Mul | Operator::Div | Operator::Mod => prec::PRODUCT,
         Operator::LParen => prec::CALL,
-        Operator::Dot => prec::SUBCRIPT,
+        Operator::Dot | Operator::OptionalDot => prec::SUBCRIPT,
         Operator::LBracket => prec::SUBCRIPT,
         _ => 0,
     }
@@ -63,6 +65,8 @@ pub enum Expr {
     },
     List(Vec<Expr>),
     Map(Vec<(Expr, Expr)>),
+    /// A translated message, `@greeting(name)`
+    Message(Box<Expr>),
 }
 
 impl Display for Expr {
@@ -100,6 +104,7 @@ impl Display for Expr {
                     .join(", ");
                 write!(f, "{fun}({s})")
             }
+            Expr::Message(message) => write!(f, "@{message}"),
         }
     }
 }
@@ -134,6 +139,8 @@ fn expr_bp(tokens: &mut Tokens, precedence: u8) -> Expr {
             // TODO: see panic
             _ => panic!("need to cover the rest of the values"),
         },
+        // The message key, and the arguments as a call
+        Kind::View => Expr::Message(Box::new(expr_bp(tokens, prec::PREFIX))),
         Kind::Eof => panic!("unexpected eof"),
         // TODO: see panic
         kind => panic!("we'll deal with this later: {kind:#?}"),
@@ -143,8 +150,11 @@ fn expr_bp(tokens: &mut Tokens, precedence: u8) -> Expr {
         // This could be EOF, which is fine.
         // It could also be any other token which would be
         // a syntax error, but I don't mind that just now
-        let Kind::Op(op) = tokens.peek_skip_indent() else {
-            return left;
+        let op = match tokens.peek_skip_indent() {
+            Kind::Op(op) => op,
+            // `else` between two expressions is a default value: `a else b`
+            Kind::Else => Operator::Default,
+            _ => return left,
         };
 
         let token_prec = get_precedence(op);
@@ -340,6 +350,12 @@ mod test {
         assert_eq!(parse(input), "(. (. <sid 0> <sid 1>) <sid 2>)");
     }
 
+    #[test]
+    fn optional_dot_lookup() {
+        let input = "a?.b?.c";
+        assert_eq!(parse(input), "(?. (?. <sid 0> <sid 1>) <sid 2>)");
+    }
+
     #[test]
     fn modulo() {
         let input = "5 + 1 % 2";
@@ -364,6 +380,24 @@ mod test {
         assert_eq!(parse(input), "(== 1 2)");
     }
 
+    #[test]
+    fn coalesce() {
+        let input = "a.b ?? 1 + 2";
+        assert_eq!(parse(input), "(?? (. <sid 0> <sid 1>) (+ 1 2))");
+
+        let input = "a ?? b || c";
+        assert_eq!(parse(input), "(?? <sid 0> (|| <sid 1> <sid 2>))");
+    }
+
+    #[test]
+    fn default() {
+        let input = "a.b else 1 + 2";
+        assert_eq!(parse(input), "(else (. <sid 0> <sid 1>) (+ 1 2))");
+
+        let input = "a ?? b else c";
+        assert_eq!(parse(input), "(else (?? <sid 0> <sid 1>) <sid 2>)");
+    }
+
     #[test]
     fn map() {
         let input = "{a: 1, b: c}";
@@ -33,7 +33,10 @@ pub enum Operator {
     Not,
     And,
     Or,
+    Coalesce,
+    Default,
     Dot,
+    OptionalDot,
     Comma,
     Colon,
 }
@@ -64,7 +67,10 @@ impl Display for Operator {
             Self::Not => write!(f, "!"),
             Self::And => write!(f, "&&"),
             Self::Or => write!(f, "||"),
+            Self::Coalesce => write!(f, "??"),
+            Self::Default => write!(f, "else"),
             Self::Dot => write!(f, "."),
+            Self::OptionalDot => write!(f, "?."),
             Self::Comma => write!(f, ","),
             Self::Colon => write!(f, ":"),
             Self::LCurly => write!(f, "{{"),
@@ -104,6 +110,7 @@ pub(crate) enum Kind {
     In,
     If,
     Else,
+    Const,
     View,
     Newline,
     Indent(usize),
@@ -127,6 +134,7 @@ impl Display for Kind {
             Self::In => write!(f, "<in>"),
             Self::If => write!(f, "<if>"),
             Self::Else => write!(f, "<else>"),
+            Self::Const => write!(f, "<const>"),
             Self::View => write!(f, "<view>"),
             Self::Newline => write!(f, "\\n"),
             Self::Indent(s) => write!(f, "<indent {s}>"),
@@ -191,6 +199,13 @@ impl Tokens {
         }
     }
 
+    /// Skip all tokens up until the next new line
+    pub fn skip_line(&mut self) {
+        while !matches!(self.peek(), Kind::Newline | Kind::Eof) {
+            self.index += 1;
+        }
+    }
+
     pub fn consume_newlines(&mut self) {
         loop {
             if matches!(self.inner.get(self.index), Some(Token(Kind::Newline, _))) {
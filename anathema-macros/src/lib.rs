@@ -0,0 +1,38 @@
+use anathema_compiler::ViewIds;
+use manyhow::{bail, manyhow, Result};
+use quote::quote;
+
+/// Check a template at compile time.
+///
+/// Syntax errors in the template are reported as compiler errors,
+/// and the template is returned as a `&'static str`.
+///
+/// The macro only checks the template: it is still compiled into
+/// instructions when it's loaded, no precompiled output is emitted.
+///
+/// ```
+/// # use anathema_macros::template;
+/// let src: &str = template!("
+/// border
+///     text [bold: true] 'hello world'
+/// ");
+/// ```
+///
+/// ```compile_fail
+/// # use anathema_macros::template;
+/// let src = template!("text 'missing quote");
+/// ```
+#[manyhow]
+#[proc_macro]
+pub fn template(src: syn::LitStr) -> Result {
+    let template = src.value();
+
+    // Views are only resolved once the templates are loaded,
+    // so any view name is accepted here
+    let mut view_ids = ViewIds::new();
+    if let Err(err) = anathema_compiler::compile(&template, &mut view_ids) {
+        bail!(src, "invalid template\n{err}");
+    }
+
+    Ok(quote!(#src))
+}
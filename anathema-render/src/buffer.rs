@@ -1,5 +1,6 @@
 #![deny(missing_docs)]
 use std::io::{Result, Write};
+use std::sync::Arc;
 
 use crossterm::style::Print;
 use crossterm::{cursor, QueueableCommand};
@@ -7,10 +8,13 @@ use unicode_width::UnicodeWidthChar;
 
 use super::{ScreenPos, Size, Style};
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct Cell {
     pub(crate) style: Style,
     pub(crate) inner: CellState,
+    pub(crate) link: Option<Arc<str>>,
+    // The style was changed after the cell was painted, see `Buffer::restyle`
+    restyled: bool,
 }
 
 impl Cell {
@@ -20,6 +24,8 @@ impl Cell {
         Self {
             style: Style::reset(),
             inner: CellState::Empty,
+            link: None,
+            restyled: false,
         }
     }
 
@@ -27,6 +33,8 @@ impl Cell {
         Self {
             style,
             inner: CellState::Continuation,
+            link: None,
+            restyled: false,
         }
     }
 
@@ -34,8 +42,15 @@ impl Cell {
         Self {
             style,
             inner: CellState::Occupied(c),
+            link: None,
+            restyled: false,
         }
     }
+
+    fn with_link(mut self, link: Arc<str>) -> Self {
+        self.link = Some(link);
+        self
+    }
 }
 
 /// Represent the state of a cell inside a [`Buffer`].
@@ -79,6 +94,11 @@ impl Buffer {
         self.size
     }
 
+    /// The approximate number of bytes used by the cells of the buffer
+    pub fn memory_usage(&self) -> usize {
+        self.inner.capacity() * std::mem::size_of::<Cell>()
+    }
+
     /// Resize the buffer, truncating what doesn't fit but keeps what does.
     pub fn resize(&mut self, size: Size) {
         let mut new_buf = Buffer::new(size);
@@ -93,7 +113,7 @@ impl Buffer {
                 }
 
                 let pos = ScreenPos::new(x as u16, y as u16);
-                new_buf.put(*cell, pos);
+                new_buf.put(cell.clone(), pos);
             }
         }
 
@@ -107,6 +127,19 @@ impl Buffer {
         self.put(cell, pos);
     }
 
+    /// Put a character with a style at a given position,
+    /// linking it to a URL (rendered as an OSC 8 hyperlink).
+    pub fn put_char_with_link(&mut self, c: char, style: Style, link: Arc<str>, pos: ScreenPos) {
+        let cell = Cell::new(c, style).with_link(link);
+        self.put(cell, pos);
+    }
+
+    /// Get the URL a cell at a given position links to.
+    pub fn link(&self, pos: ScreenPos) -> Option<&Arc<str>> {
+        let index = self.index(pos);
+        self.inner.get(index)?.link.as_ref()
+    }
+
     /// Get a `char` and [`Style`] at a given position inside the buffer.
     pub fn get(&self, pos: ScreenPos) -> Option<(char, Style)> {
         let index = self.index(pos);
@@ -117,6 +150,35 @@ impl Buffer {
         }
     }
 
+    /// Change the style of the character at a given position, keeping the character
+    /// and its link. Empty cells are left as they are.
+    ///
+    /// A restyled cell is beneath anything painted after it:
+    /// painting over the cell replaces the style rather than merging with it.
+    pub fn restyle(&mut self, pos: ScreenPos, f: impl FnOnce(&mut Style)) {
+        let index = self.index(pos);
+        if let Some(
+            cell @ Cell {
+                inner: CellState::Occupied(_),
+                ..
+            },
+        ) = self.inner.get_mut(index)
+        {
+            f(&mut cell.style);
+            cell.restyled = true;
+        }
+    }
+
+    // Returns true if nothing is painted at the position,
+    // not even the second half of a wide char
+    pub(crate) fn is_empty(&self, pos: ScreenPos) -> bool {
+        let index = self.index(pos);
+        matches!(
+            self.inner.get(index).map(|cell| &cell.inner),
+            Some(CellState::Empty)
+        )
+    }
+
     /// Empty a cell at a given position
     pub fn empty(&mut self, pos: ScreenPos) {
         let index = self.index(pos);
@@ -133,6 +195,31 @@ impl Buffer {
         })
     }
 
+    /// The characters of the buffer without any styles,
+    /// with trailing spaces and trailing empty lines removed.
+    pub fn plain_text(&self) -> String {
+        let mut lines = self
+            .cell_lines()
+            .map(|line| {
+                let line = line
+                    .iter()
+                    .filter_map(|cell| match cell.inner {
+                        CellState::Empty => Some(' '),
+                        CellState::Occupied(c) => Some(c),
+                        CellState::Continuation => None,
+                    })
+                    .collect::<String>();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>();
+
+        while lines.last().is_some_and(String::is_empty) {
+            lines.pop();
+        }
+
+        lines.join("\n")
+    }
+
     fn index(&self, pos: ScreenPos) -> usize {
         pos.y as usize * self.size.width + pos.x as usize
     }
@@ -155,12 +242,17 @@ impl Buffer {
         }
 
         let current = &mut self.inner[index];
+        if current.restyled {
+            *current = cell;
+            return;
+        }
         cell.style.merge(current.style);
 
         match (&mut current.inner, cell.inner) {
             // Merge the styles
             (CellState::Occupied(ref mut current_char), CellState::Occupied(new_char)) => {
                 *current_char = new_char;
+                current.link = cell.link;
                 current.style.attributes |= cell.style.attributes;
 
                 if let Some(col) = cell.style.fg {
@@ -182,11 +274,12 @@ impl Buffer {
 
 #[cfg(test)]
 impl Buffer {
-    fn cell_at(&self, x: usize, y: usize) -> Cell {
+    fn cell_at(&self, x: usize, y: usize) -> &Cell {
         let index = y * self.size.width + x;
-        self.inner[index]
+        &self.inner[index]
     }
 
+    /// Get the character at the given position.
     pub fn char_at(&self, x: usize, y: usize) -> char {
         let cell = self.cell_at(x, y);
         match cell.inner {
@@ -211,7 +304,9 @@ impl Change {
     }
 }
 
-pub(crate) fn diff(old: &Buffer, new: &Buffer) -> Result<Vec<(ScreenPos, Option<Style>, Change)>> {
+pub(crate) type Changes = Vec<(ScreenPos, Option<Style>, Option<Arc<str>>, Change)>;
+
+pub(crate) fn diff(old: &Buffer, new: &Buffer) -> Result<Changes> {
     let mut changes = Vec::new();
 
     let mut previous_style = None;
@@ -238,7 +333,12 @@ pub(crate) fn diff(old: &Buffer, new: &Buffer) -> Result<Vec<(ScreenPos, Option<
                 CellState::Occupied(c) => Change::Insert(c),
             };
 
-            changes.push((ScreenPos::new(x, y), style, change));
+            let link = match change {
+                Change::Insert(_) => new_cell.link.clone(),
+                Change::Remove => None,
+            };
+
+            changes.push((ScreenPos::new(x, y), style, link, change));
         }
     }
 
@@ -248,14 +348,12 @@ pub(crate) fn diff(old: &Buffer, new: &Buffer) -> Result<Vec<(ScreenPos, Option<
 // -----------------------------------------------------------------------------
 //     - Draw changes -
 // -----------------------------------------------------------------------------
-pub(crate) fn draw_changes(
-    mut w: impl Write,
-    changes: Vec<(ScreenPos, Option<Style>, Change)>,
-) -> Result<()> {
+pub(crate) fn draw_changes(mut w: impl Write, changes: Changes) -> Result<()> {
     let mut last_y = None;
     let mut next_cell_x = None;
+    let mut current_link: Option<Arc<str>> = None;
 
-    for (screen_pos, style, change) in changes {
+    for (screen_pos, style, link, change) in changes {
         // Cursor movement
         let should_move = match (last_y, next_cell_x) {
             (Some(last_y), Some(next_x)) => screen_pos.y > last_y || next_x != screen_pos.x,
@@ -274,6 +372,12 @@ pub(crate) fn draw_changes(
             style.write(&mut w)?;
         }
 
+        // Open or close a hyperlink
+        if link != current_link {
+            write_link(&mut w, link.as_deref())?;
+            current_link = link;
+        }
+
         // Draw changes
         match change {
             Change::Insert(c) => w.queue(Print(c))?,
@@ -281,12 +385,24 @@ pub(crate) fn draw_changes(
         };
     }
 
+    if current_link.is_some() {
+        write_link(&mut w, None)?;
+    }
+
+    Ok(())
+}
+
+// Start an OSC 8 hyperlink, or end the current one if there is no url.
+// Terminals without support for hyperlinks ignore the sequence.
+fn write_link(mut w: impl Write, url: Option<&str>) -> Result<()> {
+    w.queue(Print(format_args!("\x1b]8;;{}\x1b\\", url.unwrap_or(""))))?;
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::Attributes;
 
     #[test]
     fn changes() {
@@ -304,9 +420,9 @@ mod test {
 
         let changes = diff(&old_buffer, &new_buffer).unwrap();
 
-        let (_, _, change_1) = changes[0]; // Insert 'C'
-        let (_, _, change_2) = changes[1]; // Remove 'V'
-        let (_, _, change_3) = changes[2]; // Insert 'N'
+        let (_, _, _, change_1) = changes[0]; // Insert 'C'
+        let (_, _, _, change_2) = changes[1]; // Remove 'V'
+        let (_, _, _, change_3) = changes[2]; // Insert 'N'
 
         assert_eq!(Change::Insert('C'), change_1);
         assert_eq!(Change::Remove, change_2);
@@ -325,4 +441,56 @@ mod test {
         assert_eq!(buffer.inner[0], Cell::new('1', Style::reset()));
         assert_eq!(buffer.inner[1], Cell::new('3', Style::reset()));
     }
+
+    #[test]
+    fn restyle() {
+        let mut buffer = Buffer::new((2u16, 1));
+        buffer.put_char_with_link(
+            'a',
+            Style::new(),
+            "https://example.com".into(),
+            ScreenPos::new(0, 0),
+        );
+        buffer.restyle(ScreenPos::new(0, 0), |style| style.set_dim(true));
+        buffer.restyle(ScreenPos::new(1, 0), |style| style.set_dim(true));
+
+        let (c, style) = buffer.get(ScreenPos::new(0, 0)).unwrap();
+        assert_eq!(c, 'a');
+        assert!(style.attributes.contains(Attributes::DIM));
+        assert!(buffer.link(ScreenPos::new(0, 0)).is_some());
+        assert_eq!(buffer.get(ScreenPos::new(1, 0)), None);
+
+        // Painting over a restyled cell doesn't merge the styles
+        buffer.put_char('b', Style::new(), ScreenPos::new(0, 0));
+        let (_, style) = buffer.get(ScreenPos::new(0, 0)).unwrap();
+        assert!(!style.attributes.contains(Attributes::DIM));
+    }
+
+    #[test]
+    fn plain_text() {
+        let mut buffer = Buffer::new((4u16, 3));
+        buffer.put_char('a', Style::reset(), ScreenPos::new(1, 0));
+        buffer.put_char('💖', Style::reset(), ScreenPos::new(0, 1));
+        buffer.put_char('b', Style::reset(), ScreenPos::new(2, 1));
+
+        assert_eq!(buffer.plain_text(), " a\n💖b");
+    }
+
+    #[test]
+    fn draw_links() {
+        let old_buffer = Buffer::new((3u16, 1));
+        let mut new_buffer = Buffer::new((3u16, 1));
+        let link: Arc<str> = "https://example.com".into();
+        new_buffer.put_char_with_link('a', Style::reset(), link.clone(), ScreenPos::new(0, 0));
+        new_buffer.put_char_with_link('b', Style::reset(), link.clone(), ScreenPos::new(1, 0));
+        new_buffer.put_char('c', Style::reset(), ScreenPos::new(2, 0));
+        assert_eq!(new_buffer.link(ScreenPos::new(1, 0)), Some(&link));
+
+        let mut output = vec![];
+        draw_changes(&mut output, diff(&old_buffer, &new_buffer).unwrap()).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("\x1b]8;;https://example.com\x1b\\ab"));
+        assert!(output.contains("\x1b]8;;\x1b\\c"));
+        assert_eq!(output.matches("\x1b]8;;").count(), 2);
+    }
 }
@@ -0,0 +1,219 @@
+use std::fmt;
+
+use unicode_width::UnicodeWidthChar;
+
+use super::{Buffer, ScreenPos, Size, Style};
+
+/// A cell of a [`FixedScreen`]
+pub type FixedCell = Option<(char, Style)>;
+
+/// A screen with a size known at compile time, storing its cells inline
+/// rather than on the heap.
+///
+/// Unlike the [`crate::Screen`] it doesn't render to a terminal.
+/// It's meant for embedding, e.g drawing a status bar or rendering
+/// into a grid provided by a host application, which reads the cells
+/// back through [`FixedScreen::rows`].
+///
+/// Drawing outside of the screen is ignored rather than panicking.
+///
+/// ```
+/// use anathema_render::{FixedScreen, ScreenPos, Style};
+/// let mut screen = FixedScreen::<4, 2>::new();
+/// screen.put('a', Style::new(), ScreenPos::new(1, 0));
+/// screen.put('b', Style::new(), ScreenPos::new(9, 9));
+/// assert_eq!(screen.to_string(), " a  \n    ");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FixedScreen<const W: usize, const H: usize> {
+    cells: [[FixedCell; W]; H],
+    cursor: Option<ScreenPos>,
+}
+
+impl<const W: usize, const H: usize> FixedScreen<W, H> {
+    /// The size of the screen
+    pub const SIZE: Size = Size::new(W, H);
+
+    /// Create a new, empty screen
+    pub const fn new() -> Self {
+        Self {
+            cells: [[None; W]; H],
+            cursor: None,
+        }
+    }
+
+    /// The size of the screen
+    pub const fn size(&self) -> Size {
+        Self::SIZE
+    }
+
+    /// Put a char at the given screen position, with a given style.
+    /// The style is merged with the style of the cell, see [`Style::merge`].
+    ///
+    /// A char that is wider than one cell empties the cell after it.
+    pub fn put(&mut self, c: char, mut style: Style, pos: ScreenPos) {
+        let Some(cell) = self.cell_mut(pos) else {
+            return;
+        };
+
+        if let Some((_, current)) = cell {
+            style.merge(*current);
+        }
+        *cell = Some((c, style));
+
+        if let Some(2..) = c.width() {
+            if let Some(next) = self.cell_mut(ScreenPos::new(pos.x + 1, pos.y)) {
+                *next = None;
+            }
+        }
+    }
+
+    /// Get the `char` and [`Style`] at a given position
+    pub fn get(&self, pos: ScreenPos) -> FixedCell {
+        *self.cells.get(pos.y as usize)?.get(pos.x as usize)?
+    }
+
+    /// Change the style of what is painted at the given screen position.
+    /// Empty cells are left as they are.
+    pub fn restyle(&mut self, pos: ScreenPos, f: impl FnOnce(&mut Style)) {
+        if let Some(Some((_, style))) = self.cell_mut(pos) {
+            f(style);
+        }
+    }
+
+    /// Erase the entire screen.
+    /// This also removes the cursor.
+    pub fn erase(&mut self) {
+        self.cells = [[None; W]; H];
+        self.cursor = None;
+    }
+
+    /// Erase a specific region
+    pub fn erase_region(&mut self, pos: ScreenPos, size: Size) {
+        let to_x = (pos.x as usize + size.width).min(W);
+        let to_y = (pos.y as usize + size.height).min(H);
+
+        for row in self.cells.iter_mut().take(to_y).skip(pos.y as usize) {
+            for cell in row.iter_mut().take(to_x).skip(pos.x as usize) {
+                *cell = None;
+            }
+        }
+    }
+
+    /// Set the position of the cursor, or hide it if the position is `None`.
+    /// The host decides how to show the cursor.
+    pub fn set_cursor(&mut self, pos: Option<ScreenPos>) {
+        self.cursor = pos;
+    }
+
+    /// The position of the cursor
+    pub fn cursor(&self) -> Option<ScreenPos> {
+        self.cursor
+    }
+
+    /// The rows of the screen, from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[FixedCell; W]> + '_ {
+        self.cells.iter()
+    }
+
+    /// Copy the top left of a [`Buffer`] onto the screen,
+    /// e.g the buffer widgets were painted into.
+    /// Cells of the buffer that don't fit are ignored.
+    pub fn copy_from(&mut self, buffer: &Buffer) {
+        for (y, row) in buffer.rows().enumerate().take(H) {
+            for (x, cell) in row.enumerate().take(W) {
+                self.cells[y][x] = cell;
+            }
+        }
+    }
+
+    fn cell_mut(&mut self, pos: ScreenPos) -> Option<&mut FixedCell> {
+        self.cells.get_mut(pos.y as usize)?.get_mut(pos.x as usize)
+    }
+}
+
+impl<const W: usize, const H: usize> Default for FixedScreen<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The characters of the screen without any styles, one line per row.
+/// Empty cells are written as spaces.
+impl<const W: usize, const H: usize> fmt::Display for FixedScreen<W, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.cells.iter().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            let mut skip = false;
+            for cell in row {
+                match cell {
+                    Some((c, _)) => {
+                        write!(f, "{c}")?;
+                        skip = matches!(c.width(), Some(2..));
+                    }
+                    // The cell is covered by a wide char
+                    None if skip => skip = false,
+                    None => write!(f, " ")?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::style::Color;
+
+    use super::*;
+
+    #[test]
+    fn merge_styles() {
+        let mut screen = FixedScreen::<2, 1>::new();
+        let mut style = Style::new();
+        style.set_bg(Color::Red);
+        screen.put('a', style, ScreenPos::ZERO);
+        screen.put('b', Style::new(), ScreenPos::ZERO);
+
+        let (c, style) = screen.get(ScreenPos::ZERO).unwrap();
+        assert_eq!(c, 'b');
+        assert_eq!(style.bg, Some(Color::Red));
+    }
+
+    #[test]
+    fn erase_region() {
+        let mut screen = FixedScreen::<3, 3>::new();
+        for y in 0..3 {
+            for x in 0..3 {
+                screen.put('x', Style::new(), ScreenPos::new(x, y));
+            }
+        }
+
+        screen.erase_region(ScreenPos::new(1, 1), Size::new(5, 5));
+        assert_eq!(screen.to_string(), "xxx\nx  \nx  ");
+    }
+
+    #[test]
+    fn wide_chars() {
+        let mut screen = FixedScreen::<4, 1>::new();
+        screen.put('a', Style::new(), ScreenPos::new(1, 0));
+        screen.put('💖', Style::new(), ScreenPos::new(0, 0));
+        assert_eq!(screen.get(ScreenPos::new(1, 0)), None);
+        assert_eq!(screen.to_string(), "💖  ");
+    }
+
+    #[test]
+    fn copy_from_buffer() {
+        let mut buffer = Buffer::new(Size::new(5, 1));
+        buffer.put_char('a', Style::new(), ScreenPos::new(0, 0));
+        buffer.put_char('b', Style::new(), ScreenPos::new(3, 0));
+
+        let mut screen = FixedScreen::<2, 2>::new();
+        screen.copy_from(&buffer);
+        assert_eq!(screen.to_string(), "a \n  ");
+    }
+}
@@ -31,6 +31,8 @@
 use std::ops::{Add, Sub};
 
 mod buffer;
+mod fixed_screen;
+mod render_thread;
 mod screen;
 mod style;
 
@@ -39,9 +41,11 @@ mod style;
 // -----------------------------------------------------------------------------
 pub use crossterm::style::{Attributes as CrossAttrib, Color};
 pub use crossterm::terminal::size;
-pub use screen::Screen;
+pub use render_thread::RenderThread;
+pub use screen::{Highlight, HighlightStyle, Screen};
 
 pub use crate::buffer::Buffer;
+pub use crate::fixed_screen::{FixedCell, FixedScreen};
 pub use crate::style::{Attributes, Style};
 
 /// Size
@@ -0,0 +1,225 @@
+// -----------------------------------------------------------------------------
+//   - Render thread -
+//   Diffing the buffers and writing to the terminal happens on a
+//   dedicated thread, so the next frame can be laid out and painted
+//   while the previous frame is still being written.
+//
+//   The buffers are double buffered: the painted buffer is sent to the
+//   thread, and once written the thread sends the buffer of the frame
+//   before it back to be painted again.
+//
+//   At most one frame is in flight. Presenting a frame while the
+//   previous frame is still being written blocks until it's done.
+// -----------------------------------------------------------------------------
+use std::io::{Error, ErrorKind, Result, Write};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread::JoinHandle;
+
+use crate::buffer::Buffer;
+use crate::screen::{clear_terminal, draw_frame};
+use crate::{Screen, ScreenPos};
+
+struct Frame {
+    buffer: Buffer,
+    cursor: Option<ScreenPos>,
+}
+
+// A buffer to paint the next frame in,
+// and the number of cells written for the last frame
+struct Written {
+    buffer: Buffer,
+    cells_written: usize,
+}
+
+/// Write frames to the output on a dedicated thread.
+///
+/// ```
+/// # use anathema_render::{RenderThread, Screen, ScreenPos, Style};
+/// let mut screen = Screen::new((10u16, 2u16));
+/// let mut render_thread = RenderThread::spawn(Vec::new());
+///
+/// screen.put('x', Style::new(), ScreenPos::new(1, 1));
+/// render_thread.present(&mut screen).unwrap();
+/// render_thread.stop().unwrap();
+/// ```
+pub struct RenderThread {
+    frames: Option<SyncSender<Frame>>,
+    written: Receiver<Written>,
+    handle: Option<JoinHandle<Result<()>>>,
+    cells_written: usize,
+}
+
+impl RenderThread {
+    /// Spawn a thread writing frames to the output
+    pub fn spawn<W: Write + Send + 'static>(output: W) -> Self {
+        let (frames, frame_rx) = sync_channel::<Frame>(1);
+        let (written_tx, written) = sync_channel(1);
+        let handle = std::thread::spawn(move || render(output, frame_rx, written_tx));
+
+        Self {
+            frames: Some(frames),
+            written,
+            handle: Some(handle),
+            cells_written: 0,
+        }
+    }
+
+    /// Send the painted buffer of the screen to the render thread,
+    /// and give the screen a buffer to paint the next frame in.
+    ///
+    /// Unlike [`Screen::render`] the screen is not erased:
+    /// call [`Screen::erase`] before painting the next frame.
+    ///
+    /// Returns the number of cells written for the most recently written frame,
+    /// which can be the frame before this one.
+    pub fn present(&mut self, screen: &mut Screen) -> Result<usize> {
+        let Some(frames) = self.frames.as_ref() else {
+            return Err(stopped());
+        };
+
+        // Reuse the buffer of a written frame, if it still fits
+        let empty = match self.written.try_recv() {
+            Ok(written) => {
+                self.cells_written = written.cells_written;
+                written.buffer
+            }
+            Err(_) => Buffer::new(screen.size()),
+        };
+        let empty = match empty.size() == screen.size() {
+            true => empty,
+            false => Buffer::new(screen.size()),
+        };
+
+        let (buffer, cursor) = screen.take_frame(empty);
+        if frames.send(Frame { buffer, cursor }).is_err() {
+            // The thread stopped writing, most likely because of an error
+            self.stop()?;
+            return Err(stopped());
+        }
+
+        Ok(self.cells_written)
+    }
+
+    /// Write any remaining frame and stop the thread.
+    /// Returns the first error the thread ran into while writing.
+    pub fn stop(&mut self) -> Result<()> {
+        self.frames.take();
+        match self.handle.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err(Error::other("render thread panicked")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for RenderThread {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+fn stopped() -> Error {
+    Error::new(ErrorKind::BrokenPipe, "render thread stopped")
+}
+
+fn render(
+    mut output: impl Write,
+    frames: Receiver<Frame>,
+    written: SyncSender<Written>,
+) -> Result<()> {
+    let mut old: Option<Buffer> = None;
+    let mut old_cursor = None;
+
+    while let Ok(mut frame) = frames.recv() {
+        let old_buffer = match old.take() {
+            Some(buffer) if buffer.size() == frame.buffer.size() => buffer,
+            // First frame or the screen was resized: start from a blank terminal
+            _ => {
+                clear_terminal(&mut output)?;
+                Buffer::new(frame.buffer.size())
+            }
+        };
+
+        let cells_written = draw_frame(
+            &mut output,
+            &old_buffer,
+            &frame.buffer,
+            frame.cursor,
+            old_cursor,
+        )?;
+
+        old_cursor = frame.cursor;
+        let buffer = std::mem::replace(&mut frame.buffer, old_buffer);
+        old = Some(buffer);
+
+        // The main thread allocates a new buffer if this one isn't picked up
+        let _ = written.try_send(Written {
+            buffer: frame.buffer,
+            cells_written,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::Style;
+
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_frames_on_thread() {
+        let output = Output::default();
+        let mut screen = Screen::new((4u16, 1u16));
+        let mut render_thread = RenderThread::spawn(output.clone());
+
+        for c in ['a', 'b', 'c'] {
+            screen.erase();
+            screen.put(c, Style::new(), ScreenPos::new(0, 0));
+            render_thread.present(&mut screen).unwrap();
+        }
+        render_thread.stop().unwrap();
+
+        let written = String::from_utf8_lossy(&output.0.lock().unwrap()).to_string();
+        for c in ["a", "b", "c"] {
+            assert!(written.contains(c));
+        }
+        assert!(render_thread.present(&mut screen).is_err());
+    }
+
+    #[test]
+    fn write_errors_are_returned() {
+        struct Broken;
+
+        impl Write for Broken {
+            fn write(&mut self, _: &[u8]) -> Result<usize> {
+                Err(Error::other("broken"))
+            }
+
+            fn flush(&mut self) -> Result<()> {
+                Err(Error::other("broken"))
+            }
+        }
+
+        let mut screen = Screen::new((4u16, 1u16));
+        let mut render_thread = RenderThread::spawn(Broken);
+        screen.put('a', Style::new(), ScreenPos::new(0, 0));
+        let _ = render_thread.present(&mut screen);
+        assert!(render_thread.stop().is_err());
+    }
+}
@@ -1,20 +1,72 @@
 use std::io::{Result, Write};
+use std::sync::Arc;
 
-use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
 use crossterm::style::{Color, SetBackgroundColor, SetForegroundColor};
 use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
+    disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, Clear, ClearType,
+    EnterAlternateScreen, LeaveAlternateScreen,
 };
 use crossterm::{cursor, ExecutableCommand, QueueableCommand};
 
 use super::buffer::{diff, draw_changes, Buffer};
-use super::{ScreenPos, Size, Style};
+use super::{Attributes, ScreenPos, Size, Style};
+
+/// How a [`Highlight`] restyles the cells beneath it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum HighlightStyle {
+    /// Swap the foreground and background of the cells.
+    /// Highlighting inverted cells turns the inversion off.
+    Inverse,
+    /// Tint the background of the cells
+    Background(Color),
+    /// Merge a style into the cells, the colors of the highlight take precedence
+    Style(Style),
+}
+
+impl HighlightStyle {
+    fn apply(&self, style: &mut Style) {
+        match *self {
+            Self::Inverse => {
+                let inverse = style.attributes.contains(Attributes::INVERSE);
+                style.set_inverse(!inverse);
+            }
+            Self::Background(color) => style.set_bg(color),
+            Self::Style(mut highlight) => {
+                highlight.merge(*style);
+                *style = highlight;
+            }
+        }
+    }
+}
+
+/// A region of cells restyled once everything is painted,
+/// without painting the content again.
+/// e.g a selection or the matches of a search.
+///
+/// See [`Screen::highlight`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Highlight {
+    /// The top left cell of the region
+    pub pos: ScreenPos,
+    /// The size of the region, cells outside of the screen are ignored
+    pub size: Size,
+    /// How the cells are restyled
+    pub style: HighlightStyle,
+}
 
 /// The `Screen` is used to draw to some `std::io::Write`able output (generally `stdout`);
 pub struct Screen {
     // This is pub(crate) for testing purposes
     pub(crate) new_buffer: Buffer,
     old_buffer: Buffer,
+    cursor: Option<ScreenPos>,
+    old_cursor: Option<ScreenPos>,
+    keyboard_enhancement: bool,
+    highlights: Vec<Highlight>,
 }
 
 impl Screen {
@@ -42,6 +94,54 @@ impl Screen {
         Ok(())
     }
 
+    /// Enable bracketed paste, delivering pasted text in one go.
+    /// Does nothing on Windows, where bracketed paste is not supported.
+    pub fn enable_paste(mut output: impl Write) -> Result<()> {
+        if cfg!(not(target_os = "windows")) {
+            output.queue(EnableBracketedPaste)?;
+        }
+        Ok(())
+    }
+
+    /// Disable bracketed paste.
+    /// Does nothing on Windows, where bracketed paste is not supported.
+    pub fn disable_paste(mut output: impl Write) -> Result<()> {
+        if cfg!(not(target_os = "windows")) {
+            output.queue(DisableBracketedPaste)?;
+        }
+        Ok(())
+    }
+
+    /// Enable the kitty keyboard protocol, if the terminal supports it.
+    /// This reports key release and repeat events, modifiers that can't otherwise
+    /// be told apart (`Ctrl+I` and `Tab`) and modified non-ASCII keys.
+    ///
+    /// Returns `false` if the terminal doesn't support the protocol,
+    /// in which case key events are reported as before.
+    /// Raw mode should be enabled before calling this.
+    pub fn enable_keyboard_enhancement(&mut self, mut output: impl Write) -> Result<bool> {
+        if !supports_keyboard_enhancement().unwrap_or(false) {
+            return Ok(false);
+        }
+
+        output.queue(PushKeyboardEnhancementFlags(
+            KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                | KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS
+                | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES,
+        ))?;
+        self.keyboard_enhancement = true;
+        Ok(true)
+    }
+
+    /// Disable the kitty keyboard protocol, if it was enabled
+    pub fn disable_keyboard_enhancement(&mut self, mut output: impl Write) -> Result<()> {
+        if std::mem::take(&mut self.keyboard_enhancement) {
+            output.queue(PopKeyboardEnhancementFlags)?;
+        }
+        Ok(())
+    }
+
     /// Create a new instance of a screen.
     /// The `output` should be a mutable reference to whatever this screen renders to.
     /// The `output` is used initially to move the cursor and hide it.
@@ -51,6 +151,10 @@ impl Screen {
         Self {
             old_buffer: Buffer::new(size),
             new_buffer: Buffer::new(size),
+            cursor: None,
+            old_cursor: None,
+            keyboard_enhancement: false,
+            highlights: vec![],
         }
     }
 
@@ -64,6 +168,11 @@ impl Screen {
         self.new_buffer.size()
     }
 
+    /// The approximate number of bytes used by the buffers of the screen
+    pub fn memory_usage(&self) -> usize {
+        self.new_buffer.memory_usage() + self.old_buffer.memory_usage()
+    }
+
     /// Resize the buffer.
     /// This will empty the underlying buffers so everything will have
     /// to be redrawn.
@@ -83,17 +192,15 @@ impl Screen {
     pub fn clear_all(&mut self, mut output: impl Write) -> Result<()> {
         self.erase();
         output.flush()?;
-        output.queue(cursor::MoveTo(0, 0))?;
-        output.queue(SetForegroundColor(Color::Reset))?;
-        output.queue(SetBackgroundColor(Color::Reset))?;
-        output.queue(Clear(ClearType::All))?;
-        output.flush()?;
-        Ok(())
+        clear_terminal(output)
     }
 
-    /// Erase the entire buffer by writing empty cells
+    /// Erase the entire buffer by writing empty cells.
+    /// This also removes the cursor and the highlights.
     pub fn erase(&mut self) {
         self.erase_region(ScreenPos::ZERO, self.size());
+        self.cursor = None;
+        self.highlights.clear();
     }
 
     /// Erase a specific region.
@@ -116,25 +223,105 @@ impl Screen {
         self.new_buffer.put_char(c, style, pos);
     }
 
+    /// Put a char at the given screen position, with a given style,
+    /// linking it to a URL.
+    ///
+    /// Links are rendered as OSC 8 hyperlinks, terminals without support
+    /// for hyperlinks render the char as if it had no link.
+    pub fn put_with_link(&mut self, c: char, style: Style, link: Arc<str>, pos: ScreenPos) {
+        self.new_buffer.put_char_with_link(c, style, link, pos);
+    }
+
+    /// Change the style of what is painted at the given screen position,
+    /// e.g to dim everything beneath a dialog. See [`Buffer::restyle`].
+    pub fn restyle(&mut self, pos: ScreenPos, f: impl FnOnce(&mut Style)) {
+        self.new_buffer.restyle(pos, f);
+    }
+
+    /// Get the URL the cell at a given screen position links to
+    pub fn link(&self, pos: ScreenPos) -> Option<&Arc<str>> {
+        self.new_buffer.link(pos)
+    }
+
+    /// Restyle a region of cells on the next render, after everything is painted.
+    /// Highlights are applied in the order they are added.
+    pub fn highlight(&mut self, highlight: Highlight) {
+        self.highlights.push(highlight);
+    }
+
+    /// The highlights for the next render
+    pub fn highlights(&self) -> &[Highlight] {
+        &self.highlights
+    }
+
+    /// Restyle the cells of the highlights, and remove the highlights.
+    /// This is done by [`Screen::render`], and only has to be called
+    /// when reading the buffer without rendering it.
+    pub fn apply_highlights(&mut self) {
+        let size = self.size();
+        for highlight in std::mem::take(&mut self.highlights) {
+            let to_x = (highlight.pos.x as usize + highlight.size.width).min(size.width);
+            let to_y = (highlight.pos.y as usize + highlight.size.height).min(size.height);
+            for y in highlight.pos.y as usize..to_y {
+                for x in highlight.pos.x as usize..to_x {
+                    let pos = ScreenPos::new(x as u16, y as u16);
+                    // Empty cells are highlighted as well
+                    if self.new_buffer.is_empty(pos) {
+                        self.new_buffer.put_char(' ', Style::new(), pos);
+                    }
+                    self.new_buffer
+                        .restyle(pos, |style| highlight.style.apply(style));
+                }
+            }
+        }
+    }
+
+    /// Show the terminal cursor at the given position on the next render,
+    /// or hide it if the position is `None`.
+    ///
+    /// Terminals draw the composition string of an input method (IME)
+    /// at the cursor, so a widget accepting text input should place the
+    /// cursor where the text is inserted.
+    pub fn set_cursor(&mut self, pos: Option<ScreenPos>) {
+        self.cursor = pos;
+    }
+
+    /// The cursor position for the next render
+    pub fn cursor(&self) -> Option<ScreenPos> {
+        self.cursor
+    }
+
     /// Get character and style at a given sceen position
     pub fn get(&self, pos: ScreenPos) -> Option<(char, Style)> {
         self.new_buffer.get(pos)
     }
 
-    /// Draw the changes to the screen
-    pub fn render(&mut self, mut output: impl Write) -> Result<()> {
-        let changes = diff(&self.old_buffer, &self.new_buffer)?;
-
-        if changes.is_empty() {
-            return Ok(());
+    /// Draw the changes to the screen.
+    /// Returns the number of cells written.
+    pub fn render(&mut self, output: impl Write) -> Result<usize> {
+        self.apply_highlights();
+        let cells_written = draw_frame(
+            output,
+            &self.old_buffer,
+            &self.new_buffer,
+            self.cursor,
+            self.old_cursor,
+        )?;
+
+        if cells_written > 0 {
+            self.old_buffer = self.new_buffer.clone();
         }
+        self.old_cursor = self.cursor;
 
-        draw_changes(&mut output, changes)?;
-        output.flush()?;
-
-        self.old_buffer = self.new_buffer.clone();
+        Ok(cells_written)
+    }
 
-        Ok(())
+    // Swap the buffer being painted for an empty one,
+    // returning the painted buffer and the cursor.
+    pub(crate) fn take_frame(&mut self, empty: Buffer) -> (Buffer, Option<ScreenPos>) {
+        self.apply_highlights();
+        let buffer = std::mem::replace(&mut self.new_buffer, empty);
+        (buffer, self.cursor)
     }
 
     /// Enter an alternative screen.
@@ -159,15 +346,65 @@ impl Screen {
     /// Restore the terminal by setting the cursor to show, disable raw mode, disable mouse capture
     /// and leave any alternative screens
     pub fn restore(&mut self, mut output: impl Write) -> Result<()> {
+        self.disable_keyboard_enhancement(&mut output)?;
         disable_raw_mode()?;
         output.execute(LeaveAlternateScreen)?;
         #[cfg(not(target_os = "windows"))]
         output.execute(DisableMouseCapture)?;
+        #[cfg(not(target_os = "windows"))]
+        output.execute(DisableBracketedPaste)?;
         output.execute(cursor::Show)?;
         Ok(())
     }
 }
 
+// Draw the difference between two buffers and place the cursor.
+// Returns the number of cells written.
+pub(crate) fn draw_frame(
+    mut output: impl Write,
+    old: &Buffer,
+    new: &Buffer,
+    cursor: Option<ScreenPos>,
+    old_cursor: Option<ScreenPos>,
+) -> Result<usize> {
+    let changes = diff(old, new)?;
+    let cells_written = changes.len();
+
+    if cells_written == 0 && cursor == old_cursor {
+        return Ok(0);
+    }
+
+    if cells_written > 0 {
+        draw_changes(&mut output, changes)?;
+    }
+
+    // Drawing moves the cursor, so it has to be placed again
+    match cursor {
+        Some(pos) => {
+            output.queue(cursor::MoveTo(pos.x, pos.y))?;
+            output.queue(cursor::Show)?;
+        }
+        None if old_cursor.is_some() => {
+            output.queue(cursor::Hide)?;
+        }
+        None => {}
+    }
+
+    output.flush()?;
+
+    Ok(cells_written)
+}
+
+// Clear the terminal, e.g after a resize
+pub(crate) fn clear_terminal(mut output: impl Write) -> Result<()> {
+    output.queue(cursor::MoveTo(0, 0))?;
+    output.queue(SetForegroundColor(Color::Reset))?;
+    output.queue(SetBackgroundColor(Color::Reset))?;
+    output.queue(Clear(ClearType::All))?;
+    output.flush()?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -192,11 +429,76 @@ mod test {
         let mut render_output = vec![];
         let mut screen = make_screen(Size::new(1, 1));
         screen.put('x', Style::reset(), ScreenPos::ZERO);
-        screen.render(&mut render_output).unwrap();
+        let cells_written = screen.render(&mut render_output).unwrap();
 
         let expected = Cell::new('x', Style::reset());
-        let actual = screen.new_buffer.inner[0];
+        let actual = screen.new_buffer.inner[0].clone();
         assert_eq!(expected, actual);
+        assert_eq!(cells_written, 1);
+
+        // Nothing changed
+        let cells_written = screen.render(&mut render_output).unwrap();
+        assert_eq!(cells_written, 0);
+    }
+
+    #[test]
+    fn render_cursor() {
+        let mut screen = make_screen(Size::new(2, 2));
+        screen.render(&mut vec![]).unwrap();
+
+        // Moving the cursor renders even if no cells changed
+        let mut render_output = vec![];
+        screen.set_cursor(Some(ScreenPos::new(1, 1)));
+        assert_eq!(screen.render(&mut render_output).unwrap(), 0);
+        let mut expected = vec![];
+        expected.queue(cursor::MoveTo(1, 1)).unwrap();
+        expected.queue(cursor::Show).unwrap();
+        assert_eq!(render_output, expected);
+
+        // Same cursor, nothing to render
+        let mut render_output = vec![];
+        screen.render(&mut render_output).unwrap();
+        assert!(render_output.is_empty());
+
+        // Erasing the screen hides the cursor
+        let mut render_output = vec![];
+        screen.erase();
+        screen.render(&mut render_output).unwrap();
+        let mut expected = vec![];
+        expected.queue(cursor::Hide).unwrap();
+        assert!(render_output.ends_with(&expected));
+    }
+
+    #[test]
+    fn apply_highlights() {
+        let mut screen = make_screen(Size::new(3, 2));
+        screen.erase_region(ScreenPos::new(2, 1), Size::new(1, 1));
+        screen.highlight(Highlight {
+            pos: ScreenPos::new(1, 0),
+            size: Size::new(5, 5),
+            style: HighlightStyle::Background(Color::Blue),
+        });
+        screen.highlight(Highlight {
+            pos: ScreenPos::new(2, 0),
+            size: Size::new(1, 1),
+            style: HighlightStyle::Inverse,
+        });
+
+        // Highlights are applied when rendering
+        assert_eq!(screen.get(ScreenPos::new(1, 0)).unwrap().1, Style::reset());
+        screen.render(&mut vec![]).unwrap();
+        assert!(screen.highlights().is_empty());
+
+        let style = |x, y| screen.get(ScreenPos::new(x, y)).unwrap().1;
+        assert_eq!(style(0, 0), Style::reset());
+        assert_eq!(style(1, 1).bg, Some(Color::Blue));
+        assert!(style(2, 0).attributes.contains(Attributes::INVERSE));
+        assert!(!style(1, 0).attributes.contains(Attributes::INVERSE));
+
+        // The empty cell is highlighted as well
+        let (c, style) = screen.get(ScreenPos::new(2, 1)).unwrap();
+        assert_eq!(c, ' ');
+        assert_eq!(style.bg, Some(Color::Blue));
     }
 
     #[test]
@@ -209,9 +511,9 @@ mod test {
         screen.erase_region(ScreenPos::new(1, 1), Size::new(1, 1));
         screen.render(&mut render_output).unwrap();
 
-        let top_left = screen.new_buffer.inner[0];
+        let top_left = screen.new_buffer.inner[0].clone();
         assert_eq!(Cell::new('0', Style::reset()), top_left);
-        let bottom_right = screen.new_buffer.inner[3];
+        let bottom_right = screen.new_buffer.inner[3].clone();
         assert_eq!(Cell::empty(), bottom_right);
     }
 
@@ -221,7 +523,7 @@ mod test {
         let mut render_output = vec![];
         let mut screen = make_screen(Size::new(1, 1));
         screen.clear_all(&mut render_output).unwrap();
-        let actual = screen.new_buffer.inner[0];
+        let actual = screen.new_buffer.inner[0].clone();
         assert_eq!(Cell::empty(), actual);
     }
 
@@ -0,0 +1,160 @@
+// -----------------------------------------------------------------------------
+//   - Event coalescing -
+//   Terminals can report mouse events faster than the runtime renders frames.
+//   The events of a frame are coalesced before they are handled:
+//   * consecutive mouse moves are replaced by the last move
+//   * consecutive scroll events are replaced by their sum, so scrolling
+//     up and down again within a frame cancels out
+//
+//   Any other event ends the run, so the order of events is preserved.
+// -----------------------------------------------------------------------------
+use anathema_widget_core::{Event, KeyModifiers};
+
+#[derive(Debug, Default)]
+struct Scroll {
+    // The position and modifiers of the last scroll event
+    last: Option<(u16, u16, KeyModifiers)>,
+    // Positive is down / right
+    vertical: i32,
+    horizontal: i32,
+}
+
+impl Scroll {
+    fn add(&mut self, event: &Event) {
+        let (x, y, modifiers, vertical, horizontal) = match *event {
+            Event::MouseScrollDown(x, y, m) => (x, y, m, 1, 0),
+            Event::MouseScrollUp(x, y, m) => (x, y, m, -1, 0),
+            Event::MouseScrollRight(x, y, m) => (x, y, m, 0, 1),
+            Event::MouseScrollLeft(x, y, m) => (x, y, m, 0, -1),
+            _ => return,
+        };
+
+        self.last = Some((x, y, modifiers));
+        self.vertical += vertical;
+        self.horizontal += horizontal;
+    }
+
+    fn drain_into(&mut self, events: &mut Vec<Event>) {
+        let Some((x, y, modifiers)) = self.last else {
+            return;
+        };
+
+        let vertical = match self.vertical > 0 {
+            true => Event::MouseScrollDown(x, y, modifiers),
+            false => Event::MouseScrollUp(x, y, modifiers),
+        };
+        let horizontal = match self.horizontal > 0 {
+            true => Event::MouseScrollRight(x, y, modifiers),
+            false => Event::MouseScrollLeft(x, y, modifiers),
+        };
+
+        let vertical = std::iter::repeat_n(vertical, self.vertical.unsigned_abs() as usize);
+        let horizontal = std::iter::repeat_n(horizontal, self.horizontal.unsigned_abs() as usize);
+        events.extend(vertical.chain(horizontal));
+
+        *self = Self::default();
+    }
+}
+
+fn is_scroll(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::MouseScrollDown(..)
+            | Event::MouseScrollUp(..)
+            | Event::MouseScrollLeft(..)
+            | Event::MouseScrollRight(..)
+    )
+}
+
+/// Coalesce the mouse moves and scroll events of a frame.
+pub(crate) fn coalesce(events: Vec<Event>) -> Vec<Event> {
+    let mut output = Vec::with_capacity(events.len());
+    let mut scroll = Scroll::default();
+
+    for event in events {
+        if is_scroll(&event) {
+            scroll.add(&event);
+            continue;
+        }
+
+        scroll.drain_into(&mut output);
+
+        if let (Event::MouseMove(..), Some(Event::MouseMove(..))) = (&event, output.last()) {
+            output.pop();
+        }
+
+        output.push(event);
+    }
+
+    scroll.drain_into(&mut output);
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::{KeyCode, KeyEventState};
+
+    use super::*;
+
+    const NONE: KeyModifiers = KeyModifiers::NONE;
+
+    fn key(c: char) -> Event {
+        Event::KeyPress(KeyCode::Char(c), NONE, KeyEventState::NONE)
+    }
+
+    #[test]
+    fn keep_last_move() {
+        let events = vec![
+            Event::MouseMove(0, 0, NONE),
+            Event::MouseMove(1, 0, NONE),
+            Event::MouseMove(2, 0, NONE),
+            key('a'),
+            Event::MouseMove(3, 0, NONE),
+            Event::MouseMove(4, 1, NONE),
+        ];
+
+        let expected = vec![
+            Event::MouseMove(2, 0, NONE),
+            key('a'),
+            Event::MouseMove(4, 1, NONE),
+        ];
+
+        assert_eq!(coalesce(events), expected);
+    }
+
+    #[test]
+    fn sum_scrolls() {
+        let events = vec![
+            Event::MouseScrollDown(0, 0, NONE),
+            Event::MouseScrollDown(0, 1, NONE),
+            Event::MouseScrollUp(0, 2, NONE),
+            Event::MouseScrollDown(0, 3, NONE),
+            Event::MouseScrollLeft(0, 3, NONE),
+            key('a'),
+            Event::MouseScrollUp(1, 1, NONE),
+            Event::MouseScrollDown(1, 1, NONE),
+        ];
+
+        let expected = vec![
+            Event::MouseScrollDown(0, 3, NONE),
+            Event::MouseScrollDown(0, 3, NONE),
+            Event::MouseScrollLeft(0, 3, NONE),
+            key('a'),
+        ];
+
+        assert_eq!(coalesce(events), expected);
+    }
+
+    #[test]
+    fn keep_other_events() {
+        let events = vec![
+            Event::MouseDown(0, 0, anathema_widget_core::MouseButton::Left, NONE, 1),
+            Event::MouseMove(1, 0, NONE),
+            key('a'),
+            key('a'),
+        ];
+
+        assert_eq!(coalesce(events.clone()), events);
+    }
+}
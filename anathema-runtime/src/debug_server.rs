@@ -0,0 +1,573 @@
+// -----------------------------------------------------------------------------
+//   - Debug server -
+//   Inspect a running application over TCP or a unix socket.
+//
+//   Every command is a single line, and every reply is a single line of JSON:
+//   tree                  the widget tree
+//   state [path]          a value from the state, e.g `state _size.width`,
+//                         or all the runtime values if there is no path
+//   set <path> <value>    pass the value to the `on_debug_set` callback
+//   relayout              layout and paint the next frame
+//   frame                 the next frame as plain text
+//   history               the recorded state changes, see
+//                         `anathema_values::history::record_changes`
+//   travel on|off         start or stop travelling through the recorded
+//                         changes, the views receive no events or ticks
+//                         while travelling
+//   back [n]              undo the last n (default 1) changes
+//   forward [n]           redo the next n (default 1) changes
+//
+//   Changes are undone and redone with the `on_debug_set` callback,
+//   in a transaction so every step is a single frame.
+//
+//   Errors are replied as `{"error":"..."}`.
+//
+//   e.g:
+//   $ echo "state _size.width" | nc localhost 9999
+//   {"_size.width":"80"}
+// -----------------------------------------------------------------------------
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+
+use anathema_values::history::{self, ChangeRecord};
+use anathema_values::transaction::transaction;
+use anathema_values::{NodeId, Path, State, ValueRef};
+use anathema_widget_core::nodes::debug::{self, write_str};
+use anathema_widget_core::views::Views;
+
+use crate::Runtime;
+
+pub(super) type DebugSetCallback = Box<dyn FnMut(&str, &str) -> Result<(), String>>;
+
+// Values provided by the runtime, listed by `state` without a path
+const RUNTIME_VALUES: &[&str] = &[
+    "_size.width",
+    "_size.height",
+    "_focus",
+    "_count",
+    "_timings.layout",
+    "_timings.position",
+    "_timings.paint",
+    "_timings.render",
+    "_timings.total",
+    "_memory.nodes",
+    "_memory.subscriptions",
+    "_memory.buffers",
+    "_memory.total",
+];
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Command {
+    Tree,
+    State(Option<String>),
+    Set(String, String),
+    Relayout,
+    Frame,
+    History,
+    Travel(bool),
+    Step(isize),
+}
+
+impl Command {
+    fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (command, args) = match line.split_once(' ') {
+            Some((command, args)) => (command, args.trim()),
+            None => (line, ""),
+        };
+
+        match (command, args) {
+            ("tree", "") => Ok(Self::Tree),
+            ("state", "") => Ok(Self::State(None)),
+            ("state", path) => Ok(Self::State(Some(path.into()))),
+            ("set", args) => match args.split_once(' ') {
+                Some((path, value)) => Ok(Self::Set(path.into(), value.trim().into())),
+                None => Err("usage: set <path> <value>".into()),
+            },
+            ("relayout", "") => Ok(Self::Relayout),
+            ("frame", "") => Ok(Self::Frame),
+            ("history", "") => Ok(Self::History),
+            ("travel", "on") => Ok(Self::Travel(true)),
+            ("travel", "off") => Ok(Self::Travel(false)),
+            ("back" | "forward", steps) => {
+                let steps = match steps {
+                    "" => 1,
+                    steps => steps
+                        .parse::<isize>()
+                        .map_err(|_| format!("usage: {command} [steps]"))?,
+                };
+                match command {
+                    "back" => Ok(Self::Step(-steps)),
+                    _ => Ok(Self::Step(steps)),
+                }
+            }
+            _ => Err(format!("unknown command: {line}")),
+        }
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.set_nonblocking(nonblocking),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Self::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+struct Client {
+    id: u64,
+    stream: Stream,
+    buffer: Vec<u8>,
+    closed: bool,
+}
+
+impl Client {
+    fn read(&mut self) {
+        let mut buf = [0; 1024];
+        loop {
+            match self.stream.read(&mut buf) {
+                Ok(0) => break self.closed = true,
+                Ok(n) => self.buffer.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => break self.closed = true,
+            }
+        }
+    }
+
+    fn next_line(&mut self) -> Option<String> {
+        let end = self.buffer.iter().position(|b| *b == b'\n')?;
+        let line = self.buffer.drain(..=end).collect::<Vec<_>>();
+        Some(String::from_utf8_lossy(&line).into_owned())
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        // Replies can be larger than the socket buffer
+        self.stream.set_nonblocking(false)?;
+        self.stream.write_all(line.as_bytes())?;
+        self.stream.write_all(b"\n")?;
+        self.stream.flush()?;
+        self.stream.set_nonblocking(true)
+    }
+}
+
+pub(super) struct DebugServer {
+    listener: Listener,
+    clients: Vec<Client>,
+    next_id: u64,
+    // Clients waiting for the next frame
+    frame_requests: Vec<u64>,
+}
+
+impl DebugServer {
+    pub(super) fn tcp(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self::new(Listener::Tcp(listener)))
+    }
+
+    #[cfg(unix)]
+    pub(super) fn unix(path: impl AsRef<std::path::Path>) -> io::Result<Self> {
+        let listener = UnixListener::bind(path)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self::new(Listener::Unix(listener)))
+    }
+
+    fn new(listener: Listener) -> Self {
+        Self {
+            listener,
+            clients: vec![],
+            next_id: 0,
+            frame_requests: vec![],
+        }
+    }
+
+    fn accept(&mut self) {
+        loop {
+            let stream = match &self.listener {
+                Listener::Tcp(listener) => listener.accept().map(|(s, _)| Stream::Tcp(s)),
+                #[cfg(unix)]
+                Listener::Unix(listener) => listener.accept().map(|(s, _)| Stream::Unix(s)),
+            };
+
+            let Ok(stream) = stream else { break };
+            if stream.set_nonblocking(true).is_err() {
+                continue;
+            }
+
+            self.clients.push(Client {
+                id: self.next_id,
+                stream,
+                buffer: vec![],
+                closed: false,
+            });
+            self.next_id += 1;
+        }
+    }
+
+    /// Accept new connections and read the commands
+    /// received since the last poll.
+    pub(super) fn poll(&mut self) -> Vec<(u64, Result<Command, String>)> {
+        self.clients.retain(|client| !client.closed);
+        self.accept();
+
+        let mut commands = vec![];
+        for client in &mut self.clients {
+            client.read();
+            while let Some(line) = client.next_line() {
+                if !line.trim().is_empty() {
+                    commands.push((client.id, Command::parse(&line)));
+                }
+            }
+        }
+        commands
+    }
+
+    pub(super) fn reply(&mut self, client: u64, reply: &str) {
+        let Some(client) = self.clients.iter_mut().find(|c| c.id == client) else {
+            return;
+        };
+
+        if client.write_line(reply).is_err() {
+            client.closed = true;
+        }
+    }
+
+    pub(super) fn frame_requested(&self) -> bool {
+        !self.frame_requests.is_empty()
+    }
+
+    pub(super) fn send_frame(&mut self, frame: &str) {
+        let mut reply = String::from("{\"frame\":");
+        write_str(&mut reply, frame);
+        reply.push('}');
+
+        for client in std::mem::take(&mut self.frame_requests) {
+            self.reply(client, &reply);
+        }
+    }
+}
+
+fn error(msg: &str) -> String {
+    let mut reply = String::from("{\"error\":");
+    write_str(&mut reply, msg);
+    reply.push('}');
+    reply
+}
+
+const OK: &str = "{\"ok\":true}";
+
+// `a.b.0` is the key `a`, the key `b` and the index `0`
+fn parse_path(path: &str) -> Path {
+    let mut segments = path
+        .split('.')
+        .map(|segment| match segment.parse::<usize>() {
+            Ok(index) => Path::Index(index),
+            Err(_) => Path::Key(segment.into()),
+        });
+
+    let first = segments.next().unwrap_or_else(|| Path::Key(String::new()));
+    segments.fold(first, |path, segment| path.compose(segment))
+}
+
+// `[{"time":1532,"path":"user.name","change":"Update","nodes":[[0,1]],"old":"\"Arthur\"","new":"\"Ford\""}]`
+fn history_to_json(records: &[ChangeRecord]) -> String {
+    fn write_opt(out: &mut String, value: Option<&str>) {
+        match value {
+            Some(value) => write_str(out, value),
+            None => out.push_str("null"),
+        }
+    }
+
+    let mut out = String::from("[");
+    for (i, record) in records.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&format!("{{\"time\":{},\"path\":", record.time.as_micros()));
+        write_opt(
+            &mut out,
+            record.path.as_ref().map(history::dotted).as_deref(),
+        );
+        out.push_str(",\"change\":");
+        write_str(&mut out, &format!("{:?}", record.change));
+        out.push_str(",\"nodes\":");
+        out.push_str(
+            &format!(
+                "{:?}",
+                record
+                    .node_ids
+                    .iter()
+                    .map(NodeId::as_slice)
+                    .collect::<Vec<_>>()
+            )
+            .replace(' ', ""),
+        );
+        out.push_str(",\"old\":");
+        write_opt(&mut out, record.old.as_deref());
+        out.push_str(",\"new\":");
+        write_opt(&mut out, record.new.as_deref());
+        out.push('}');
+    }
+    out.push(']');
+    out
+}
+
+// `{"position":3,"changes":10}`
+fn position_to_json() -> String {
+    let (position, changes) = history::position().unwrap_or_default();
+    format!("{{\"position\":{position},\"changes\":{changes}}}")
+}
+
+fn format_value(value: ValueRef<'_>) -> Option<String> {
+    match value {
+        ValueRef::Str(s) => Some(s.into()),
+        ValueRef::Owned(owned) => Some(owned.to_string()),
+        ValueRef::Map(_) => Some("<map>".into()),
+        ValueRef::List(list) => Some(format!("<list of {}>", list.len())),
+        _ => None,
+    }
+}
+
+impl Runtime<'_> {
+    // Handle the commands from the debug clients.
+    // Called once per frame, before the changes are applied.
+    pub(super) fn debug_commands(&mut self) {
+        let Some(commands) = self.debug_server.as_mut().map(DebugServer::poll) else {
+            return;
+        };
+
+        for (client, command) in commands {
+            let reply = match command {
+                Ok(Command::Tree) => debug::to_json(&self.nodes.to_debug_tree()),
+                Ok(Command::State(path)) => self.debug_state(path.as_deref()),
+                Ok(Command::Set(path, value)) => match self.on_debug_set.as_mut() {
+                    Some(f) => match f(&path, &value) {
+                        Ok(()) => OK.into(),
+                        Err(e) => error(&e),
+                    },
+                    None => error("no `on_debug_set` callback registered"),
+                },
+                Ok(Command::History) => history_to_json(&history::changes()),
+                Ok(Command::Travel(true)) => match history::start_travel() {
+                    true => position_to_json(),
+                    false => error("the state changes are not being recorded"),
+                },
+                Ok(Command::Travel(false)) => {
+                    history::stop_travel();
+                    OK.into()
+                }
+                Ok(Command::Step(steps)) => self.debug_step(steps),
+                Ok(Command::Relayout) => {
+                    self.needs_layout = true;
+                    OK.into()
+                }
+                Ok(Command::Frame) => {
+                    self.needs_layout = true;
+                    if let Some(server) = self.debug_server.as_mut() {
+                        server.frame_requests.push(client);
+                    }
+                    continue;
+                }
+                Err(e) => error(&e),
+            };
+
+            if let Some(server) = self.debug_server.as_mut() {
+                server.reply(client, &reply);
+            }
+        }
+    }
+
+    // Write the values of the recorded changes back to the state
+    fn debug_step(&mut self, steps: isize) -> String {
+        if !history::is_travelling() {
+            return error("not travelling, see `travel on`");
+        }
+
+        let Some(f) = self.on_debug_set.as_mut() else {
+            return error("no `on_debug_set` callback registered");
+        };
+
+        let Some(step) = history::step(steps) else {
+            return error("not travelling, see `travel on`");
+        };
+        let result = transaction(|| {
+            step.replays
+                .iter()
+                .try_for_each(|replay| f(&replay.path, &replay.value))
+        });
+
+        // The position only moves once every value is written
+        match result {
+            Ok(()) => {
+                history::seek(step.position);
+                position_to_json()
+            }
+            Err(e) => error(&e),
+        }
+    }
+
+    fn debug_state(&mut self, path: Option<&str>) -> String {
+        let paths = match path {
+            Some(path) => vec![path],
+            None => RUNTIME_VALUES.to_vec(),
+        };
+
+        let mut reply = String::from("{");
+        for (i, path) in paths.iter().enumerate() {
+            let Some(value) = self.lookup(path) else {
+                return error(&format!("no value at `{path}`"));
+            };
+
+            if i > 0 {
+                reply.push(',');
+            }
+            write_str(&mut reply, path);
+            reply.push(':');
+            write_str(&mut reply, &value);
+        }
+        reply.push('}');
+        reply
+    }
+
+    // Look up a value in the runtime values, then in the state of the views.
+    fn lookup(&mut self, path: &str) -> Option<String> {
+        let path = parse_path(path);
+        // The lookup subscribes to the value.
+        // Changes to a node that doesn't exist are ignored.
+        let node_id = NodeId::new(usize::MAX);
+
+        if let Some(value) = format_value(self.meta.state_get(&path, &node_id)) {
+            return Some(value);
+        }
+
+        let mut views = vec![];
+        Views::for_each(|view_id, _| views.push(view_id.clone()));
+
+        let mut value = None;
+        for view_id in views {
+            self.nodes.with_view(&view_id, |view| {
+                value = value
+                    .take()
+                    .or_else(|| format_value(view.state().state_get(&path, &node_id)));
+            });
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(Command::parse("tree\n").unwrap(), Command::Tree);
+        assert_eq!(Command::parse("state").unwrap(), Command::State(None));
+        assert_eq!(
+            Command::parse("state _size.width").unwrap(),
+            Command::State(Some("_size.width".into()))
+        );
+        assert_eq!(
+            Command::parse("set name hello world").unwrap(),
+            Command::Set("name".into(), "hello world".into())
+        );
+        assert_eq!(Command::parse("history").unwrap(), Command::History);
+        assert_eq!(Command::parse("travel on").unwrap(), Command::Travel(true));
+        assert_eq!(Command::parse("back").unwrap(), Command::Step(-1));
+        assert_eq!(Command::parse("forward 3").unwrap(), Command::Step(3));
+        assert!(Command::parse("travel").is_err());
+        assert!(Command::parse("back many").is_err());
+        assert!(Command::parse("set name").is_err());
+        assert!(Command::parse("tree please").is_err());
+    }
+
+    #[test]
+    fn history_json() {
+        let records = [ChangeRecord {
+            time: std::time::Duration::from_micros(12),
+            path: Some(Path::from("user").compose("name")),
+            change: anathema_values::Change::Update,
+            old: Some("\"a\"".into()),
+            new: None,
+            raw: None,
+            node_ids: vec![NodeId::new(0).child(1)],
+        }];
+        assert_eq!(
+            history_to_json(&records),
+            r#"[{"time":12,"path":"user.name","change":"Update","nodes":[[0,1]],"old":"\"a\"","new":null}]"#
+        );
+    }
+
+    #[test]
+    fn path_segments() {
+        let path = parse_path("a.0.b");
+        let expected = Path::from("a").compose(0).compose("b");
+        assert_eq!(path, expected);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut server = DebugServer::tcp("127.0.0.1:0").unwrap();
+        let Listener::Tcp(listener) = &server.listener else {
+            unreachable!()
+        };
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        client.write_all(b"relayout\nnope\n").unwrap();
+
+        let mut commands = vec![];
+        while commands.len() < 2 {
+            commands.extend(server.poll());
+        }
+        assert_eq!(commands[0].1, Ok(Command::Relayout));
+        assert!(commands[1].1.is_err());
+
+        server.reply(commands[0].0, OK);
+        let mut reply = [0; 12];
+        client.read_exact(&mut reply).unwrap();
+        assert_eq!(&reply, b"{\"ok\":true}\n");
+    }
+}
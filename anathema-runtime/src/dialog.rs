@@ -0,0 +1,366 @@
+// -----------------------------------------------------------------------------
+//   - Dialogs -
+//   Modal confirmation and prompt dialogs, painted as an overlay in the
+//   middle of the screen.
+//
+//   Dialogs can be opened from anywhere on the runtime thread (e.g a view),
+//   and are shown one at a time in the order they were opened.
+//   While a dialog is open it takes all key, mouse and paste events.
+//   The answer is passed to the callback of the dialog.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use anathema_render::{Size, Style};
+use anathema_widget_core::overlay::Overlay;
+use anathema_widget_core::{Event, KeyCode, KeyModifiers, LocalPos, Pos};
+use unicode_width::UnicodeWidthStr;
+
+const MIN_WIDTH: usize = 24;
+const YES: &str = "[ Yes ]";
+const NO: &str = "[ No ]";
+
+thread_local! {
+    static OPENED: RefCell<Vec<Dialog>> = const { RefCell::new(vec![]) };
+}
+
+/// Ask the user a yes or no question.
+/// The callback is called with `true` if the user answered yes.
+///
+/// `y` and `n` answer the question, `Enter` picks the selected answer
+/// and `Esc` answers no.
+///
+/// ```
+/// # use anathema_runtime::{confirm, QuitHandle};
+/// # let quit_handle = QuitHandle::default();
+/// confirm("Quit without saving?", move |yes| {
+///     if yes {
+///         quit_handle.quit();
+///     }
+/// });
+/// ```
+pub fn confirm(message: impl Into<String>, f: impl FnOnce(bool) + 'static) {
+    open(Dialog::confirm(message.into(), Box::new(f)));
+}
+
+/// Ask the user for a line of text.
+/// The callback is called with the text once the user presses `Enter`,
+/// or with `None` if the user cancelled with `Esc`.
+pub fn prompt(message: impl Into<String>, f: impl FnOnce(Option<String>) + 'static) {
+    open(Dialog::prompt(message.into(), Box::new(f)));
+}
+
+fn open(dialog: Dialog) {
+    OPENED.with_borrow_mut(|opened| opened.push(dialog));
+}
+
+enum Kind {
+    Confirm {
+        yes: bool,
+        f: Box<dyn FnOnce(bool)>,
+    },
+    Prompt {
+        input: String,
+        f: Box<dyn FnOnce(Option<String>)>,
+    },
+}
+
+pub(super) struct Dialog {
+    message: String,
+    kind: Kind,
+}
+
+impl Dialog {
+    fn confirm(message: String, f: Box<dyn FnOnce(bool)>) -> Self {
+        Self {
+            message,
+            kind: Kind::Confirm { yes: true, f },
+        }
+    }
+
+    fn prompt(message: String, f: Box<dyn FnOnce(Option<String>)>) -> Self {
+        Self {
+            message,
+            kind: Kind::Prompt {
+                input: String::new(),
+                f,
+            },
+        }
+    }
+
+    // Handle a key press, returns the dialog if it's still open
+    fn key_press(mut self, code: KeyCode, modifiers: KeyModifiers) -> Option<Self> {
+        match &mut self.kind {
+            Kind::Confirm { yes, .. } => match code {
+                KeyCode::Char('y' | 'Y') => return self.confirmed(true),
+                KeyCode::Char('n' | 'N') | KeyCode::Esc => return self.confirmed(false),
+                KeyCode::Enter => {
+                    let yes = *yes;
+                    return self.confirmed(yes);
+                }
+                KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => *yes = !*yes,
+                _ => {}
+            },
+            Kind::Prompt { input, .. } => match code {
+                KeyCode::Enter => {
+                    let input = std::mem::take(input);
+                    return self.submitted(Some(input));
+                }
+                KeyCode::Esc => return self.submitted(None),
+                KeyCode::Backspace => drop(input.pop()),
+                KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => input.push(c),
+                _ => {}
+            },
+        }
+        Some(self)
+    }
+
+    // Close a confirmation dialog with the answer
+    fn confirmed(self, yes: bool) -> Option<Self> {
+        if let Kind::Confirm { f, .. } = self.kind {
+            f(yes);
+        }
+        None
+    }
+
+    // Close a prompt with the text, or `None` if it was cancelled
+    fn submitted(self, text: Option<String>) -> Option<Self> {
+        if let Kind::Prompt { f, .. } = self.kind {
+            f(text);
+        }
+        None
+    }
+
+    fn overlay(&self, screen: Size) -> Overlay {
+        let input_width = match &self.kind {
+            Kind::Confirm { .. } => 0,
+            Kind::Prompt { input, .. } => input.width() + 1,
+        };
+        let width = (self.message.width().max(input_width).max(MIN_WIDTH) + 4).min(screen.width);
+        let height = 5.min(screen.height);
+        let inner = width.saturating_sub(2);
+
+        let pos = Pos::new(
+            (screen.width - width) as i32 / 2,
+            (screen.height - height) as i32 / 2,
+        );
+        let mut overlay = Overlay::new(pos, Size::new(width, height));
+        let style = Style::new();
+
+        for y in 0..height {
+            let (left, fill, right) = match y {
+                0 => ('┌', '─', '┐'),
+                _ if y == height - 1 => ('└', '─', '┘'),
+                _ => ('│', ' ', '│'),
+            };
+            overlay.put(left, style, LocalPos::new(0, y));
+            overlay.print(&fill.to_string().repeat(inner), style, LocalPos::new(1, y));
+            overlay.put(right, style, LocalPos::new(width - 1, y));
+        }
+
+        overlay.print(&self.message, style, LocalPos::new(2, 1));
+
+        let mut selected = Style::new();
+        selected.set_inverse(true);
+
+        match &self.kind {
+            Kind::Confirm { yes, .. } => {
+                let (yes_style, no_style) = match yes {
+                    true => (selected, style),
+                    false => (style, selected),
+                };
+                let x = width.saturating_sub(YES.width() + NO.width() + 4);
+                let end = overlay.print(YES, yes_style, LocalPos::new(x, 3));
+                overlay.print(NO, no_style, LocalPos::new(end.x + 1, 3));
+            }
+            Kind::Prompt { input, .. } => {
+                let end = overlay.print(input, style, LocalPos::new(2, 3));
+                overlay.put(' ', selected, end);
+            }
+        }
+
+        overlay
+    }
+}
+
+/// The open dialogs, the first dialog is shown
+pub(super) struct Dialogs {
+    dialogs: VecDeque<Dialog>,
+}
+
+impl Dialogs {
+    pub(super) fn new() -> Self {
+        Self {
+            dialogs: VecDeque::new(),
+        }
+    }
+
+    /// Take the dialogs opened with [`confirm`] and [`prompt`].
+    /// Returns true if any dialog was opened.
+    pub(super) fn take_opened(&mut self) -> bool {
+        let opened = OPENED.take();
+        let any = !opened.is_empty();
+        self.dialogs.extend(opened);
+        any
+    }
+
+    /// Handle an event, returns true if the event was taken by a dialog
+    pub(super) fn event(&mut self, event: &Event) -> bool {
+        let Some(dialog) = self.dialogs.pop_front() else {
+            return false;
+        };
+
+        let taken = match *event {
+            Event::KeyPress(code, modifiers, _) => {
+                if let Some(dialog) = dialog.key_press(code, modifiers) {
+                    self.dialogs.push_front(dialog);
+                }
+                // The callback can open another dialog
+                self.take_opened();
+                return true;
+            }
+            Event::Paste(ref text) => {
+                let mut dialog = dialog;
+                if let Kind::Prompt { input, .. } = &mut dialog.kind {
+                    input.extend(text.chars().filter(|c| !c.is_control()));
+                }
+                self.dialogs.push_front(dialog);
+                return true;
+            }
+            Event::KeyRelease(..)
+            | Event::KeyRepeat(..)
+            | Event::MouseDown(..)
+            | Event::MouseDrag(..)
+            | Event::MouseUp(..)
+            | Event::MouseMove(..)
+            | Event::MouseScrollDown(..)
+            | Event::MouseScrollUp(..)
+            | Event::MouseScrollLeft(..)
+            | Event::MouseScrollRight(..)
+            | Event::MouseScrollMoved(..) => true,
+            _ => false,
+        };
+
+        self.dialogs.push_front(dialog);
+        taken
+    }
+
+    /// Show the first dialog as an overlay
+    pub(super) fn show(&self, screen: Size) {
+        if let Some(dialog) = self.dialogs.front() {
+            dialog.overlay(screen).show();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anathema_render::{Screen, ScreenPos};
+    use anathema_widget_core::{overlay, KeyEventState};
+
+    use super::*;
+
+    fn press(code: KeyCode) -> Event {
+        Event::KeyPress(code, KeyModifiers::NONE, KeyEventState::empty())
+    }
+
+    fn row(screen: &Screen, y: usize) -> String {
+        (0..screen.size().width)
+            .map(|x| {
+                screen
+                    .get(ScreenPos::new(x as u16, y as u16))
+                    .map_or(' ', |(c, _)| c)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn confirm_and_answer() {
+        let answer = Rc::new(RefCell::new(None));
+        let mut dialogs = Dialogs::new();
+
+        let a = answer.clone();
+        confirm("Quit?", move |yes| *a.borrow_mut() = Some(yes));
+        assert!(dialogs.take_opened());
+
+        // Events that are not input are passed on
+        assert!(!dialogs.event(&Event::Resize(10, 10)));
+        assert!(dialogs.event(&press(KeyCode::Char('x'))));
+        assert!(dialogs.event(&press(KeyCode::Right)));
+        assert!(dialogs.event(&press(KeyCode::Enter)));
+        assert_eq!(*answer.borrow(), Some(false));
+
+        // The dialog is closed
+        assert!(!dialogs.event(&press(KeyCode::Enter)));
+
+        let a = answer.clone();
+        confirm("Quit?", move |yes| *a.borrow_mut() = Some(yes));
+        dialogs.take_opened();
+        dialogs.event(&press(KeyCode::Char('y')));
+        assert_eq!(*answer.borrow(), Some(true));
+    }
+
+    #[test]
+    fn prompt_for_text() {
+        let answer = Rc::new(RefCell::new(None));
+        let mut dialogs = Dialogs::new();
+
+        let a = answer.clone();
+        prompt("Name:", move |name| *a.borrow_mut() = Some(name));
+        dialogs.take_opened();
+
+        for c in "bobx".chars() {
+            dialogs.event(&press(KeyCode::Char(c)));
+        }
+        dialogs.event(&press(KeyCode::Backspace));
+        dialogs.event(&Event::Paste("by\n".into()));
+        dialogs.event(&press(KeyCode::Enter));
+        assert_eq!(*answer.borrow(), Some(Some("bobby".to_string())));
+
+        let a = answer.clone();
+        prompt("Name:", move |name| *a.borrow_mut() = Some(name));
+        dialogs.take_opened();
+        dialogs.event(&press(KeyCode::Char('x')));
+        dialogs.event(&press(KeyCode::Esc));
+        assert_eq!(*answer.borrow(), Some(None));
+    }
+
+    #[test]
+    fn one_dialog_at_a_time() {
+        let answers = Rc::new(RefCell::new(vec![]));
+        let mut dialogs = Dialogs::new();
+
+        let a = answers.clone();
+        confirm("First", move |yes| a.borrow_mut().push(("first", yes)));
+        let a = answers.clone();
+        confirm("Second", move |yes| a.borrow_mut().push(("second", yes)));
+        dialogs.take_opened();
+
+        dialogs.event(&press(KeyCode::Char('n')));
+        dialogs.event(&press(KeyCode::Char('y')));
+        assert_eq!(*answers.borrow(), [("first", false), ("second", true)]);
+    }
+
+    #[test]
+    fn paint_dialog() {
+        let mut dialogs = Dialogs::new();
+        confirm("Quit without saving?", |_| {});
+        dialogs.take_opened();
+
+        let mut screen = Screen::new(Size::new(30, 7));
+        dialogs.show(screen.size());
+        overlay::paint(&mut screen);
+
+        assert_eq!(row(&screen, 1), " ┌──────────────────────────┐ ");
+        assert_eq!(row(&screen, 2), " │ Quit without saving?     │ ");
+        assert_eq!(row(&screen, 4), " │          [ Yes ] [ No ]  │ ");
+        assert_eq!(row(&screen, 5), " └──────────────────────────┘ ");
+
+        let (_, style) = screen.get(ScreenPos::new(12, 4)).unwrap();
+        assert!(style
+            .attributes
+            .contains(anathema_render::Attributes::INVERSE));
+    }
+}
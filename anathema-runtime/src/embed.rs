@@ -0,0 +1,85 @@
+// -----------------------------------------------------------------------------
+//   - Embedding -
+//   Render templates into a buffer without the event loop or any terminal
+//   control, to embed them in another TUI framework or to generate static
+//   output.
+//
+//   Each call lays out, positions and paints the templates once.
+//   Nothing is kept between calls, so events, focus and tooltips
+//   are not available.
+// -----------------------------------------------------------------------------
+use anathema_render::{Buffer, Screen, Size};
+use anathema_values::{Context, State};
+use anathema_widget_core::contexts::PaintCtx;
+use anathema_widget_core::error::Result;
+use anathema_widget_core::expressions::Expression;
+use anathema_widget_core::layout::Constraints;
+use anathema_widget_core::nodes::make_it_so;
+use anathema_widget_core::{overlay, LayoutNodes, Pos};
+use anathema_widgets::register_default_widgets;
+
+/// Render the expressions with the given state into a buffer of the given size.
+/// The cells of the buffer can be copied into the host application,
+/// see [`Buffer::rows`].
+///
+/// ```
+/// # use anathema_render::Size;
+/// # use anathema_values::Map;
+/// let expressions = anathema_vm::compile_template("text 'hello ' name").unwrap();
+/// let state = Map::new([("name", "world".to_string())]);
+/// let buffer = anathema_runtime::render_to_buffer(&expressions, &state, Size::new(20, 1)).unwrap();
+/// assert_eq!(buffer.plain_text(), "hello world");
+/// ```
+pub fn render_to_buffer(
+    expressions: &[Expression],
+    state: &dyn State,
+    size: Size,
+) -> Result<Buffer> {
+    // The widgets are already registered if there is a runtime
+    let _ = register_default_widgets();
+
+    let constraints = Constraints::new(Some(size.width), Some(size.height));
+    let context = Context::root(state);
+    let mut nodes = make_it_so(expressions);
+
+    LayoutNodes::new(&mut nodes, constraints, &context).for_each(|mut node| {
+        node.layout(constraints)?;
+        Ok(())
+    })?;
+
+    for (widget, children) in nodes.iter_mut() {
+        widget.position(children, Pos::ZERO);
+    }
+
+    let mut screen = Screen::new(size);
+    for (widget, children) in nodes.iter_mut() {
+        widget.paint(children, PaintCtx::new(&mut screen, None));
+    }
+    overlay::paint(&mut screen);
+    screen.apply_highlights();
+
+    Ok(screen.buffer().clone())
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::ScreenPos;
+    use anathema_values::Map;
+
+    use super::*;
+
+    #[test]
+    fn render_template() {
+        let expressions =
+            anathema_vm::compile_template("border [foreground: \"red\"]\n    text name\n").unwrap();
+        let state = Map::new([("name", "Ford".to_string())]);
+
+        let buffer = render_to_buffer(&expressions, &state, Size::new(10, 4)).unwrap();
+        assert_eq!(buffer.size(), Size::new(10, 4));
+        assert_eq!(buffer.plain_text(), "┌────┐\n│Ford│\n└────┘");
+
+        let (c, style) = buffer.get(ScreenPos::ZERO).unwrap();
+        assert_eq!(c, '┌');
+        assert_eq!(style.fg, Some(anathema_render::Color::Red));
+    }
+}
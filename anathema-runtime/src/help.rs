@@ -0,0 +1,309 @@
+// -----------------------------------------------------------------------------
+//   - Help overlay -
+//   List the key bindings of the keymap on top of the ui.
+//
+//   Bindings are grouped by the prefix of the action (`file.save` is in the
+//   `file` group), and actions without a prefix are in the `general` group.
+//   Typing while the overlay is open filters the bindings.
+// -----------------------------------------------------------------------------
+use anathema_render::{Screen, Size, Style};
+use anathema_widget_core::contexts::PaintCtx;
+use anathema_widget_core::keymap::{Key, Keymap};
+use anathema_widget_core::{Event, KeyCode, KeyModifiers, LocalPos, Pos};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const GENERAL: &str = "general";
+const TITLE: &str = " Key bindings ";
+const MIN_WIDTH: usize = 30;
+
+#[derive(Debug, PartialEq)]
+struct Group {
+    name: &'static str,
+    // The keys and the name of the action, without the group prefix
+    bindings: Vec<(String, &'static str)>,
+}
+
+// Group the bindings matching the query, keeping the order of the keymap
+fn groups(keymap: &Keymap, query: &str) -> Vec<Group> {
+    let query = query.to_lowercase();
+    let mut groups: Vec<Group> = vec![];
+
+    for (chord, action) in keymap.bindings() {
+        let keys = chord
+            .iter()
+            .map(Key::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let action = action.as_str();
+
+        if !keys.to_lowercase().contains(&query) && !action.to_lowercase().contains(&query) {
+            continue;
+        }
+
+        let (name, action) = action.split_once('.').unwrap_or((GENERAL, action));
+        match groups.iter_mut().find(|group| group.name == name) {
+            Some(group) => group.bindings.push((keys, action)),
+            None => groups.push(Group {
+                name,
+                bindings: vec![(keys, action)],
+            }),
+        }
+    }
+
+    groups
+}
+
+// The lines of text, and if they are group headers
+fn lines(groups: &[Group]) -> Vec<(String, bool)> {
+    let key_width = groups
+        .iter()
+        .flat_map(|group| &group.bindings)
+        .map(|(keys, _)| keys.width())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = vec![];
+    for group in groups {
+        if !lines.is_empty() {
+            lines.push((String::new(), false));
+        }
+
+        lines.push((group.name.to_string(), true));
+        for (keys, action) in &group.bindings {
+            let padding = key_width - keys.width();
+            lines.push((format!("  {keys}{:padding$}  {action}", ""), false));
+        }
+    }
+
+    lines
+}
+
+pub(super) struct Help {
+    open: bool,
+    query: String,
+    scroll: usize,
+}
+
+impl Help {
+    pub(super) fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            scroll: 0,
+        }
+    }
+
+    /// Handle an event.
+    /// Returns true if the event was used by the help overlay, in which case
+    /// it shouldn't be passed on to the views.
+    pub(super) fn event(&mut self, event: &Event, toggle: Key) -> bool {
+        if !self.open {
+            let Event::KeyPress(code, modifiers, _) = *event else {
+                return false;
+            };
+
+            self.open = Key::new(code, modifiers) == toggle;
+            self.query.clear();
+            self.scroll = 0;
+            return self.open;
+        }
+
+        match event {
+            Event::KeyPress(code, modifiers, _) => {
+                match code {
+                    _ if Key::new(*code, *modifiers) == toggle => self.open = false,
+                    KeyCode::Esc => self.open = false,
+                    KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+                    KeyCode::Down => self.scroll += 1,
+                    KeyCode::Backspace => {
+                        self.query.pop();
+                    }
+                    KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.query.push(*c);
+                        self.scroll = 0;
+                    }
+                    _ => {}
+                }
+                true
+            }
+            Event::Paste(text) => {
+                self.query.push_str(text);
+                true
+            }
+            Event::KeyRelease(..)
+            | Event::KeyRepeat(..)
+            | Event::MouseDown(..)
+            | Event::MouseDrag(..)
+            | Event::MouseUp(..)
+            | Event::MouseMove(..)
+            | Event::MouseScrollDown(..)
+            | Event::MouseScrollUp(..)
+            | Event::MouseScrollLeft(..)
+            | Event::MouseScrollRight(..)
+            | Event::MouseScrollMoved(..)
+            | Event::Action(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Paint the overlay, centered on the screen, if it's open
+    pub(super) fn paint(&mut self, keymap: &Keymap, screen: &mut Screen) {
+        if !self.open {
+            return;
+        }
+
+        let lines = lines(&groups(keymap, &self.query));
+        let search = format!("/{}", self.query);
+
+        let content_width = lines
+            .iter()
+            .map(|(line, _)| line.width())
+            .chain([search.width(), TITLE.width(), MIN_WIDTH])
+            .max()
+            .unwrap_or(0);
+
+        // The border and a space on each side, and the search line with a blank line below
+        let screen_size = screen.size();
+        let width = (content_width + 4).min(screen_size.width);
+        let height = (lines.len() + 4).min(screen_size.height);
+        let visible = height.saturating_sub(4);
+        self.scroll = self.scroll.min(lines.len().saturating_sub(visible));
+
+        let pos = Pos::new(
+            (screen_size.width - width) as i32 / 2,
+            (screen_size.height - height) as i32 / 2,
+        );
+        let mut ctx = PaintCtx::new(screen, None).into_sized(Size::new(width, height), pos);
+        let style = Style::new();
+        let mut bold = Style::new();
+        bold.set_bold(true);
+
+        // Border and background
+        let inner = width.saturating_sub(2);
+        for y in 0..height {
+            let (left, fill, right) = match y {
+                0 => ('┌', '─', '┐'),
+                _ if y == height - 1 => ('└', '─', '┘'),
+                _ => ('│', ' ', '│'),
+            };
+            ctx.put(left, style, LocalPos::new(0, y));
+            ctx.print(&fill.to_string().repeat(inner), style, LocalPos::new(1, y));
+            ctx.put(right, style, LocalPos::new(width - 1, y));
+        }
+        ctx.print(TITLE, bold, LocalPos::new(2, 0));
+
+        if height > 2 {
+            ctx.print(&search, style, LocalPos::new(2, 1));
+        }
+
+        for (y, (line, header)) in lines.iter().skip(self.scroll).take(visible).enumerate() {
+            let style = if *header { bold } else { style };
+            let line = truncate(line, width.saturating_sub(4));
+            ctx.print(line, style, LocalPos::new(2, y + 3));
+        }
+    }
+}
+
+fn truncate(line: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in line.char_indices() {
+        used += c.width().unwrap_or(0);
+        if used > width {
+            return &line[..i];
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::ScreenPos;
+    use anathema_widget_core::KeyEventState;
+
+    use super::*;
+
+    fn keymap() -> Keymap {
+        Keymap::parse(
+            r#"
+            ctrl+s = "file.save"
+            ctrl+o = "file.open"
+            "ctrl+x ctrl+c" = "quit"
+            g = "nav.top"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn press(code: KeyCode) -> Event {
+        Event::KeyPress(code, KeyModifiers::NONE, KeyEventState::empty())
+    }
+
+    #[test]
+    fn group_bindings() {
+        let groups = groups(&keymap(), "");
+        let names = groups.iter().map(|g| g.name).collect::<Vec<_>>();
+        assert_eq!(names, ["file", "general", "nav"]);
+        assert_eq!(
+            groups[0].bindings,
+            [
+                ("ctrl+s".to_string(), "save"),
+                ("ctrl+o".to_string(), "open")
+            ]
+        );
+        assert_eq!(groups[1].bindings, [("ctrl+x ctrl+c".to_string(), "quit")]);
+    }
+
+    #[test]
+    fn filter_bindings() {
+        let found = groups(&keymap(), "CTRL+O");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].bindings, [("ctrl+o".to_string(), "open")]);
+
+        // Group names are searchable too
+        let found = groups(&keymap(), "nav");
+        assert_eq!(found[0].bindings, [("g".to_string(), "top")]);
+    }
+
+    #[test]
+    fn toggle_and_search() {
+        let toggle = Key::new(KeyCode::F(1), KeyModifiers::NONE);
+        let mut help = Help::new();
+
+        assert!(!help.event(&press(KeyCode::Char('a')), toggle));
+        assert!(help.event(&press(KeyCode::F(1)), toggle));
+
+        assert!(help.event(&press(KeyCode::Char('s')), toggle));
+        assert!(help.event(&press(KeyCode::Char('x')), toggle));
+        assert!(help.event(&press(KeyCode::Backspace), toggle));
+        assert_eq!(help.query, "s");
+
+        assert!(!help.event(&Event::Resize(10, 10), toggle));
+        assert!(help.event(&press(KeyCode::Esc), toggle));
+        assert!(!help.open);
+    }
+
+    #[test]
+    fn paint_overlay() {
+        let mut screen = Screen::new((40usize, 12usize));
+        let mut help = Help::new();
+        help.open = true;
+        help.paint(&keymap(), &mut screen);
+
+        let row = |y| {
+            (0..40)
+                .map(|x| {
+                    screen
+                        .get(ScreenPos::new(x, y))
+                        .map(|(c, _)| c)
+                        .unwrap_or(' ')
+                })
+                .collect::<String>()
+        };
+
+        assert_eq!(row(0), "   ┌─ Key bindings ─────────────────┐   ");
+        assert_eq!(row(1), "   │ /                              │   ");
+        assert_eq!(row(3), "   │ file                           │   ");
+        assert_eq!(row(4), "   │   ctrl+s         save          │   ");
+        assert_eq!(row(11), "   └────────────────────────────────┘   ");
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use anathema_widget_core::error::Result;
+use anathema_widget_core::keymap::{Keymap, KeymapMatch};
+use anathema_widget_core::Event;
+
+// How often to check the keymap file for changes
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A keymap loaded from a file.
+/// The keymap is reloaded when the file changes.
+pub(super) struct KeymapFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+    keymap: Keymap,
+}
+
+impl KeymapFile {
+    pub(super) fn load(path: PathBuf) -> Result<Self> {
+        let modified = std::fs::metadata(&path)?.modified().ok();
+        let keymap = Keymap::load(&path)?;
+
+        Ok(Self {
+            path,
+            modified,
+            last_check: Instant::now(),
+            keymap,
+        })
+    }
+
+    pub(super) fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// Reload the keymap if the file has changed.
+    /// An invalid keymap is ignored, leaving the current bindings in place,
+    /// so a typo while editing the file doesn't remove all the bindings.
+    pub(super) fn reload_if_changed(&mut self) {
+        if self.last_check.elapsed() < RELOAD_INTERVAL {
+            return;
+        }
+        self.last_check = Instant::now();
+
+        let Ok(modified) = std::fs::metadata(&self.path).and_then(|m| m.modified()) else {
+            return;
+        };
+
+        if self.modified == Some(modified) {
+            return;
+        }
+        self.modified = Some(modified);
+
+        if let Ok(keymap) = Keymap::load(&self.path) {
+            self.keymap.replace(keymap);
+        }
+    }
+
+    /// Translate key presses to actions.
+    /// Keys that are part of an incomplete chord are swallowed.
+    pub(super) fn translate(&mut self, event: Event) -> Event {
+        let Event::KeyPress(code, modifiers, _) = event else {
+            return event;
+        };
+
+        match self.keymap.feed(code, modifiers) {
+            KeymapMatch::Action(action) => Event::Action(action),
+            KeymapMatch::Pending => Event::Noop,
+            KeymapMatch::None => event,
+        }
+    }
+}
@@ -1,26 +1,87 @@
-use std::io::{stdout, Stdout};
+use std::io::{stdout, BufWriter, IsTerminal, Stdout, Write};
 use std::time::{Duration, Instant};
 
-use anathema_render::{size, Screen, Size};
-use anathema_values::{drain_dirty_nodes, Context};
+use anathema_render::{size, RenderThread, Screen, Size};
+use anathema_values::{
+    drain_dirty_nodes, drain_removed_nodes, has_dirty_nodes, history, hover, is_removed,
+    subscription_count, subscriptions_memory, Context, Symbol,
+};
 use anathema_widget_core::contexts::PaintCtx;
-use anathema_widget_core::error::Result;
+use anathema_widget_core::error::{Error, Result};
 use anathema_widget_core::expressions::Expression;
+use anathema_widget_core::keymap::{shortcut, Key};
 use anathema_widget_core::layout::Constraints;
-use anathema_widget_core::nodes::{make_it_so, Nodes};
+use anathema_widget_core::nodes::{make_it_so, preserve, Nodes};
 use anathema_widget_core::views::Views;
-use anathema_widget_core::{Event, Events, KeyCode, LayoutNodes, Pos};
+use anathema_widget_core::{
+    drain_actions, drain_changes, drain_inputs, ClickCounter, Event, EventResult, Events, Factory,
+    KeyCode, KeyModifiers, LayoutNodes, Pos, WidgetKind,
+};
+use anathema_widget_core::{overflow, overlay};
 use anathema_widgets::register_default_widgets;
 use crossterm::terminal::enable_raw_mode;
 use tabindex::Direction;
 
+use crate::dialog::Dialogs;
+use crate::help::Help;
+use crate::keymap::KeymapFile;
+use crate::palette::Palette;
+use crate::quit::QuitCallback;
+use crate::recording::{Playback, Recorder};
 use crate::tabindex::TabIndexing;
+use crate::tooltip::TooltipTracker;
 
 #[allow(unused_extern_crates)]
 extern crate anathema_values as anathema;
 
+pub use crate::dialog::{confirm, prompt};
+pub use crate::embed::render_to_buffer;
+pub use crate::quit::{QuitAction, QuitHandle};
+pub use crate::schedule::request_redraw;
+pub use crate::sender::EventSender;
+pub use crate::stats::{FrameStats, MemoryStats};
+
+mod coalesce;
+#[cfg(feature = "debug-server")]
+mod debug_server;
+mod dialog;
+mod embed;
+mod help;
+mod keymap;
 mod meta;
+mod palette;
+mod quit;
+mod recording;
+mod schedule;
+mod sender;
+mod stats;
 mod tabindex;
+mod tooltip;
+
+type FrameCallback = Box<dyn FnMut(&FrameStats)>;
+type PaintCallback = Box<dyn FnMut(&mut Screen)>;
+
+// The smallest size the widgets are laid out in
+const MIN_SIZE: Size = Size::new(1, 1);
+
+// Terminals can report a size of zero while being resized
+fn clamp_size(size: Size) -> Size {
+    Size::new(
+        size.width.max(MIN_SIZE.width),
+        size.height.max(MIN_SIZE.height),
+    )
+}
+
+/// What the runtime does when stdout is not a terminal,
+/// e.g when the output is redirected to a file or piped to another program.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NonTty {
+    /// Layout and paint a single frame and print it as plain text,
+    /// without any escape codes.
+    RenderOnce,
+    /// Return [`Error::NotATerminal`] from [`Runtime::run`].
+    Error,
+}
 
 /// The runtime handles events, tab indices and configuration of the display
 ///
@@ -38,10 +99,57 @@ mod tabindex;
 pub struct Runtime<'e> {
     pub enable_meta: bool,
     pub enable_mouse: bool,
+    pub enable_paste: bool,
+    /// Use the kitty keyboard protocol on terminals that support it,
+    /// reporting key releases and repeats, and modifiers that are otherwise
+    /// indistinguishable (`Ctrl+I` and `Tab`).
+    /// Terminals without support report key events as usual.
+    pub enable_kitty_keyboard: bool,
+    /// Write frames to the terminal on a dedicated thread,
+    /// so the next frame is laid out while the previous frame is written.
+    /// This hides the latency of slow terminals, e.g over ssh.
+    pub enable_render_thread: bool,
+    /// Keep the state of removed widgets (e.g scroll offsets and selections),
+    /// and restore it when a widget with the same `id`, or the same node path,
+    /// is created again.
+    pub enable_state_restoration: bool,
     pub enable_ctrlc: bool,
     pub enable_tabindex: bool,
     pub enable_alt_screen: bool,
+    /// Coalesce the events received during a frame before handling them:
+    /// consecutive mouse moves are replaced by the last move,
+    /// and consecutive scroll events by their sum.
+    /// This keeps the runtime responsive when the terminal floods it with mouse events.
+    pub enable_coalescing: bool,
+    /// Only render a frame when something changed: the state of a view,
+    /// an event that was handled, or a requested redraw.
+    /// Turn this off to render every frame, at the rate set by `fps`.
+    pub enable_render_on_change: bool,
+    /// The maximum number of frames per second.
+    /// The runtime sleeps for the rest of the frame once it's rendered.
     pub fps: u8,
+    /// How often the views are ticked while the runtime is busy.
+    /// `None` ticks the views once per frame.
+    pub tick_rate: Option<Duration>,
+    /// How long the mouse has to rest on a widget before its tooltip is shown
+    pub tooltip_delay: Duration,
+    /// How often the views are ticked when nothing changes.
+    /// Frames are only rendered when something changed (see `enable_render_on_change`),
+    /// and when nothing did the runtime waits for the next event for up to this long.
+    /// `None` waits until there is an event.
+    /// See [`request_redraw`] for animations.
+    pub idle_tick: Option<Duration>,
+    /// Double and triple click detection.
+    /// Change the `interval` and `radius` to configure what counts as consecutive clicks.
+    pub clicks: ClickCounter,
+    /// Toggle the help overlay, listing the bindings of the keymap.
+    /// The help is only available once a keymap is loaded.
+    pub help_key: Option<Key>,
+    /// Toggle the command palette, listing the registered actions
+    /// and the actions of the keymap.
+    pub palette_key: Option<Key>,
+    /// What to do when stdout is not a terminal
+    pub non_tty: NonTty,
     screen: Screen,
     output: Stdout,
     constraints: Constraints,
@@ -50,11 +158,55 @@ pub struct Runtime<'e> {
     needs_layout: bool,
     meta: meta::Meta,
     tabindex: TabIndexing,
+    stats: FrameStats,
+    on_frame: Option<FrameCallback>,
+    on_before_paint: Option<PaintCallback>,
+    on_after_paint: Option<PaintCallback>,
+    render_thread: Option<RenderThread>,
+    should_quit: Option<QuitCallback>,
+    quit: QuitHandle,
+    channel: Option<sender::EventChannel>,
+    keymap: Option<KeymapFile>,
+    recorder: Option<Recorder>,
+    playback: Option<Playback>,
+    frame: u64,
+    tooltips: TooltipTracker,
+    help: Help,
+    dialogs: Dialogs,
+    actions: Vec<(Symbol, String)>,
+    palette: Palette,
+    overflow_log: Option<BufWriter<std::fs::File>>,
+    #[cfg(feature = "profile")]
+    profile: Option<std::path::PathBuf>,
+    #[cfg(feature = "debug-server")]
+    debug_server: Option<debug_server::DebugServer>,
+    #[cfg(feature = "debug-server")]
+    on_debug_set: Option<debug_server::DebugSetCallback>,
 }
 
 impl<'e> Drop for Runtime<'e> {
     fn drop(&mut self) {
-        let _ = self.screen.restore(&mut self.output);
+        // Stop reading the terminal before restoring it
+        self.channel.take();
+
+        // Finish writing the last frame before restoring the terminal
+        if let Some(mut render_thread) = self.render_thread.take() {
+            let _ = render_thread.stop();
+        }
+
+        // Nothing to restore if the output was never a terminal
+        if self.output.is_terminal() {
+            let _ = self.screen.restore(&mut self.output);
+        }
+
+        #[cfg(feature = "profile")]
+        if let Some(path) = self.profile.take() {
+            let spans = anathema_widget_core::profile::drain_spans();
+            if let Ok(file) = std::fs::File::create(path) {
+                let output = std::io::BufWriter::new(file);
+                let _ = anathema_widget_core::profile::write_chrome_trace(&spans, output);
+            }
+        }
     }
 }
 
@@ -64,30 +216,301 @@ impl<'e> Runtime<'e> {
 
         let nodes = make_it_so(expressions);
 
-        let size: Size = size()?.into();
+        let output = stdout();
+        let size: Size = match size() {
+            Ok(size) => clamp_size(size.into()),
+            // Render at a common terminal size when there is no terminal
+            Err(_) if !output.is_terminal() => Size::new(80, 24),
+            Err(err) => return Err(err.into()),
+        };
         let constraints = Constraints::new(Some(size.width), Some(size.height));
         let screen = Screen::new(size);
 
         let inst = Self {
-            output: stdout(),
+            output,
             screen,
             constraints,
             nodes,
             enable_meta: false,
             enable_mouse: false,
+            enable_paste: true,
+            enable_kitty_keyboard: false,
+            enable_render_thread: false,
+            enable_state_restoration: true,
             enable_alt_screen: true,
+            enable_coalescing: true,
+            enable_render_on_change: true,
             events: Events,
             fps: 30,
+            tick_rate: None,
+            tooltip_delay: Duration::from_millis(500),
+            idle_tick: Some(Duration::from_millis(100)),
+            clicks: ClickCounter::default(),
+            help_key: Some(Key::new(KeyCode::F(1), KeyModifiers::NONE)),
+            palette_key: Some(Key::new(KeyCode::Char('p'), KeyModifiers::CONTROL)),
+            non_tty: NonTty::RenderOnce,
             needs_layout: true,
             meta: meta::Meta::new(size.width, size.height),
             tabindex: TabIndexing::new(),
             enable_ctrlc: true,
             enable_tabindex: true,
+            stats: FrameStats::default(),
+            on_frame: None,
+            on_before_paint: None,
+            on_after_paint: None,
+            render_thread: None,
+            should_quit: None,
+            quit: QuitHandle::default(),
+            channel: None,
+            keymap: None,
+            recorder: None,
+            playback: None,
+            frame: 0,
+            tooltips: TooltipTracker::new(),
+            help: Help::new(),
+            dialogs: Dialogs::new(),
+            actions: vec![],
+            palette: Palette::new(),
+            overflow_log: None,
+            #[cfg(feature = "profile")]
+            profile: None,
+            #[cfg(feature = "debug-server")]
+            debug_server: None,
+            #[cfg(feature = "debug-server")]
+            on_debug_set: None,
         };
 
         Ok(inst)
     }
 
+    /// Make the environment variables available to the templates as `_env`,
+    /// e.g `text _env.USER`.
+    pub fn inject_env(&mut self) {
+        for (key, value) in std::env::vars() {
+            self.meta._env.insert(key, value);
+        }
+    }
+
+    /// Make the named command line arguments of the process available
+    /// to the templates as `_args`.
+    ///
+    /// `--theme dark` and `--theme=dark` are available as `_args.theme`,
+    /// and flags without a value (`--verbose`) are set to `"true"`.
+    pub fn inject_cli_args(&mut self) {
+        let args = meta::parse_args(std::env::args().skip(1));
+        self.inject_args(args);
+    }
+
+    /// Make already parsed arguments available to the templates as `_args`.
+    pub fn inject_args(
+        &mut self,
+        args: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) {
+        for (key, value) in args {
+            self.meta._args.insert(key.into(), value.into());
+        }
+    }
+
+    /// All registered widgets with the number of instances of each widget
+    pub fn widget_kinds(&self) -> Vec<WidgetKind> {
+        Factory::kinds(&self.nodes)
+    }
+
+    /// Performance counters for the last rendered frame
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    /// Call the closure with the performance counters at the end of every rendered frame.
+    /// Frames where nothing changed are not rendered, see `enable_render_on_change`.
+    pub fn on_frame(&mut self, f: impl FnMut(&FrameStats) + 'static) {
+        self.on_frame = Some(Box::new(f));
+    }
+
+    /// Call the closure with the screen before the widgets are painted.
+    /// Anything drawn here is painted over by the widgets, e.g a background grid.
+    pub fn on_before_paint(&mut self, f: impl FnMut(&mut Screen) + 'static) {
+        self.on_before_paint = Some(Box::new(f));
+    }
+
+    /// Call the closure with the screen after everything else is painted,
+    /// to draw on top of the widgets, e.g an FPS counter.
+    pub fn on_after_paint(&mut self, f: impl FnMut(&mut Screen) + 'static) {
+        self.on_after_paint = Some(Box::new(f));
+    }
+
+    /// Decide what happens when the runtime receives [`Event::Quit`] (e.g from Ctrl-c),
+    /// for instance to save data or ask the user to confirm.
+    /// Without this the runtime quits right away.
+    pub fn should_quit(&mut self, f: impl FnMut() -> QuitAction + 'static) {
+        self.should_quit = Some(Box::new(f));
+    }
+
+    /// A handle to stop the runtime, e.g once the user confirmed quitting
+    pub fn quit_handle(&self) -> QuitHandle {
+        self.quit.clone()
+    }
+
+    /// Show a dialog asking the user a yes or no question.
+    /// Use [`confirm`](crate::confirm) to open a dialog from a view.
+    pub fn confirm(&mut self, message: &str, f: impl FnOnce(bool) + 'static) {
+        confirm(message, f);
+        self.needs_layout |= self.dialogs.take_opened();
+    }
+
+    /// Show a dialog asking the user for a line of text.
+    /// Use [`prompt`](crate::prompt) to open a dialog from a view.
+    pub fn prompt(&mut self, message: &str, f: impl FnOnce(Option<String>) + 'static) {
+        prompt(message, f);
+        self.needs_layout |= self.dialogs.take_opened();
+    }
+
+    /// Load a keymap file, mapping keys to [`Event::Action`]s.
+    /// The keymap is reloaded when the file changes.
+    pub fn keymap(&mut self, path: impl Into<std::path::PathBuf>) -> Result<()> {
+        self.keymap = Some(KeymapFile::load(path.into())?);
+        Ok(())
+    }
+
+    /// Register an action with a description, listed in the command palette.
+    /// Running the action from the palette sends an [`Event::Action`] to the views.
+    pub fn register_action(&mut self, action: &str, description: &str) {
+        let action = Symbol::new(action);
+        match self.actions.iter_mut().find(|(a, _)| *a == action) {
+            Some((_, existing)) => *existing = description.to_string(),
+            None => self.actions.push((action, description.to_string())),
+        }
+    }
+
+    fn palette_entries(&self) -> Vec<palette::Entry> {
+        let keymap = self.keymap.as_ref().map(KeymapFile::keymap);
+        palette::entries(&self.actions, keymap)
+    }
+
+    /// Record all incoming events to a file.
+    /// The recording can be played back with [`Runtime::play_back_events`].
+    pub fn record_events(&mut self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        self.recorder = Some(Recorder::create(path)?);
+        Ok(())
+    }
+
+    /// Play back recorded events.
+    /// The events are delivered in the same frames they were recorded in.
+    /// Events from the terminal are still handled.
+    pub fn play_back_events(&mut self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        self.playback = Some(Playback::load(path)?);
+        Ok(())
+    }
+
+    /// Send events to the views from other threads, see [`EventSender`].
+    /// With a sender the terminal is read on a separate thread.
+    pub fn event_sender(&mut self) -> EventSender {
+        self.channel
+            .get_or_insert_with(sender::EventChannel::new)
+            .sender()
+    }
+
+    fn poll_event(&mut self) -> Option<Event> {
+        self.next_event(Some(Duration::from_millis(1)))
+    }
+
+    // Wait for the next event until the timeout passed,
+    // or until there is an event if there is no timeout
+    fn next_event(&mut self, timeout: Option<Duration>) -> Option<Event> {
+        if let Some(event) = self.playback.as_mut().and_then(|p| p.next(self.frame)) {
+            return Some(event);
+        }
+
+        let (event, read_at) = match (&self.channel, timeout) {
+            // Terminal events are forwarded into the channel by the input thread
+            (Some(channel), _) => match channel.recv(timeout)? {
+                sender::Incoming::Terminal(event, read_at) => (event, read_at),
+                sender::Incoming::Sent(event) => return Some(event),
+            },
+            (None, Some(timeout)) => (self.events.poll(timeout)?, Instant::now()),
+            (None, None) => (self.events.read()?, Instant::now()),
+        };
+
+        // Played back events already have their click count
+        Some(self.clicks.count(event, read_at))
+    }
+
+    // How long to wait for an event when nothing changed
+    fn idle_timeout(&self) -> Option<Duration> {
+        // Keep answering the debug server
+        #[cfg(feature = "debug-server")]
+        if self.debug_server.is_some() {
+            let timeout = Duration::from_millis(50);
+            return Some(self.idle_tick.map_or(timeout, |tick| tick.min(timeout)));
+        }
+
+        self.idle_tick
+    }
+
+    /// Log widgets that size themselves outside of their constraints,
+    /// or paint outside of their size, to a file.
+    /// If `highlight` is true the widgets are painted with a red background.
+    pub fn debug_overflow(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        highlight: bool,
+    ) -> Result<()> {
+        let file = std::fs::File::create(path)?;
+        self.overflow_log = Some(BufWriter::new(file));
+        overflow::enable(highlight);
+        Ok(())
+    }
+
+    fn log_overflow(&mut self) -> Result<()> {
+        let reports = overflow::drain_reports();
+        let Some(log) = self.overflow_log.as_mut() else {
+            return Ok(());
+        };
+
+        for report in &reports {
+            writeln!(log, "frame {}: {report}", self.frame)?;
+        }
+
+        if !reports.is_empty() {
+            log.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a chrome://tracing compatible profile of every frame
+    /// to the given path when the runtime is dropped.
+    #[cfg(feature = "profile")]
+    pub fn profile(&mut self, path: impl Into<std::path::PathBuf>) {
+        self.profile = Some(path.into());
+    }
+
+    /// Listen for debug commands on a TCP address, e.g `127.0.0.1:9999`.
+    /// See the `debug_server` module for the commands.
+    #[cfg(feature = "debug-server")]
+    pub fn debug_server(&mut self, addr: impl std::net::ToSocketAddrs) -> Result<()> {
+        self.debug_server = Some(debug_server::DebugServer::tcp(addr)?);
+        Ok(())
+    }
+
+    /// Listen for debug commands on a unix socket.
+    #[cfg(all(feature = "debug-server", unix))]
+    pub fn debug_server_unix(&mut self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        self.debug_server = Some(debug_server::DebugServer::unix(path)?);
+        Ok(())
+    }
+
+    /// Call the closure with the path and the value of a `set <path> <value>` debug command.
+    /// The runtime has no way to change the state of a view,
+    /// so it's up to the closure to apply the value.
+    #[cfg(feature = "debug-server")]
+    pub fn on_debug_set(
+        &mut self,
+        f: impl FnMut(&str, &str) -> std::result::Result<(), String> + 'static,
+    ) {
+        self.on_debug_set = Some(Box::new(f));
+    }
+
     fn layout(&mut self) -> Result<()> {
         self.nodes.reset_cache();
         let context = Context::root(&self.meta);
@@ -109,14 +532,36 @@ impl<'e> Runtime<'e> {
     }
 
     fn paint(&mut self) {
+        if let Some(on_before_paint) = self.on_before_paint.as_mut() {
+            on_before_paint(&mut self.screen);
+        }
+
         for (widget, children) in self.nodes.iter_mut() {
             widget.paint(children, PaintCtx::new(&mut self.screen, None));
         }
+        self.dialogs.show(self.screen.size());
+        overlay::paint(&mut self.screen);
+
+        if let Some(tooltip) = self.tooltips.find(&mut self.nodes, self.tooltip_delay) {
+            tooltip.paint(&mut self.screen);
+        }
+
+        if let Some(keymap) = self.keymap.as_ref() {
+            self.help.paint(keymap.keymap(), &mut self.screen);
+        }
+
+        let entries = self.palette_entries();
+        self.palette.paint(&entries, &mut self.screen);
+
+        if let Some(on_after_paint) = self.on_after_paint.as_mut() {
+            on_after_paint(&mut self.screen);
+        }
     }
 
     fn changes(&mut self) {
         let dirty_nodes = drain_dirty_nodes();
         if dirty_nodes.is_empty() {
+            drain_removed_nodes();
             return;
         }
 
@@ -126,8 +571,15 @@ impl<'e> Runtime<'e> {
         let context = Context::root(state);
 
         for (node_id, change) in dirty_nodes {
+            // The node could have been removed by a previous change
+            if is_removed(&node_id) {
+                continue;
+            }
             self.nodes.update(node_id.as_slice(), &change, &context);
         }
+
+        // Node ids can be reused by new nodes once the changes are applied
+        drain_removed_nodes();
     }
 
     fn tick_views(&mut self) {
@@ -137,6 +589,42 @@ impl<'e> Runtime<'e> {
     }
 
     fn global_event(&mut self, event: Event) -> Event {
+        if self.dialogs.event(&event) {
+            self.needs_layout = true;
+            return Event::Noop;
+        }
+
+        if let Some(palette_key) = self.palette_key {
+            let entries = self.palette_entries();
+            if !entries.is_empty() {
+                if let Some(event) = self.palette.event(&event, palette_key, &entries) {
+                    self.needs_layout = true;
+                    return event;
+                }
+            }
+        }
+
+        if let Some(help_key) = self.help_key.filter(|_| self.keymap.is_some()) {
+            if self.help.event(&event, help_key) {
+                self.needs_layout = true;
+                return Event::Noop;
+            }
+        }
+
+        let event = match self.keymap.as_mut() {
+            Some(keymap) => keymap.translate(event),
+            None => event,
+        };
+
+        // Shortcuts declared by the widgets
+        let event = match event {
+            Event::KeyPress(code, modifiers, _) => match shortcut(Key::new(code, modifiers)) {
+                Some(action) => Event::Action(action),
+                None => event,
+            },
+            event => event,
+        };
+
         // -----------------------------------------------------------------------------
         //   - Ctrl-c to quite -
         //   This should be on by default.
@@ -163,16 +651,158 @@ impl<'e> Runtime<'e> {
                     self.nodes.with_view(&old, |view| view.blur());
                 }
 
+                Views::set_focused(self.tabindex.current_node().cloned());
                 if let Some(next) = self.tabindex.current_node() {
                     self.nodes.with_view(next, |view| view.focus());
+                    self.tooltips.focus(next.clone());
+                    self.needs_layout = true;
                 }
+
+                return event;
             }
         }
 
+        if self.tooltips.event(&event) {
+            self.needs_layout = true;
+        }
+
         event
     }
 
+    // Pass the event to the focused view, or the root view
+    fn send_event(&mut self, event: Event) {
+        // A widget consuming an event has changed its own state
+        // (e.g a window was moved), which is not tracked by the state.
+        let mut result = EventResult::Ignored;
+        if self.enable_tabindex {
+            if let Some(view_id) = self.tabindex.current_node() {
+                self.nodes
+                    .with_view(view_id, |view| result = view.on_event(event));
+            }
+        } else {
+            // TODO: this is a bit sketchy
+            let root = 0.into(); // TODO: this should be a `const`
+            self.nodes
+                .with_view(&root, |view| result = view.on_event(event));
+        }
+
+        if result.is_consumed() {
+            self.needs_layout = true;
+        }
+    }
+
+    // Layout, position, paint and render a frame,
+    // `changes` is the time it took to apply the changes before the frame
+    fn render_frame(&mut self, changes: Duration) -> Result<()> {
+        #[cfg(feature = "profile")]
+        let _span = anathema_widget_core::profile::span("frame", "runtime");
+
+        self.stats.clear();
+        self.stats.changes = changes;
+        let frame_start = Instant::now();
+
+        self.layout()?;
+        self.stats.layout = frame_start.elapsed();
+        self.stats.widgets = self.nodes.count();
+
+        let now = Instant::now();
+        self.position();
+        self.stats.position = now.elapsed();
+
+        let now = Instant::now();
+        self.paint();
+        self.stats.paint = now.elapsed();
+        self.log_overflow()?;
+
+        #[cfg(feature = "debug-server")]
+        if let Some(server) = self.debug_server.as_mut().filter(|s| s.frame_requested()) {
+            server.send_frame(&self.screen.buffer().plain_text());
+        }
+
+        // With a render thread this is only the time it takes to hand over the frame,
+        // and the cells written can be from the previous frame
+        let now = Instant::now();
+        self.stats.cells_written = match self.render_thread.as_mut() {
+            Some(render_thread) => render_thread.present(&mut self.screen)?,
+            None => self.screen.render(&mut self.output)?,
+        };
+        self.stats.render = now.elapsed();
+        self.stats.total = self.stats.changes + frame_start.elapsed();
+
+        self.stats.memory = MemoryStats {
+            nodes: self.nodes.memory_usage(),
+            subscriptions: subscriptions_memory(),
+            subscription_count: subscription_count(),
+            buffers: self.screen.memory_usage(),
+        };
+
+        self.screen.erase();
+
+        *self.meta._timings.layout = format!("{:?}", self.stats.layout);
+        *self.meta._timings.position = format!("{:?}", self.stats.position);
+        *self.meta._timings.paint = format!("{:?}", self.stats.paint);
+        *self.meta._timings.render = format!("{:?}", self.stats.render);
+        *self.meta._timings.total = format!("{:?}", self.stats.total);
+        *self.meta._memory.nodes = self.stats.memory.nodes;
+        *self.meta._memory.subscriptions = self.stats.memory.subscription_count;
+        *self.meta._memory.buffers = self.stats.memory.buffers;
+        *self.meta._memory.total = self.stats.memory.total();
+
+        if let Some(on_frame) = self.on_frame.as_mut() {
+            on_frame(&self.stats);
+        }
+
+        self.needs_layout = false;
+        Ok(())
+    }
+
+    // Apply the new size and render a frame straight away,
+    // so the screen is never left blank until the next frame
+    fn resize(&mut self, width: u16, height: u16) -> Result<()> {
+        let size = clamp_size(Size::from((width, height)));
+
+        if size == self.screen.size() {
+            return Ok(());
+        }
+
+        self.screen.resize(size);
+        // The render thread clears the terminal when the size changes
+        if self.render_thread.is_none() {
+            self.screen.clear_all(&mut self.output)?;
+        }
+
+        self.constraints.max_width = size.width;
+        self.constraints.max_height = size.height;
+
+        *self.meta._size.width = size.width;
+        *self.meta._size.height = size.height;
+
+        let now = Instant::now();
+        self.changes();
+        self.needs_layout = true;
+        self.render_frame(now.elapsed())
+    }
+
+    // Print a single frame as plain text
+    fn render_once(&mut self) -> Result<()> {
+        self.layout()?;
+        self.position();
+        self.paint();
+        self.log_overflow()?;
+
+        writeln!(self.output, "{}", self.screen.buffer().plain_text())?;
+        self.output.flush()?;
+        Ok(())
+    }
+
     pub fn run(mut self) -> Result<()> {
+        if !self.output.is_terminal() {
+            return match self.non_tty {
+                NonTty::RenderOnce => self.render_once(),
+                NonTty::Error => Err(Error::NotATerminal),
+            };
+        }
+
         if self.enable_alt_screen {
             self.screen.enter_alt_screen(&mut self.output)?;
         }
@@ -180,14 +810,24 @@ impl<'e> Runtime<'e> {
         enable_raw_mode()?;
         Screen::hide_cursor(&mut self.output)?;
 
+        preserve::retain_removed_states(self.enable_state_restoration);
         self.layout()?;
 
         if self.enable_mouse {
             Screen::enable_mouse(&mut self.output)?;
         }
 
+        if self.enable_paste {
+            Screen::enable_paste(&mut self.output)?;
+        }
+
+        if self.enable_kitty_keyboard {
+            self.screen.enable_keyboard_enhancement(&mut self.output)?;
+        }
+
         if self.enable_tabindex {
             self.tabindex.next(Direction::Forwards);
+            Views::set_focused(self.tabindex.current_node().cloned());
             if let Some(next) = self.tabindex.current_node() {
                 self.nodes.with_view(next, |view| view.focus());
             }
@@ -195,84 +835,155 @@ impl<'e> Runtime<'e> {
 
         self.screen.clear_all(&mut self.output)?;
 
+        if self.enable_render_thread {
+            self.render_thread = Some(RenderThread::spawn(stdout()));
+        }
+
+        // Block on the channel rather than the terminal, so sent events wake the runtime
+        if let Some(channel) = self.channel.as_mut() {
+            channel.forward_terminal();
+        }
+
         let mut fps_now = Instant::now();
-        let sleep_micros = ((1.0 / self.fps as f64) * 1000.0 * 1000.0) as u128;
+        let sleep_micros = ((1.0 / self.fps.max(1) as f64) * 1000.0 * 1000.0) as u128;
+        let mut last_tick = Instant::now();
+
+        let mut idle = false;
 
         'run: loop {
-            while let Some(event) = self.events.poll(Duration::from_millis(1)) {
-                let event = self.global_event(event);
+            if self.quit.is_quitting() {
+                break Ok(());
+            }
+
+            // Nothing changed during the last frame,
+            // so wait for an event rather than rendering the same frame again
+            let mut next_event = None;
+            if idle {
+                next_event = self.next_event(self.idle_timeout());
+                fps_now = Instant::now();
+            }
+            let mut events = vec![];
 
+            loop {
                 // Make sure event handling isn't holding up the rest of the event loop.
-                if fps_now.elapsed().as_micros() > sleep_micros {
+                // Recorded events are always handled in the frame they were recorded in.
+                if self.playback.is_none() && fps_now.elapsed().as_micros() > sleep_micros {
+                    break;
+                }
+
+                let Some(event) = next_event.take().or_else(|| self.poll_event()) else {
                     break;
+                };
+
+                if let Some(recorder) = self.recorder.as_mut() {
+                    recorder.record(self.frame, &event)?;
                 }
 
+                events.push(event);
+            }
+
+            let had_events = !events.is_empty();
+            if self.enable_coalescing {
+                events = coalesce::coalesce(events);
+            }
+
+            for event in events {
+                let event = self.global_event(event);
+
                 match event {
-                    Event::Resize(width, height) => {
-                        let size = Size::from((width, height));
-                        self.screen.erase();
-                        self.screen.render(&mut self.output)?;
-                        self.screen.resize(size);
-                        self.screen.clear_all(&mut self.output)?;
-
-                        self.constraints.max_width = size.width;
-                        self.constraints.max_height = size.height;
-
-                        *self.meta._size.width = size.width;
-                        *self.meta._size.height = size.height;
+                    Event::Resize(width, height) => self.resize(width, height)?,
+                    Event::MouseMove(x, y, _) => {
+                        let pos = Pos::new(x as i32, y as i32);
+                        hover::set_hovered(self.nodes.widget_at(pos));
+                    }
+                    Event::Blur => {
+                        *self.meta._focus = false;
+                        hover::set_hovered(None);
                     }
-                    Event::Blur => *self.meta._focus = false,
                     Event::Focus => *self.meta._focus = true,
-                    Event::Quit => break 'run Ok(()),
+                    Event::Quit => {
+                        match self.should_quit.as_mut().map_or(QuitAction::Quit, |f| f()) {
+                            QuitAction::Quit => break 'run Ok(()),
+                            QuitAction::Continue => continue,
+                            QuitAction::Prompt => {}
+                        }
+                    }
                     _ => {}
                 }
 
-                if self.enable_tabindex {
-                    if let Some(view_id) = self.tabindex.current_node() {
-                        self.nodes.with_view(view_id, |view| view.on_event(event));
-                    }
-                } else {
-                    // TODO: this is a bit sketchy
-                    let root = 0.into(); // TODO: this should be a `const`
-                    self.nodes.with_view(&root, |view| view.on_event(event));
+                // The views are frozen while travelling through the state history
+                if history::is_travelling() {
+                    continue;
+                }
+
+                self.send_event(event);
+
+                // Actions sent by the widgets (e.g from a menu)
+                for action in drain_actions() {
+                    self.send_event(Event::Action(action));
+                }
+                for (action, value) in drain_changes() {
+                    self.send_event(Event::Change(action, value));
                 }
+                for (action, text) in drain_inputs() {
+                    self.send_event(Event::Input(action, text));
+                }
+
+                // Dialogs opened by the views
+                self.needs_layout |= self.dialogs.take_opened();
             }
 
+            #[cfg(feature = "debug-server")]
+            self.debug_commands();
+
+            let now = Instant::now();
             self.changes();
+            let changes = now.elapsed();
 
             *self.meta._count = self.nodes.count();
 
-            // TODO: the meta info should only be updated if `self.enable_meta`
-            if self.needs_layout {
-                let meta_total = Instant::now();
-
-                self.layout()?;
-                *self.meta._timings.layout = format!("{:?}", meta_total.elapsed());
-
-                let now = Instant::now();
-                self.position();
-                *self.meta._timings.position = format!("{:?}", now.elapsed());
+            if self.tooltips.is_due(self.tooltip_delay) {
+                self.needs_layout = true;
+            }
 
-                let now = Instant::now();
-                self.paint();
-                *self.meta._timings.paint = format!("{:?}", now.elapsed());
+            // TODO: the meta info should only be updated if `self.enable_meta`
+            let render =
+                schedule::take_redraw() || self.needs_layout || !self.enable_render_on_change;
+            if render {
+                self.render_frame(changes)?;
+            }
 
-                let now = Instant::now();
-                self.screen.render(&mut self.output)?;
-                *self.meta._timings.render = format!("{:?}", now.elapsed());
-                *self.meta._timings.total = format!("{:?}", meta_total.elapsed());
-                self.screen.erase();
+            let tick_due = self
+                .tick_rate
+                .is_none_or(|rate| last_tick.elapsed() >= rate);
+            if tick_due && !history::is_travelling() {
+                self.tick_views();
+                last_tick = Instant::now();
+            }
 
-                self.needs_layout = false;
+            if let Some(keymap) = self.keymap.as_mut() {
+                keymap.reload_if_changed();
             }
 
-            self.tick_views();
+            // Ticking the views can change the state
+            idle = !had_events
+                && !render
+                && !has_dirty_nodes()
+                && !schedule::redraw_requested()
+                && !self.tooltips.is_pending()
+                && self.playback.is_none()
+                && self.enable_render_on_change;
 
             let sleep = sleep_micros.saturating_sub(fps_now.elapsed().as_micros()) as u64;
-            if sleep > 0 {
+            if sleep > 0 && !idle {
                 std::thread::sleep(Duration::from_micros(sleep));
             }
 
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.flush()?;
+            }
+
+            self.frame += 1;
             fps_now = Instant::now();
         }
     }
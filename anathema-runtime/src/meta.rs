@@ -1,5 +1,5 @@
 use anathema::values::State;
-use anathema::StateValue;
+use anathema::{Map, StateValue};
 
 #[derive(Debug, State)]
 pub(super) struct Size {
@@ -11,8 +11,11 @@ pub(super) struct Size {
 pub(super) struct Meta {
     pub(super) _size: Size,
     pub(super) _timings: Timings,
+    pub(super) _memory: Memory,
     pub(super) _focus: StateValue<bool>,
     pub(super) _count: StateValue<usize>,
+    pub(super) _env: Map<String>,
+    pub(super) _args: Map<String>,
 }
 
 impl Meta {
@@ -23,8 +26,11 @@ impl Meta {
                 height: height.into(),
             },
             _timings: Timings::default(),
+            _memory: Memory::default(),
             _focus: true.into(),
             _count: 0.into(),
+            _env: Map::empty(),
+            _args: Map::empty(),
         }
     }
 }
@@ -37,3 +43,66 @@ pub(super) struct Timings {
     pub(super) render: StateValue<String>,
     pub(super) total: StateValue<String>,
 }
+
+#[derive(Debug, Default, State)]
+pub(super) struct Memory {
+    pub(super) nodes: StateValue<usize>,
+    pub(super) subscriptions: StateValue<usize>,
+    pub(super) buffers: StateValue<usize>,
+    pub(super) total: StateValue<usize>,
+}
+
+// Named command line arguments:
+// `--name value`, `--name=value` and flags (`--verbose`, `-v`) that are set to "true".
+// Positional arguments are skipped.
+pub(super) fn parse_args(args: impl IntoIterator<Item = String>) -> Vec<(String, String)> {
+    let mut parsed: Vec<(String, String)> = vec![];
+    let mut args = args.into_iter().peekable();
+
+    while let Some(arg) = args.next() {
+        let name = match arg.strip_prefix("--").or_else(|| arg.strip_prefix('-')) {
+            Some("") | None => continue,
+            Some(name) => name,
+        };
+
+        if let Some((name, value)) = name.split_once('=') {
+            parsed.push((name.into(), value.into()));
+            continue;
+        }
+
+        let value = match args.peek() {
+            Some(value) if !value.starts_with('-') => args.next().expect("peeked"),
+            _ => "true".into(),
+        };
+        parsed.push((name.into(), value));
+    }
+
+    parsed
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn named_args() {
+        let args = [
+            "file.txt",
+            "--theme",
+            "dark",
+            "--width=80",
+            "-v",
+            "--",
+            "--debug",
+        ];
+        let parsed = parse_args(args.map(String::from));
+        let expected = [
+            ("theme", "dark"),
+            ("width", "80"),
+            ("v", "true"),
+            ("debug", "true"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        assert_eq!(parsed, expected);
+    }
+}
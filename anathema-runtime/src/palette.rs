@@ -0,0 +1,327 @@
+// -----------------------------------------------------------------------------
+//   - Command palette -
+//   Search the registered actions, and the actions of the keymap,
+//   and run the selected action.
+//
+//   Running an action sends an `Event::Action` to the views,
+//   the same as a key binding would.
+// -----------------------------------------------------------------------------
+use anathema_render::{Screen, Size, Style};
+use anathema_values::fuzzy::fuzzy_match;
+use anathema_values::Symbol;
+use anathema_widget_core::contexts::PaintCtx;
+use anathema_widget_core::keymap::{Key, Keymap};
+use anathema_widget_core::{Event, KeyCode, KeyModifiers, LocalPos, Pos};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const MAX_WIDTH: usize = 60;
+const MAX_RESULTS: usize = 10;
+
+/// An action listed in the palette
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Entry {
+    pub(super) action: Symbol,
+    pub(super) label: String,
+    // The keys bound to the action, if any
+    pub(super) keys: Option<String>,
+}
+
+/// Combine the registered actions with the actions of the keymap.
+/// Registered actions are listed first, using their description as the label.
+pub(super) fn entries(registered: &[(Symbol, String)], keymap: Option<&Keymap>) -> Vec<Entry> {
+    let keys_for = |action: Symbol| {
+        let (chord, _) = keymap?.bindings().find(|(_, a)| *a == action)?;
+        let keys = chord.iter().map(Key::to_string).collect::<Vec<_>>();
+        Some(keys.join(" "))
+    };
+
+    let mut entries = registered
+        .iter()
+        .map(|(action, label)| Entry {
+            action: *action,
+            label: label.clone(),
+            keys: keys_for(*action),
+        })
+        .collect::<Vec<_>>();
+
+    for (_, action) in keymap.into_iter().flat_map(Keymap::bindings) {
+        if entries.iter().any(|entry| entry.action == action) {
+            continue;
+        }
+
+        entries.push(Entry {
+            action,
+            label: action.to_string(),
+            keys: keys_for(action),
+        });
+    }
+
+    entries
+}
+
+// Entries matching the query, best match first, with the matched char indices
+fn search<'a>(entries: &'a [Entry], query: &str) -> Vec<(&'a Entry, Vec<usize>)> {
+    let mut results = entries
+        .iter()
+        .filter_map(|entry| {
+            let found = fuzzy_match(query, &entry.label)?;
+            Some((found.score, entry, found.indices))
+        })
+        .collect::<Vec<_>>();
+
+    // Stable sort keeps the order of the entries for equal scores
+    results.sort_by_key(|(score, ..)| -score);
+    results
+        .into_iter()
+        .map(|(_, entry, indices)| (entry, indices))
+        .collect()
+}
+
+pub(super) struct Palette {
+    open: bool,
+    query: String,
+    selected: usize,
+}
+
+impl Palette {
+    pub(super) fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Handle an event.
+    /// Returns the event to pass on to the views instead of the original event,
+    /// or `None` if the palette didn't handle the event.
+    pub(super) fn event(&mut self, event: &Event, toggle: Key, entries: &[Entry]) -> Option<Event> {
+        if !self.open {
+            let Event::KeyPress(code, modifiers, _) = *event else {
+                return None;
+            };
+
+            if Key::new(code, modifiers) != toggle {
+                return None;
+            }
+
+            self.open = true;
+            self.query.clear();
+            self.selected = 0;
+            return Some(Event::Noop);
+        }
+
+        match event {
+            Event::KeyPress(code, modifiers, _) => match code {
+                _ if Key::new(*code, *modifiers) == toggle => self.open = false,
+                KeyCode::Esc => self.open = false,
+                KeyCode::Enter => {
+                    self.open = false;
+                    let results = search(entries, &self.query);
+                    if let Some((entry, _)) = results.get(self.selected) {
+                        return Some(Event::Action(entry.action));
+                    }
+                }
+                KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+                KeyCode::Down => {
+                    let count = search(entries, &self.query).len().min(MAX_RESULTS);
+                    self.selected = (self.selected + 1).min(count.saturating_sub(1));
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.selected = 0;
+                }
+                KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.query.push(*c);
+                    self.selected = 0;
+                }
+                _ => {}
+            },
+            Event::Paste(text) => {
+                self.query.push_str(text);
+                self.selected = 0;
+            }
+            Event::KeyRelease(..)
+            | Event::KeyRepeat(..)
+            | Event::MouseDown(..)
+            | Event::MouseDrag(..)
+            | Event::MouseUp(..)
+            | Event::MouseMove(..)
+            | Event::MouseScrollDown(..)
+            | Event::MouseScrollUp(..)
+            | Event::MouseScrollLeft(..)
+            | Event::MouseScrollRight(..)
+            | Event::MouseScrollMoved(..)
+            | Event::Action(_) => {}
+            _ => return None,
+        }
+
+        Some(Event::Noop)
+    }
+
+    /// Paint the palette at the top of the screen, if it's open
+    pub(super) fn paint(&self, entries: &[Entry], screen: &mut Screen) {
+        if !self.open {
+            return;
+        }
+
+        let results = search(entries, &self.query);
+        let results = &results[..results.len().min(MAX_RESULTS)];
+
+        let screen_size = screen.size();
+        let width = MAX_WIDTH.min(screen_size.width);
+        let height = (results.len() + 3).min(screen_size.height);
+        let inner = width.saturating_sub(2);
+
+        let pos = Pos::new((screen_size.width - width) as i32 / 2, 0);
+        let mut ctx = PaintCtx::new(screen, None).into_sized(Size::new(width, height), pos);
+        let style = Style::new();
+
+        for y in 0..height {
+            let (left, fill, right) = match y {
+                0 => ('┌', '─', '┐'),
+                _ if y == height - 1 => ('└', '─', '┘'),
+                _ => ('│', ' ', '│'),
+            };
+            ctx.put(left, style, LocalPos::new(0, y));
+            ctx.print(&fill.to_string().repeat(inner), style, LocalPos::new(1, y));
+            ctx.put(right, style, LocalPos::new(width - 1, y));
+        }
+
+        if height > 2 {
+            ctx.print(&format!("> {}", self.query), style, LocalPos::new(2, 1));
+        }
+
+        let mut selected = Style::new();
+        selected.set_inverse(true);
+        let mut dim = Style::new();
+        dim.set_dim(true);
+
+        let visible = height.saturating_sub(3);
+        for (row, (entry, indices)) in results.iter().take(visible).enumerate() {
+            let y = row + 2;
+            let mut style = match row == self.selected {
+                true => selected,
+                false => Style::new(),
+            };
+
+            if row == self.selected {
+                ctx.print(&" ".repeat(inner), style, LocalPos::new(1, y));
+            }
+
+            // Keys are right aligned, and the label is cut short to make room for them
+            let keys_width = entry.keys.as_ref().map(|k| k.width() + 1).unwrap_or(0);
+            let label_width = inner.saturating_sub(2 + keys_width);
+
+            let mut x = 2;
+            for (i, c) in entry.label.chars().enumerate() {
+                let w = c.width().unwrap_or(0);
+                if x + w > label_width + 2 {
+                    break;
+                }
+                style.set_bold(indices.contains(&i));
+                ctx.put(c, style, LocalPos::new(x, y));
+                x += w;
+            }
+
+            if let Some(keys) = entry.keys.as_ref() {
+                let mut keys_style = dim;
+                keys_style.set_inverse(row == self.selected);
+                let x = width.saturating_sub(keys_width + 1);
+                ctx.print(keys, keys_style, LocalPos::new(x, y));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::ScreenPos;
+    use anathema_widget_core::KeyEventState;
+
+    use super::*;
+
+    fn entries() -> Vec<Entry> {
+        let keymap = Keymap::parse(
+            r#"
+            ctrl+s = "save"
+            ctrl+q = "quit"
+            "#,
+        )
+        .unwrap();
+        let registered = [
+            ("save".into(), "Save file".to_string()),
+            ("open".into(), "Open file".to_string()),
+        ];
+        super::entries(&registered, Some(&keymap))
+    }
+
+    fn press(code: KeyCode) -> Event {
+        Event::KeyPress(code, KeyModifiers::NONE, KeyEventState::empty())
+    }
+
+    fn toggle() -> Key {
+        Key::new(KeyCode::Char('p'), KeyModifiers::CONTROL)
+    }
+
+    #[test]
+    fn combine_registered_and_keymap_actions() {
+        let entries = entries();
+        let labels = entries.iter().map(|e| e.label.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, ["Save file", "Open file", "quit"]);
+        assert_eq!(entries[0].keys.as_deref(), Some("ctrl+s"));
+        assert_eq!(entries[1].keys, None);
+    }
+
+    #[test]
+    fn run_selected_action() {
+        let entries = entries();
+        let mut palette = Palette::new();
+
+        assert_eq!(
+            palette.event(&press(KeyCode::Char('x')), toggle(), &entries),
+            None
+        );
+        let open = Event::KeyPress(
+            KeyCode::Char('p'),
+            KeyModifiers::CONTROL,
+            KeyEventState::empty(),
+        );
+        assert_eq!(palette.event(&open, toggle(), &entries), Some(Event::Noop));
+
+        for c in "file".chars() {
+            palette.event(&press(KeyCode::Char(c)), toggle(), &entries);
+        }
+        palette.event(&press(KeyCode::Down), toggle(), &entries);
+        palette.event(&press(KeyCode::Down), toggle(), &entries);
+
+        let event = palette.event(&press(KeyCode::Enter), toggle(), &entries);
+        assert_eq!(event, Some(Event::Action("open".into())));
+        assert!(!palette.open);
+    }
+
+    #[test]
+    fn paint_palette() {
+        let mut screen = Screen::new((30usize, 8usize));
+        let mut palette = Palette::new();
+        palette.open = true;
+        palette.query = "qu".into();
+        palette.paint(&entries(), &mut screen);
+
+        let row = |y| {
+            (0..30)
+                .map(|x| {
+                    screen
+                        .get(ScreenPos::new(x, y))
+                        .map(|(c, _)| c)
+                        .unwrap_or(' ')
+                })
+                .collect::<String>()
+        };
+
+        assert_eq!(row(0), "┌────────────────────────────┐");
+        assert_eq!(row(1), "│ > qu                       │");
+        assert_eq!(row(2), "│ quit                ctrl+q │");
+        assert_eq!(row(3), "└────────────────────────────┘");
+    }
+}
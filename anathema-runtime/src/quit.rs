@@ -0,0 +1,51 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// What to do when the runtime receives a request to quit,
+/// e.g from Ctrl-c.
+/// See [`Runtime::should_quit`](crate::Runtime::should_quit).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum QuitAction {
+    /// Ignore the request and keep running
+    Continue,
+    /// Stop the runtime
+    Quit,
+    /// Keep running, and pass [`Event::Quit`](anathema_widget_core::Event::Quit)
+    /// on to the views so they can ask the user to confirm.
+    /// Use a [`QuitHandle`] to quit once confirmed.
+    Prompt,
+}
+
+pub(super) type QuitCallback = Box<dyn FnMut() -> QuitAction>;
+
+/// Stop the runtime from anywhere, e.g from a view once the user
+/// confirmed quitting, or from another thread.
+///
+/// The runtime stops at the start of the next frame,
+/// without asking [`Runtime::should_quit`](crate::Runtime::should_quit).
+#[derive(Debug, Clone, Default)]
+pub struct QuitHandle(Arc<AtomicBool>);
+
+impl QuitHandle {
+    pub fn quit(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub(super) fn is_quitting(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quit_from_clone() {
+        let handle = QuitHandle::default();
+        let clone = handle.clone();
+        assert!(!handle.is_quitting());
+        std::thread::spawn(move || clone.quit()).join().unwrap();
+        assert!(handle.is_quitting());
+    }
+}
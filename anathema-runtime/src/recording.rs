@@ -0,0 +1,363 @@
+// -----------------------------------------------------------------------------
+//   - Event recording -
+//   Record incoming events to a file, and play them back later.
+//
+//   Every event is written on a separate line:
+//   <frame> <microseconds since the recording started> <event>
+//
+//   e.g:
+//   12 401032 key-press s 2 0
+//   40 1334511 resize 80 24
+//
+//   Events are played back in the same frame they were recorded in,
+//   rather than at the same time, so the frames are deterministic.
+//   The time is only recorded for reference.
+// -----------------------------------------------------------------------------
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use anathema_widget_core::error::Result;
+use anathema_widget_core::keymap::Key;
+use anathema_widget_core::{Event, KeyModifiers};
+use crossterm::event::{KeyEventState, MouseButton};
+
+pub(super) struct Recorder {
+    output: BufWriter<File>,
+    start: Instant,
+}
+
+impl Recorder {
+    pub(super) fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let output = BufWriter::new(File::create(path)?);
+        Ok(Self {
+            output,
+            start: Instant::now(),
+        })
+    }
+
+    pub(super) fn record(&mut self, frame: u64, event: &Event) -> Result<()> {
+        let micros = self.start.elapsed().as_micros();
+        write!(self.output, "{frame} {micros} ")?;
+        write_event(&mut self.output, event)?;
+        writeln!(self.output)?;
+        Ok(())
+    }
+
+    pub(super) fn flush(&mut self) -> Result<()> {
+        self.output.flush()?;
+        Ok(())
+    }
+}
+
+pub(super) struct Playback {
+    events: VecDeque<(u64, Event)>,
+}
+
+impl Playback {
+    /// Load a recording.
+    /// Lines that can't be parsed are skipped.
+    pub(super) fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let src = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&src))
+    }
+
+    fn parse(src: &str) -> Self {
+        let events = src
+            .lines()
+            .filter_map(|line| {
+                let (frame, line) = line.split_once(' ')?;
+                let (_micros, event) = line.split_once(' ')?;
+                Some((frame.parse().ok()?, parse_event(event)?))
+            })
+            .collect();
+
+        Self { events }
+    }
+
+    /// The next event recorded in, or before, the given frame.
+    pub(super) fn next(&mut self, frame: u64) -> Option<Event> {
+        match self.events.front() {
+            Some((event_frame, _)) if *event_frame <= frame => {
+                self.events.pop_front().map(|(_, e)| e)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "left",
+        MouseButton::Right => "right",
+        MouseButton::Middle => "middle",
+    }
+}
+
+fn parse_button(name: &str) -> Option<MouseButton> {
+    match name {
+        "left" => Some(MouseButton::Left),
+        "right" => Some(MouseButton::Right),
+        "middle" => Some(MouseButton::Middle),
+        _ => None,
+    }
+}
+
+fn write_event(output: &mut impl Write, event: &Event) -> io::Result<()> {
+    let key = |code| Key::new(code, KeyModifiers::NONE);
+
+    match event {
+        Event::Noop => write!(output, "noop"),
+        Event::Quit => write!(output, "quit"),
+        Event::Blur => write!(output, "blur"),
+        Event::Focus => write!(output, "focus"),
+        Event::CtrlC => write!(output, "ctrl-c"),
+        Event::KeyPress(code, mods, state) => {
+            write!(
+                output,
+                "key-press {} {} {}",
+                key(*code),
+                mods.bits(),
+                state.bits()
+            )
+        }
+        Event::KeyRelease(code, mods, state) => {
+            write!(
+                output,
+                "key-release {} {} {}",
+                key(*code),
+                mods.bits(),
+                state.bits()
+            )
+        }
+        Event::KeyRepeat(code, mods, state) => {
+            write!(
+                output,
+                "key-repeat {} {} {}",
+                key(*code),
+                mods.bits(),
+                state.bits()
+            )
+        }
+        Event::MouseDown(x, y, button, mods, count) => {
+            write!(
+                output,
+                "mouse-down {x} {y} {} {} {count}",
+                button_name(*button),
+                mods.bits()
+            )
+        }
+        Event::MouseDrag(x, y, button, mods) => {
+            write!(
+                output,
+                "mouse-drag {x} {y} {} {}",
+                button_name(*button),
+                mods.bits()
+            )
+        }
+        Event::MouseUp(x, y, button, mods) => {
+            write!(
+                output,
+                "mouse-up {x} {y} {} {}",
+                button_name(*button),
+                mods.bits()
+            )
+        }
+        Event::MouseMove(x, y, mods) => write!(output, "mouse-move {x} {y} {}", mods.bits()),
+        Event::MouseScrollDown(x, y, mods) => {
+            write!(output, "scroll-down {x} {y} {}", mods.bits())
+        }
+        Event::MouseScrollMoved(x, y, mods) => {
+            write!(output, "scroll-moved {x} {y} {}", mods.bits())
+        }
+        Event::MouseScrollUp(x, y, mods) => write!(output, "scroll-up {x} {y} {}", mods.bits()),
+        Event::MouseScrollLeft(x, y, mods) => {
+            write!(output, "scroll-left {x} {y} {}", mods.bits())
+        }
+        Event::MouseScrollRight(x, y, mods) => {
+            write!(output, "scroll-right {x} {y} {}", mods.bits())
+        }
+        Event::Resize(width, height) => write!(output, "resize {width} {height}"),
+        Event::Paste(text) => {
+            write!(output, "paste ")?;
+            write_escaped(output, text, false)
+        }
+        Event::Action(action) => write!(output, "action {action}"),
+        Event::Change(action, value) => write!(output, "change {action} {value}"),
+        Event::Input(action, text) => {
+            write!(output, "input ")?;
+            write_escaped(output, action.as_str(), true)?;
+            write!(output, " ")?;
+            write_escaped(output, text, false)
+        }
+    }
+}
+
+// Escape the chars that would end the line, and optionally spaces
+fn write_escaped(output: &mut impl Write, text: &str, spaces: bool) -> io::Result<()> {
+    for c in text.chars() {
+        match c {
+            '\\' => write!(output, "\\\\")?,
+            '\n' => write!(output, "\\n")?,
+            '\r' => write!(output, "\\r")?,
+            ' ' if spaces => write!(output, "\\s")?,
+            c => write!(output, "{c}")?,
+        }
+    }
+    Ok(())
+}
+
+fn unescape(input: &str) -> Option<String> {
+    let mut text = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => text.push('\n'),
+                'r' => text.push('\r'),
+                's' => text.push(' '),
+                c => text.push(c),
+            },
+            c => text.push(c),
+        }
+    }
+    Some(text)
+}
+
+fn parse_event(input: &str) -> Option<Event> {
+    let (name, args) = input.split_once(' ').unwrap_or((input, ""));
+
+    // Pasted text and actions can contain spaces
+    match name {
+        "paste" => return Some(Event::Paste(unescape(args)?)),
+        "action" => return Some(Event::Action(args.into())),
+        "change" => {
+            let (action, value) = args.rsplit_once(' ')?;
+            return Some(Event::Change(action.into(), value.parse().ok()?));
+        }
+        "input" => {
+            let (action, text) = args.split_once(' ')?;
+            return Some(Event::Input(unescape(action)?.into(), unescape(text)?));
+        }
+        _ => {}
+    }
+
+    let mut args = args.split(' ');
+    let num = |arg: Option<&str>| arg?.parse::<u16>().ok();
+
+    let event = match name {
+        "noop" => Event::Noop,
+        "quit" => Event::Quit,
+        "blur" => Event::Blur,
+        "focus" => Event::Focus,
+        "ctrl-c" => Event::CtrlC,
+        "key-press" | "key-release" | "key-repeat" => {
+            let code = Key::parse(args.next()?)?.code;
+            let mods = KeyModifiers::from_bits_truncate(args.next()?.parse().ok()?);
+            let state = KeyEventState::from_bits_truncate(args.next()?.parse().ok()?);
+            match name {
+                "key-press" => Event::KeyPress(code, mods, state),
+                "key-release" => Event::KeyRelease(code, mods, state),
+                _ => Event::KeyRepeat(code, mods, state),
+            }
+        }
+        "mouse-down" | "mouse-drag" | "mouse-up" => {
+            let (x, y) = (num(args.next())?, num(args.next())?);
+            let button = parse_button(args.next()?)?;
+            let mods = KeyModifiers::from_bits_truncate(args.next()?.parse().ok()?);
+            match name {
+                // Recordings made before click counting have no count
+                "mouse-down" => {
+                    let count = args.next().and_then(|c| c.parse().ok()).unwrap_or(1);
+                    Event::MouseDown(x, y, button, mods, count)
+                }
+                "mouse-drag" => Event::MouseDrag(x, y, button, mods),
+                _ => Event::MouseUp(x, y, button, mods),
+            }
+        }
+        "mouse-move" | "scroll-down" | "scroll-moved" | "scroll-up" | "scroll-left"
+        | "scroll-right" => {
+            let (x, y) = (num(args.next())?, num(args.next())?);
+            let mods = KeyModifiers::from_bits_truncate(args.next()?.parse().ok()?);
+            match name {
+                "mouse-move" => Event::MouseMove(x, y, mods),
+                "scroll-down" => Event::MouseScrollDown(x, y, mods),
+                "scroll-moved" => Event::MouseScrollMoved(x, y, mods),
+                "scroll-up" => Event::MouseScrollUp(x, y, mods),
+                "scroll-left" => Event::MouseScrollLeft(x, y, mods),
+                _ => Event::MouseScrollRight(x, y, mods),
+            }
+        }
+        "resize" => Event::Resize(num(args.next())?, num(args.next())?),
+        _ => return None,
+    };
+
+    Some(event)
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::KeyCode;
+
+    use super::*;
+
+    fn roundtrip(event: Event) {
+        let mut output = vec![];
+        write_event(&mut output, &event).unwrap();
+        let line = String::from_utf8(output).unwrap();
+        assert_eq!(parse_event(&line), Some(event), "{line}");
+    }
+
+    #[test]
+    fn event_roundtrip() {
+        let state = KeyEventState::empty();
+        roundtrip(Event::Quit);
+        roundtrip(Event::KeyPress(
+            KeyCode::Char(' '),
+            KeyModifiers::NONE,
+            state,
+        ));
+        roundtrip(Event::KeyPress(
+            KeyCode::Char('S'),
+            KeyModifiers::SHIFT,
+            state,
+        ));
+        roundtrip(Event::KeyRelease(
+            KeyCode::F(4),
+            KeyModifiers::CONTROL,
+            state,
+        ));
+        roundtrip(Event::MouseDown(
+            3,
+            4,
+            MouseButton::Right,
+            KeyModifiers::ALT,
+            2,
+        ));
+        roundtrip(Event::MouseScrollUp(1, 2, KeyModifiers::NONE));
+        roundtrip(Event::Resize(80, 24));
+        roundtrip(Event::Paste("a \\ b\nc".into()));
+        roundtrip(Event::Action("save file".into()));
+        roundtrip(Event::Change("volume level".into(), -0.25));
+        roundtrip(Event::Input("user name".into(), "a \\ b\nc".into()));
+    }
+
+    #[test]
+    fn play_back_by_frame() {
+        let src = "\
+            0 100 focus\n\
+            0 200 key-press a 0 0\n\
+            not an event\n\
+            2 900 quit\n";
+
+        let mut playback = Playback::parse(src);
+        assert_eq!(playback.next(0), Some(Event::Focus));
+        assert!(matches!(playback.next(0), Some(Event::KeyPress(..))));
+        assert_eq!(playback.next(0), None);
+        assert_eq!(playback.next(1), None);
+        assert_eq!(playback.next(2), Some(Event::Quit));
+        assert_eq!(playback.next(3), None);
+    }
+}
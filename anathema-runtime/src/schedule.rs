@@ -0,0 +1,44 @@
+// -----------------------------------------------------------------------------
+//   - Frame scheduling -
+//   The runtime only lays out and paints a frame when something changed.
+//   When a frame had no events and no changes, the runtime waits for
+//   the next event rather than polling at the frame rate.
+//
+//   Animations that change what is painted without changing any state
+//   call `request_redraw` for every frame they need.
+// -----------------------------------------------------------------------------
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REDRAW: AtomicBool = AtomicBool::new(false);
+
+/// Render the next frame, even if nothing changed,
+/// and keep the runtime from waiting for events until then.
+///
+/// This can be called from any thread, however a runtime that is
+/// already waiting for events only sees the request once it wakes up
+/// (see [`Runtime::idle_tick`](crate::Runtime::idle_tick)).
+pub fn request_redraw() {
+    REDRAW.store(true, Ordering::Relaxed);
+}
+
+pub(super) fn take_redraw() -> bool {
+    REDRAW.swap(false, Ordering::Relaxed)
+}
+
+pub(super) fn redraw_requested() -> bool {
+    REDRAW.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn take_request() {
+        request_redraw();
+        assert!(redraw_requested());
+        assert!(take_redraw());
+        assert!(!redraw_requested());
+        assert!(!take_redraw());
+    }
+}
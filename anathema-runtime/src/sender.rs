@@ -0,0 +1,203 @@
+// -----------------------------------------------------------------------------
+//   - Event sender -
+//   Send events to the views from other threads, e.g from a network client
+//   running on an async runtime, pushing the data it received.
+//
+//   Sending never blocks, so it can be done from an async task.
+//   The views receive the events in the order they were sent,
+//   in between the events from the terminal.
+//
+//   Once there is a sender the terminal is read on an input thread,
+//   forwarding the terminal events into the same channel.
+//   The runtime blocks on that one channel while idle, so both a key press
+//   and a sent event wake it up straight away.
+// -----------------------------------------------------------------------------
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anathema_values::Symbol;
+use anathema_widget_core::{Event, Events};
+
+// How often the input thread checks if the runtime is gone.
+// The thread is blocked on the terminal in between, so this is not a busy wait.
+const INPUT_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+pub(super) enum Incoming {
+    /// An event from the terminal, with the time it was read,
+    /// as clicks are counted on the runtime
+    Terminal(Event, Instant),
+    /// An event sent with an [`EventSender`]
+    Sent(Event),
+}
+
+/// Send events to the runtime from any thread.
+/// See [`Runtime::event_sender`](crate::Runtime::event_sender).
+///
+/// ```ignore
+/// let sender = runtime.event_sender();
+/// tokio::spawn(async move {
+///     let messages = fetch_messages().await;
+///     let _ = sender.send_change("unread", messages.len() as f64);
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventSender(Sender<Incoming>);
+
+impl EventSender {
+    /// Send an event to the views.
+    /// Returns the event as an error if the runtime is gone.
+    pub fn send(&self, event: Event) -> Result<(), Event> {
+        self.0
+            .send(Incoming::Sent(event))
+            .map_err(|err| match err.0 {
+                Incoming::Terminal(event, _) | Incoming::Sent(event) => event,
+            })
+    }
+
+    /// Send an [`Event::Action`] to the views
+    pub fn send_action(&self, action: impl Into<Symbol>) -> Result<(), Event> {
+        self.send(Event::Action(action.into()))
+    }
+
+    /// Send an [`Event::Change`] to the views
+    pub fn send_change(&self, action: impl Into<Symbol>, value: f64) -> Result<(), Event> {
+        self.send(Event::Change(action.into(), value))
+    }
+}
+
+// Reads the terminal and forwards the events to the runtime.
+// Stopped and joined when dropped, so no events are read
+// once the terminal is restored.
+struct InputThread {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl InputThread {
+    fn spawn(sender: Sender<Incoming>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = std::thread::spawn({
+            let stop = stop.clone();
+            move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let Some(event) = Events.poll(INPUT_TIMEOUT) else {
+                        continue;
+                    };
+
+                    if sender
+                        .send(Incoming::Terminal(event, Instant::now()))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for InputThread {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+pub(super) struct EventChannel {
+    sender: Sender<Incoming>,
+    receiver: Receiver<Incoming>,
+    input: Option<InputThread>,
+}
+
+impl EventChannel {
+    pub(super) fn new() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            sender,
+            receiver,
+            input: None,
+        }
+    }
+
+    pub(super) fn sender(&self) -> EventSender {
+        EventSender(self.sender.clone())
+    }
+
+    /// Read the terminal on an input thread, forwarding the events into the channel
+    pub(super) fn forward_terminal(&mut self) {
+        if self.input.is_none() {
+            self.input = Some(InputThread::spawn(self.sender.clone()));
+        }
+    }
+
+    /// Wait for the next event until the timeout passed,
+    /// or until there is an event if there is no timeout
+    pub(super) fn recv(&self, timeout: Option<Duration>) -> Option<Incoming> {
+        match timeout {
+            Some(timeout) => self.receiver.recv_timeout(timeout).ok(),
+            None => self.receiver.recv().ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sent(incoming: Option<Incoming>) -> Option<Event> {
+        match incoming? {
+            Incoming::Sent(event) => Some(event),
+            Incoming::Terminal(..) => panic!("not a sent event"),
+        }
+    }
+
+    #[test]
+    fn send_from_thread() {
+        let channel = EventChannel::new();
+        let sender = channel.sender();
+        std::thread::spawn(move || {
+            sender.send(Event::Focus).unwrap();
+            sender.send_change("volume", 0.5).unwrap();
+        })
+        .join()
+        .unwrap();
+
+        let timeout = Some(Duration::ZERO);
+        assert_eq!(sent(channel.recv(timeout)), Some(Event::Focus));
+        assert_eq!(
+            sent(channel.recv(timeout)),
+            Some(Event::Change(Symbol::new("volume"), 0.5))
+        );
+        assert_eq!(sent(channel.recv(timeout)), None);
+
+        // The runtime is gone
+        let sender = channel.sender();
+        drop(channel);
+        assert_eq!(sender.send(Event::Blur), Err(Event::Blur));
+    }
+
+    #[test]
+    fn sent_event_wakes_receiver() {
+        let channel = EventChannel::new();
+        let sender = channel.sender();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            sender.send(Event::Focus).unwrap();
+        });
+
+        // Blocks without a timeout until the event is sent
+        assert_eq!(sent(channel.recv(None)), Some(Event::Focus));
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+/// Performance counters for a single rendered frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FrameStats {
+    /// Time spent applying changes to the node tree
+    pub changes: Duration,
+    /// Time spent on layout
+    pub layout: Duration,
+    /// Time spent on positioning widgets
+    pub position: Duration,
+    /// Time spent on painting widgets to the buffer
+    pub paint: Duration,
+    /// Time spent on rendering the buffer to the output
+    pub render: Duration,
+    /// Total frame time, excluding the time spent sleeping
+    pub total: Duration,
+    /// Number of widgets laid out
+    pub widgets: usize,
+    /// Number of cells written to the output
+    pub cells_written: usize,
+    /// Approximate memory usage at the end of the frame
+    pub memory: MemoryStats,
+}
+
+impl FrameStats {
+    /// Reset all the counters
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Approximate memory usage, in bytes.
+///
+/// These numbers only count what the runtime can see (e.g the widgets but not
+/// what the widgets allocate), so they are best used to spot something
+/// that keeps growing between frames, like subscriptions of removed nodes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// The nodes and widgets of the node tree
+    pub nodes: usize,
+    /// The subscriptions to state values
+    pub subscriptions: usize,
+    /// Number of subscriptions to state values
+    pub subscription_count: usize,
+    /// The screen buffers
+    pub buffers: usize,
+}
+
+impl MemoryStats {
+    /// Total number of bytes
+    pub fn total(&self) -> usize {
+        self.nodes + self.subscriptions + self.buffers
+    }
+}
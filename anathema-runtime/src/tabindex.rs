@@ -67,6 +67,7 @@ impl TabIndexing {
         Views::all(|views| {
             let mut views = views
                 .iter()
+                .filter(|f| !Views::is_disabled(f.key()))
                 .filter_map(|f| {
                     Some(TabIndexRef {
                         node_id: f.key(),
@@ -200,4 +201,18 @@ mod test {
         let last = tabs.current_node().unwrap();
         assert_eq!(last, &NodeId::from(usize::MAX));
     }
+
+    #[test]
+    fn skip_disabled_views() {
+        insert_ids();
+        Views::test_disable(NodeId::from(vec![11]));
+
+        let node_ids = ids();
+        let mut tabs = TabIndexing::new();
+
+        tabs.next(Direction::Forwards);
+        comp_index(&node_ids[0], &tabs);
+        tabs.next(Direction::Forwards);
+        comp_index(&node_ids[2], &tabs);
+    }
 }
@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use anathema_values::NodeId;
+use anathema_widget_core::nodes::Nodes;
+use anathema_widget_core::tooltip::Tooltip;
+use anathema_widget_core::{Event, Pos};
+
+enum Trigger {
+    None,
+    // The mouse position and when the mouse stopped there
+    Hover(Pos, Instant),
+    // A view gained focus
+    Focus(NodeId),
+}
+
+/// Decide when to show tooltips.
+///
+/// A tooltip is shown once the mouse has been resting on a widget for
+/// the hover delay, or when a view gains focus.
+/// It's hidden again on the next mouse movement or key press.
+pub(super) struct TooltipTracker {
+    trigger: Trigger,
+    // The trigger has been checked for a tooltip
+    checked: bool,
+    visible: bool,
+}
+
+impl TooltipTracker {
+    pub(super) fn new() -> Self {
+        Self {
+            trigger: Trigger::None,
+            checked: false,
+            visible: false,
+        }
+    }
+
+    /// Returns true if the screen should be repainted to hide the tooltip
+    pub(super) fn event(&mut self, event: &Event) -> bool {
+        match *event {
+            Event::MouseMove(x, y, _) => {
+                self.trigger = Trigger::Hover(Pos::new(x as i32, y as i32), Instant::now());
+            }
+            Event::KeyPress(..)
+            | Event::MouseDown(..)
+            | Event::MouseScrollUp(..)
+            | Event::MouseScrollDown(..)
+            | Event::Blur => self.trigger = Trigger::None,
+            _ => return false,
+        }
+
+        self.checked = false;
+        std::mem::take(&mut self.visible)
+    }
+
+    pub(super) fn focus(&mut self, view_id: NodeId) {
+        self.trigger = Trigger::Focus(view_id);
+        self.checked = false;
+    }
+
+    /// Returns true if the hover delay passed since the last frame,
+    /// and the screen should be repainted to show the tooltip
+    pub(super) fn is_due(&self, delay: Duration) -> bool {
+        if self.checked {
+            return false;
+        }
+
+        match self.trigger {
+            Trigger::Hover(_, since) => since.elapsed() >= delay,
+            Trigger::Focus(_) => true,
+            Trigger::None => false,
+        }
+    }
+
+    /// Returns true if a tooltip could still be shown,
+    /// once the hover delay passed
+    pub(super) fn is_pending(&self) -> bool {
+        !self.checked && !matches!(self.trigger, Trigger::None)
+    }
+
+    /// The tooltip to paint, if any
+    pub(super) fn find(&mut self, nodes: &mut Nodes<'_>, delay: Duration) -> Option<Tooltip> {
+        let tooltip = match &self.trigger {
+            Trigger::Hover(pos, since) => {
+                // Frames painted before the delay passed don't count
+                if since.elapsed() < delay {
+                    return None;
+                }
+                Tooltip::at_position(nodes, *pos)
+            }
+            Trigger::Focus(view_id) => Tooltip::in_view(nodes, view_id),
+            Trigger::None => None,
+        };
+
+        self.checked = true;
+        self.visible = tooltip.is_some();
+        tooltip
+    }
+}
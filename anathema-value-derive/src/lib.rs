@@ -58,3 +58,147 @@ pub fn state_derive(strct: syn::ItemStruct) -> Result {
         }
     })
 }
+
+/// Generate a widget factory from the fields of a widget.
+///
+/// * `#[attr]` / `#[attr("max-width")]` on a `Value<T>` reads the attribute.
+///   Without a name the field name is used, with `_` replaced by `-`.
+/// * `#[style]` on a `WidgetStyle` reads the style attributes (`foreground`, `bold` etc.).
+/// * `#[text]` on a `Value<String>` is the text of the node.
+///
+/// All other fields are created with `Default::default()`.
+///
+/// For a widget `Gauge` this generates a `GaugeFactory`, and a
+/// `Gauge::update_attributes` function that resolves all the attributes
+/// and subscribes the node to changes. Call it from `Widget::update`.
+///
+/// ```ignore
+/// #[derive(Debug, WidgetFactory)]
+/// struct Gauge {
+///     #[attr]
+///     value: Value<usize>,
+///     #[attr("max-value")]
+///     max: Value<usize>,
+///     #[style]
+///     style: WidgetStyle,
+/// }
+///
+/// impl Widget for Gauge {
+///     fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+///         self.update_attributes(context, node_id);
+///     }
+///     ...
+/// }
+///
+/// Factory::register("gauge", GaugeFactory)?;
+/// ```
+#[manyhow]
+#[proc_macro_derive(WidgetFactory, attributes(attr, style, text))]
+pub fn widget_factory_derive(strct: syn::ItemStruct) -> Result {
+    let name = &strct.ident;
+    let vis = &strct.vis;
+    let factory = syn::Ident::new(&format!("{name}Factory"), name.span());
+
+    ensure!(
+        strct.generics.params.is_empty(),
+        strct.generics,
+        "widgets with generics are not supported"
+    );
+
+    ensure!(
+        let Fields::Named(struct_fields) = &strct.fields,
+        strct.fields,
+        "only named fields"
+    );
+
+    let mut inits = vec![];
+    let mut resolved = vec![];
+    let mut text = false;
+
+    for field in &struct_fields.named {
+        let ident = field.ident.as_ref().expect("named fields");
+        let kind = field_kind(field)?;
+
+        let init = match &kind {
+            FieldKind::Attribute(attribute) => quote!(context.get(#attribute)),
+            FieldKind::Style => quote!(context.style()),
+            FieldKind::Text => {
+                text = true;
+                quote!(context.text.take())
+            }
+            FieldKind::Other => quote!(::core::default::Default::default()),
+        };
+        inits.push(quote!(#ident: #init));
+
+        if !matches!(kind, FieldKind::Other) {
+            resolved.push(ident);
+        }
+    }
+
+    // The text is taken out of the context
+    let context = match text {
+        true => quote!(mut context),
+        false => quote!(context),
+    };
+
+    Ok(quote! {
+        # use ::anathema::core::{error, AnyWidget, FactoryContext, WidgetFactory};
+        # use ::anathema::values::{Context, NodeId};
+        /// Factory generated by `#[derive(WidgetFactory)]`
+        #vis struct #factory;
+
+        impl WidgetFactory for #factory {
+            fn make(&self, #context: FactoryContext<'_>) -> error::Result<Box<dyn AnyWidget>> {
+                let widget = #name {
+                    #(#inits,)*
+                };
+                Ok(Box::new(widget))
+            }
+        }
+
+        impl #name {
+            /// Resolve all attributes and subscribe to changes
+            #vis fn update_attributes(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+                #(self.#resolved.resolve(context, node_id);)*
+            }
+        }
+    })
+}
+
+enum FieldKind {
+    Attribute(String),
+    Style,
+    Text,
+    Other,
+}
+
+fn field_kind(field: &syn::Field) -> Result<FieldKind> {
+    let mut kind = FieldKind::Other;
+
+    for attr in &field.attrs {
+        let next = if attr.path().is_ident("attr") {
+            match &attr.meta {
+                syn::Meta::Path(_) => {
+                    let ident = field.ident.as_ref().expect("named fields");
+                    FieldKind::Attribute(ident.to_string().replace('_', "-"))
+                }
+                _ => FieldKind::Attribute(attr.parse_args::<syn::LitStr>()?.value()),
+            }
+        } else if attr.path().is_ident("style") {
+            FieldKind::Style
+        } else if attr.path().is_ident("text") {
+            FieldKind::Text
+        } else {
+            continue;
+        };
+
+        ensure!(
+            matches!(kind, FieldKind::Other),
+            attr,
+            "only one of `attr`, `style` and `text` per field"
+        );
+        kind = next;
+    }
+
+    Ok(kind)
+}
@@ -0,0 +1,87 @@
+// -----------------------------------------------------------------------------
+//   - Expression functions -
+//   Built-in functions that can be called from the templates and
+//   return more than a single value, e.g `text colorize(sparkline, green, red)`.
+//
+//   Functions can't be registered by the application.
+// -----------------------------------------------------------------------------
+use std::fmt::{self, Display};
+
+use crate::spans::{self, Spans};
+use crate::Owned;
+
+/// The names of the built-in functions
+pub const BUILTIN: &[&str] = &["colorize", "gradient"];
+
+/// An argument to, or the return value of, a function
+#[derive(Debug, Clone, PartialEq)]
+pub enum FnValue {
+    Owned(Owned),
+    String(String),
+    /// Text with colors, painted by the `text` widget. See [`spans`](crate::spans).
+    Spans(Spans),
+}
+
+impl FnValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            Self::Owned(_) | Self::Spans(_) => None,
+        }
+    }
+
+    pub fn to_owned(&self) -> Option<Owned> {
+        match self {
+            Self::Owned(owned) => Some(*owned),
+            Self::String(_) | Self::Spans(_) => None,
+        }
+    }
+}
+
+impl Display for FnValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Owned(owned) => write!(f, "{owned}"),
+            Self::String(s) => write!(f, "{s}"),
+            Self::Spans(spans) => write!(f, "{spans}"),
+        }
+    }
+}
+
+impl<T: Into<Owned>> From<T> for FnValue {
+    fn from(val: T) -> Self {
+        Self::Owned(val.into())
+    }
+}
+
+impl From<String> for FnValue {
+    fn from(val: String) -> Self {
+        Self::String(val)
+    }
+}
+
+impl From<&str> for FnValue {
+    fn from(val: &str) -> Self {
+        Self::String(val.into())
+    }
+}
+
+/// Call a built-in function, see [`BUILTIN`].
+/// Calls to any other function evaluate to nothing.
+pub fn call(name: &str, args: &[FnValue]) -> Option<FnValue> {
+    spans::call(name, args).map(FnValue::Spans)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn call_builtin_only() {
+        let args = ["text".into(), "red".into()];
+        assert!(BUILTIN
+            .iter()
+            .all(|name| matches!(call(name, &args), Some(FnValue::Spans(_)))));
+        assert_eq!(call("shout", &args), None);
+    }
+}
@@ -0,0 +1,181 @@
+// -----------------------------------------------------------------------------
+//   - Fuzzy matching -
+//   Match a query against a candidate where every character of the query
+//   has to be found in the candidate, in order, ignoring case.
+//
+//   Matches are scored so that consecutive characters, and characters
+//   at the start of words, rank higher than scattered characters.
+//
+//   In templates `fuzzy(items, query)` filters and sorts a collection
+//   in a loop, and the `highlight` attribute of `text` and `span`
+//   highlights the characters matching a query.
+// -----------------------------------------------------------------------------
+use std::ops::Range;
+
+const MATCH: i64 = 16;
+const CONSECUTIVE: i64 = 16;
+const WORD_START: i64 = 24;
+const GAP: i64 = 2;
+const LEADING_GAP: i64 = 1;
+
+/// A successful match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better
+    pub score: i64,
+    /// Char indices of the matched characters in the candidate
+    pub indices: Vec<usize>,
+}
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    let Some(prev) = index.checked_sub(1).map(|i| chars[i]) else {
+        return true;
+    };
+    let current = chars[index];
+    !prev.is_alphanumeric() && current.is_alphanumeric()
+        || prev.is_lowercase() && current.is_uppercase()
+}
+
+fn eq(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+impl FuzzyMatch {
+    /// Byte ranges of the matched characters in the candidate,
+    /// where consecutive characters are merged into a single range.
+    ///
+    /// ```
+    /// # use anathema_values::fuzzy::fuzzy_match;
+    /// let candidate = "file open";
+    /// let found = fuzzy_match("fiop", candidate).unwrap();
+    /// assert_eq!(found.byte_ranges(candidate), vec![0..2, 5..7]);
+    /// ```
+    pub fn byte_ranges(&self, candidate: &str) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = vec![];
+        let mut indices = self.indices.iter().peekable();
+
+        for (i, (offset, c)) in candidate.char_indices().enumerate() {
+            if indices.next_if_eq(&&i).is_none() {
+                continue;
+            }
+
+            let end = offset + c.len_utf8();
+            match ranges.last_mut() {
+                Some(range) if range.end == offset => range.end = end,
+                _ => ranges.push(offset..end),
+            }
+        }
+
+        ranges
+    }
+}
+
+/// Match the query against the candidate.
+/// An empty query matches everything with a score of zero.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let query = query.chars().collect::<Vec<_>>();
+    let chars = candidate.chars().collect::<Vec<_>>();
+
+    if query.len() > chars.len() {
+        return None;
+    }
+
+    // scores[i][j]: best score with the i:th query char matched at the j:th candidate char
+    let mut scores = vec![vec![None::<i64>; chars.len()]; query.len()];
+    let mut prev = vec![vec![0; chars.len()]; query.len()];
+
+    for (i, q) in query.iter().enumerate() {
+        for j in i..chars.len() {
+            if !eq(*q, chars[j]) {
+                continue;
+            }
+
+            let mut bonus = MATCH;
+            if is_word_start(&chars, j) {
+                bonus += WORD_START;
+            }
+
+            if i == 0 {
+                scores[i][j] = Some(bonus - LEADING_GAP * j as i64);
+                continue;
+            }
+
+            let best = (i - 1..j)
+                .filter_map(|k| {
+                    let score = scores[i - 1][k]?;
+                    let score = match j - k - 1 {
+                        0 => score + CONSECUTIVE,
+                        gap => score - GAP * gap as i64,
+                    };
+                    Some((score, k))
+                })
+                .max_by_key(|(score, _)| *score);
+
+            if let Some((score, k)) = best {
+                scores[i][j] = Some(score + bonus);
+                prev[i][j] = k;
+            }
+        }
+    }
+
+    let Some(last) = query.len().checked_sub(1) else {
+        return Some(FuzzyMatch {
+            score: 0,
+            indices: vec![],
+        });
+    };
+
+    let (score, mut j) = scores[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| Some(((*score)?, j)))
+        .max_by_key(|(score, _)| *score)?;
+
+    let mut indices = vec![0; query.len()];
+    for i in (0..query.len()).rev() {
+        indices[i] = j;
+        j = prev[i][j];
+    }
+
+    Some(FuzzyMatch { score, indices })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn indices(query: &str, candidate: &str) -> Option<Vec<usize>> {
+        fuzzy_match(query, candidate).map(|m| m.indices)
+    }
+
+    fn score(query: &str, candidate: &str) -> i64 {
+        fuzzy_match(query, candidate).unwrap().score
+    }
+
+    #[test]
+    fn match_in_order() {
+        assert_eq!(indices("fo", "file open"), Some(vec![0, 5]));
+        assert_eq!(indices("FO", "file open"), Some(vec![0, 5]));
+        assert_eq!(indices("of", "file open"), None);
+        assert_eq!(indices("", "anything"), Some(vec![]));
+        assert_eq!(indices("long query", "short"), None);
+    }
+
+    #[test]
+    fn prefer_word_starts_and_consecutive() {
+        // Prefer the start of `save` over the `s` in `close`
+        assert_eq!(indices("sa", "close save"), Some(vec![6, 7]));
+        assert_eq!(indices("gs", "git status"), Some(vec![0, 4]));
+        assert_eq!(indices("ts", "toggleSidebar"), Some(vec![0, 6]));
+
+        assert!(score("save", "save file") > score("save", "select all of everything"));
+        assert!(score("op", "open") > score("op", "close project"));
+    }
+
+    #[test]
+    fn byte_ranges() {
+        let candidate = "héllo wörld";
+        let found = fuzzy_match("hélw", candidate).unwrap();
+        assert_eq!(found.byte_ranges(candidate), vec![0..4, 7..8]);
+    }
+}
@@ -0,0 +1,434 @@
+// -----------------------------------------------------------------------------
+//   - Change history -
+//   Record every change made to the state, to find out why a node
+//   was updated.
+//
+//   Recording is opt-in and the history is bounded: once the history is full
+//   the oldest change is discarded.
+//
+//   The path of a change is the path the value was last read through
+//   (e.g `user.name`, or `item` inside a loop), and is only known for values
+//   that were read while recording.
+//
+//   The old and the new value are only known for changes made with
+//   `StateValue::set`, as a change through `DerefMut` happens before
+//   the value is mutated.
+//
+//   Time travel steps backward and forward through the recorded changes,
+//   producing the values to write back to the state. Nothing is recorded
+//   while travelling, and once travelling stops the changes after the
+//   current position are discarded.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::{Change, NodeId, Path};
+
+thread_local! {
+    static HISTORY: RefCell<Option<History>> = const { RefCell::new(None) };
+}
+
+/// A change made to the state while recording.
+#[derive(Debug, Clone)]
+pub struct ChangeRecord {
+    /// Time since the recording started
+    pub time: Duration,
+    /// The path the value was last read through
+    pub path: Option<Path>,
+    pub change: Change,
+    /// The old value, formatted with `Debug`
+    pub old: Option<String>,
+    /// The new value, formatted with `Debug`
+    pub new: Option<String>,
+    /// The old and the new value as they are written back to the state,
+    /// strings without quotes or escapes
+    pub raw: Option<(String, String)>,
+    /// The nodes notified of the change
+    pub node_ids: Vec<NodeId>,
+}
+
+struct History {
+    records: VecDeque<ChangeRecord>,
+    capacity: usize,
+    start: Instant,
+    // The path of the value currently being read
+    reading: Option<Path>,
+    // The old and the new value of the next change
+    values: Option<Values>,
+    // The number of changes applied to the state, `None` unless travelling
+    position: Option<usize>,
+}
+
+// The old and the new value of a change made with `StateValue::set`
+pub(crate) struct Values {
+    pub(crate) old: String,
+    pub(crate) new: String,
+    pub(crate) raw_old: String,
+    pub(crate) raw_new: String,
+}
+
+/// A value to write back to the state, produced by [`step`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    /// The path of the value, e.g `user.name`
+    pub path: String,
+    /// The value to write, a string as it is
+    pub value: String,
+}
+
+/// The values to write back to the state to reach a position, produced by [`step`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    /// The position once the values are written
+    pub position: usize,
+    /// The values to write, in order
+    pub replays: Vec<Replay>,
+}
+
+/// Start recording the changes on this thread, keeping at most `capacity` changes.
+/// This clears any previous recording.
+pub fn record_changes(capacity: usize) {
+    HISTORY.set(Some(History {
+        records: VecDeque::with_capacity(capacity.min(1024)),
+        capacity,
+        start: Instant::now(),
+        reading: None,
+        values: None,
+        position: None,
+    }));
+}
+
+/// Stop recording and discard the history.
+pub fn stop_recording() {
+    HISTORY.set(None);
+}
+
+/// Returns true if the changes are being recorded.
+pub fn is_recording() -> bool {
+    HISTORY.with_borrow(Option::is_some)
+}
+
+/// The recorded changes, oldest first.
+pub fn changes() -> Vec<ChangeRecord> {
+    HISTORY.with_borrow(|history| match history {
+        Some(history) => history.records.iter().cloned().collect(),
+        None => vec![],
+    })
+}
+
+/// Start travelling through the recorded changes, from the most recent one.
+/// Returns false if the changes are not being recorded.
+pub fn start_travel() -> bool {
+    HISTORY.with_borrow_mut(|history| {
+        let Some(history) = history else {
+            return false;
+        };
+        history.position.get_or_insert(history.records.len());
+        true
+    })
+}
+
+/// Stop travelling and resume recording.
+/// The changes after the current position are discarded.
+pub fn stop_travel() {
+    HISTORY.with_borrow_mut(|history| {
+        let Some(history) = history else { return };
+        if let Some(position) = history.position.take() {
+            history.records.truncate(position);
+        }
+    });
+}
+
+/// Returns true if travelling through the recorded changes.
+pub fn is_travelling() -> bool {
+    HISTORY.with_borrow(|history| history.as_ref().is_some_and(|h| h.position.is_some()))
+}
+
+/// The current position and the number of recorded changes,
+/// or `None` unless travelling.
+pub fn position() -> Option<(usize, usize)> {
+    HISTORY.with_borrow(|history| {
+        let history = history.as_ref()?;
+        Some((history.position?, history.records.len()))
+    })
+}
+
+/// Step backward (negative) or forward through the recorded changes,
+/// returning the values to write back to the state, in order.
+///
+/// Stepping backward produces the old values, and stepping forward the new ones.
+/// Changes without a path or values can't be replayed and are stepped over.
+///
+/// The position doesn't move until [`seek`] is called with the position of the step,
+/// once the values are written.
+pub fn step(steps: isize) -> Option<Step> {
+    HISTORY.with_borrow(|history| {
+        let history = history.as_ref()?;
+        let position = history.position?;
+
+        let target = position
+            .saturating_add_signed(steps)
+            .min(history.records.len());
+
+        let replay = |record: &ChangeRecord, old: bool| {
+            let (raw_old, raw_new) = record.raw.as_ref()?;
+            Some(Replay {
+                path: dotted(record.path.as_ref()?),
+                value: match old {
+                    true => raw_old.clone(),
+                    false => raw_new.clone(),
+                },
+            })
+        };
+
+        let replays = match target < position {
+            true => history
+                .records
+                .range(target..position)
+                .rev()
+                .filter_map(|record| replay(record, true))
+                .collect(),
+            false => history
+                .records
+                .range(position..target)
+                .filter_map(|record| replay(record, false))
+                .collect(),
+        };
+
+        Some(Step {
+            position: target,
+            replays,
+        })
+    })
+}
+
+/// Move to a position produced by [`step`].
+/// Does nothing unless travelling.
+pub fn seek(position: usize) {
+    HISTORY.with_borrow_mut(|history| {
+        let Some(history) = history else { return };
+        if history.position.is_some() {
+            history.position = Some(position.min(history.records.len()));
+        }
+    });
+}
+
+/// Write the recorded changes, one per line:
+/// `<microseconds> <path> <change> <node ids> [<old> -> <new>]`
+///
+/// e.g:
+/// `1532 user.name Update [[0, 1]] "Arthur" -> "Ford"`
+pub fn dump(mut output: impl Write) -> io::Result<()> {
+    for record in changes() {
+        let path = record.path.as_ref().map(dotted);
+        write!(
+            output,
+            "{} {} {:?} {:?}",
+            record.time.as_micros(),
+            path.as_deref().unwrap_or("-"),
+            record.change,
+            record
+                .node_ids
+                .iter()
+                .map(NodeId::as_slice)
+                .collect::<Vec<_>>(),
+        )?;
+
+        if let (Some(old), Some(new)) = (&record.old, &record.new) {
+            write!(output, " {old} -> {new}")?;
+        }
+        writeln!(output)?;
+    }
+    Ok(())
+}
+
+/// A path as it's written in a template, `list.0.name`
+pub fn dotted(path: &Path) -> String {
+    match path {
+        Path::Key(key) => key.clone(),
+        Path::Index(index) => index.to_string(),
+        Path::Composite(lhs, rhs) => format!("{}.{}", dotted(lhs), dotted(rhs)),
+    }
+}
+
+// Called by the resolver before reading a value from the state.
+// A key is appended to the path of the value that was read before it (`a.b`).
+pub(crate) fn read(path: &Path, append: bool) {
+    HISTORY.with_borrow_mut(|history| {
+        let Some(history) = history else { return };
+        history.reading = match history.reading.take() {
+            Some(lhs) if append => Some(lhs.compose(path.clone())),
+            _ => Some(path.clone()),
+        };
+    });
+}
+
+// The path of the value currently being read
+pub(crate) fn reading() -> Option<Path> {
+    HISTORY.with_borrow(|history| history.as_ref()?.reading.clone())
+}
+
+// The old and the new value of the next change
+pub(crate) fn values(values: Values) {
+    HISTORY.with_borrow_mut(|history| {
+        if let Some(history) = history {
+            history.values = Some(values);
+        }
+    });
+}
+
+pub(crate) fn record(path: Option<Path>, change: &Change, node_ids: Vec<NodeId>) {
+    HISTORY.with_borrow_mut(|history| {
+        let Some(history) = history else { return };
+        let values = history.values.take();
+        if history.capacity == 0 || history.position.is_some() {
+            return;
+        }
+
+        if history.records.len() == history.capacity {
+            history.records.pop_front();
+        }
+
+        history.records.push_back(ChangeRecord {
+            time: history.start.elapsed(),
+            path,
+            change: change.clone(),
+            old: values.as_ref().map(|values| values.old.clone()),
+            new: values.as_ref().map(|values| values.new.clone()),
+            raw: values.map(|values| (values.raw_old, values.raw_new)),
+            node_ids,
+        });
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestState;
+    use crate::{drain_dirty_nodes, Context, Immediate, ValueExpr};
+
+    #[test]
+    fn record_state_changes() {
+        record_changes(2);
+        let mut state = TestState::new();
+        let node_id = NodeId::from(0);
+        let expr = ValueExpr::Dot(
+            ValueExpr::Ident("inner".into()).into(),
+            ValueExpr::Ident("name".into()).into(),
+        );
+        {
+            let context = Context::root(&state);
+            let mut resolver = Immediate::new(context.lookup(), &node_id);
+            let _ = expr.eval(&mut resolver);
+        }
+
+        state.inner.name.set("Arthur".into());
+        *state.counter = 1;
+        let _ = drain_dirty_nodes();
+
+        let changes = changes();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].path.as_ref().map(dotted).unwrap(), "inner.name");
+        assert_eq!(changes[0].node_ids, vec![node_id]);
+        assert_eq!(changes[0].old.as_deref(), Some("\"Fiddle McStick\""));
+        assert_eq!(changes[0].new.as_deref(), Some("\"Arthur\""));
+
+        // The counter was never read
+        assert_eq!(changes[1].path, None);
+        assert!(changes[1].node_ids.is_empty());
+
+        // The oldest change is discarded
+        *state.counter = 2;
+        let changes = self::changes();
+        assert_eq!(changes.len(), 2);
+        assert!(changes[0].path.is_none());
+
+        let mut output = vec![];
+        dump(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.lines().all(|line| line.contains(" - Update []")));
+
+        stop_recording();
+        assert!(!is_recording());
+        assert!(self::changes().is_empty());
+    }
+
+    #[test]
+    fn time_travel() {
+        assert!(!start_travel());
+
+        record_changes(10);
+        let mut state = TestState::new();
+        let node_id = NodeId::from(0);
+        let expr = ValueExpr::Ident("name".into());
+        {
+            let context = Context::root(&state);
+            let mut resolver = Immediate::new(context.lookup(), &node_id);
+            let _ = expr.eval(&mut resolver);
+        }
+        state.name.set("Arthur".into());
+        state.name.set("Ford \"Prefect\"".into());
+        let _ = drain_dirty_nodes();
+
+        let replay = |value: &str| Replay {
+            path: "name".into(),
+            value: value.into(),
+        };
+        let step = |steps| {
+            let step = step(steps).unwrap();
+            seek(step.position);
+            step.replays
+        };
+
+        assert!(start_travel());
+        assert_eq!(position(), Some((2, 2)));
+        assert_eq!(step(-5), vec![replay("Arthur"), replay("Dirk Gently")]);
+        assert_eq!(position(), Some((0, 2)));
+
+        // Nothing is recorded while travelling
+        state.name.set("Dirk Gently".into());
+        assert_eq!(changes().len(), 2);
+
+        assert_eq!(step(1), vec![replay("Arthur")]);
+        assert_eq!(step(1), vec![replay("Ford \"Prefect\"")]);
+        assert!(step(1).is_empty());
+
+        step(-1);
+        stop_travel();
+        assert!(!is_travelling());
+        assert_eq!(changes().len(), 1);
+        stop_recording();
+    }
+
+    #[test]
+    fn replay_raw_values() {
+        record_changes(10);
+        let mut state = TestState::new();
+        let node_id = NodeId::from(0);
+        {
+            let context = Context::root(&state);
+            let mut resolver = Immediate::new(context.lookup(), &node_id);
+            let _ = ValueExpr::Ident("name".into()).eval(&mut resolver);
+        }
+        let escaped = "a\rb\0c\u{1b}d\\n";
+        state.name.set(escaped.into());
+        let _ = drain_dirty_nodes();
+
+        assert!(start_travel());
+        let back = super::step(-1).unwrap();
+        assert_eq!(back.position, 0);
+        assert_eq!(back.replays[0].value, "Dirk Gently");
+
+        // The position doesn't move until the values are written
+        assert_eq!(position(), Some((1, 1)));
+        seek(back.position);
+        assert_eq!(position(), Some((0, 1)));
+
+        let forward = super::step(1).unwrap();
+        assert_eq!(forward.replays[0].value, escaped);
+        stop_recording();
+        assert!(super::step(1).is_none());
+    }
+}
@@ -0,0 +1,124 @@
+// -----------------------------------------------------------------------------
+//   - Hover -
+//   The widget under the mouse cursor, set by the runtime.
+//
+//   Templates read the hover state of a widget through `hovered`,
+//   e.g `border [background: hovered ? #333 : #000]`.
+//   A widget is hovered if the widget itself or any of its
+//   descendants is under the mouse cursor.
+//
+//   When the hovered widget changes, only the nodes where
+//   `hovered` changed are marked as dirty.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+
+use crate::subscriber::Subscribers;
+use crate::{Change, NodeId};
+
+/// The name used to read the hover state in a template
+pub const HOVERED: &str = "hovered";
+
+thread_local! {
+    static HOVER: Hover = Hover::new();
+}
+
+struct Hover {
+    hovered: RefCell<Option<NodeId>>,
+    subscribers: Subscribers,
+}
+
+impl Hover {
+    fn new() -> Self {
+        Self {
+            hovered: RefCell::new(None),
+            subscribers: Subscribers::new(),
+        }
+    }
+}
+
+fn contains(node_id: &[usize], hovered: Option<&NodeId>) -> bool {
+    hovered.is_some_and(|hovered| hovered.0.starts_with(node_id))
+}
+
+/// Set the widget under the mouse cursor.
+pub fn set_hovered(node_id: Option<NodeId>) {
+    HOVER.with(|hover| {
+        let old = hover.hovered.replace(node_id);
+        let new = hover.hovered.borrow();
+        if *new == old {
+            return;
+        }
+
+        hover.subscribers.notify_if(Change::Update, |node_id| {
+            contains(node_id, old.as_ref()) != contains(node_id, new.as_ref())
+        });
+    });
+}
+
+/// The widget under the mouse cursor
+pub fn hovered() -> Option<NodeId> {
+    HOVER.with(|hover| hover.hovered.borrow().clone())
+}
+
+/// Returns true if the node, or any of its descendants, is hovered
+pub fn is_hovered(node_id: &NodeId) -> bool {
+    HOVER.with(|hover| contains(&node_id.0, hover.hovered.borrow().as_ref()))
+}
+
+/// Update the node when the hovered widget changes,
+/// e.g for a widget that paints itself differently when hovered.
+pub fn subscribe(node_id: NodeId) {
+    HOVER.with(|hover| hover.subscribers.subscribe(node_id));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::drain_dirty_nodes;
+
+    #[test]
+    fn notify_enter_and_leave() {
+        let parent = NodeId::from(vec![0]);
+        let child = parent.child(1);
+        let sibling = parent.child(2);
+        let other = NodeId::from(vec![1]);
+
+        let subscribe_all = || {
+            for node_id in [&parent, &child, &sibling, &other] {
+                subscribe(node_id.clone());
+            }
+        };
+
+        subscribe_all();
+        set_hovered(Some(child.clone()));
+        assert!(is_hovered(&parent));
+        assert!(is_hovered(&child));
+        assert!(!is_hovered(&sibling));
+
+        let mut dirty = drain_dirty_nodes();
+        dirty.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            dirty,
+            vec![
+                (parent.clone(), Change::Update),
+                (child.clone(), Change::Update)
+            ]
+        );
+
+        // Moving within the parent leaves the parent untouched
+        subscribe_all();
+        set_hovered(Some(sibling.clone()));
+        let mut dirty = drain_dirty_nodes();
+        dirty.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            dirty,
+            vec![
+                (child.clone(), Change::Update),
+                (sibling.clone(), Change::Update)
+            ]
+        );
+
+        set_hovered(None);
+        assert!(!is_hovered(&parent));
+    }
+}
@@ -0,0 +1,411 @@
+// -----------------------------------------------------------------------------
+//   - Translations -
+//   Templates reference messages by key (`text @greeting(name)`),
+//   and the message is looked up in the catalogue of the current locale.
+//
+//   Every node that resolved a message is subscribed to the locale,
+//   so switching locale marks the nodes as dirty and the messages
+//   are resolved again.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::path::Path;
+
+use crate::hashmap::HashMap;
+use crate::subscriber::Subscribers;
+use crate::{Change, NodeId, Num};
+
+thread_local! {
+    static TRANSLATIONS: Translations = Translations::new();
+}
+
+struct Translations {
+    locale: RefCell<String>,
+    catalogues: RefCell<HashMap<String, Catalogue>>,
+    subscribers: Subscribers,
+}
+
+impl Translations {
+    fn new() -> Self {
+        Self {
+            locale: RefCell::new(String::new()),
+            catalogues: RefCell::new(HashMap::default()),
+            subscribers: Subscribers::new(),
+        }
+    }
+}
+
+/// Error loading a catalogue
+#[derive(Debug)]
+pub enum CatalogueError {
+    Io(std::io::Error),
+    Parse { line: usize, msg: String },
+}
+
+impl Display for CatalogueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Parse { line, msg } => write!(f, "line {line}: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for CatalogueError {}
+
+impl From<std::io::Error> for CatalogueError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Messages of a single locale.
+///
+/// The format is one message per line, where lines starting
+/// with `#` are comments:
+///
+/// ```text
+/// # Greetings
+/// greeting = Hello, {name}!
+/// farewell = Goodbye, { $name }
+///     see you soon.
+/// ```
+///
+/// Indented lines continue the previous message on a new line,
+/// so simple Fluent files can be loaded as they are.
+#[derive(Debug, Default, Clone)]
+pub struct Catalogue {
+    messages: HashMap<String, String>,
+}
+
+impl Catalogue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a catalogue from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CatalogueError> {
+        let src = std::fs::read_to_string(path)?;
+        Self::parse(&src)
+    }
+
+    /// Parse a catalogue.
+    pub fn parse(src: &str) -> Result<Self, CatalogueError> {
+        let mut catalogue = Self::new();
+        let mut last_key: Option<&str> = None;
+
+        for (line_no, line) in src.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let err = |msg: &str| CatalogueError::Parse {
+                line: line_no + 1,
+                msg: msg.into(),
+            };
+
+            if line.starts_with(char::is_whitespace) {
+                let key = last_key.ok_or_else(|| err("continuation without a message"))?;
+                let message = catalogue.messages.get_mut(key).expect("inserted below");
+                if !message.is_empty() {
+                    message.push('\n');
+                }
+                message.push_str(line.trim());
+                continue;
+            }
+
+            let (key, message) = line.split_once('=').ok_or_else(|| err("missing `=`"))?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(err("missing message key"));
+            }
+
+            catalogue.insert(key, message.trim());
+            last_key = Some(key);
+        }
+
+        Ok(catalogue)
+    }
+
+    /// Insert a message, replacing any existing message with the same key.
+    pub fn insert(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.messages.insert(key.into(), message.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+}
+
+/// Add a catalogue for a locale.
+/// Messages are merged into any existing catalogue of the locale.
+///
+/// Nodes showing messages of the current locale are updated.
+pub fn add_catalogue(locale: &str, catalogue: Catalogue) {
+    TRANSLATIONS.with(|translations| {
+        translations
+            .catalogues
+            .borrow_mut()
+            .entry(locale.to_string())
+            .or_default()
+            .messages
+            .extend(catalogue.messages);
+
+        if *translations.locale.borrow() == locale {
+            translations.subscribers.notify(Change::Update);
+        }
+    });
+}
+
+/// Switch the current locale, e.g `en-GB`.
+///
+/// Nodes showing messages are updated.
+pub fn set_locale(locale: &str) {
+    TRANSLATIONS.with(|translations| {
+        if *translations.locale.borrow() == locale {
+            return;
+        }
+
+        *translations.locale.borrow_mut() = locale.to_string();
+        translations.subscribers.notify(Change::Update);
+    });
+}
+
+/// The current locale
+pub fn locale() -> String {
+    TRANSLATIONS.with(|translations| translations.locale.borrow().clone())
+}
+
+// Update the node when the locale changes
+pub(crate) fn subscribe(node_id: NodeId) {
+    TRANSLATIONS.with(|translations| translations.subscribers.subscribe(node_id));
+}
+
+/// Translate a message in the current locale.
+///
+/// If the locale has no such message the language is tried (`en` for `en-GB`),
+/// and if that fails as well the key is returned as it is.
+///
+/// Placeholders are replaced by the arguments, either by name (`{name}` or
+/// Fluent style `{ $name }`) or by position (`{0}`).
+/// Use `{{` for a literal `{`.
+pub fn translate(key: &str, args: &[(Option<&str>, String)]) -> String {
+    let message = TRANSLATIONS.with(|translations| {
+        let locale = translations.locale.borrow();
+        let catalogues = translations.catalogues.borrow();
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+
+        let message = [locale.as_str(), language]
+            .into_iter()
+            .find_map(|locale| catalogues.get(locale)?.get(key).map(str::to_string));
+        message
+    });
+
+    match message {
+        Some(message) => format(&message, args),
+        None => key.to_string(),
+    }
+}
+
+/// The index of the plural form to use for a count in the current locale.
+///
+/// Forms are given in the order of the plural categories of the language,
+/// e.g `one, other` for English and `one, few, many` for Russian.
+/// If there are fewer forms than categories the last form is used.
+pub fn plural_form(count: Num, forms: usize) -> usize {
+    let locale = locale();
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+
+    let index = match count {
+        // Fractions use the last form ("1.5 items")
+        Num::Float(f) if f.fract() != 0.0 => usize::MAX,
+        Num::Float(f) => plural_rule(language, f.abs() as u64),
+        Num::Signed(n) => plural_rule(language, n.unsigned_abs()),
+        Num::Unsigned(n) => plural_rule(language, n),
+    };
+
+    index.min(forms.saturating_sub(1))
+}
+
+fn plural_rule(language: &str, n: u64) -> usize {
+    let (ones, tens) = (n % 10, n % 100);
+
+    match language {
+        // One form
+        "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" => 0,
+        // Zero and one are singular
+        "fr" | "pt" | "hi" => match n {
+            0 | 1 => 0,
+            _ => 1,
+        },
+        // one, few, many
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" => match (ones, tens) {
+            (1, t) if t != 11 => 0,
+            (2..=4, t) if !(12..=14).contains(&t) => 1,
+            _ => 2,
+        },
+        "pl" => match (n, ones, tens) {
+            (1, ..) => 0,
+            (_, 2..=4, t) if !(12..=14).contains(&t) => 1,
+            _ => 2,
+        },
+        "cs" | "sk" => match n {
+            1 => 0,
+            2..=4 => 1,
+            _ => 2,
+        },
+        // one, other
+        _ => match n {
+            1 => 0,
+            _ => 1,
+        },
+    }
+}
+
+fn format(message: &str, args: &[(Option<&str>, String)]) -> String {
+    let mut output = String::with_capacity(message.len());
+    let mut rest = message;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        if let Some(escaped) = rest.strip_prefix('{') {
+            output.push('{');
+            rest = escaped;
+            continue;
+        }
+
+        let Some(end) = rest.find('}') else {
+            output.push('{');
+            continue;
+        };
+
+        let placeholder = rest[..end].trim();
+        let name = placeholder.strip_prefix('$').unwrap_or(placeholder);
+        let arg = match name.parse::<usize>() {
+            Ok(index) => args.get(index),
+            Err(_) => args.iter().find(|(arg, _)| *arg == Some(name)),
+        };
+
+        match arg {
+            Some((_, value)) => output.push_str(value),
+            // Unknown placeholders are left as they are
+            None => {
+                output.push('{');
+                output.push_str(&rest[..=end]);
+            }
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::drain_dirty_nodes;
+    use crate::testing::{dot, ident, message, strlit};
+
+    #[test]
+    fn parse_catalogue() {
+        let catalogue = Catalogue::parse(
+            "
+            # comment
+greeting = Hello, {name}!
+farewell = Goodbye
+    see you soon
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(catalogue.get("greeting"), Some("Hello, {name}!"));
+        assert_eq!(catalogue.get("farewell"), Some("Goodbye\nsee you soon"));
+
+        let err = Catalogue::parse("greeting").unwrap_err();
+        assert!(matches!(err, CatalogueError::Parse { line: 1, .. }));
+    }
+
+    #[test]
+    fn format_placeholders() {
+        let args = [
+            (Some("name"), "Fiddle".to_string()),
+            (None, "3".to_string()),
+        ];
+        assert_eq!(format("Hi {name}", &args), "Hi Fiddle");
+        assert_eq!(format("Hi { $name }", &args), "Hi Fiddle");
+        assert_eq!(format("{1} items", &args), "3 items");
+        assert_eq!(format("{{name}}", &args), "{name}}");
+        assert_eq!(format("Hi {unknown}", &args), "Hi {unknown}");
+    }
+
+    #[test]
+    fn locale_fallback() {
+        let mut catalogue = Catalogue::new();
+        catalogue.insert("colour", "colour");
+        add_catalogue("en", catalogue);
+
+        let mut catalogue = Catalogue::new();
+        catalogue.insert("colour", "color");
+        add_catalogue("en-US", catalogue);
+
+        set_locale("en-US");
+        assert_eq!(translate("colour", &[]), "color");
+        set_locale("en-GB");
+        assert_eq!(translate("colour", &[]), "colour");
+        assert_eq!(translate("missing", &[]), "missing");
+    }
+
+    #[test]
+    fn switch_locale() {
+        let mut catalogue = Catalogue::new();
+        catalogue.insert("greeting", "Hello, {name}! ({1})");
+        add_catalogue("en", catalogue);
+        let mut catalogue = Catalogue::new();
+        catalogue.insert("greeting", "Hej, {name}! ({1})");
+        add_catalogue("sv", catalogue);
+
+        set_locale("en");
+        let expr = message(
+            "greeting",
+            vec![dot(ident("user"), ident("name")), strlit("x")],
+        );
+        let test = expr.with_data([("user", crate::Map::new([("name", "Fiddle".to_string())]))]);
+        test.expect_string("Hello, Fiddle! (x)");
+        assert!(drain_dirty_nodes().is_empty());
+
+        // The node resolving the message is updated
+        set_locale("sv");
+        assert_eq!(drain_dirty_nodes().len(), 1);
+        test.expect_string("Hej, Fiddle! (x)");
+    }
+
+    #[test]
+    fn plural_rules() {
+        let forms = |counts: &[u64], total| {
+            counts
+                .iter()
+                .map(|n| plural_form(Num::Unsigned(*n), total))
+                .collect::<Vec<_>>()
+        };
+
+        set_locale("en-GB");
+        assert_eq!(forms(&[0, 1, 2, 11], 2), [1, 0, 1, 1]);
+        assert_eq!(plural_form(Num::Signed(-1), 2), 0);
+        assert_eq!(plural_form(Num::Float(1.5), 2), 1);
+
+        set_locale("fr");
+        assert_eq!(forms(&[0, 1, 2], 2), [0, 0, 1]);
+
+        set_locale("ru");
+        assert_eq!(forms(&[1, 2, 5, 11, 21, 22, 112], 3), [0, 1, 2, 2, 0, 1, 2]);
+        // Missing forms fall back to the last form
+        assert_eq!(forms(&[1, 2, 5], 2), [0, 1, 1]);
+
+        set_locale("ja");
+        assert_eq!(forms(&[1, 2], 2), [0, 0]);
+    }
+}
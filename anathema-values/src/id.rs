@@ -18,73 +18,34 @@ impl NextNodeId {
     }
 }
 
+/// The id of a node: the path of indices from the root node to the node.
+///
+/// Node ids are cloned for every child and every subscription,
+/// so the path is shared (cloning is a reference count increment).
 #[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
-// #[repr(transparent)]
-// TODO: This could possibly be Rc<[usize]> instead,
-//       or even Arc<[usize]>, given that both the `WidgetContainer`
-//       and the wrapping `Node` has the same id, and these ids are
-//       shared with the ids tracking the changes.
-// #[derive(PartialOrd, Ord)]
 pub struct NodeId(pub Arc<[usize]>);
-// pub struct NodeId(pub Vec<usize>);
 
 impl NodeId {
     pub fn new(id: usize) -> Self {
-        Self(vec![id].into())
+        Self(Arc::new([id]))
     }
 
+    /// Returns true if `other` is this node or a descendant of this node.
     pub fn contains(&self, other: &[usize]) -> bool {
-        *self.0 == other[..self.0.len()]
+        other.starts_with(&self.0)
     }
 
     pub fn last(&self) -> usize {
         self.0[self.0.len() - 1]
     }
 
-    // pub fn next(&self) -> NodeId {
-    //     let mut child = self.0.to_vec();
-    //     if let Some(v) = child.last_mut() {
-    //         *v += 1;
-    //     }
-    //     Self(child.into())
-    // }
-
-    // pub fn next(&mut self) -> NodeId {
-    //     let ret = NodeId(self.0.clone());
-    //     if let Some(v) = self.0.last_mut() {
-    //         *v += 1;
-    //     }
-    //     ret
-    // }
-
-    //     pub fn child(&self, next: usize) -> Self {
-    //         let mut v = Vec::with_capacity(self.0.len() + 1);
-    //         v.extend_from_slice(&*self.0);
-    //         v.push(next);
-    //         Self(v.into())
-    //     }
-
-    //     pub fn as_slice(&self) -> &[usize] {
-    //         &self.0
-    //     }
-}
-
-impl NodeId {
-    // pub fn new(id: usize) -> Self {
-    //     Self(vec![id])
-    // }
-
-    // pub fn contains(&self, other: &[usize]) -> bool {
-    //     *self.0 == other[..self.0.len()]
-    // }
-
-    // pub fn next(&mut self) -> NodeId {
-    //     let ret = NodeId(self.0.clone());
-    //     if let Some(v) = self.0.last_mut() {
-    //         *v += 1;
-    //     }
-    //     ret
-    // }
+    /// The id of the parent node, or `None` for a root node.
+    pub fn parent(&self) -> Option<Self> {
+        match &*self.0 {
+            [] | [_] => None,
+            [parent @ .., _] => Some(Self(parent.into())),
+        }
+    }
 
     pub fn child(&self, next: usize) -> Self {
         let mut v = Vec::with_capacity(self.0.len() + 1);
@@ -115,3 +76,25 @@ impl From<usize> for NodeId {
         Self::new(value)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn contains() {
+        let parent = NodeId::from(vec![0, 1]);
+        assert!(parent.contains(&[0, 1]));
+        assert!(parent.contains(&[0, 1, 2, 3]));
+        assert!(!parent.contains(&[0, 2, 1]));
+        assert!(!parent.contains(&[0]));
+    }
+
+    #[test]
+    fn parent_and_child() {
+        let node_id = NodeId::new(0).child(1).child(2);
+        assert_eq!(node_id.as_slice(), &[0, 1, 2]);
+        assert_eq!(node_id.parent(), Some(NodeId::from(vec![0, 1])));
+        assert_eq!(NodeId::new(0).parent(), None);
+    }
+}
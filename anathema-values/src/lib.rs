@@ -1,28 +1,45 @@
 use std::cell::RefCell;
+use std::collections::HashSet;
 
 pub use anathema_value_derive::State;
 
 pub use self::collection::Collection;
 pub use self::id::{NextNodeId, NodeId};
 pub use self::list::List;
-pub use self::map::Map;
-pub use self::path::Path;
+pub use self::map::{Errors, Map};
+pub use self::path::{Path, PathId};
+pub use self::provider::{DataProvider, LazyList, Loader};
 pub use self::scope::{Context, Scope, ScopeStorage, ScopeValue};
+pub use self::selection::Selection;
 pub use self::slab::Slab;
 pub use self::state::{Change, State, StateValue};
+pub use self::subscriber::{subscription_count, subscriptions_memory};
+pub use self::symbol::Symbol;
 pub use self::value::{ExpressionMap, Expressions, Num, Owned, ValueRef};
 pub use self::value_expr::{Deferred, Immediate, Resolver, ValueExpr};
 
+pub mod functions;
+pub mod fuzzy;
 pub mod hashmap;
+pub mod history;
+pub mod hover;
+pub mod i18n;
 mod path;
+pub mod search;
+pub mod spans;
 
 mod collection;
 mod id;
 mod list;
 mod map;
+mod provider;
 mod scope;
+mod selection;
 mod slab;
 pub mod state;
+mod subscriber;
+mod symbol;
+pub mod transaction;
 mod value;
 mod value_expr;
 
@@ -34,14 +51,53 @@ extern crate self as anathema;
 #[allow(unused_imports)]
 pub use crate as values;
 
-pub type Attributes = hashmap::HashMap<String, ValueExpr>;
+pub type Attributes = hashmap::HashMap<Symbol, ValueExpr>;
+
+/// The attribute key of a map spread into the attributes, `border [..panel_style]`.
+/// Attributes set on the widget take precedence over the keys of the map.
+pub const SPREAD_ATTRIBUTE: &str = "..";
 
 thread_local! {
     static DIRTY_NODES: RefCell<Vec<(NodeId, Change)>> = Default::default();
+    static REMOVED_NODES: RefCell<HashSet<NodeId>> = Default::default();
+}
+
+/// Returns true if there are changes that haven't been drained
+pub fn has_dirty_nodes() -> bool {
+    DIRTY_NODES.with_borrow(|nodes| !nodes.is_empty())
 }
 
+/// Drain all changes.
+/// Changes for nodes that have been removed are discarded.
 pub fn drain_dirty_nodes() -> Vec<(NodeId, Change)> {
-    DIRTY_NODES.with(|nodes| nodes.borrow_mut().drain(..).collect())
+    REMOVED_NODES.with_borrow(|removed| {
+        DIRTY_NODES.with(|nodes| {
+            nodes
+                .borrow_mut()
+                .drain(..)
+                .filter(|(node_id, _)| !removed.contains(node_id))
+                .collect()
+        })
+    })
+}
+
+/// Mark a node as removed.
+/// Any change to a value the node subscribed to will no longer reach the node.
+pub fn remove_node(node_id: NodeId) {
+    REMOVED_NODES.with_borrow_mut(|removed| removed.insert(node_id));
+}
+
+/// Returns true if the node has been removed since the last
+/// call to [`drain_removed_nodes`].
+pub fn is_removed(node_id: &NodeId) -> bool {
+    REMOVED_NODES.with_borrow(|removed| removed.contains(node_id))
+}
+
+/// Drain all removed nodes.
+/// This should be done once all changes have been applied, as the
+/// node ids can be reused by new nodes.
+pub fn drain_removed_nodes() -> Vec<NodeId> {
+    REMOVED_NODES.with_borrow_mut(|removed| removed.drain().collect())
 }
 
 #[cfg(any(feature = "testing", test))]
@@ -272,6 +328,168 @@ impl DynValue for anathema_render::Color {
 
 // impl_dyn_value!(anathema_render::Color);
 
+// A list of numbers, from a list in the state or a list expression (e.g `[1, 2, 3]`).
+// Values that are not numbers are skipped.
+impl DynValue for Vec<f64> {
+    fn init_value(context: &Context<'_, '_>, node_id: &NodeId, expr: &ValueExpr) -> Value<Self> {
+        let mut resolver = Immediate::new(context.lookup(), node_id);
+        let inner = numbers(expr.eval(&mut resolver), &mut resolver, node_id);
+
+        match resolver.is_deferred() {
+            true => Value::Dyn {
+                inner,
+                expr: expr.clone(),
+            },
+            false => match inner {
+                Some(val) => Value::Static(val),
+                None => Value::Empty,
+            },
+        }
+    }
+
+    fn resolve(value: &mut Value<Self>, context: &Context<'_, '_>, node_id: &NodeId) {
+        if let Value::Dyn { inner, expr } = value {
+            let mut resolver = Immediate::new(context.lookup(), node_id);
+            *inner = numbers(expr.eval(&mut resolver), &mut resolver, node_id);
+        }
+    }
+}
+
+// A grid of numbers, from a list of lists (e.g `[[1, 2], [3, 4]]`).
+// Rows that are not lists are empty.
+impl DynValue for Vec<Vec<f64>> {
+    fn init_value(context: &Context<'_, '_>, node_id: &NodeId, expr: &ValueExpr) -> Value<Self> {
+        let mut resolver = Immediate::new(context.lookup(), node_id);
+        let inner = grid(expr.eval(&mut resolver), &mut resolver, node_id);
+
+        match resolver.is_deferred() {
+            true => Value::Dyn {
+                inner,
+                expr: expr.clone(),
+            },
+            false => match inner {
+                Some(val) => Value::Static(val),
+                None => Value::Empty,
+            },
+        }
+    }
+
+    fn resolve(value: &mut Value<Self>, context: &Context<'_, '_>, node_id: &NodeId) {
+        if let Value::Dyn { inner, expr } = value {
+            let mut resolver = Immediate::new(context.lookup(), node_id);
+            *inner = grid(expr.eval(&mut resolver), &mut resolver, node_id);
+        }
+    }
+}
+
+// A list of texts, from a list in the state or a list expression.
+// Values that are not strings are formatted, and maps and lists are empty,
+// so every text has the index of its value.
+impl DynValue for Vec<String> {
+    fn init_value(context: &Context<'_, '_>, node_id: &NodeId, expr: &ValueExpr) -> Value<Self> {
+        let mut resolver = Immediate::new(context.lookup(), node_id);
+        let inner = texts(expr.eval(&mut resolver), &mut resolver, node_id);
+
+        match resolver.is_deferred() {
+            true => Value::Dyn {
+                inner,
+                expr: expr.clone(),
+            },
+            false => match inner {
+                Some(val) => Value::Static(val),
+                None => Value::Empty,
+            },
+        }
+    }
+
+    fn resolve(value: &mut Value<Self>, context: &Context<'_, '_>, node_id: &NodeId) {
+        if let Value::Dyn { inner, expr } = value {
+            let mut resolver = Immediate::new(context.lookup(), node_id);
+            *inner = texts(expr.eval(&mut resolver), &mut resolver, node_id);
+        }
+    }
+}
+
+fn texts<'expr>(
+    value: ValueRef<'expr>,
+    resolver: &mut Immediate<'expr>,
+    node_id: &NodeId,
+) -> Option<Vec<String>> {
+    let text = |value: ValueRef<'_>| match value {
+        ValueRef::Str(s) => s.to_string(),
+        ValueRef::Owned(owned) => owned.to_string(),
+        _ => String::new(),
+    };
+
+    match value {
+        ValueRef::List(list) => {
+            list.subscribe(node_id.clone());
+            let texts = (0..list.len())
+                .map(|index| text(list.state_get(&Path::Index(index), node_id)))
+                .collect();
+            Some(texts)
+        }
+        ValueRef::Expressions(Expressions(list)) => {
+            Some(list.iter().map(|expr| text(expr.eval(resolver))).collect())
+        }
+        _ => None,
+    }
+}
+
+fn grid<'expr>(
+    value: ValueRef<'expr>,
+    resolver: &mut Immediate<'expr>,
+    node_id: &NodeId,
+) -> Option<Vec<Vec<f64>>> {
+    match value {
+        ValueRef::List(list) => {
+            list.subscribe(node_id.clone());
+            let rows = (0..list.len())
+                .map(|index| {
+                    let row = list.state_get(&Path::Index(index), node_id);
+                    numbers(row, resolver, node_id).unwrap_or_default()
+                })
+                .collect();
+            Some(rows)
+        }
+        ValueRef::Expressions(Expressions(list)) => {
+            let rows = list
+                .iter()
+                .map(|expr| numbers(expr.eval(resolver), resolver, node_id).unwrap_or_default())
+                .collect();
+            Some(rows)
+        }
+        _ => None,
+    }
+}
+
+fn numbers<'expr>(
+    value: ValueRef<'expr>,
+    resolver: &mut Immediate<'expr>,
+    node_id: &NodeId,
+) -> Option<Vec<f64>> {
+    let number = |value: ValueRef<'_>| match value {
+        ValueRef::Owned(Owned::Num(num)) => Some(num.to_f64()),
+        _ => None,
+    };
+
+    match value {
+        ValueRef::List(list) => {
+            list.subscribe(node_id.clone());
+            let numbers = (0..list.len())
+                .filter_map(|index| number(list.state_get(&Path::Index(index), node_id)))
+                .collect();
+            Some(numbers)
+        }
+        ValueRef::Expressions(Expressions(list)) => Some(
+            list.iter()
+                .filter_map(|expr| number(expr.eval(resolver)))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
 impl_dyn_value!(usize);
 impl_dyn_value!(u64);
 impl_dyn_value!(u32);
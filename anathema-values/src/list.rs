@@ -1,15 +1,15 @@
-use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::ops::{Deref, Index, IndexMut};
 
 use crate::state::State;
-use crate::{Change, Collection, NodeId, Path, StateValue, ValueRef, DIRTY_NODES};
+use crate::subscriber::Subscribers;
+use crate::{Change, Collection, NodeId, Path, StateValue, ValueRef};
 
 #[derive(Debug)]
 pub struct List<T> {
     inner: VecDeque<StateValue<T>>,
-    subscribers: RefCell<Vec<NodeId>>,
+    subscribers: Subscribers,
 }
 
 impl<T> List<T> {
@@ -20,7 +20,7 @@ impl<T> List<T> {
     pub fn new(inner: impl IntoIterator<Item = T>) -> Self {
         Self {
             inner: inner.into_iter().map(StateValue::new).collect(),
-            subscribers: RefCell::new(vec![]),
+            subscribers: Subscribers::new(),
         }
     }
 
@@ -35,64 +35,63 @@ impl<T> List<T> {
     pub fn pop_front(&mut self) -> Option<StateValue<T>> {
         let ret = self.inner.pop_front()?;
         let index = self.inner.len();
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::RemoveIndex(index)))
-            });
-        }
+        self.subscribers.notify(Change::RemoveIndex(index));
         Some(ret)
     }
 
     pub fn pop_back(&mut self) -> Option<StateValue<T>> {
         let ret = self.inner.pop_back()?;
         let index = self.inner.len();
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::RemoveIndex(index)))
-            });
-        }
+        self.subscribers.notify(Change::RemoveIndex(index));
         Some(ret)
     }
 
     pub fn remove(&mut self, index: usize) -> Option<StateValue<T>> {
         let ret = self.inner.remove(index);
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::RemoveIndex(index)))
-            });
-        }
+        self.subscribers.notify(Change::RemoveIndex(index));
         ret
     }
 
     pub fn push_front(&mut self, value: T) {
         self.inner.push_front(StateValue::new(value));
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| nodes.borrow_mut().push((s.clone(), Change::InsertIndex(0))));
-        }
+        self.subscribers.notify(Change::InsertIndex(0));
     }
 
     pub fn push_back(&mut self, value: T) {
         self.inner.push_back(StateValue::new(value));
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| nodes.borrow_mut().push((s.clone(), Change::Push)));
-        }
+        self.subscribers.notify(Change::Push);
     }
 
     pub fn insert(&mut self, index: usize, value: T) {
         self.inner.insert(index, StateValue::new(value));
+        self.subscribers.notify(Change::InsertIndex(index));
+    }
+
+    /// Swap two items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.inner.swap(a, b);
+        if a != b {
+            self.subscribers.notify(Change::Swap(a, b));
+        }
+    }
 
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::InsertIndex(index)))
-            });
+    /// Move an item from one index to another, shifting the items in between.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn move_index(&mut self, from: usize, to: usize) {
+        assert!(to < self.inner.len(), "index out of bounds");
+        let Some(value) = self.inner.remove(from) else {
+            panic!("index out of bounds");
+        };
+        self.inner.insert(to, value);
+        if from != to {
+            self.subscribers.notify(Change::Move(from, to));
         }
     }
 }
@@ -115,7 +114,7 @@ where
     }
 
     fn subscribe(&self, node_id: NodeId) {
-        self.subscribers.borrow_mut().push(node_id);
+        self.subscribers.subscribe(node_id);
     }
 }
 
@@ -160,7 +159,7 @@ impl<T> IndexMut<usize> for List<T> {
 mod test {
     use super::*;
     use crate::testing::TestState;
-    use crate::Owned;
+    use crate::{drain_dirty_nodes, Owned};
 
     #[test]
     fn access_list() {
@@ -173,6 +172,25 @@ mod test {
         assert_eq!(x.to_i128(), 2);
     }
 
+    #[test]
+    fn swap_and_move() {
+        let mut list = List::new(vec![1, 2, 3, 4]);
+        let node_id: NodeId = 0.into();
+        list.subscribe(node_id.clone());
+        list.swap(0, 3);
+        assert_eq!(
+            vec![(node_id.clone(), Change::Swap(0, 3))],
+            drain_dirty_nodes()
+        );
+
+        list.subscribe(node_id.clone());
+        list.move_index(0, 2);
+        assert_eq!(vec![(node_id, Change::Move(0, 2))], drain_dirty_nodes());
+
+        let items = (0..list.len()).map(|i| list[i]).collect::<Vec<_>>();
+        assert_eq!(items, vec![2, 3, 4, 1]);
+    }
+
     #[test]
     fn create_list() {
         let _list = List::new(vec![1, 2, 3]);
@@ -1,15 +1,22 @@
-use std::cell::RefCell;
 use std::fmt::Debug;
 use std::ops::{Deref, DerefMut};
 
 use crate::hashmap::HashMap;
 use crate::state::State;
-use crate::{Change, Collection, NodeId, Path, StateValue, ValueRef, DIRTY_NODES};
+use crate::subscriber::Subscribers;
+use crate::{Change, Collection, NodeId, Path, StateValue, ValueRef};
+
+/// Validation errors, keyed by the name of the field.
+///
+/// By convention the errors are stored in the `errors` field of the state,
+/// where a widget with a `field` attribute reads the error of its field,
+/// see `FactoryContext::field_error`.
+pub type Errors = Map<String>;
 
 #[derive(Debug)]
 pub struct Map<T> {
     inner: HashMap<String, StateValue<T>>,
-    subscribers: RefCell<Vec<NodeId>>,
+    subscribers: Subscribers,
 }
 
 impl<T> Map<T> {
@@ -23,35 +30,23 @@ impl<T> Map<T> {
             .map(|(k, v)| (k.into(), StateValue::new(v)));
         Self {
             inner: HashMap::from_iter(inner),
-            subscribers: RefCell::new(vec![]),
+            subscribers: Subscribers::new(),
         }
     }
 
     pub fn subscribe(&self, node_id: NodeId) {
-        self.subscribers.borrow_mut().push(node_id);
+        self.subscribers.subscribe(node_id);
     }
 
     pub fn remove(&mut self, key: String) -> Option<StateValue<T>> {
         let ret = self.inner.remove(&key);
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::RemoveKey(key.clone())))
-            });
-        }
+        self.subscribers.notify(Change::RemoveKey(key));
         ret
     }
 
     pub fn insert(&mut self, key: String, value: T) {
         self.inner.insert(key.clone(), StateValue::new(value));
-        for s in self.subscribers.borrow_mut().drain(..) {
-            DIRTY_NODES.with(|nodes| {
-                nodes
-                    .borrow_mut()
-                    .push((s.clone(), Change::InsertKey(key.clone())))
-            });
-        }
+        self.subscribers.notify(Change::InsertKey(key.clone()));
     }
 
     pub fn get(&self, key: &str) -> Option<&T> {
@@ -94,6 +89,9 @@ where
         match key {
             Path::Key(key) => {
                 let Some(value) = self.inner.get(key) else {
+                    // Subscribe to the map instead,
+                    // to be notified when the key is inserted
+                    self.subscribe(node_id.clone());
                     return ValueRef::Empty;
                 };
                 value.subscribe(node_id.clone());
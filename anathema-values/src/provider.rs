@@ -0,0 +1,412 @@
+// -----------------------------------------------------------------------------
+//   - Data provider -
+//   A lazy list fetches its items from a data provider, a page at a time,
+//   as the items are accessed from the templates.
+//
+//   The provider can deliver the items immediately, or at a later stage
+//   from another thread (or an async task) using the `Loader`.
+//   Delivered items are applied to the list on `LazyList::poll`, which
+//   should be called from the main thread (e.g `View::tick`).
+//
+//   Items still in flight when the list is reloaded are discarded:
+//   every loader belongs to a generation of the list, and reloading
+//   starts a new generation.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt::{self, Debug};
+use std::ops::{Deref, Range};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use crate::hashmap::IntMap;
+use crate::state::State;
+use crate::subscriber::Subscribers;
+use crate::{Change, Collection, NodeId, Path, StateValue, ValueRef, DIRTY_NODES};
+
+const DEFAULT_PAGE_SIZE: usize = 32;
+
+/// Provide items for a [`LazyList`] on demand.
+pub trait DataProvider<T> {
+    /// The total number of items the provider can provide.
+    fn len(&self) -> usize;
+
+    /// Fetch the items in the given range.
+    /// Items are delivered through the [`Loader`], either before this function
+    /// returns or at a later stage from another thread.
+    ///
+    /// The items should be delivered at once. Items of the range
+    /// that are not delivered are considered missing.
+    fn fetch(&mut self, range: Range<usize>, loader: Loader<T>);
+}
+
+// The generation of the list, the offset and the items
+type Delivery<T> = (usize, usize, Vec<T>);
+
+/// Deliver items to a [`LazyList`].
+pub struct Loader<T> {
+    sender: Sender<Delivery<T>>,
+    generation: usize,
+}
+
+impl<T> Loader<T> {
+    /// Deliver the items, starting at `offset`.
+    pub fn load(&self, offset: usize, items: Vec<T>) {
+        // If the receiving end is gone there is nothing
+        // to deliver the items to.
+        let _ = self.sender.send((self.generation, offset, items));
+    }
+}
+
+impl<T> Clone for Loader<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            generation: self.generation,
+        }
+    }
+}
+
+/// A collection where the items are loaded on demand from a [`DataProvider`].
+///
+/// Accessing an item that is not yet loaded will return the placeholder (if any),
+/// and the accessing node will be notified once the item arrives.
+pub struct LazyList<T> {
+    provider: Box<dyn DataProvider<T>>,
+    items: Vec<Option<StateValue<T>>>,
+    placeholder: Option<T>,
+    page_size: usize,
+    loader: Sender<Delivery<T>>,
+    arrivals: Receiver<Delivery<T>>,
+    generation: usize,
+    // The fetched pages, and whether they are delivered
+    requested_pages: IntMap<bool>,
+    // The nodes waiting for an item
+    pending: RefCell<IntMap<HashSet<NodeId>>>,
+    subscribers: Subscribers,
+}
+
+impl<T> LazyList<T> {
+    pub fn new(provider: impl DataProvider<T> + 'static) -> Self {
+        let (loader, arrivals) = channel();
+        Self {
+            provider: Box::new(provider),
+            items: vec![],
+            placeholder: None,
+            page_size: DEFAULT_PAGE_SIZE,
+            loader,
+            arrivals,
+            generation: 0,
+            requested_pages: IntMap::default(),
+            pending: RefCell::new(IntMap::default()),
+            subscribers: Subscribers::new(),
+        }
+    }
+
+    /// Value to use in place of items that are still loading.
+    pub fn with_placeholder(mut self, placeholder: T) -> Self {
+        self.placeholder = Some(placeholder);
+        self
+    }
+
+    /// Number of items to fetch from the provider at a time.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.provider.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get a loaded item
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.items.get(index)?.as_deref()
+    }
+
+    /// Fetch the pages for any item accessed since the last poll,
+    /// and apply all items delivered by the provider.
+    pub fn poll(&mut self) {
+        let pending = self.pending.borrow().keys().copied().collect::<Vec<_>>();
+        for index in pending {
+            let page = index / self.page_size;
+            if self.requested_pages.contains_key(&page) {
+                continue;
+            }
+            self.requested_pages.insert(page, false);
+            let loader = Loader {
+                sender: self.loader.clone(),
+                generation: self.generation,
+            };
+            self.provider.fetch(self.page_range(page), loader);
+        }
+
+        while let Ok((generation, offset, items)) = self.arrivals.try_recv() {
+            // Fetched before the list was reloaded
+            if generation != self.generation {
+                continue;
+            }
+
+            let end = offset + items.len();
+            for (index, item) in (offset..).zip(items) {
+                if self.items.len() <= index {
+                    self.items.resize_with(index + 1, || None);
+                }
+                self.items[index] = Some(StateValue::new(item));
+                self.notify_pending(index);
+            }
+
+            // The items of the page that were not delivered are missing
+            let page = offset / self.page_size;
+            if let Some(delivered) = self.requested_pages.get_mut(&page) {
+                *delivered = true;
+                for index in end..self.page_range(page).end {
+                    self.notify_pending(index);
+                }
+            }
+        }
+    }
+
+    /// Drop all loaded items, and notify any subscriber of the list.
+    /// Items will be fetched from the provider again as they are accessed,
+    /// and items that are still being fetched are discarded.
+    pub fn reload(&mut self) {
+        self.items.clear();
+        self.requested_pages.clear();
+        // The subscribers of the list are notified, and read the items again
+        self.pending.borrow_mut().clear();
+        self.generation += 1;
+        self.subscribers.notify(Change::Push);
+    }
+
+    fn page_range(&self, page: usize) -> Range<usize> {
+        let start = page * self.page_size;
+        start..(start + self.page_size).min(self.provider.len())
+    }
+
+    fn notify_pending(&self, index: usize) {
+        let Some(subscribers) = self.pending.borrow_mut().remove(&index) else {
+            return;
+        };
+
+        for s in subscribers {
+            DIRTY_NODES.with(|nodes| nodes.borrow_mut().push((s, Change::Update)));
+        }
+    }
+}
+
+impl<T> Debug for LazyList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyList")
+            .field("len", &self.provider.len())
+            .field("page_size", &self.page_size)
+            .field("requested_pages", &self.requested_pages)
+            .finish()
+    }
+}
+
+impl<T> Collection for LazyList<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    fn len(&self) -> usize {
+        self.provider.len()
+    }
+
+    fn subscribe(&self, node_id: NodeId) {
+        self.subscribers.subscribe(node_id);
+    }
+}
+
+impl<T> State for LazyList<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_> {
+        match key {
+            Path::Index(index) => match self.items.get(*index) {
+                Some(Some(value)) => {
+                    value.subscribe(node_id.clone());
+                    value.deref().into()
+                }
+                _ if *index < self.provider.len() => {
+                    // An item missing from a delivered page is not coming
+                    let page = index / self.page_size;
+                    if self.requested_pages.get(&page) != Some(&true) {
+                        self.pending
+                            .borrow_mut()
+                            .entry(*index)
+                            .or_default()
+                            .insert(node_id.clone());
+                    }
+                    match &self.placeholder {
+                        Some(placeholder) => placeholder.into(),
+                        None => ValueRef::Empty,
+                    }
+                }
+                _ => ValueRef::Empty,
+            },
+            Path::Composite(lhs, rhs) => match self.state_get(lhs, node_id) {
+                ValueRef::Map(map) => map.state_get(rhs, node_id),
+                ValueRef::List(collection) => collection.state_get(rhs, node_id),
+                _ => ValueRef::Empty,
+            },
+            Path::Key(_) => ValueRef::Empty,
+        }
+    }
+}
+
+impl<T> LazyList<T>
+where
+    for<'a> &'a T: Into<ValueRef<'a>>,
+{
+    pub fn get_value(&self, _node_id: &NodeId) -> ValueRef<'_> {
+        ValueRef::List(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{drain_dirty_nodes, Owned};
+
+    struct Numbers;
+
+    impl DataProvider<usize> for Numbers {
+        fn len(&self) -> usize {
+            10
+        }
+
+        fn fetch(&mut self, range: Range<usize>, loader: Loader<usize>) {
+            loader.load(range.start, range.map(|i| i * 10).collect());
+        }
+    }
+
+    #[test]
+    fn load_on_access() {
+        let mut list = LazyList::new(Numbers).with_page_size(4);
+        let node_id: NodeId = 1.into();
+        let path = Path::from(5);
+
+        assert!(matches!(list.state_get(&path, &node_id), ValueRef::Empty));
+
+        list.poll();
+        assert_eq!(list.get(4), Some(&40));
+        assert_eq!(list.get(5), Some(&50));
+        assert_eq!(list.get(8), None);
+        assert_eq!(vec![(node_id.clone(), Change::Update)], drain_dirty_nodes());
+
+        let ValueRef::Owned(Owned::Num(num)) = list.state_get(&path, &node_id) else {
+            panic!()
+        };
+        assert_eq!(num.to_usize(), 50);
+    }
+
+    #[test]
+    fn placeholder() {
+        let list = LazyList::new(Numbers).with_placeholder(0);
+        let ValueRef::Owned(Owned::Num(num)) = list.state_get(&Path::from(2), &0.into()) else {
+            panic!()
+        };
+        assert_eq!(num.to_usize(), 0);
+    }
+
+    type Fetches = Rc<RefCell<Vec<(Range<usize>, Loader<usize>)>>>;
+
+    // Keeps the loaders to deliver the items later
+    struct Deferred(Fetches);
+
+    impl DataProvider<usize> for Deferred {
+        fn len(&self) -> usize {
+            10
+        }
+
+        fn fetch(&mut self, range: Range<usize>, loader: Loader<usize>) {
+            self.0.borrow_mut().push((range, loader));
+        }
+    }
+
+    #[test]
+    fn reload_discards_items_in_flight() {
+        let fetches = Fetches::default();
+        let mut list = LazyList::new(Deferred(Rc::clone(&fetches))).with_page_size(4);
+        let node_id: NodeId = 1.into();
+
+        let _ = list.state_get(&Path::from(1), &node_id);
+        list.poll();
+        let (range, loader) = fetches.borrow_mut().remove(0);
+
+        list.reload();
+        loader.load(range.start, range.map(|i| i * 10).collect());
+        list.poll();
+        assert_eq!(list.get(1), None);
+        assert!(drain_dirty_nodes().is_empty());
+
+        // The page is fetched again
+        let _ = list.state_get(&Path::from(1), &node_id);
+        list.poll();
+        let (range, loader) = fetches.borrow_mut().remove(0);
+        loader.load(range.start, range.map(|i| i * 10).collect());
+        list.poll();
+        assert_eq!(list.get(1), Some(&10));
+    }
+
+    #[test]
+    fn reload_clears_pending() {
+        let fetches = Fetches::default();
+        let mut list = LazyList::new(Deferred(Rc::clone(&fetches))).with_page_size(4);
+        let node_id: NodeId = 1.into();
+
+        // The page is requested but not delivered
+        let _ = list.state_get(&Path::from(1), &node_id);
+        list.poll();
+        assert_eq!(fetches.borrow().len(), 1);
+
+        list.reload();
+        assert!(list.pending.borrow().is_empty());
+
+        // Nothing is fetched until an item is accessed again
+        list.poll();
+        assert_eq!(fetches.borrow().len(), 1);
+        assert!(drain_dirty_nodes().is_empty());
+
+        let _ = list.state_get(&Path::from(5), &node_id);
+        list.poll();
+        let (range, _) = fetches.borrow_mut().remove(1);
+        assert_eq!(range, 4..8);
+        assert_eq!(
+            list.pending.borrow().keys().copied().collect::<Vec<_>>(),
+            [5]
+        );
+    }
+
+    #[test]
+    fn short_page() {
+        let fetches = Fetches::default();
+        let mut list = LazyList::new(Deferred(Rc::clone(&fetches))).with_page_size(4);
+        let node_id: NodeId = 1.into();
+
+        // Subscribing twice only notifies the node once
+        let _ = list.state_get(&Path::from(9), &node_id);
+        let _ = list.state_get(&Path::from(9), &node_id);
+        list.poll();
+
+        // Only the first item of the last page is delivered
+        let (range, loader) = fetches.borrow_mut().remove(0);
+        assert_eq!(range, 8..10);
+        loader.load(8, vec![80]);
+        list.poll();
+
+        assert_eq!(vec![(node_id.clone(), Change::Update)], drain_dirty_nodes());
+        assert!(list.pending.borrow().is_empty());
+
+        // The missing item is not waited for again
+        let _ = list.state_get(&Path::from(9), &node_id);
+        assert!(list.pending.borrow().is_empty());
+    }
+}
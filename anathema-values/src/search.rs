@@ -0,0 +1,190 @@
+// -----------------------------------------------------------------------------
+//   - Search -
+//   Find every occurrence of a query in a list of texts (e.g the lines of a log),
+//   and step through the matches.
+//
+//   The query is matched ignoring case, unless it contains an upper case
+//   character ("smart case").
+//
+//   The `search-bar` widget keeps a search over its `source`,
+//   see `anathema_widgets::SearchBar`.
+// -----------------------------------------------------------------------------
+use std::ops::Range;
+
+/// An occurrence of the query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// The index of the text in the list
+    pub item: usize,
+    /// Byte range of the occurrence in the text
+    pub range: Range<usize>,
+}
+
+/// Byte ranges of every occurrence of the query in the text.
+/// Occurrences don't overlap.
+pub fn find(query: &str, text: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return vec![];
+    }
+
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let eq = |a: char, b: char| a == b || ignore_case && a.to_lowercase().eq(b.to_lowercase());
+    let query = query.chars().collect::<Vec<_>>();
+    let chars = text.char_indices().collect::<Vec<_>>();
+
+    let mut ranges = vec![];
+    let mut index = 0;
+    while index + query.len() <= chars.len() {
+        let found = query
+            .iter()
+            .zip(&chars[index..])
+            .all(|(&q, &(_, c))| eq(c, q));
+
+        if !found {
+            index += 1;
+            continue;
+        }
+
+        let start = chars[index].0;
+        index += query.len();
+        let end = chars.get(index).map_or(text.len(), |&(offset, _)| offset);
+        ranges.push(start..end);
+    }
+    ranges
+}
+
+/// Every occurrence of the query in the texts, in order
+pub fn find_all<S: AsRef<str>>(query: &str, items: &[S]) -> Vec<Match> {
+    items
+        .iter()
+        .enumerate()
+        .flat_map(|(item, text)| {
+            find(query, text.as_ref())
+                .into_iter()
+                .map(move |range| Match { item, range })
+        })
+        .collect()
+}
+
+/// The matches of a query and the current match.
+///
+/// ```
+/// use anathema_values::search::Search;
+/// let mut search = Search::default();
+/// search.update("error", &["error: a", "ok", "error: b"]);
+/// assert_eq!(search.count(), 2);
+/// assert_eq!(search.next().map(|found| found.item), Some(2));
+/// assert_eq!(search.next().map(|found| found.item), Some(0));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Search {
+    query: String,
+    matches: Vec<Match>,
+    current: Option<usize>,
+}
+
+impl Search {
+    /// Find the query in the texts.
+    /// A new query starts at the first match, otherwise
+    /// the current match is kept, if there are still as many matches.
+    pub fn update<S: AsRef<str>>(&mut self, query: &str, items: &[S]) {
+        if self.query != query {
+            self.query = query.to_string();
+            self.current = None;
+        }
+
+        self.matches = find_all(query, items);
+        self.current = match self.matches.len() {
+            0 => None,
+            len => Some(self.current.unwrap_or(0).min(len - 1)),
+        };
+    }
+
+    /// The query
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// All the matches, in order
+    pub fn matches(&self) -> &[Match] {
+        &self.matches
+    }
+
+    /// The number of matches
+    pub fn count(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// The index of the current match
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// The current match
+    pub fn current_match(&self) -> Option<&Match> {
+        self.matches.get(self.current?)
+    }
+
+    /// Move to the next match, wrapping around to the first match
+    pub fn next(&mut self) -> Option<&Match> {
+        let len = self.matches.len();
+        self.current = self.current.map(|current| (current + 1) % len);
+        self.current_match()
+    }
+
+    /// Move to the previous match, wrapping around to the last match
+    pub fn previous(&mut self) -> Option<&Match> {
+        let len = self.matches.len();
+        self.current = self.current.map(|current| (current + len - 1) % len);
+        self.current_match()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_smart_case() {
+        assert_eq!(find("ab", "Ab ab aab"), vec![0..2, 3..5, 7..9]);
+        assert_eq!(find("Ab", "Ab ab aab"), vec![0..2]);
+        assert_eq!(find("aa", "aaa"), vec![0..2]);
+        assert_eq!(find("ö", "Öl, Öl"), vec![0..2, 5..7]);
+        assert!(find("", "abc").is_empty());
+        assert!(find("abcd", "abc").is_empty());
+    }
+
+    #[test]
+    fn step_through_matches() {
+        let lines = ["a x", "b", "x x"];
+        let mut search = Search::default();
+        search.update("x", &lines);
+        assert_eq!(search.count(), 3);
+        assert_eq!(
+            search.current_match(),
+            Some(&Match {
+                item: 0,
+                range: 2..3
+            })
+        );
+
+        assert_eq!(
+            search.previous().map(|found| found.range.clone()),
+            Some(2..3)
+        );
+        assert_eq!(search.current(), Some(2));
+
+        // The same query keeps the current match
+        search.update("x", &lines);
+        assert_eq!(search.current(), Some(2));
+
+        // A new query starts over
+        search.update("b", &lines);
+        assert_eq!(search.current(), Some(0));
+        assert_eq!(search.current_match().map(|found| found.item), Some(1));
+
+        search.update("z", &lines);
+        assert_eq!(search.current(), None);
+        assert_eq!(search.next(), None);
+    }
+}
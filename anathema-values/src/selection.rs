@@ -0,0 +1,240 @@
+use crate::state::State;
+use crate::{List, NodeId, Path, StateValue, ValueRef};
+
+/// Cursor and marked items for a list of items.
+///
+/// Exposed to the templates as:
+/// * `cursor`: the index of the item under the cursor
+/// * `marked`: the marked indices, in ascending order
+/// * `is_marked`: a boolean for every item, true if the item is marked
+///
+/// ```text
+/// for item in items
+///     if selection.is_marked[loop]
+///         text [foreground: #ff0] item
+///     else
+///         text item
+/// ```
+#[derive(Debug)]
+pub struct Selection {
+    cursor: StateValue<usize>,
+    marked: List<usize>,
+    is_marked: List<bool>,
+    // Range selection extends from the anchor
+    anchor: usize,
+}
+
+impl Selection {
+    /// Create a selection for a given number of items
+    pub fn new(len: usize) -> Self {
+        Self {
+            cursor: StateValue::new(0),
+            marked: List::empty(),
+            is_marked: List::new(std::iter::repeat_n(false, len)),
+            anchor: 0,
+        }
+    }
+
+    /// Number of items
+    pub fn len(&self) -> usize {
+        self.is_marked.len()
+    }
+
+    /// Update the number of items, e.g when items are added or removed.
+    /// Marks outside of the new length are removed.
+    pub fn set_len(&mut self, len: usize) {
+        while self.is_marked.len() > len {
+            self.is_marked.pop_back();
+        }
+
+        while self.is_marked.len() < len {
+            self.is_marked.push_back(false);
+        }
+
+        while !self.marked.is_empty() && self.marked[self.marked.len() - 1] >= len {
+            self.marked.pop_back();
+        }
+
+        let max = len.saturating_sub(1);
+        if *self.cursor > max {
+            *self.cursor = max;
+        }
+        self.anchor = self.anchor.min(max);
+    }
+
+    /// The index of the item under the cursor
+    pub fn cursor(&self) -> usize {
+        *self.cursor
+    }
+
+    /// Move the cursor to an index.
+    /// This also moves the anchor of range selections.
+    pub fn move_to(&mut self, index: usize) {
+        let index = index.min(self.len().saturating_sub(1));
+        if *self.cursor != index {
+            *self.cursor = index;
+        }
+        self.anchor = index;
+    }
+
+    /// Move the cursor to the next item
+    pub fn next(&mut self) {
+        self.move_to(self.cursor() + 1);
+    }
+
+    /// Move the cursor to the previous item
+    pub fn prev(&mut self) {
+        self.move_to(self.cursor().saturating_sub(1));
+    }
+
+    /// Returns true if the item at the index is marked
+    pub fn is_marked(&self, index: usize) -> bool {
+        index < self.len() && self.is_marked[index]
+    }
+
+    /// The marked indices, in ascending order
+    pub fn marked(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.marked.len()).map(|i| self.marked[i])
+    }
+
+    /// Mark, or unmark, the item at the index
+    pub fn set_marked(&mut self, index: usize, marked: bool) {
+        if index >= self.len() || self.is_marked[index] == marked {
+            return;
+        }
+
+        self.is_marked[index] = marked;
+
+        let pos = self
+            .marked()
+            .position(|i| i >= index)
+            .unwrap_or(self.marked.len());
+        match marked {
+            true => self.marked.insert(pos, index),
+            false => drop(self.marked.remove(pos)),
+        }
+    }
+
+    /// Toggle the mark of the item under the cursor
+    pub fn toggle(&mut self) {
+        let cursor = self.cursor();
+        self.set_marked(cursor, !self.is_marked(cursor));
+        self.anchor = cursor;
+    }
+
+    /// Move the cursor to the index, marking every item between
+    /// the anchor and the index.
+    pub fn extend_to(&mut self, index: usize) {
+        let index = index.min(self.len().saturating_sub(1));
+        let anchor = self.anchor;
+        for i in anchor.min(index)..=anchor.max(index) {
+            self.set_marked(i, true);
+        }
+
+        if *self.cursor != index {
+            *self.cursor = index;
+        }
+    }
+
+    /// Remove all marks
+    pub fn clear(&mut self) {
+        while let Some(index) = self.marked.pop_back() {
+            self.is_marked[*index] = false;
+        }
+    }
+}
+
+impl State for Selection {
+    fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_> {
+        match key {
+            Path::Key(key) => match key.as_str() {
+                "cursor" => self.cursor.get_value(node_id),
+                "marked" => self.marked.get_value(node_id),
+                "is_marked" => self.is_marked.get_value(node_id),
+                _ => ValueRef::Empty,
+            },
+            Path::Composite(lhs, rhs) => {
+                let Path::Key(ref key) = &**lhs else {
+                    return ValueRef::Empty;
+                };
+                match key.as_str() {
+                    "marked" => self.marked.state_get(rhs, node_id),
+                    "is_marked" => self.is_marked.state_get(rhs, node_id),
+                    _ => ValueRef::Empty,
+                }
+            }
+            _ => ValueRef::Empty,
+        }
+    }
+}
+
+impl<'a> From<&'a Selection> for ValueRef<'a> {
+    fn from(selection: &'a Selection) -> Self {
+        ValueRef::Map(selection)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{drain_dirty_nodes, Change, Owned};
+
+    fn marked(selection: &Selection) -> Vec<usize> {
+        selection.marked().collect()
+    }
+
+    #[test]
+    fn toggle_marks() {
+        let mut selection = Selection::new(5);
+        selection.toggle();
+        selection.move_to(3);
+        selection.toggle();
+        selection.prev();
+        selection.toggle();
+        assert_eq!(marked(&selection), vec![0, 2, 3]);
+
+        selection.move_to(0);
+        selection.toggle();
+        assert_eq!(marked(&selection), vec![2, 3]);
+        assert!(!selection.is_marked(0));
+        assert!(selection.is_marked(2));
+    }
+
+    #[test]
+    fn range_selection() {
+        let mut selection = Selection::new(10);
+        selection.move_to(6);
+        selection.extend_to(3);
+        assert_eq!(marked(&selection), vec![3, 4, 5, 6]);
+        assert_eq!(selection.cursor(), 3);
+
+        selection.clear();
+        assert!(selection.marked().next().is_none());
+        assert!(!selection.is_marked(4));
+    }
+
+    #[test]
+    fn shrink() {
+        let mut selection = Selection::new(10);
+        selection.move_to(9);
+        selection.extend_to(7);
+        selection.set_len(8);
+        assert_eq!(marked(&selection), vec![7]);
+        assert_eq!(selection.cursor(), 7);
+        assert_eq!(selection.len(), 8);
+    }
+
+    #[test]
+    fn notify_marked_item() {
+        let mut selection = Selection::new(3);
+        let node_id: NodeId = 1.into();
+        let path = Path::from("is_marked").compose(1);
+        let ValueRef::Owned(Owned::Bool(false)) = selection.state_get(&path, &node_id) else {
+            panic!()
+        };
+
+        selection.move_to(1);
+        selection.toggle();
+        assert_eq!(drain_dirty_nodes(), vec![(node_id, Change::Update)]);
+    }
+}
@@ -0,0 +1,234 @@
+// -----------------------------------------------------------------------------
+//   - Styled spans -
+//   Functions returning the text split into spans with their own colors,
+//   e.g a heat colored sparkline: `text colorize(sparkline, green, #ff0000)`.
+//
+//   The `text` widget paints the colors of the spans,
+//   any other widget only gets the text.
+//
+//   The colors of a scale are blended if they are both RGB colors,
+//   otherwise the closest color of the scale is used.
+// -----------------------------------------------------------------------------
+use std::fmt::{self, Display};
+
+use anathema_render::Color;
+
+use crate::functions::FnValue;
+use crate::{Context, DynValue, Immediate, NodeId, Owned, Value, ValueExpr};
+
+const BLOCKS: &str = "▁▂▃▄▅▆▇█";
+
+/// A chunk of text with its own foreground color
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub foreground: Option<Color>,
+}
+
+/// Text made up of spans, returned by a function such as `colorize`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Spans(pub Vec<Span>);
+
+impl Spans {
+    // Append a char, extending the last span if it has the same color
+    fn push(&mut self, c: char, foreground: Option<Color>) {
+        match self.0.last_mut() {
+            Some(span) if span.foreground == foreground => span.text.push(c),
+            _ => self.0.push(Span {
+                text: c.into(),
+                foreground,
+            }),
+        }
+    }
+
+    /// The foreground color of the char at a byte offset of the text
+    pub fn foreground_at(&self, offset: usize) -> Option<Color> {
+        let mut start = 0;
+        for span in &self.0 {
+            let end = start + span.text.len();
+            if offset < end {
+                return span.foreground;
+            }
+            start = end;
+        }
+        None
+    }
+}
+
+/// The text of the spans without the colors
+impl Display for Spans {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0
+            .iter()
+            .try_for_each(|span| write!(f, "{}", span.text))
+    }
+}
+
+/// Color every block element (`▁` to `█`) and digit by its value,
+/// from the first to the last color of the scale.
+/// Any other character is not colored.
+pub fn colorize(text: &str, scale: &[Color]) -> Spans {
+    let mut spans = Spans::default();
+    for c in text.chars() {
+        let value = match c {
+            '0'..='9' => Some((c as u8 - b'0') as f64 / 9.0),
+            c => BLOCKS
+                .chars()
+                .position(|block| block == c)
+                .map(|i| i as f64 / 7.0),
+        };
+        spans.push(c, value.and_then(|value| pick(scale, value)));
+    }
+    spans
+}
+
+/// Color the characters by their position in the text,
+/// from the first to the last color of the scale.
+/// Whitespace is not colored.
+pub fn gradient(text: &str, scale: &[Color]) -> Spans {
+    let count = text.chars().filter(|c| !c.is_whitespace()).count();
+    let mut index = 0;
+    let mut spans = Spans::default();
+    for c in text.chars() {
+        if c.is_whitespace() {
+            spans.push(c, None);
+            continue;
+        }
+
+        let value = match count {
+            0 | 1 => 0.0,
+            _ => index as f64 / (count - 1) as f64,
+        };
+        index += 1;
+        spans.push(c, pick(scale, value));
+    }
+    spans
+}
+
+// The color at a value from 0 to 1 along the scale
+fn pick(scale: &[Color], value: f64) -> Option<Color> {
+    let last = scale.len().checked_sub(1)?;
+    let position = value.clamp(0.0, 1.0) * last as f64;
+    let index = (position.floor() as usize).min(last);
+    let t = position - index as f64;
+
+    let low = scale[index];
+    let Some(&high) = scale.get(index + 1) else {
+        return Some(low);
+    };
+
+    let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    let color = match (low, high) {
+        (
+            Color::Rgb { r, g, b },
+            Color::Rgb {
+                r: r2,
+                g: g2,
+                b: b2,
+            },
+        ) => Color::Rgb {
+            r: channel(r, r2),
+            g: channel(g, g2),
+            b: channel(b, b2),
+        },
+        _ if t < 0.5 => low,
+        _ => high,
+    };
+    Some(color)
+}
+
+// Call a built-in span function: the text followed by the colors of the scale
+pub(crate) fn call(name: &str, args: &[FnValue]) -> Option<Spans> {
+    let f = match name {
+        "colorize" => colorize,
+        "gradient" => gradient,
+        _ => return None,
+    };
+
+    let (text, scale) = args.split_first()?;
+    let scale = scale
+        .iter()
+        .filter_map(|color| match color {
+            FnValue::Owned(Owned::Color(color)) => Some(*color),
+            FnValue::String(name) => Color::try_from(name.as_str()).ok(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    Some(f(&text.to_string(), &scale))
+}
+
+impl DynValue for Spans {
+    fn init_value(context: &Context<'_, '_>, node_id: &NodeId, expr: &ValueExpr) -> Value<Self> {
+        let mut resolver = Immediate::new(context.lookup(), node_id);
+        let inner = expr.eval_spans(&mut resolver);
+
+        match resolver.is_deferred() {
+            true => Value::Dyn {
+                inner,
+                expr: expr.clone(),
+            },
+            false => match inner {
+                Some(val) => Value::Static(val),
+                None => Value::Empty,
+            },
+        }
+    }
+
+    fn resolve(value: &mut Value<Self>, context: &Context<'_, '_>, node_id: &NodeId) {
+        if let Value::Dyn { inner, expr } = value {
+            let mut resolver = Immediate::new(context.lookup(), node_id);
+            *inner = expr.eval_spans(&mut resolver);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::functions;
+
+    const GREEN: Color = Color::Rgb { r: 0, g: 255, b: 0 };
+    const RED: Color = Color::Rgb { r: 255, g: 0, b: 0 };
+
+    #[test]
+    fn colorize_by_value() {
+        let spans = colorize("▁▁x█", &[GREEN, RED]);
+        assert_eq!(spans.to_string(), "▁▁x█");
+        assert_eq!(spans.0.len(), 3);
+        assert_eq!(spans.0[0].foreground, Some(GREEN));
+        assert_eq!(spans.0[1].foreground, None);
+        assert_eq!(spans.foreground_at("▁▁x".len()), Some(RED));
+
+        let spans = colorize("09", &[Color::Green, Color::Yellow, Color::Red]);
+        assert_eq!(spans.foreground_at(0), Some(Color::Green));
+        assert_eq!(spans.foreground_at(1), Some(Color::Red));
+    }
+
+    #[test]
+    fn gradient_by_position() {
+        let spans = gradient("a b c", &[GREEN, RED]);
+        assert_eq!(spans.foreground_at(0), Some(GREEN));
+        assert_eq!(spans.foreground_at(1), None);
+        let middle = Color::Rgb {
+            r: 128,
+            g: 128,
+            b: 0,
+        };
+        assert_eq!(spans.foreground_at(2), Some(middle));
+        assert_eq!(spans.foreground_at(4), Some(RED));
+
+        // Without a scale nothing is colored
+        assert_eq!(gradient("abc", &[]).foreground_at(0), None);
+    }
+
+    #[test]
+    fn call_builtin() {
+        let args = ["▁█".into(), "green".into(), Owned::Color(RED).into()];
+        let Some(FnValue::Spans(spans)) = functions::call("colorize", &args) else {
+            panic!("expected spans");
+        };
+        assert_eq!(spans.foreground_at(0), Some(Color::Green));
+        assert_eq!(spans.foreground_at("▁".len()), Some(RED));
+    }
+}
@@ -16,6 +16,15 @@ pub trait State: std::fmt::Debug {
     /// Get a value reference from the state
     fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_>;
 
+    /// Mutate the state in a [`transaction`](crate::transaction::transaction).
+    /// Nodes are notified of all the changes at once, when the closure returns.
+    fn transaction<T, E>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, E>) -> Result<T, E>
+    where
+        Self: Sized,
+    {
+        crate::transaction::transaction(|| f(self))
+    }
+
     #[doc(hidden)]
     fn get_value(&self, _: &NodeId) -> ValueRef<'_>
     where
@@ -1,8 +1,9 @@
-use std::cell::RefCell;
-use std::collections::HashSet;
+use std::any::Any;
+use std::fmt::Debug;
 use std::ops::{Deref, DerefMut};
 
-use crate::{NodeId, Owned, Path, State, ValueRef, DIRTY_NODES};
+use crate::subscriber::Subscribers;
+use crate::{history, NodeId, Owned, Path, State, ValueRef};
 
 // TODO: Can we make this `Copy` as well?
 //       This depends if `RemoveKey` is required here or not.
@@ -23,19 +24,23 @@ pub enum Change {
     InsertKey(String),
     RemoveIndex(usize),
     RemoveKey(String),
+    /// Two indices swapped places
+    Swap(usize, usize),
+    /// An item moved from one index to another
+    Move(usize, usize),
 }
 
 #[derive(Debug, Default)]
 pub struct StateValue<T> {
     pub(crate) inner: T,
-    subscribers: RefCell<HashSet<NodeId>>,
+    subscribers: Subscribers,
 }
 
 impl<T> StateValue<T> {
     pub fn new(inner: T) -> Self {
         Self {
             inner,
-            subscribers: RefCell::new(HashSet::new()),
+            subscribers: Subscribers::new(),
         }
     }
 
@@ -45,7 +50,37 @@ impl<T> StateValue<T> {
     }
 
     pub fn subscribe(&self, subscriber: NodeId) {
-        self.subscribers.borrow_mut().insert(subscriber);
+        self.subscribers.subscribe(subscriber);
+    }
+}
+
+impl<T: Debug + 'static> StateValue<T> {
+    /// Replace the value and notify the subscribers.
+    /// Unlike a change made through `DerefMut`, the old and the new value
+    /// are part of the [`history`](crate::history).
+    pub fn set(&mut self, value: T) {
+        if history::is_recording() {
+            history::values(history::Values {
+                old: format!("{:?}", self.inner),
+                new: format!("{value:?}"),
+                raw_old: raw(&self.inner),
+                raw_new: raw(&value),
+            });
+        }
+        *self.deref_mut() = value;
+    }
+}
+
+// The value as it's written back to the state: strings as they are,
+// anything else formatted with `Debug`
+fn raw<T: Debug + 'static>(value: &T) -> String {
+    let any = value as &dyn Any;
+    match any.downcast_ref::<String>() {
+        Some(s) => s.clone(),
+        None => match any.downcast_ref::<&str>() {
+            Some(s) => s.to_string(),
+            None => format!("{value:?}"),
+        },
     }
 }
 
@@ -69,9 +104,7 @@ impl<T> Deref for StateValue<T> {
 
 impl<T> DerefMut for StateValue<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        for s in self.subscribers.borrow_mut().drain() {
-            DIRTY_NODES.with(|nodes| nodes.borrow_mut().push((s.clone(), Change::Update)));
-        }
+        self.subscribers.notify(Change::Update);
 
         &mut self.inner
     }
@@ -107,7 +140,7 @@ impl<T: State> State for StateValue<T> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::drain_dirty_nodes;
+    use crate::{drain_dirty_nodes, drain_removed_nodes, remove_node};
 
     #[test]
     fn notify_subscriber() {
@@ -118,4 +151,16 @@ mod test {
 
         assert_eq!((id, Change::Update), drain_dirty_nodes()[0]);
     }
+
+    #[test]
+    fn removed_subscriber() {
+        let id: NodeId = 123.into();
+        let mut value = StateValue::new("hello world".to_string());
+        value.subscribe(id.clone());
+        remove_node(id);
+        value.push_str(", updated");
+
+        assert!(drain_dirty_nodes().is_empty());
+        assert_eq!(drain_removed_nodes().len(), 1);
+    }
 }
@@ -0,0 +1,241 @@
+// -----------------------------------------------------------------------------
+//   - Subscribers -
+//   Nodes are regenerated with new node ids (e.g when a loop is rebuilt),
+//   so holding on to the node ids would grow the subscriber sets forever
+//   for values that rarely change.
+//
+//   Subscriptions are weak references to the node ids, keyed by the value
+//   of the node id.
+//   Once a node is dropped from the node tree the subscription is dead,
+//   and will be pruned from the set.
+//
+//   The node id passed to `subscribe` has to be owned by a node
+//   (cloned from the node's id), as a node id that is only held by the
+//   subscription is dropped straight away and would never be notified.
+// -----------------------------------------------------------------------------
+use std::cell::{Cell, RefCell};
+use std::sync::{Arc, Weak};
+
+use crate::hashmap::HashMap;
+use crate::{history, transaction, Change, NodeId, Path, DIRTY_NODES};
+
+// Weak node ids, keyed by the value of the node id
+type Subscriptions = HashMap<Box<[usize]>, Weak<[usize]>>;
+
+// Don't bother pruning small sets
+const MIN_PRUNE_THRESHOLD: usize = 16;
+
+thread_local! {
+    static SUBSCRIPTIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// The number of subscriptions held by all the values on this thread,
+/// including the ones of removed nodes that haven't been pruned yet.
+///
+/// A number that keeps growing while the node tree does not is a sign of a leak.
+pub fn subscription_count() -> usize {
+    SUBSCRIPTIONS.get()
+}
+
+/// The approximate number of bytes used by the subscriptions,
+/// see [`subscription_count`].
+pub fn subscriptions_memory() -> usize {
+    subscription_count() * std::mem::size_of::<(Box<[usize]>, Weak<[usize]>)>()
+}
+
+// Track the change in the number of subscriptions
+fn track(before: usize, after: usize) {
+    SUBSCRIPTIONS.set((SUBSCRIPTIONS.get() + after).saturating_sub(before));
+}
+
+#[derive(Debug)]
+pub(crate) struct Subscribers {
+    // Subscriptions are keyed by the value of the node id,
+    // so a node subscribing multiple times only has one subscription,
+    // even when subscribing with different copies of the same id.
+    inner: RefCell<Subscriptions>,
+    prune_threshold: Cell<usize>,
+    // The path the value was last read through, only set while
+    // recording the change history
+    path: RefCell<Option<Box<Path>>>,
+}
+
+impl Subscribers {
+    pub(crate) fn new() -> Self {
+        Self {
+            inner: RefCell::new(Subscriptions::default()),
+            prune_threshold: Cell::new(MIN_PRUNE_THRESHOLD),
+            path: RefCell::new(None),
+        }
+    }
+
+    pub(crate) fn subscribe(&self, node_id: NodeId) {
+        if let Some(path) = history::reading() {
+            self.path.replace(Some(Box::new(path)));
+        }
+
+        debug_assert!(
+            Arc::strong_count(&node_id.0) > 1,
+            "subscribing with a node id that is not owned by a node: {node_id:?}"
+        );
+
+        let mut subscribers = self.inner.borrow_mut();
+        let before = subscribers.len();
+        subscribers.insert(node_id.0.as_ref().into(), Arc::downgrade(&node_id.0));
+
+        if subscribers.len() > self.prune_threshold.get() {
+            subscribers.retain(|_, node_id| node_id.strong_count() > 0);
+            let threshold = (subscribers.len() * 2).max(MIN_PRUNE_THRESHOLD);
+            self.prune_threshold.set(threshold);
+        }
+
+        track(before, subscribers.len());
+    }
+
+    /// Notify all live subscribers of the change.
+    /// This removes all subscriptions.
+    pub(crate) fn notify(&self, change: Change) {
+        self.notify_if(change, |_| true);
+        self.prune_threshold.set(MIN_PRUNE_THRESHOLD);
+    }
+
+    /// Notify the live subscribers matching the predicate of the change.
+    /// This removes the subscriptions of the notified subscribers.
+    pub(crate) fn notify_if(&self, change: Change, f: impl Fn(&[usize]) -> bool) {
+        if history::is_recording() {
+            let node_ids = self
+                .inner
+                .borrow()
+                .values()
+                .filter_map(Weak::upgrade)
+                .filter(|node_id| f(node_id))
+                .map(NodeId)
+                .collect();
+            let path = self.path.borrow().as_deref().cloned();
+            history::record(path, &change, node_ids);
+        }
+
+        // Inside a transaction the subscribers are kept until the change is committed
+        if transaction::is_open() {
+            let subscribers = self.inner.borrow();
+            let node_ids = subscribers
+                .values()
+                .filter_map(Weak::upgrade)
+                .filter(|node_id| f(node_id))
+                .map(NodeId);
+            if transaction::hold(node_ids, &change) {
+                return;
+            }
+        }
+
+        let mut notified = vec![];
+        let mut subscribers = self.inner.borrow_mut();
+        let before = subscribers.len();
+        subscribers.retain(|_, node_id| {
+            let Some(node_id) = node_id.upgrade() else {
+                return false;
+            };
+
+            if !f(&node_id) {
+                return true;
+            }
+
+            notified.push((NodeId(node_id), change.clone()));
+            false
+        });
+        track(before, subscribers.len());
+
+        DIRTY_NODES.with_borrow_mut(|nodes| nodes.extend(notified));
+    }
+}
+
+impl Default for Subscribers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Subscribers {
+    fn drop(&mut self) {
+        track(self.inner.get_mut().len(), 0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::drain_dirty_nodes;
+
+    impl Subscribers {
+        fn len(&self) -> usize {
+            self.inner.borrow().len()
+        }
+    }
+
+    #[test]
+    fn subscribe_once() {
+        let subscribers = Subscribers::new();
+        let node_id: NodeId = 1.into();
+        subscribers.subscribe(node_id.clone());
+        subscribers.subscribe(node_id.clone());
+        assert_eq!(subscribers.len(), 1);
+    }
+
+    #[test]
+    fn subscribe_once_by_value() {
+        let subscribers = Subscribers::new();
+        let first: NodeId = vec![0, 1].into();
+        let second: NodeId = vec![0, 1].into();
+        subscribers.subscribe(first.clone());
+        subscribers.subscribe(second.clone());
+        assert_eq!(subscribers.len(), 1);
+
+        // The subscription survives the copy it was first made with
+        drop(first);
+        subscribers.notify(Change::Update);
+        assert_eq!(vec![(second, Change::Update)], drain_dirty_nodes());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not owned by a node")]
+    fn subscribe_temporary_node_id() {
+        let subscribers = Subscribers::new();
+        subscribers.subscribe(1.into());
+    }
+
+    #[test]
+    fn prune_dropped_nodes() {
+        let subscribers = Subscribers::new();
+        let live: NodeId = 0.into();
+        subscribers.subscribe(live.clone());
+
+        for i in 1..=MIN_PRUNE_THRESHOLD * 4 {
+            let removed: NodeId = i.into();
+            subscribers.subscribe(removed.clone());
+        }
+        assert!(subscribers.len() <= MIN_PRUNE_THRESHOLD + 1);
+
+        subscribers.notify(Change::Update);
+        assert_eq!(vec![(live, Change::Update)], drain_dirty_nodes());
+    }
+
+    #[test]
+    fn count_subscriptions() {
+        let before = subscription_count();
+        let subscribers = Subscribers::new();
+        let node_ids = (0..3).map(NodeId::from).collect::<Vec<_>>();
+        node_ids
+            .iter()
+            .for_each(|id| subscribers.subscribe(id.clone()));
+        assert_eq!(subscription_count(), before + 3);
+
+        subscribers.notify(Change::Update);
+        assert_eq!(subscription_count(), before);
+        let _ = drain_dirty_nodes();
+
+        subscribers.subscribe(node_ids[0].clone());
+        drop(subscribers);
+        assert_eq!(subscription_count(), before);
+    }
+}
@@ -0,0 +1,193 @@
+// -----------------------------------------------------------------------------
+//   - Symbols -
+//   Widget identifiers and attribute keys are interned as symbols,
+//   so comparing and hashing them is an integer operation.
+//
+//   Interned strings are never freed, which is fine as the number of
+//   distinct identifiers and keys is bound by the templates.
+//
+//   Every thread keeps a copy of the interner, so resolving a symbol
+//   that is already interned doesn't take the lock. As symbols are never
+//   freed the copy can only be missing the most recent symbols, and it
+//   is brought up to date when a string isn't found in it.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+use std::fmt::{self, Debug, Display};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use crate::hashmap::HashMap;
+use crate::SPREAD_ATTRIBUTE;
+
+// Interned before any other string, in this order
+const PREDEFINED: &[&str] = &[SPREAD_ATTRIBUTE];
+
+// The number of interned strings
+static LEN: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static LOCAL: RefCell<Interner> = RefCell::default();
+}
+
+#[derive(Default)]
+struct Interner {
+    lookup: HashMap<&'static str, Symbol>,
+    strings: Vec<&'static str>,
+}
+
+impl Interner {
+    fn insert(&mut self, s: &'static str) -> Symbol {
+        let symbol = Symbol(self.strings.len() as u32);
+        self.strings.push(s);
+        self.lookup.insert(s, symbol);
+        symbol
+    }
+
+    // Copy the strings interned since the last sync from the global interner.
+    // Returns false if there was nothing to copy.
+    fn sync(&mut self) -> bool {
+        // The local copy is never empty once synced, as there are predefined symbols
+        if !self.strings.is_empty() && LEN.load(Ordering::Acquire) == self.strings.len() {
+            return false;
+        }
+
+        let global = interner().lock().expect("the interner is never poisoned");
+        for &s in &global.strings[self.strings.len()..] {
+            self.insert(s);
+        }
+        true
+    }
+}
+
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(|| {
+        let mut interner = Interner::default();
+        for s in PREDEFINED {
+            interner.insert(s);
+        }
+        LEN.store(interner.strings.len(), Ordering::Release);
+        Mutex::new(interner)
+    })
+}
+
+/// An interned string.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// The key of a map spread into the attributes, see [`SPREAD_ATTRIBUTE`]
+    pub const SPREAD: Self = Self(0);
+
+    /// Intern a string, returning the existing symbol if the
+    /// string is already interned.
+    pub fn new(s: &str) -> Self {
+        if let Some(symbol) = Self::get(s) {
+            return symbol;
+        }
+
+        let mut interner = interner().lock().expect("the interner is never poisoned");
+        if let Some(symbol) = interner.lookup.get(s) {
+            return *symbol;
+        }
+
+        let symbol = interner.insert(Box::leak(s.into()));
+        LEN.store(interner.strings.len(), Ordering::Release);
+        symbol
+    }
+
+    /// Get the symbol for a string, without interning the string.
+    pub fn get(s: &str) -> Option<Self> {
+        LOCAL.with_borrow_mut(|local| {
+            if let Some(symbol) = local.lookup.get(s) {
+                return Some(*symbol);
+            }
+            match local.sync() {
+                true => local.lookup.get(s).copied(),
+                false => None,
+            }
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        let index = self.0 as usize;
+        LOCAL.with_borrow_mut(|local| {
+            if index >= local.strings.len() {
+                local.sync();
+            }
+            local.strings[index]
+        })
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(s: String) -> Self {
+        Self::new(&s)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn intern() {
+        let a = Symbol::new("border");
+        let b = Symbol::from("border".to_string());
+        assert_eq!(a, b);
+        assert_eq!(a.as_str(), "border");
+        assert_ne!(a, Symbol::new("text"));
+    }
+
+    #[test]
+    fn get_without_interning() {
+        assert!(Symbol::get("an uninterned symbol").is_none());
+        let symbol = Symbol::new("an interned symbol");
+        assert_eq!(Symbol::get("an interned symbol"), Some(symbol));
+    }
+
+    #[test]
+    fn predefined() {
+        assert_eq!(Symbol::SPREAD.as_str(), SPREAD_ATTRIBUTE);
+        assert_eq!(Symbol::new(SPREAD_ATTRIBUTE), Symbol::SPREAD);
+    }
+
+    #[test]
+    fn intern_on_other_threads() {
+        let symbol = std::thread::spawn(|| Symbol::new("interned on another thread"))
+            .join()
+            .unwrap();
+        assert_eq!(symbol, "interned on another thread");
+        assert_eq!(Symbol::get("interned on another thread"), Some(symbol));
+    }
+}
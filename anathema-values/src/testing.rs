@@ -1,9 +1,9 @@
-use crate::map::Map;
+use crate::map::{Errors, Map};
 use crate::{Context, Immediate, List, NodeId, Owned, StateValue, ValueExpr, ValueRef};
 
 #[derive(Debug, crate::State)]
 pub struct Inner {
-    name: StateValue<String>,
+    pub name: StateValue<String>,
     names: List<String>,
 }
 
@@ -25,6 +25,7 @@ pub struct TestState {
     pub generic_list: List<usize>,
     pub nested_list: List<List<usize>>,
     pub debug: StateValue<bool>,
+    pub errors: Errors,
 }
 
 impl TestState {
@@ -37,6 +38,7 @@ impl TestState {
             generic_list: List::new(vec![1, 2, 3]),
             nested_list: List::new(vec![List::new(vec![1, 2, 3])]),
             debug: StateValue::new(false),
+            errors: Errors::new([("email", "Not an email address".to_string())]),
         }
     }
 }
@@ -188,6 +190,48 @@ pub fn list<E: Into<ValueExpr>>(input: impl IntoIterator<Item = E>) -> Box<Value
     ValueExpr::List(vec.into()).into()
 }
 
+// -----------------------------------------------------------------------------
+//   - Transforms -
+// -----------------------------------------------------------------------------
+pub fn filter(collection: Box<ValueExpr>, predicate: Box<ValueExpr>) -> Box<ValueExpr> {
+    ValueExpr::Filter(collection, predicate).into()
+}
+
+pub fn sort_by(collection: Box<ValueExpr>, key: Box<ValueExpr>) -> Box<ValueExpr> {
+    ValueExpr::SortBy(collection, key).into()
+}
+
+pub fn paginate(
+    collection: Box<ValueExpr>,
+    page: Box<ValueExpr>,
+    per_page: Box<ValueExpr>,
+) -> Box<ValueExpr> {
+    ValueExpr::Paginate(collection, page, per_page).into()
+}
+
+pub fn fuzzy(collection: Box<ValueExpr>, query: Box<ValueExpr>) -> Box<ValueExpr> {
+    ValueExpr::Fuzzy(collection, query).into()
+}
+
+pub fn message(key: &str, args: Vec<Box<ValueExpr>>) -> Box<ValueExpr> {
+    let args = args.into_iter().map(|arg| *arg).collect::<Vec<_>>();
+    ValueExpr::Message(key.into(), args.into()).into()
+}
+
+pub fn plural(count: Box<ValueExpr>, forms: Vec<Box<ValueExpr>>) -> Box<ValueExpr> {
+    let forms = forms.into_iter().map(|form| *form).collect::<Vec<_>>();
+    ValueExpr::Plural(count, forms.into()).into()
+}
+
+pub fn call(fun: &str, args: Vec<Box<ValueExpr>>) -> Box<ValueExpr> {
+    let args = args.into_iter().map(|arg| *arg).collect::<Vec<_>>();
+    ValueExpr::Call(fun.into(), args.into()).into()
+}
+
+pub fn page_count(collection: Box<ValueExpr>, per_page: Box<ValueExpr>) -> Box<ValueExpr> {
+    ValueExpr::PageCount(collection, per_page).into()
+}
+
 // -----------------------------------------------------------------------------
 //   - Op -
 // -----------------------------------------------------------------------------
@@ -210,3 +254,11 @@ pub fn and(lhs: Box<ValueExpr>, rhs: Box<ValueExpr>) -> Box<ValueExpr> {
 pub fn or(lhs: Box<ValueExpr>, rhs: Box<ValueExpr>) -> Box<ValueExpr> {
     ValueExpr::Or(lhs, rhs).into()
 }
+
+pub fn coalesce(lhs: Box<ValueExpr>, rhs: Box<ValueExpr>) -> Box<ValueExpr> {
+    ValueExpr::Coalesce(lhs, rhs).into()
+}
+
+pub fn default(lhs: Box<ValueExpr>, rhs: Box<ValueExpr>) -> Box<ValueExpr> {
+    ValueExpr::Default(lhs, rhs).into()
+}
@@ -0,0 +1,218 @@
+// -----------------------------------------------------------------------------
+//   - Transactions -
+//   Changes made inside a transaction are held back until the transaction
+//   is committed, so several related values are updated in the same frame.
+//
+//   Subscribers are kept while the transaction is open: if the transaction
+//   is rolled back the nodes are still subscribed to the values.
+//
+//   The state can't be restored generically, so rolling back keeps the
+//   mutations. The held changes are still delivered, so the nodes never
+//   show a value the state no longer has.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use crate::{Change, NodeId, DIRTY_NODES};
+
+thread_local! {
+    // One set of changes per open transaction, the last one is the innermost
+    static PENDING: RefCell<Vec<Pending>> = const { RefCell::new(vec![]) };
+}
+
+#[derive(Debug, Default)]
+struct Pending {
+    changes: Vec<(NodeId, Change)>,
+    // Nodes with an update in `changes`:
+    // updating the same node more than once has no effect
+    updated: HashSet<NodeId>,
+}
+
+impl Pending {
+    fn push(&mut self, node_id: NodeId, change: Change) {
+        if change == Change::Update && !self.updated.insert(node_id.clone()) {
+            return;
+        }
+        self.changes.push((node_id, change));
+    }
+}
+
+// Hand the changes of the innermost transaction to the outer transaction,
+// or notify the nodes if it's the outermost transaction
+fn close() {
+    PENDING.with_borrow_mut(|pending| {
+        let closed = pending.pop().expect("transaction is open");
+        match pending.last_mut() {
+            Some(outer) => closed
+                .changes
+                .into_iter()
+                .for_each(|(node_id, change)| outer.push(node_id, change)),
+            None => DIRTY_NODES.with_borrow_mut(|nodes| nodes.extend(closed.changes)),
+        }
+    });
+}
+
+/// An open transaction.
+/// The transaction is rolled back if it's dropped without being committed.
+#[derive(Debug)]
+pub struct Transaction {
+    done: bool,
+}
+
+impl Transaction {
+    /// Open a new transaction.
+    /// Transactions can be nested: committing an inner transaction
+    /// hands the changes to the outer transaction.
+    pub fn begin() -> Self {
+        PENDING.with_borrow_mut(|pending| pending.push(Pending::default()));
+        Self { done: false }
+    }
+
+    /// Notify the nodes of all the changes made during the transaction.
+    pub fn commit(mut self) {
+        self.done = true;
+        close();
+    }
+
+    /// Close the transaction after it failed.
+    ///
+    /// This does not undo the mutations made during the transaction,
+    /// the nodes are notified of them the same as on commit,
+    /// so they keep showing what is in the state.
+    pub fn rollback(mut self) {
+        self.done = true;
+        close();
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if !self.done {
+            close();
+        }
+    }
+}
+
+/// Run the closure in a transaction.
+/// The transaction is committed if the closure returns `Ok`,
+/// and rolled back if it returns `Err`, see [`Transaction::rollback`].
+///
+/// ```
+/// # use anathema_values::{transaction, StateValue};
+/// let mut first = StateValue::new(1);
+/// let mut second = StateValue::new(2);
+///
+/// let result: Result<(), ()> = transaction::transaction(|| {
+///     *first = 10;
+///     *second = 20;
+///     Ok(())
+/// });
+/// ```
+pub fn transaction<T, E>(f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let tx = Transaction::begin();
+    let result = f();
+    match result {
+        Ok(_) => tx.commit(),
+        Err(_) => tx.rollback(),
+    }
+    result
+}
+
+/// Returns true if a transaction is open
+pub fn is_open() -> bool {
+    PENDING.with_borrow(|pending| !pending.is_empty())
+}
+
+// Hold on to the changes until the transaction is committed.
+// Returns false if there is no open transaction.
+pub(crate) fn hold(node_ids: impl Iterator<Item = NodeId>, change: &Change) -> bool {
+    PENDING.with_borrow_mut(|pending| {
+        let Some(changes) = pending.last_mut() else {
+            return false;
+        };
+
+        for node_id in node_ids {
+            changes.push(node_id, change.clone());
+        }
+
+        true
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::TestState;
+    use crate::{drain_dirty_nodes, State, StateValue};
+
+    #[test]
+    fn commit() {
+        let id: NodeId = 1.into();
+        let mut first = StateValue::new(1);
+        let mut second = StateValue::new(2);
+        first.subscribe(id.clone());
+        second.subscribe(id.clone());
+
+        let tx = Transaction::begin();
+        *first = 10;
+        *second = 20;
+        assert!(drain_dirty_nodes().is_empty());
+        tx.commit();
+
+        assert_eq!(drain_dirty_nodes(), vec![(id, Change::Update)]);
+    }
+
+    #[test]
+    fn rollback_keeps_subscribers() {
+        let id: NodeId = 1.into();
+        let mut value = StateValue::new(1);
+        value.subscribe(id.clone());
+
+        let result: Result<(), ()> = transaction(|| {
+            *value = 2;
+            *value = 3;
+            Err(())
+        });
+        assert!(result.is_err());
+
+        // The mutations are kept, so the node has to show them
+        assert_eq!(*value, 3);
+        assert_eq!(drain_dirty_nodes(), vec![(id.clone(), Change::Update)]);
+
+        // The node is still subscribed
+        *value = 3;
+        assert_eq!(drain_dirty_nodes(), vec![(id, Change::Update)]);
+    }
+
+    #[test]
+    fn nested() {
+        let id: NodeId = 1.into();
+        let mut value = StateValue::new(1);
+        value.subscribe(id.clone());
+
+        let outer = Transaction::begin();
+        let inner = Transaction::begin();
+        *value = 2;
+        inner.commit();
+        assert!(drain_dirty_nodes().is_empty());
+        outer.commit();
+        assert_eq!(drain_dirty_nodes(), vec![(id, Change::Update)]);
+        assert!(!is_open());
+    }
+
+    #[test]
+    fn state_transaction() {
+        let id: NodeId = 1.into();
+        let mut state = TestState::new();
+        state.counter.subscribe(id.clone());
+        state.name.subscribe(id.clone());
+
+        let result: Result<(), ()> = state.transaction(|state| {
+            *state.counter = 4;
+            state.name.push_str(" jr.");
+            Ok(())
+        });
+        assert!(result.is_ok());
+        assert_eq!(drain_dirty_nodes(), vec![(id, Change::Update)]);
+    }
+}
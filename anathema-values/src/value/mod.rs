@@ -8,7 +8,7 @@ pub use self::num::Num;
 pub use self::owned::Owned;
 use crate::hashmap::HashMap;
 use crate::map::Map;
-use crate::{Collection, List, State, ValueExpr};
+use crate::{Collection, List, Resolver, State, ValueExpr};
 
 mod num;
 mod owned;
@@ -61,13 +61,51 @@ pub enum ValueRef<'a> {
 }
 
 impl<'a> ValueRef<'a> {
+    /// The truthiness of a value, as used by `if`, `!`, `&&` and `||`.
+    ///
+    /// * Strings are true if they are not empty
+    /// * Numbers are true if they are not zero (`NaN` is false)
+    /// * Lists are true if they are not empty
+    /// * Maps from expressions are true if they are not empty,
+    ///   maps from a state are always true
+    /// * Chars and colors are always true
+    /// * Empty and deferred values are false
     pub fn is_true(&self) -> bool {
         match self {
             Self::Str(s) => !s.is_empty(),
+            Self::Map(_) => true,
+            Self::List(list) => list.len() > 0,
+            Self::Expressions(Expressions(list)) => !list.is_empty(),
+            Self::ExpressionMap(ExpressionMap(map)) => !map.is_empty(),
             Self::Owned(Owned::Bool(b)) => *b,
-            Self::Owned(Owned::Num(Num::Unsigned(n))) => *n > 0,
-            Self::Owned(Owned::Num(Num::Signed(n))) => *n > 0,
-            _ => false,
+            Self::Owned(Owned::Num(Num::Unsigned(n))) => *n != 0,
+            Self::Owned(Owned::Num(Num::Signed(n))) => *n != 0,
+            Self::Owned(Owned::Num(Num::Float(n))) => *n != 0.0 && !n.is_nan(),
+            Self::Owned(Owned::Char(_) | Owned::Color(_)) => true,
+            Self::Deferred | Self::Empty => false,
+        }
+    }
+}
+
+impl<'a> ValueRef<'a> {
+    /// Expand a list of expressions, or a list from a state,
+    /// into a vector of values.
+    /// For a list from a state the resolver will subscribe to the list,
+    /// as well as to each value.
+    pub fn to_vec(self, resolver: &mut impl Resolver<'a>) -> Option<Vec<ValueRef<'a>>> {
+        match self {
+            Self::Expressions(Expressions(list)) => {
+                Some(list.iter().map(|expr| expr.eval(resolver)).collect())
+            }
+            Self::List(list) => {
+                let len = resolver.resolve_len(list)?;
+                Some(
+                    (0..len)
+                        .map(|index| resolver.resolve_list(list, index))
+                        .collect(),
+                )
+            }
+            _ => None,
         }
     }
 }
@@ -165,8 +203,9 @@ macro_rules! float_try_from {
             type Error = ();
 
             fn try_from(value: ValueRef<'_>) -> Result<Self, Self::Error> {
+                // Whole numbers are valid floats, e.g `max: 100`
                 match value {
-                    ValueRef::Owned(Owned::Num(Num::Float(num))) => Ok(num as $t),
+                    ValueRef::Owned(Owned::Num(num)) => Ok(num.to_f64() as $t),
                     _ => Err(()),
                 }
             }
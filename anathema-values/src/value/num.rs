@@ -45,6 +45,14 @@ impl Num {
 
     to_num!(to_i8, i8);
 
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Self::Signed(num) => num as f64,
+            Self::Unsigned(num) => num as f64,
+            Self::Float(num) => num,
+        }
+    }
+
     pub fn to_negative(self) -> Self {
         Self::Signed(-self.to_i128() as i64)
     }
@@ -242,6 +250,22 @@ macro_rules! into_signed_num {
     };
 }
 
+macro_rules! into_float_num {
+    ($t:ty) => {
+        impl From<$t> for Num {
+            fn from(n: $t) -> Self {
+                Self::Float(n as f64)
+            }
+        }
+
+        impl From<&$t> for Num {
+            fn from(n: &$t) -> Self {
+                Self::Float(*n as f64)
+            }
+        }
+    };
+}
+
 into_unsigned_num!(u8);
 into_unsigned_num!(u16);
 into_unsigned_num!(u32);
@@ -253,3 +277,6 @@ into_signed_num!(i16);
 into_signed_num!(i32);
 into_signed_num!(i64);
 into_signed_num!(isize);
+
+into_float_num!(f32);
+into_float_num!(f64);
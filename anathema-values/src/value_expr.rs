@@ -1,9 +1,12 @@
 use std::fmt::Display;
 use std::rc::Rc;
 
+use crate::functions::{self, FnValue};
 use crate::hashmap::HashMap;
 use crate::scope::ContextRef;
+use crate::spans::Spans;
 use crate::value::{ExpressionMap, Expressions};
+use crate::{history, hover, i18n};
 use crate::{Collection, NodeId, Owned, Path, ScopeValue, State, ValueRef};
 
 // -----------------------------------------------------------------------------
@@ -15,6 +18,19 @@ pub trait Resolver<'expr> {
     fn resolve_list(&mut self, list: &'expr dyn Collection, index: usize) -> ValueRef<'expr>;
 
     fn resolve_map(&mut self, map: &'expr dyn State, key: &str) -> ValueRef<'expr>;
+
+    /// Resolve the length of a collection.
+    /// Returns `None` if the resolver can not access the state.
+    fn resolve_len(&mut self, collection: &'expr dyn Collection) -> Option<usize>;
+
+    /// Prepare to resolve a value depending on the locale,
+    /// such as a translated message or a plural form.
+    /// Returns `false` if the resolver can not access the translations.
+    fn resolve_message(&mut self) -> bool;
+
+    /// Called before calling a function, such as `colorize`.
+    /// Returns `false` if the resolver can not call functions.
+    fn resolve_call(&mut self) -> bool;
 }
 
 // -----------------------------------------------------------------------------
@@ -56,6 +72,18 @@ impl<'a, 'expr> Resolver<'expr> for Deferred<'a, 'expr> {
     fn resolve_map(&mut self, _: &dyn State, _: &str) -> ValueRef<'expr> {
         ValueRef::Deferred
     }
+
+    fn resolve_len(&mut self, _: &dyn Collection) -> Option<usize> {
+        None
+    }
+
+    fn resolve_message(&mut self) -> bool {
+        false
+    }
+
+    fn resolve_call(&mut self) -> bool {
+        false
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -110,6 +138,7 @@ impl<'frame> Resolver<'frame> for Immediate<'frame> {
         //         self.is_deferred = true;
         //     }
 
+        history::read(path, false);
         match self.context.lookup_state(path, self.node_id) {
             ValueRef::Empty => match self.context.lookup_scope(path) {
                 None => {
@@ -120,7 +149,15 @@ impl<'frame> Resolver<'frame> for Immediate<'frame> {
                             self.is_deferred = true;
                         }
                         val
+                    } else if matches!(path, Path::Key(key) if key == hover::HOVERED) {
+                        // Nothing else is called `hovered`
+                        self.is_deferred = true;
+                        hover::subscribe(self.node_id.clone());
+                        ValueRef::Owned(hover::is_hovered(self.node_id).into())
                     } else {
+                        // The state is subscribed to even if the value is missing,
+                        // so the value is updated once it's inserted.
+                        self.is_deferred = true;
                         ValueRef::Empty
                     }
                 }
@@ -153,15 +190,34 @@ impl<'frame> Resolver<'frame> for Immediate<'frame> {
 
     fn resolve_list(&mut self, list: &'frame dyn Collection, index: usize) -> ValueRef<'frame> {
         let path = index.into();
+        history::read(&path, true);
         self.is_deferred = true;
         list.state_get(&path, self.node_id)
     }
 
     fn resolve_map(&mut self, map: &'frame dyn State, key: &str) -> ValueRef<'frame> {
         let path = key.into();
+        history::read(&path, true);
         self.is_deferred = true;
         map.state_get(&path, self.node_id)
     }
+
+    fn resolve_len(&mut self, collection: &'frame dyn Collection) -> Option<usize> {
+        self.is_deferred = true;
+        collection.subscribe(self.node_id.clone());
+        Some(collection.len())
+    }
+
+    fn resolve_message(&mut self) -> bool {
+        self.is_deferred = true;
+        i18n::subscribe(self.node_id.clone());
+        true
+    }
+
+    fn resolve_call(&mut self) -> bool {
+        self.is_deferred = true;
+        true
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -177,6 +233,12 @@ pub enum ValueExpr {
     Negative(Box<ValueExpr>),
     And(Box<ValueExpr>, Box<ValueExpr>),
     Or(Box<ValueExpr>, Box<ValueExpr>),
+    /// The left hand side, or the right hand side if the left hand side
+    /// is missing from the state: `settings.width ?? 40`.
+    Coalesce(Box<ValueExpr>, Box<ValueExpr>),
+    /// The left hand side, or the right hand side if the left hand side
+    /// is missing or not true (see [`ValueRef::is_true`]): `title else "untitled"`.
+    Default(Box<ValueExpr>, Box<ValueExpr>),
     Equality(Box<ValueExpr>, Box<ValueExpr>),
     Greater(Box<ValueExpr>, Box<ValueExpr>),
     GreaterEqual(Box<ValueExpr>, Box<ValueExpr>),
@@ -197,6 +259,30 @@ pub enum ValueExpr {
     Div(Box<ValueExpr>, Box<ValueExpr>),
     Mul(Box<ValueExpr>, Box<ValueExpr>),
     Mod(Box<ValueExpr>, Box<ValueExpr>),
+
+    // Collection transforms.
+    // Both evaluate to the underlying collection, the transform itself
+    // is applied by the loop that iterates over the collection.
+    Filter(Box<ValueExpr>, Box<ValueExpr>),
+    SortBy(Box<ValueExpr>, Box<ValueExpr>),
+    Paginate(Box<ValueExpr>, Box<ValueExpr>, Box<ValueExpr>),
+    /// Keep the items fuzzy matching the query, best match first.
+    /// See [`fuzzy_match`](crate::fuzzy::fuzzy_match).
+    Fuzzy(Box<ValueExpr>, Box<ValueExpr>),
+
+    /// Number of pages in a collection, given the number of items per page.
+    PageCount(Box<ValueExpr>, Box<ValueExpr>),
+
+    /// A translated message: the message key and the arguments.
+    /// Only evaluates to a value as a string, see [`translate`](crate::i18n::translate).
+    Message(Rc<str>, Rc<[ValueExpr]>),
+    /// Pick the plural form for a count in the current locale,
+    /// `plural(count, "item", "items")`.
+    /// See [`plural_form`](crate::i18n::plural_form).
+    Plural(Box<ValueExpr>, Rc<[ValueExpr]>),
+    /// A call to a built-in function, `colorize(sparkline, green, red)`.
+    /// See [`functions`](crate::functions).
+    Call(Rc<str>, Rc<[ValueExpr]>),
 }
 
 impl Display for ValueExpr {
@@ -236,11 +322,50 @@ impl Display for ValueExpr {
             }
             Self::And(lhs, rhs) => write!(f, "{lhs} && {rhs}"),
             Self::Or(lhs, rhs) => write!(f, "{lhs} || {rhs}"),
+            Self::Coalesce(lhs, rhs) => write!(f, "{lhs} ?? {rhs}"),
+            Self::Default(lhs, rhs) => write!(f, "{lhs} else {rhs}"),
             Self::Equality(lhs, rhs) => write!(f, "{lhs} == {rhs}"),
             Self::Greater(lhs, rhs) => write!(f, "{lhs} > {rhs}"),
             Self::GreaterEqual(lhs, rhs) => write!(f, "{lhs} >= {rhs}"),
             Self::Less(lhs, rhs) => write!(f, "{lhs} < {rhs}"),
             Self::LessEqual(lhs, rhs) => write!(f, "{lhs} <= {rhs}"),
+            Self::Filter(collection, predicate) => write!(f, "filter({collection}, {predicate})"),
+            Self::SortBy(collection, key) => write!(f, "sort_by({collection}, {key})"),
+            Self::Paginate(collection, page, per_page) => {
+                write!(f, "paginate({collection}, {page}, {per_page})")
+            }
+            Self::PageCount(collection, per_page) => {
+                write!(f, "page_count({collection}, {per_page})")
+            }
+            Self::Fuzzy(collection, query) => write!(f, "fuzzy({collection}, {query})"),
+            Self::Plural(count, forms) => {
+                write!(f, "plural({count}")?;
+                for form in forms.iter() {
+                    write!(f, ", {form}")?;
+                }
+                write!(f, ")")
+            }
+            Self::Call(fun, args) => {
+                write!(
+                    f,
+                    "{fun}({})",
+                    args.iter()
+                        .map(|val| val.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+            Self::Message(key, args) if args.is_empty() => write!(f, "@{key}"),
+            Self::Message(key, args) => {
+                write!(
+                    f,
+                    "@{key}({})",
+                    args.iter()
+                        .map(|val| val.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
         }
     }
 }
@@ -257,6 +382,36 @@ macro_rules! eval_num {
 
 impl ValueExpr {
     pub fn eval_string<'expr>(&'expr self, resolver: &mut impl Resolver<'expr>) -> Option<String> {
+        match self {
+            Self::Message(key, args) => return Self::eval_message(key, args, resolver),
+            // The form could be a message
+            Self::Plural(count, forms) => {
+                return Self::plural(count, forms, resolver)?.eval_string(resolver)
+            }
+            Self::Call(fun, args) => {
+                return Self::call(fun, args, resolver).map(|value| value.to_string())
+            }
+            // The left hand side could be a message
+            Self::Coalesce(lhs, rhs) => {
+                return lhs
+                    .eval_string(resolver)
+                    .or_else(|| rhs.eval_string(resolver))
+            }
+            // The left hand side could be a message, which falls back if it's empty
+            Self::Default(lhs, rhs) => {
+                return match lhs.eval(resolver) {
+                    ValueRef::Deferred => None,
+                    ValueRef::Empty => lhs
+                        .eval_string(resolver)
+                        .filter(|s| !s.is_empty())
+                        .or_else(|| rhs.eval_string(resolver)),
+                    val if val.is_true() => lhs.eval_string(resolver),
+                    _ => rhs.eval_string(resolver),
+                }
+            }
+            _ => {}
+        }
+
         match self.eval(resolver) {
             ValueRef::Str(s) => Some(s.into()),
             ValueRef::Owned(s) => Some(s.to_string()),
@@ -278,21 +433,89 @@ impl ValueExpr {
         }
     }
 
+    /// Evaluate a call to a function returning text with colors,
+    /// see [`spans`](crate::spans).
+    /// Returns `None` for any other expression.
+    pub fn eval_spans<'expr>(&'expr self, resolver: &mut impl Resolver<'expr>) -> Option<Spans> {
+        match self {
+            Self::Call(fun, args) => match Self::call(fun, args, resolver)? {
+                FnValue::Spans(spans) => Some(spans),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn eval_message<'expr>(
+        key: &str,
+        args: &'expr [ValueExpr],
+        resolver: &mut impl Resolver<'expr>,
+    ) -> Option<String> {
+        if !resolver.resolve_message() {
+            return None;
+        }
+
+        // Arguments are named after the last segment of their path,
+        // so `@greeting(user.name)` can use `{name}`
+        let args = args
+            .iter()
+            .map(|arg| {
+                let name = match arg {
+                    Self::Ident(name) => Some(&**name),
+                    Self::Dot(_, rhs) => match &**rhs {
+                        Self::Ident(name) => Some(&**name),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                (name, arg.eval_string(resolver).unwrap_or_default())
+            })
+            .collect::<Vec<_>>();
+
+        Some(i18n::translate(key, &args))
+    }
+
+    fn call<'expr>(
+        fun: &str,
+        args: &'expr [ValueExpr],
+        resolver: &mut impl Resolver<'expr>,
+    ) -> Option<FnValue> {
+        if !resolver.resolve_call() {
+            return None;
+        }
+
+        let args = args
+            .iter()
+            .map(|arg| match arg.eval(resolver) {
+                ValueRef::Owned(owned) => FnValue::Owned(owned),
+                _ => FnValue::String(arg.eval_string(resolver).unwrap_or_default()),
+            })
+            .collect::<Vec<_>>();
+
+        functions::call(fun, &args)
+    }
+
+    fn plural<'expr>(
+        count: &'expr ValueExpr,
+        forms: &'expr [ValueExpr],
+        resolver: &mut impl Resolver<'expr>,
+    ) -> Option<&'expr ValueExpr> {
+        let ValueRef::Owned(Owned::Num(count)) = count.eval(resolver) else {
+            return None;
+        };
+
+        if !resolver.resolve_message() {
+            return None;
+        }
+
+        forms.get(i18n::plural_form(count, forms.len()))
+    }
+
     pub fn eval_vec<'expr>(
         &'expr self,
         resolver: &mut impl Resolver<'expr>,
-    ) -> Option<Vec<ValueRef<'_>>> {
-        match self.eval(resolver) {
-            ValueRef::Expressions(Expressions(list)) => {
-                let mut v = Vec::with_capacity(list.len());
-                for expr in list {
-                    let res = expr.eval(resolver);
-                    v.push(res);
-                }
-                Some(v)
-            }
-            _ => None,
-        }
+    ) -> Option<Vec<ValueRef<'expr>>> {
+        self.eval(resolver).to_vec(resolver)
     }
 
     // Even though the lifetime is named `'expr`, the value isn't necessarily tied to an expression.
@@ -358,16 +581,38 @@ impl ValueExpr {
                 let rhs = rhs.eval(resolver);
                 ValueRef::Owned((lhs == rhs).into())
             }
-            Self::Or(lhs, rhs) => {
-                let lhs = lhs.eval(resolver);
-                let rhs = rhs.eval(resolver);
-                ValueRef::Owned((lhs.is_true() || rhs.is_true()).into())
-            }
-            Self::And(lhs, rhs) => {
-                let lhs = lhs.eval(resolver);
-                let rhs = rhs.eval(resolver);
-                ValueRef::Owned((lhs.is_true() && rhs.is_true()).into())
-            }
+            // Both `||` and `&&` short-circuit: the right hand side is only
+            // evaluated (and subscribed to) if the left hand side doesn't
+            // already decide the outcome.
+            Self::Or(lhs, rhs) => match lhs.eval(resolver) {
+                ValueRef::Deferred => ValueRef::Deferred,
+                lhs if lhs.is_true() => ValueRef::Owned(true.into()),
+                _ => match rhs.eval(resolver) {
+                    ValueRef::Deferred => ValueRef::Deferred,
+                    rhs => ValueRef::Owned(rhs.is_true().into()),
+                },
+            },
+            // Only an empty value falls back to the right hand side,
+            // unlike `||` a `false`, zero or empty string is kept.
+            Self::Coalesce(lhs, rhs) => match lhs.eval(resolver) {
+                ValueRef::Empty => rhs.eval(resolver),
+                val => val,
+            },
+            // Unlike `??` a `false`, zero or empty string also falls back,
+            // and unlike `||` the value itself is kept rather than a bool.
+            Self::Default(lhs, rhs) => match lhs.eval(resolver) {
+                ValueRef::Deferred => ValueRef::Deferred,
+                val if val.is_true() => val,
+                _ => rhs.eval(resolver),
+            },
+            Self::And(lhs, rhs) => match lhs.eval(resolver) {
+                ValueRef::Deferred => ValueRef::Deferred,
+                lhs if !lhs.is_true() => ValueRef::Owned(false.into()),
+                _ => match rhs.eval(resolver) {
+                    ValueRef::Deferred => ValueRef::Deferred,
+                    rhs => ValueRef::Owned(rhs.is_true().into()),
+                },
+            },
 
             // -----------------------------------------------------------------------------
             //   - Paths -
@@ -379,7 +624,7 @@ impl ValueExpr {
             Self::Index(lhs, index) => match lhs.eval(resolver) {
                 ValueRef::Expressions(list) => {
                     let index = eval_num!(index, resolver).to_usize();
-                    return list.0[index].eval(resolver);
+                    list.0[index].eval(resolver)
                 }
                 ValueRef::ExpressionMap(map) => {
                     let key = index.eval_string(resolver).unwrap_or(String::new());
@@ -402,7 +647,10 @@ impl ValueExpr {
                         ValueExpr::Ident(key) => key,
                         _ => return ValueRef::Empty,
                     };
-                    return map.0[&**key].eval(resolver);
+                    match map.0.get(&**key) {
+                        Some(expr) => expr.eval(resolver),
+                        None => ValueRef::Empty,
+                    }
                 }
                 ValueRef::Map(map) => {
                     let key = match &**rhs {
@@ -419,6 +667,44 @@ impl ValueExpr {
             // -----------------------------------------------------------------------------
             Self::List(list) => ValueRef::Expressions(Expressions::new(list)),
             Self::Map(map) => ValueRef::ExpressionMap(ExpressionMap::new(map)),
+
+            // -----------------------------------------------------------------------------
+            //   - Transforms -
+            // -----------------------------------------------------------------------------
+            Self::Filter(collection, _)
+            | Self::SortBy(collection, _)
+            | Self::Paginate(collection, ..)
+            | Self::Fuzzy(collection, _) => collection.eval(resolver),
+            Self::PageCount(collection, per_page) => {
+                let per_page = eval_num!(per_page, resolver).to_usize();
+                if per_page == 0 {
+                    return ValueRef::Empty;
+                }
+
+                let len = match collection.eval(resolver) {
+                    ValueRef::Expressions(list) => list.0.len(),
+                    ValueRef::List(list) => match resolver.resolve_len(list) {
+                        Some(len) => len,
+                        None => return ValueRef::Deferred,
+                    },
+                    ValueRef::Deferred => return ValueRef::Deferred,
+                    _ => return ValueRef::Empty,
+                };
+
+                ValueRef::Owned(Owned::Num(len.div_ceil(per_page).into()))
+            }
+
+            // Messages are formatted, and can only be resolved as strings
+            Self::Message(..) => ValueRef::Empty,
+            Self::Plural(count, forms) => match Self::plural(count, forms, resolver) {
+                Some(form) => form.eval(resolver),
+                None => ValueRef::Empty,
+            },
+            // Only functions returning an owned value can be evaluated as anything but a string
+            Self::Call(fun, args) => match Self::call(fun, args, resolver) {
+                Some(FnValue::Owned(owned)) => ValueRef::Owned(owned),
+                Some(FnValue::String(_) | FnValue::Spans(_)) | None => ValueRef::Empty,
+            },
         }
     }
 }
@@ -454,8 +740,12 @@ impl From<&str> for ValueExpr {
 mod test {
     use crate::map::Map;
     use crate::testing::{
-        add, and, div, dot, eq, greater_than, greater_than_equal, ident, inum, less_than,
-        less_than_equal, list, modulo, mul, neg, not, or, strlit, sub, unum,
+        add, and, call, coalesce, default, div, dot, eq, greater_than, greater_than_equal, ident,
+        inum, less_than, less_than_equal, list, modulo, mul, neg, not, or, page_count, plural,
+        strlit, sub, unum,
+    };
+    use crate::{
+        drain_dirty_nodes, subscription_count, Context, Immediate, NodeId, ValueExpr, ValueRef,
     };
 
     #[test]
@@ -578,6 +868,184 @@ mod test {
             .eval_bool(false);
     }
 
+    #[test]
+    fn truthiness() {
+        assert!(strlit("a").test().eval_bool(true));
+        assert!(strlit("").test().eval_bool(false));
+        assert!(neg(inum(1)).test().eval_bool(true));
+        assert!(unum(0).test().eval_bool(false));
+        assert!(ValueExpr::from(0.5).test().eval_bool(true));
+        assert!(ValueExpr::from(0.0).test().eval_bool(false));
+        assert!(list([1]).test().eval_bool(true));
+        assert!(list::<ValueExpr>([]).test().eval_bool(false));
+        assert!(ident("missing").test().eval_bool(false));
+        assert!(not(strlit("")).test().eval_bool(true));
+    }
+
+    #[test]
+    fn short_circuit() {
+        let state = Map::new([("yes", true), ("no", false)]);
+        let context = Context::root(&state);
+        let node_id = NodeId::from(0);
+        let before = subscription_count();
+
+        // The right hand side is never evaluated, therefore never subscribed to
+        let expr = and(ident("no"), ident("yes"));
+        let mut resolver = Immediate::new(context.lookup(), &node_id);
+        assert!(!expr.eval(&mut resolver).is_true());
+        assert_eq!(subscription_count(), before + 1);
+
+        let expr = or(ident("yes"), ident("no"));
+        let mut resolver = Immediate::new(context.lookup(), &node_id);
+        assert!(expr.eval(&mut resolver).is_true());
+        assert_eq!(subscription_count(), before + 2);
+
+        // Truthy values are reduced to bools
+        let expr = and(strlit("a"), unum(2));
+        expr.test().expect_owned(true);
+        let expr = or(strlit(""), list::<ValueExpr>([]));
+        expr.test().expect_owned(false);
+    }
+
+    #[test]
+    fn coalesce_missing_values() {
+        let expr = coalesce(ident("missing"), unum(40));
+        expr.test().expect_owned(40u8);
+
+        let expr = coalesce(dot(ident("settings"), ident("width")), unum(40));
+        expr.test().expect_owned(40u8);
+
+        let expr = coalesce(ident("width"), unum(40));
+        expr.with_data([("width", 10)]).expect_owned(10);
+
+        // Falsy values are not replaced
+        let expr = coalesce(ident("debug"), ValueExpr::from(true).into());
+        expr.with_data([("debug", false)]).expect_owned(false);
+
+        let expr = coalesce(ident("missing"), strlit("default"));
+        expr.test().expect_string("default");
+
+        let expr = coalesce(ident("missing"), coalesce(ident("other"), unum(1)));
+        expr.test().expect_owned(1u8);
+    }
+
+    #[test]
+    fn default_values() {
+        let expr = default(ident("missing"), unum(40));
+        expr.test().expect_owned(40u8);
+
+        let expr = default(ident("width"), unum(40));
+        expr.with_data([("width", 10)]).expect_owned(10);
+
+        // Falsy values are replaced as well
+        let expr = default(ident("width"), unum(40));
+        expr.with_data([("width", 0)]).expect_owned(40u8);
+
+        let expr = default(ident("title"), strlit("untitled"));
+        expr.with_data([("title", String::new())])
+            .expect_string("untitled");
+
+        let expr = default(ident("title"), strlit("untitled"));
+        expr.with_data([("title", "hello".to_string())])
+            .expect_string("hello");
+
+        let expr = default(coalesce(ident("missing"), unum(0)), unum(1));
+        expr.test().expect_owned(1u8);
+    }
+
+    #[test]
+    fn optional_paths() {
+        let mut state = Map::new([("user", Map::<Map<usize>>::empty())]);
+        let node_id = NodeId::from(0);
+        let expr = dot(dot(ident("user"), ident("address")), ident("number"));
+        {
+            let context = Context::root(&state);
+            let mut resolver = Immediate::new(context.lookup(), &node_id);
+            assert!(matches!(expr.eval(&mut resolver), ValueRef::Empty));
+            assert!(resolver.is_deferred());
+        }
+
+        // Inserting the missing map notifies the node
+        let _ = drain_dirty_nodes();
+        state
+            .get_mut("user")
+            .unwrap()
+            .insert("address".into(), Map::new([("number", 42)]));
+        assert_eq!(drain_dirty_nodes()[0].0, node_id);
+
+        let context = Context::root(&state);
+        let mut resolver = Immediate::new(context.lookup(), &node_id);
+        assert_eq!(expr.eval(&mut resolver), ValueRef::Owned(42usize.into()));
+
+        // Missing keys in an expression map
+        let expr = dot(ValueExpr::Map(Default::default()).into(), ident("missing"));
+        assert!(expr.test().eval_bool(false));
+    }
+
+    #[test]
+    fn missing_value_is_deferred() {
+        let mut state = Map::<usize>::empty();
+        let node_id = NodeId::from(0);
+        let expr = ident("counter");
+        {
+            let context = Context::root(&state);
+            let mut resolver = Immediate::new(context.lookup(), &node_id);
+            assert!(matches!(expr.eval(&mut resolver), ValueRef::Empty));
+            assert!(resolver.is_deferred());
+        }
+
+        let _ = drain_dirty_nodes();
+        state.insert("counter".into(), 1);
+        assert_eq!(drain_dirty_nodes()[0].0, node_id);
+    }
+
+    #[test]
+    fn page_count_of_list() {
+        let expr = page_count(list([1, 2, 3, 4, 5]), unum(2));
+        expr.test().expect_owned(3usize);
+
+        let expr = page_count(list([1, 2, 3, 4]), unum(2));
+        expr.test().expect_owned(2usize);
+
+        let expr = page_count(list([1, 2, 3, 4]), ident("per_page"));
+        expr.with_data([("per_page", 4)]).expect_owned(1usize);
+    }
+
+    #[test]
+    fn plural_forms() {
+        let expr = plural(ident("count"), vec![strlit("item"), strlit("items")]);
+        expr.with_data([("count", 1)]).expect_string("item");
+        expr.with_data([("count", 0)]).expect_string("items");
+        expr.with_data([("count", 2)]).expect_string("items");
+    }
+
+    #[test]
+    fn call_function() {
+        let expr = call("colorize", vec![ident("name"), strlit("red")]);
+        expr.with_data([("name", "hello".to_string())])
+            .expect_string("hello");
+
+        let expr = call("missing", vec![]);
+        assert_eq!(expr.to_string(), "missing()");
+        assert!(expr
+            .with_data([("name", "hello".to_string())])
+            .eval_bool(false));
+    }
+
+    #[test]
+    fn hovered() {
+        crate::hover::set_hovered(Some(vec![0, 3].into()));
+        ident("hovered").test().expect_owned(true);
+
+        // The state takes precedence
+        ident("hovered")
+            .with_data([("hovered", false)])
+            .expect_owned(false);
+
+        crate::hover::set_hovered(None);
+        ident("hovered").test().expect_owned(false);
+    }
+
     #[test]
     fn path() {
         let test = dot(ident("inner"), ident("name"));
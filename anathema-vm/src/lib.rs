@@ -2,7 +2,7 @@ mod error;
 mod scope;
 mod vm;
 
-use anathema_compiler::{ViewId, ViewIds};
+use anathema_compiler::{ViewId, ViewIds, DEFAULT_TAB_WIDTH};
 use anathema_values::hashmap::HashMap;
 use anathema_widget_core::expressions::{root_view, Expression};
 use anathema_widget_core::views::{AnyView, RegisteredViews, View};
@@ -14,6 +14,7 @@ pub struct ViewTemplates {
     view_ids: ViewIds,
     inner: HashMap<ViewId, Template>,
     dep_list: Vec<ViewId>,
+    tab_width: usize,
 }
 
 impl ViewTemplates {
@@ -22,6 +23,7 @@ impl ViewTemplates {
             view_ids: ViewIds::new(),
             inner: HashMap::new(),
             dep_list: vec![],
+            tab_width: DEFAULT_TAB_WIDTH,
         }
     }
 
@@ -77,6 +79,12 @@ impl Templates {
         }
     }
 
+    /// The number of columns between tab stops, when a template is indented with tabs.
+    /// This has to be set before the templates are compiled.
+    pub fn set_tab_width(&mut self, tab_width: usize) {
+        self.view_templates.tab_width = tab_width;
+    }
+
     pub fn compile(&mut self) -> Result<()> {
         let expressions = templates(&self.root, &mut self.view_templates)?;
         let root = root_view(expressions, self.view_templates.view_ids.root_id());
@@ -110,13 +118,21 @@ impl Templates {
     }
 }
 
+/// Compile a template on its own, without a root view.
+/// The expressions can be rendered without a runtime,
+/// see `anathema_runtime::render_to_buffer`.
+pub fn compile_template(src: &str) -> Result<Vec<Expression>> {
+    templates(src, &mut ViewTemplates::new())
+}
+
 enum Template {
     Pending(String),
     Evaluated(Vec<Expression>),
 }
 
 fn templates(root: &str, views: &mut ViewTemplates) -> Result<Vec<Expression>> {
-    let (instructions, constants) = anathema_compiler::compile(root, &mut views.view_ids)?;
+    let (instructions, constants) =
+        anathema_compiler::compile_with_tab_width(root, &mut views.view_ids, views.tab_width)?;
     let vm = VirtualMachine::new(instructions, constants);
     vm.exec(views)
 }
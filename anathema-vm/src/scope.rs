@@ -69,11 +69,7 @@ impl<'vm> Scope<'vm> {
                         elses: vec![],
                     };
 
-                    loop {
-                        let Some(&Instruction::Else { cond, size }) = self.instructions.first()
-                        else {
-                            break;
-                        };
+                    while let Some(&Instruction::Else { cond, size }) = self.instructions.first() {
                         self.instructions.remove(0);
                         let cond = cond.map(|cond| self.consts.lookup_value(cond));
 
@@ -112,7 +108,7 @@ impl<'vm> Scope<'vm> {
         while let Some(Instruction::LoadAttribute { key, value }) = self.instructions.get(ip) {
             let key = self.consts.lookup_string(*key);
             let value = self.consts.lookup_value(*value);
-            attributes.insert(key.to_string(), value.clone());
+            attributes.insert(key.into(), value.clone());
             ip += 1;
         }
 
@@ -146,7 +142,7 @@ impl<'vm> Scope<'vm> {
         let children = Scope::new(scope, self.consts).exec(views)?;
 
         let node = Expression::Node(SingleNodeExpr {
-            ident: ident.to_string(),
+            ident: ident.into(),
             text,
             attributes,
             children,
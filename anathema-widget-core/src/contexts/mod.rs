@@ -1,9 +1,12 @@
 use std::ops::Deref;
+use std::sync::Arc;
 
-use anathema_render::{Screen, ScreenPos, Size, Style};
+use anathema_render::{Color, Highlight, HighlightStyle, Screen, ScreenPos, Size, Style};
+use anathema_values::NodeId;
 use unicode_width::UnicodeWidthChar;
 
 use crate::layout::Constraints;
+use crate::paint_cache::PaintCache;
 use crate::{Align, LocalPos, Pos, Region};
 
 mod data;
@@ -112,6 +115,84 @@ impl<'screen> PaintCtx<'screen, WithSize> {
         region
     }
 
+    /// Paint into an offscreen buffer the size of the screen,
+    /// and keep the cells of the local region.
+    pub(crate) fn capture(&mut self, f: impl FnOnce(&mut PaintCtx<'_, WithSize>)) -> PaintCache {
+        let mut cache = self.paint_cache();
+        let mut offscreen = Screen::new(self.screen.size());
+        let clip = self.clip.copied();
+        let mut ctx = PaintCtx::new(&mut offscreen, clip.as_ref())
+            .into_sized(self.local_size, self.global_pos);
+        f(&mut ctx);
+        offscreen.apply_highlights();
+
+        for y in 0..self.local_size.height {
+            for x in 0..self.local_size.width {
+                let cell = self
+                    .translate_to_screen(LocalPos::new(x, y))
+                    .and_then(|pos| {
+                        let (c, style) = offscreen.get(pos)?;
+                        Some((c, style, offscreen.link(pos).cloned()))
+                    });
+                cache.cells.push(cell);
+            }
+        }
+
+        cache
+    }
+
+    /// An empty paint cache for the local region
+    pub(crate) fn paint_cache(&self) -> PaintCache {
+        PaintCache::new(
+            self.global_pos,
+            self.local_size,
+            self.screen.size(),
+            self.clip,
+        )
+    }
+
+    /// Copy the cells of a paint cache onto the screen
+    pub(crate) fn blit(&mut self, cache: &PaintCache) {
+        let width = self.local_size.width.max(1);
+        for (i, cell) in cache.cells.iter().enumerate() {
+            let Some((c, style, link)) = cell else {
+                continue;
+            };
+            let Some(pos) = self.translate_to_screen(LocalPos::new(i % width, i / width)) else {
+                continue;
+            };
+            match link {
+                Some(link) => self.screen.put_with_link(*c, *style, link.clone(), pos),
+                None => self.screen.put(*c, *style, pos),
+            }
+        }
+    }
+
+    /// Returns false if the local region lies entirely outside of the screen
+    /// or the clipping region, meaning nothing painted with this context would show.
+    pub fn is_visible(&self) -> bool {
+        let Size { width, height } = self.local_size;
+        if width == 0 || height == 0 {
+            return true;
+        }
+
+        let from = self.global_pos;
+        let to = Pos::new(from.x + width as i32 - 1, from.y + height as i32 - 1);
+        let screen = self.screen.size();
+        let mut bounds = Region::new(
+            Pos::ZERO,
+            Pos::new(screen.width as i32 - 1, screen.height as i32 - 1),
+        );
+        if let Some(clip) = self.clip {
+            bounds.constrain(clip);
+        }
+
+        from.x <= bounds.to.x
+            && to.x >= bounds.from.x
+            && from.y <= bounds.to.y
+            && to.y >= bounds.from.y
+    }
+
     fn clip(&self, local_pos: LocalPos, clip: &Region) -> bool {
         let pos = self.global_pos + local_pos;
         clip.contains(pos)
@@ -150,6 +231,132 @@ impl<'screen> PaintCtx<'screen, WithSize> {
         }
     }
 
+    /// Show the terminal cursor at a local position.
+    /// This is where terminals draw the composition string of an input method (IME),
+    /// so widgets accepting text input should place the cursor at the insertion point.
+    ///
+    /// The cursor is hidden unless a widget sets it while painting.
+    pub fn set_cursor(&mut self, pos: LocalPos) {
+        if let Some(clip) = self.clip.as_ref() {
+            if !self.clip(pos, clip) {
+                return;
+            }
+        }
+
+        if !self.pos_inside_local_region(pos, 0) {
+            return;
+        }
+
+        if let Some(screen_pos) = self.translate_to_screen(pos) {
+            self.screen.set_cursor(Some(screen_pos));
+        }
+    }
+
+    /// Set the background color of everything painted so far inside the local region
+    pub(crate) fn set_background(&mut self, color: Color) {
+        for y in 0..self.local_size.height {
+            for x in 0..self.local_size.width {
+                let pos = LocalPos::new(x, y);
+                let Some(screen_pos) = self.translate_to_screen(pos) else {
+                    continue;
+                };
+                let (c, mut style) = self.screen.get(screen_pos).unwrap_or((' ', Style::new()));
+                style.set_bg(color);
+                match self.screen.link(screen_pos).cloned() {
+                    Some(link) => self.screen.put_with_link(c, style, link, screen_pos),
+                    None => self.screen.put(c, style, screen_pos),
+                }
+            }
+        }
+    }
+
+    /// Erase everything painted so far inside the local region
+    pub fn erase(&mut self) {
+        for y in 0..self.local_size.height {
+            for x in 0..self.local_size.width {
+                let pos = LocalPos::new(x, y);
+                if let Some(clip) = self.clip.as_ref() {
+                    if !self.clip(pos, clip) {
+                        continue;
+                    }
+                }
+                if let Some(screen_pos) = self.translate_to_screen(pos) {
+                    self.screen.erase_region(screen_pos, Size::new(1, 1));
+                }
+            }
+        }
+    }
+
+    /// Restyle a region of cells once everything is painted, e.g a selection
+    /// or the matches of a search, without painting the content again.
+    /// The region is clipped to the local region. See [`Screen::highlight`].
+    pub fn highlight(&mut self, pos: LocalPos, size: Size, style: HighlightStyle) {
+        let width = size.width.min(self.local_size.width.saturating_sub(pos.x));
+        let height = size
+            .height
+            .min(self.local_size.height.saturating_sub(pos.y));
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let from = self.global_pos + pos;
+        let mut region = Region::new(
+            from,
+            Pos::new(from.x + width as i32 - 1, from.y + height as i32 - 1),
+        );
+        let screen = self.screen.size();
+        region.constrain(&Region::new(
+            Pos::ZERO,
+            Pos::new(screen.width as i32 - 1, screen.height as i32 - 1),
+        ));
+        if let Some(clip) = self.clip {
+            region.constrain(clip);
+        }
+        if region.from.x > region.to.x || region.from.y > region.to.y {
+            return;
+        }
+
+        self.screen.highlight(Highlight {
+            pos: ScreenPos::new(region.from.x as u16, region.from.y as u16),
+            size: Size::new(
+                (region.to.x - region.from.x + 1) as usize,
+                (region.to.y - region.from.y + 1) as usize,
+            ),
+            style,
+        });
+    }
+
+    /// Change the style of everything painted so far inside the local region,
+    /// e.g to dim the widgets beneath a dialog.
+    /// Empty cells are filled with a space if the new style has a background.
+    pub fn restyle(&mut self, f: impl Fn(&mut Style)) {
+        for y in 0..self.local_size.height {
+            for x in 0..self.local_size.width {
+                let pos = LocalPos::new(x, y);
+                if let Some(clip) = self.clip.as_ref() {
+                    if !self.clip(pos, clip) {
+                        continue;
+                    }
+                }
+                let Some(screen_pos) = self.translate_to_screen(pos) else {
+                    continue;
+                };
+
+                if self.screen.get(screen_pos).is_some() {
+                    self.screen.restyle(screen_pos, &f);
+                    continue;
+                }
+
+                let mut style = Style::new();
+                f(&mut style);
+                if style.bg.is_some() {
+                    self.screen.put(' ', Style::new(), screen_pos);
+                    self.screen.restyle(screen_pos, &f);
+                }
+            }
+        }
+    }
+
     pub fn print(&mut self, s: &str, style: Style, mut pos: LocalPos) -> Option<LocalPos> {
         for c in s.chars() {
             let p = self.put(c, style, pos)?;
@@ -158,6 +365,32 @@ impl<'screen> PaintCtx<'screen, WithSize> {
         Some(pos)
     }
 
+    /// Print a string linking to a URL, see [`Screen::put_with_link`]
+    pub fn print_link(
+        &mut self,
+        s: &str,
+        style: Style,
+        link: &Arc<str>,
+        mut pos: LocalPos,
+    ) -> Option<LocalPos> {
+        for c in s.chars() {
+            let p = self.put_link(c, style, link, pos)?;
+            pos = p;
+        }
+        Some(pos)
+    }
+
+    /// Place a char linking to a URL, see [`Screen::put_with_link`]
+    pub fn put_link(
+        &mut self,
+        c: char,
+        style: Style,
+        link: &Arc<str>,
+        input_pos: LocalPos,
+    ) -> Option<LocalPos> {
+        self.put_cell(c, style, Some(link), input_pos)
+    }
+
     // Place a char on the screen buffer, return the next cursor position in local space.
     //
     // The `input_pos` is the position, in local space, where the character
@@ -165,6 +398,16 @@ impl<'screen> PaintCtx<'screen, WithSize> {
     //
     // The `outpout_pos` is the same as the `input_pos` unless clipping has been applied.
     pub fn put(&mut self, c: char, style: Style, input_pos: LocalPos) -> Option<LocalPos> {
+        self.put_cell(c, style, None, input_pos)
+    }
+
+    fn put_cell(
+        &mut self,
+        c: char,
+        style: Style,
+        link: Option<&Arc<str>>,
+        input_pos: LocalPos,
+    ) -> Option<LocalPos> {
         let width = c.width().unwrap_or(0);
         let next = LocalPos {
             x: input_pos.x + width,
@@ -185,6 +428,7 @@ impl<'screen> PaintCtx<'screen, WithSize> {
 
         // 2. Check if the char can be placed
         if !self.pos_inside_local_region(input_pos, width) {
+            crate::overflow::report_paint(input_pos, self.local_size);
             return None;
         }
 
@@ -193,7 +437,12 @@ impl<'screen> PaintCtx<'screen, WithSize> {
             Some(pos) => pos,
             None => return Some(next),
         };
-        self.screen.put(c, style, screen_pos);
+        match link {
+            Some(link) => self
+                .screen
+                .put_with_link(c, style, link.clone(), screen_pos),
+            None => self.screen.put(c, style, screen_pos),
+        }
 
         // 4. Advance the cursor (which might trigger another newline)
         if input_pos.x >= self.local_size.width {
@@ -227,6 +476,36 @@ impl PositionCtx {
     }
 }
 
+// -----------------------------------------------------------------------------
+//     - Event context -
+// -----------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone)]
+pub struct EventCtx<'a> {
+    pub node_id: &'a NodeId,
+    /// Global position of the widget
+    pub pos: Pos,
+    pub size: Size,
+}
+
+impl<'a> EventCtx<'a> {
+    pub fn new(node_id: &'a NodeId, pos: Pos, size: Size) -> Self {
+        Self { node_id, pos, size }
+    }
+
+    /// Translate a screen position (e.g from a mouse event) to local coordinates.
+    /// Returns `None` if the position is outside of the widget.
+    pub fn local_pos(&self, x: u16, y: u16) -> Option<LocalPos> {
+        let x = x as i32 - self.pos.x;
+        let y = y as i32 - self.pos.y;
+
+        if x < 0 || y < 0 || x as usize >= self.size.width || y as usize >= self.size.height {
+            return None;
+        }
+
+        Some(LocalPos::new(x as usize, y as usize))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use anathema_render::Screen;
@@ -247,6 +526,79 @@ mod test {
         assert_eq!('x', actual);
     }
 
+    #[test]
+    fn capture_and_blit() {
+        let mut screen = Screen::new(Size::new(10, 5));
+        screen.put('x', Style::new(), ScreenPos::new(4, 2));
+        let mut ctx = PaintCtx::new(&mut screen, None).into_sized(Size::new(3, 2), Pos::new(3, 1));
+
+        // Painting into the cache leaves the screen as it is
+        let cache = ctx.capture(|ctx| {
+            ctx.print("ab", Style::new(), LocalPos::new(0, 0));
+            ctx.put('c', Style::new(), LocalPos::new(2, 1));
+        });
+        assert!(cache.matches(&ctx.paint_cache()));
+        assert_eq!(screen.get(ScreenPos::new(3, 1)), None);
+
+        let mut ctx = PaintCtx::new(&mut screen, None).into_sized(Size::new(3, 2), Pos::new(3, 1));
+        ctx.blit(&cache);
+        assert_eq!(screen.get(ScreenPos::new(3, 1)).unwrap().0, 'a');
+        assert_eq!(screen.get(ScreenPos::new(4, 1)).unwrap().0, 'b');
+        assert_eq!(screen.get(ScreenPos::new(5, 2)).unwrap().0, 'c');
+        // Cells that were not painted are left alone
+        assert_eq!(screen.get(ScreenPos::new(4, 2)).unwrap().0, 'x');
+    }
+
+    #[test]
+    fn visible() {
+        let mut screen = Screen::new(Size::new(10, 5));
+        let ctx = PaintCtx::new(&mut screen, None);
+        assert!(ctx
+            .into_sized(Size::new(2, 2), Pos::new(-1, 4))
+            .is_visible());
+
+        let ctx = PaintCtx::new(&mut screen, None);
+        assert!(!ctx
+            .into_sized(Size::new(2, 2), Pos::new(-2, 0))
+            .is_visible());
+
+        let ctx = PaintCtx::new(&mut screen, None);
+        assert!(!ctx.into_sized(Size::new(2, 2), Pos::new(0, 5)).is_visible());
+
+        // Outside of the clipping region
+        let clip = Region::new(Pos::new(2, 2), Pos::new(4, 4));
+        let ctx = PaintCtx::new(&mut screen, Some(&clip));
+        assert!(!ctx.into_sized(Size::new(2, 2), Pos::new(5, 2)).is_visible());
+
+        let ctx = PaintCtx::new(&mut screen, Some(&clip));
+        assert!(ctx.into_sized(Size::new(2, 2), Pos::new(4, 1)).is_visible());
+    }
+
+    #[test]
+    fn highlight_clipped() {
+        let mut screen = Screen::new(Size::new(10, 10));
+        let clipping_region = Region::new(Pos::new(0, 0), Pos::new(3, 9));
+        let mut ctx = PaintCtx::new(&mut screen, Some(&clipping_region))
+            .into_sized(Size::new(5, 2), Pos::new(1, 1));
+        ctx.highlight(
+            LocalPos::new(1, 0),
+            Size::new(10, 10),
+            HighlightStyle::Inverse,
+        );
+        ctx.highlight(
+            LocalPos::new(5, 0),
+            Size::new(1, 1),
+            HighlightStyle::Inverse,
+        );
+
+        let expected = Highlight {
+            pos: ScreenPos::new(2, 1),
+            size: Size::new(2, 2),
+            style: HighlightStyle::Inverse,
+        };
+        assert_eq!(screen.highlights(), &[expected]);
+    }
+
     #[test]
     fn clip() {
         // Put a character on screen
@@ -293,4 +645,19 @@ mod test {
         assert!(screen.buffer().get(ScreenPos::new(2, 2)).is_none());
         assert!(screen.buffer().get(ScreenPos::new(100, 100)).is_none());
     }
+
+    #[test]
+    fn set_cursor() {
+        let size = Size::new(10, 5);
+        let mut screen = Screen::new(size);
+        let global_pos = Pos::new(3, 2);
+        let mut ctx = PaintCtx::new(&mut screen, None).into_sized(Size::new(2, 2), global_pos);
+
+        // Outside of the context
+        ctx.set_cursor(LocalPos::new(5, 5));
+        assert!(ctx.screen.cursor().is_none());
+
+        ctx.set_cursor(LocalPos::new(1, 1));
+        assert_eq!(screen.cursor(), Some(ScreenPos::new(4, 3)));
+    }
 }
@@ -1,4 +1,4 @@
-use anathema_values::Path;
+use anathema_values::{NodeId, Path};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -23,6 +23,14 @@ pub enum Error {
     #[error("insufficient layout space available")]
     InsufficientSpaceAvailble,
 
+    /// Invalid keymap
+    #[error("invalid keymap on line {line}: {msg}")]
+    Keymap { line: usize, msg: String },
+
+    /// Stdout is not a terminal
+    #[error("stdout is not a terminal")]
+    NotATerminal,
+
     /// IO error
     #[error("{0}")]
     Io(#[from] std::io::Error),
@@ -34,4 +42,20 @@ pub enum Error {
     /// Only one instance of this view can exist
     #[error("this view has already been consumed")]
     ViewConsumed,
+
+    /// A widget is not of the expected type
+    #[error("{0}")]
+    WidgetCast(#[from] WidgetCastError),
+}
+
+/// A widget is not of the type it was cast to.
+/// See [`WidgetContainer::try_to`](crate::WidgetContainer::try_to).
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("invalid widget type: expected `{expected}`, found `{found}` (node {node_id:?})")]
+pub struct WidgetCastError {
+    /// The type name of the expected widget
+    pub expected: &'static str,
+    /// The kind of the widget, see [`Widget::kind`](crate::Widget::kind)
+    pub found: &'static str,
+    pub node_id: NodeId,
 }
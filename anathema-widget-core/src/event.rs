@@ -1,11 +1,13 @@
-use std::time::Duration;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
 
+use anathema_values::Symbol;
 use crossterm::event::{read, Event as CTEvent};
 pub use crossterm::event::{
     KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MouseButton, MouseEventKind,
 };
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Event {
     Noop,
     Quit,
@@ -15,7 +17,9 @@ pub enum Event {
     KeyPress(KeyCode, KeyModifiers, KeyEventState),
     KeyRelease(KeyCode, KeyModifiers, KeyEventState),
     KeyRepeat(KeyCode, KeyModifiers, KeyEventState),
-    MouseDown(u16, u16, MouseButton, KeyModifiers),
+    /// The last field is the click count: `1` for a single click, `2` for a double click etc.
+    /// See [`ClickCounter`].
+    MouseDown(u16, u16, MouseButton, KeyModifiers, u8),
     MouseDrag(u16, u16, MouseButton, KeyModifiers),
     MouseMove(u16, u16, KeyModifiers),
     MouseScrollDown(u16, u16, KeyModifiers),
@@ -25,6 +29,18 @@ pub enum Event {
     MouseScrollRight(u16, u16, KeyModifiers),
     MouseUp(u16, u16, MouseButton, KeyModifiers),
     Resize(u16, u16),
+    /// Pasted text.
+    /// Pasting delivers the entire text as a single event rather than one key press per character,
+    /// so it can be inserted with a single state change.
+    Paste(String),
+    /// A named action from the [`Keymap`](crate::keymap::Keymap)
+    Action(Symbol),
+    /// A widget changed its value, e.g a slider was moved.
+    /// The symbol is the `action` of the widget, see [`send_change`].
+    Change(Symbol, f64),
+    /// A widget changed its text, e.g text was typed into an input.
+    /// The symbol is the `action` of the widget, see [`send_input`].
+    Input(Symbol, String),
 }
 
 impl Event {
@@ -40,7 +56,7 @@ impl Event {
 impl From<CTEvent> for Event {
     fn from(ct_event: CTEvent) -> Self {
         match ct_event {
-            CTEvent::Paste(_) => Self::Noop,
+            CTEvent::Paste(text) => Self::Paste(text),
             CTEvent::FocusGained => Self::Focus,
             CTEvent::FocusLost => Self::Blur,
             CTEvent::Key(KeyEvent {
@@ -69,7 +85,7 @@ impl From<CTEvent> for Event {
             ) => Self::KeyRepeat(ev.code, ev.modifiers, ev.state),
             CTEvent::Mouse(m) => match m.kind {
                 MouseEventKind::Down(button) => {
-                    Self::MouseDown(m.column, m.row, button, m.modifiers)
+                    Self::MouseDown(m.column, m.row, button, m.modifiers, 1)
                 }
                 MouseEventKind::Up(button) => Self::MouseUp(m.column, m.row, button, m.modifiers),
                 MouseEventKind::Drag(button) => {
@@ -86,6 +102,87 @@ impl From<CTEvent> for Event {
     }
 }
 
+/// The outcome of a widget handling an event.
+/// A consumed event is not passed on to any other widget, nor the view.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum EventResult {
+    Consumed,
+    #[default]
+    Ignored,
+}
+
+impl EventResult {
+    pub fn is_consumed(&self) -> bool {
+        matches!(self, Self::Consumed)
+    }
+}
+
+/// Count consecutive clicks, to detect double and triple clicks.
+///
+/// A click counts towards the previous click if it's made with the same button,
+/// within `interval` of the previous click and no further than `radius` cells away.
+#[derive(Debug)]
+pub struct ClickCounter {
+    pub interval: Duration,
+    pub radius: u16,
+    last: Option<Click>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Click {
+    time: Instant,
+    x: u16,
+    y: u16,
+    button: MouseButton,
+    count: u8,
+}
+
+impl ClickCounter {
+    pub fn new(interval: Duration, radius: u16) -> Self {
+        Self {
+            interval,
+            radius,
+            last: None,
+        }
+    }
+
+    /// Set the click count of a [`Event::MouseDown`].
+    /// Any other event is returned as is.
+    pub fn count(&mut self, event: Event, now: Instant) -> Event {
+        let Event::MouseDown(x, y, button, mods, _) = event else {
+            return event;
+        };
+
+        let count = match self.last {
+            Some(last)
+                if last.button == button
+                    && now.duration_since(last.time) <= self.interval
+                    && last.x.abs_diff(x) <= self.radius
+                    && last.y.abs_diff(y) <= self.radius =>
+            {
+                last.count.saturating_add(1)
+            }
+            _ => 1,
+        };
+
+        self.last = Some(Click {
+            time: now,
+            x,
+            y,
+            button,
+            count,
+        });
+
+        Event::MouseDown(x, y, button, mods, count)
+    }
+}
+
+impl Default for ClickCounter {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(400), 1)
+    }
+}
+
 pub struct Events;
 
 impl Events {
@@ -95,4 +192,136 @@ impl Events {
             false => None,
         }
     }
+
+    /// Block until the next event
+    pub fn read(&self) -> Option<Event> {
+        read().map(Into::into).ok()
+    }
+}
+
+thread_local! {
+    static ACTIONS: RefCell<Vec<Symbol>> = const { RefCell::new(vec![]) };
+    static CHANGES: RefCell<Vec<(Symbol, f64)>> = const { RefCell::new(vec![]) };
+    static INPUTS: RefCell<Vec<(Symbol, String)>> = const { RefCell::new(vec![]) };
+}
+
+/// Send an [`Event::Action`] to the views, the same as a key binding would.
+/// This is how widgets (e.g a menu) run an action.
+///
+/// The actions are sent once the current event is handled.
+pub fn send_action(action: impl Into<Symbol>) {
+    ACTIONS.with_borrow_mut(|actions| actions.push(action.into()));
+}
+
+/// Take the actions sent with [`send_action`], in the order they were sent.
+pub fn drain_actions() -> Vec<Symbol> {
+    ACTIONS.take()
+}
+
+/// Send an [`Event::Change`] to the views.
+/// This is how widgets bound to a value (e.g a slider) report a new value,
+/// as widgets can't change the state themselves:
+///
+/// ```ignore
+/// fn on_event(&mut self, event: Event, nodes: &mut Nodes<'_>) {
+///     if let Event::Change(action, value) = event {
+///         if action == "volume" {
+///             *self.state.volume = value;
+///         }
+///     }
+/// }
+/// ```
+///
+/// The changes are sent once the current event is handled.
+pub fn send_change(action: impl Into<Symbol>, value: f64) {
+    CHANGES.with_borrow_mut(|changes| changes.push((action.into(), value)));
+}
+
+/// Take the changes sent with [`send_change`], in the order they were sent.
+pub fn drain_changes() -> Vec<(Symbol, f64)> {
+    CHANGES.take()
+}
+
+/// Send an [`Event::Input`] to the views.
+/// This is how widgets editing text (e.g an input) report the new text,
+/// the same as [`send_change`] for numbers.
+///
+/// The inputs are sent once the current event is handled.
+pub fn send_input(action: impl Into<Symbol>, text: impl Into<String>) {
+    INPUTS.with_borrow_mut(|inputs| inputs.push((action.into(), text.into())));
+}
+
+/// Take the inputs sent with [`send_input`], in the order they were sent.
+pub fn drain_inputs() -> Vec<(Symbol, String)> {
+    INPUTS.take()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn down(x: u16, y: u16, button: MouseButton) -> Event {
+        Event::MouseDown(x, y, button, KeyModifiers::NONE, 1)
+    }
+
+    fn count(event: Event) -> u8 {
+        match event {
+            Event::MouseDown(.., count) => count,
+            _ => panic!("not a mouse down event"),
+        }
+    }
+
+    #[test]
+    fn enhanced_key_events() {
+        // Reported by terminals using the kitty keyboard protocol
+        let key = |code, modifiers, kind| {
+            let mut event = KeyEvent::new(code, modifiers);
+            event.kind = kind;
+            Event::from(CTEvent::Key(event))
+        };
+        let state = KeyEventState::NONE;
+
+        let ctrl_i = key(
+            KeyCode::Char('i'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        );
+        let tab = key(KeyCode::Tab, KeyModifiers::NONE, KeyEventKind::Press);
+        assert_eq!(
+            ctrl_i,
+            Event::KeyPress(KeyCode::Char('i'), KeyModifiers::CONTROL, state)
+        );
+        assert_ne!(ctrl_i, tab);
+
+        let release = key(KeyCode::Char('ä'), KeyModifiers::ALT, KeyEventKind::Release);
+        assert_eq!(
+            release,
+            Event::KeyRelease(KeyCode::Char('ä'), KeyModifiers::ALT, state)
+        );
+    }
+
+    #[test]
+    fn double_and_triple_click() {
+        let mut clicks = ClickCounter::default();
+        let now = Instant::now();
+        let ms = Duration::from_millis;
+
+        assert_eq!(count(clicks.count(down(5, 5, MouseButton::Left), now)), 1);
+        let next = now + ms(100);
+        assert_eq!(count(clicks.count(down(6, 5, MouseButton::Left), next)), 2);
+        let next = next + ms(100);
+        assert_eq!(count(clicks.count(down(6, 5, MouseButton::Left), next)), 3);
+
+        // Too slow
+        let next = next + ms(1000);
+        assert_eq!(count(clicks.count(down(6, 5, MouseButton::Left), next)), 1);
+
+        // Too far away
+        let next = next + ms(100);
+        assert_eq!(count(clicks.count(down(9, 5, MouseButton::Left), next)), 1);
+
+        // Different button
+        let next = next + ms(100);
+        assert_eq!(count(clicks.count(down(9, 5, MouseButton::Right), next)), 1);
+    }
 }
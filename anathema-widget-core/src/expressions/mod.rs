@@ -1,13 +1,13 @@
 use anathema_render::Size;
 use anathema_values::{
     Attributes, Context, Deferred, DynValue, ExpressionMap, Expressions, Immediate, NextNodeId,
-    NodeId, Path, ScopeStorage, State, Value, ValueExpr, ValueRef,
+    NodeId, Path, ScopeStorage, State, Symbol, Value, ValueExpr, ValueRef,
 };
 
 pub use self::controlflow::{ElseExpr, IfExpr};
 use crate::error::Result;
 use crate::factory::FactoryContext;
-use crate::nodes::{IfElse, LoopNode, Node, NodeKind, Nodes, Single, View};
+use crate::nodes::{preserve, IfElse, LoopNode, Node, NodeKind, Nodes, Single, Transform, View};
 use crate::views::{RegisteredViews, Views};
 use crate::{Factory, Pos, WidgetContainer};
 
@@ -30,7 +30,7 @@ pub fn root_view(body: Vec<Expression>, id: usize) -> Expression {
 // -----------------------------------------------------------------------------
 #[derive(Debug, Clone)]
 pub struct SingleNodeExpr {
-    pub ident: String,
+    pub ident: Symbol,
     pub text: Option<ValueExpr>,
     pub attributes: Attributes,
     pub children: Vec<Expression>,
@@ -46,29 +46,39 @@ impl SingleNodeExpr {
             .map(|text| String::init_value(context, &node_id, text))
             .unwrap_or_default();
 
-        let context = FactoryContext::new(
-            context,
-            node_id.clone(),
-            &self.ident,
-            &self.attributes,
-            text,
-        );
+        let context =
+            FactoryContext::new(context, node_id.clone(), self.ident, &self.attributes, text);
 
-        let widget = WidgetContainer {
+        let mut widget = WidgetContainer {
             display: context.get("display"),
             background: context.get("background"),
+            tooltip: context.get("tooltip"),
+            sticky: context.get("sticky"),
+            disabled: context.get("disabled"),
+            role: context.get("role"),
+            label: context.get("label"),
+            shortcut: context.get("key"),
+            action: context.get("action"),
+            error: context.field_error(),
+            culled: false,
+            cache_paint: context.get("cache-paint"),
+            paint_cache: None,
             pos: Pos::ZERO,
             size: Size::ZERO,
             inner: Factory::exec(context)?,
             expr: None,
             attributes: &self.attributes,
+            node_id: node_id.clone(),
         };
+        preserve::restore_retained(&mut widget);
+        widget.track_disabled();
+        widget.track_shortcut();
 
         let node = Node {
             kind: NodeKind::Single(Single {
                 widget,
                 children: Nodes::new(&self.children, node_id.child(0)),
-                ident: &self.ident,
+                ident: self.ident,
             }),
             node_id,
             scope,
@@ -126,12 +136,14 @@ impl LoopExpr {
             }
         };
 
-        let loop_node = LoopNode::new(
+        let mut loop_node = LoopNode::new(
             &self.body,
             self.binding.clone(),
             collection,
+            Transform::from_expr(&self.collection),
             node_id.child(0),
         );
+        loop_node.apply_transforms(context, &node_id);
 
         let node = Node {
             kind: NodeKind::Loop(loop_node),
@@ -192,7 +204,8 @@ impl ViewExpr {
     fn eval<'e>(&'e self, context: &Context<'_, 'e>, node_id: NodeId) -> Result<Node<'e>> {
         let tabindex = self
             .attributes
-            .get("tabindex") // TODO: should be a constant. Look into reserving (more) keywords
+            // TODO: should be a constant. Look into reserving (more) keywords
+            .get(&Symbol::new("tabindex"))
             .map(|expr| u32::init_value(context, &node_id, expr))
             .unwrap_or(Value::Empty);
 
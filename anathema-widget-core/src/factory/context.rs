@@ -1,9 +1,12 @@
-use anathema_values::{Attributes, Context, DynValue, NodeId, Value};
+use anathema_values::{Attributes, Context, DynValue, NodeId, Symbol, Value, ValueExpr};
 
 use crate::WidgetStyle;
 
+/// The field of the state holding the validation errors, see [`FactoryContext::field_error`]
+pub const ERRORS: &str = "errors";
+
 pub struct FactoryContext<'a> {
-    pub ident: &'a str,
+    pub ident: Symbol,
     pub attributes: &'a Attributes,
     pub ctx: &'a Context<'a, 'a>,
     pub node_id: NodeId,
@@ -14,7 +17,7 @@ impl<'a> FactoryContext<'a> {
     pub fn new(
         ctx: &'a Context<'a, 'a>,
         node_id: NodeId,
-        ident: &'a str,
+        ident: Symbol,
         attributes: &'a Attributes,
         text: Value<String>,
     ) -> Self {
@@ -45,18 +48,42 @@ impl<'a> FactoryContext<'a> {
         }
     }
 
-    pub fn get<T: DynValue>(&self, name: &str) -> Value<T> {
-        let Some(val) = self.attributes.get(name) else {
+    /// The validation error of the field named by the `field` attribute,
+    /// read from the [`ERRORS`] map of the state: `field: "email"` reads `errors.email`.
+    pub fn field_error(&self) -> Value<String> {
+        let field = self.get::<String>("field");
+        let Some(field) = field.value_ref() else {
             return Value::Empty;
         };
-        T::init_value(self.ctx, &self.node_id, val)
+
+        let val = ValueExpr::Dot(
+            ValueExpr::Ident(ERRORS.into()).into(),
+            ValueExpr::Ident(field.as_str().into()).into(),
+        );
+        String::init_value(self.ctx, &self.node_id, &val)
+    }
+
+    pub fn get<T: DynValue>(&self, name: &str) -> Value<T> {
+        // If the name was never interned there is no attribute by that name
+        if let Some(val) = Symbol::get(name).and_then(|name| self.attributes.get(&name)) {
+            return T::init_value(self.ctx, &self.node_id, val);
+        }
+
+        // Fall back to the key of a map spread into the attributes
+        match self.attributes.get(&Symbol::SPREAD) {
+            Some(map) => {
+                let val = ValueExpr::Dot(map.clone().into(), ValueExpr::Ident(name.into()).into());
+                T::init_value(self.ctx, &self.node_id, &val)
+            }
+            None => Value::Empty,
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use anathema_values::testing::TestState;
-    use anathema_values::ValueExpr;
+    use anathema_values::{ValueExpr, SPREAD_ATTRIBUTE};
 
     use super::*;
 
@@ -65,11 +92,53 @@ mod test {
         let state = TestState::new();
         let ctx = Context::root(&state);
         let mut attributes = Attributes::new();
-        attributes.insert("name".to_string(), ValueExpr::Ident("name".into()));
+        attributes.insert("name".into(), ValueExpr::Ident("name".into()));
 
-        let ctx = FactoryContext::new(&ctx, 0.into(), "border", &attributes, Value::Empty);
+        let ctx = FactoryContext::new(&ctx, 0.into(), "border".into(), &attributes, Value::Empty);
 
         let name = ctx.get::<String>("name");
         assert_eq!("Dirk Gently", name.str());
     }
+
+    #[test]
+    fn get_spread_attribute() {
+        let state = TestState::new();
+        let ctx = Context::root(&state);
+        let mut attributes = Attributes::new();
+        attributes.insert(SPREAD_ATTRIBUTE.into(), ValueExpr::Ident("inner".into()));
+        attributes.insert("number".into(), ValueExpr::from(1));
+
+        let ctx = FactoryContext::new(&ctx, 0.into(), "border".into(), &attributes, Value::Empty);
+
+        let name = ctx.get::<String>("name");
+        assert_eq!("Fiddle McStick", name.str());
+
+        // Explicit attributes take precedence
+        let number = ctx.get::<usize>("number");
+        assert_eq!(Some(1), number.value());
+
+        let missing = ctx.get::<String>("missing");
+        assert!(missing.value_ref().is_none());
+    }
+
+    #[test]
+    fn get_field_error() {
+        let state = TestState::new();
+        let ctx = Context::root(&state);
+        let field = |name: &str| {
+            let mut attributes = Attributes::new();
+            attributes.insert("field".into(), ValueExpr::from(name));
+            attributes
+        };
+
+        let attributes = field("email");
+        let factory_ctx =
+            FactoryContext::new(&ctx, 0.into(), "text".into(), &attributes, Value::Empty);
+        assert_eq!("Not an email address", factory_ctx.field_error().str());
+
+        let attributes = field("name");
+        let factory_ctx =
+            FactoryContext::new(&ctx, 0.into(), "text".into(), &attributes, Value::Empty);
+        assert!(factory_ctx.field_error().value_ref().is_none());
+    }
 }
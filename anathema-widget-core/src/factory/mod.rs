@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
+use anathema_values::Symbol;
 use parking_lot::RwLock;
 
-pub use self::context::FactoryContext;
+pub use self::context::{FactoryContext, ERRORS};
 use crate::error::{Error, Result};
 use crate::widget::AnyWidget;
+use crate::Nodes;
 
 mod context;
 
@@ -15,7 +17,23 @@ pub trait WidgetFactory: Send + Sync {
     fn make(&self, context: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>>;
 }
 
-static FACTORIES: OnceLock<RwLock<HashMap<String, Box<dyn WidgetFactory>>>> = OnceLock::new();
+/// A registered widget
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WidgetKind {
+    /// The ident used in templates
+    pub ident: &'static str,
+    /// The type name of the factory
+    pub factory: &'static str,
+    /// The number of widgets of this kind in the node tree
+    pub instances: usize,
+}
+
+struct Registered {
+    factory: Box<dyn WidgetFactory>,
+    type_name: &'static str,
+}
+
+static FACTORIES: OnceLock<RwLock<HashMap<Symbol, Registered>>> = OnceLock::new();
 
 pub struct Factory;
 
@@ -23,9 +41,9 @@ impl Factory {
     pub fn exec(ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
         let factories = FACTORIES.get_or_init(Default::default).read();
         let factory = factories
-            .get(ctx.ident)
+            .get(&ctx.ident)
             .ok_or_else(|| Error::UnregisteredWidget(ctx.ident.to_string()))?;
-        let widget = factory.make(ctx)?;
+        let widget = factory.factory.make(ctx)?;
         Ok(Box::new(widget))
     }
 
@@ -36,14 +54,39 @@ impl Factory {
         }
 
         let mut factories = FACTORIES.get_or_init(Default::default).write();
-        if factories.contains_key(&ident) {
+        let symbol = Symbol::new(&ident);
+        if factories.contains_key(&symbol) {
             return Err(Error::ExistingName(ident));
         }
 
-        factories.insert(ident, Box::new(factory));
+        let type_name = std::any::type_name_of_val(&factory);
+        factories.insert(
+            symbol,
+            Registered {
+                factory: Box::new(factory),
+                type_name,
+            },
+        );
 
         Ok(())
     }
+
+    /// All registered widgets, sorted by ident,
+    /// with the number of instances in the node tree.
+    pub fn kinds(nodes: &Nodes<'_>) -> Vec<WidgetKind> {
+        let counts = nodes.count_by_ident();
+        let factories = FACTORIES.get_or_init(Default::default).read();
+        let mut kinds = factories
+            .iter()
+            .map(|(ident, registered)| WidgetKind {
+                ident: ident.as_str(),
+                factory: registered.type_name,
+                instances: counts.get(ident).copied().unwrap_or(0),
+            })
+            .collect::<Vec<_>>();
+        kinds.sort_by_key(|kind| kind.ident);
+        kinds
+    }
 }
 
 // // // -----------------------------------------------------------------------------
@@ -144,3 +187,64 @@ impl Factory {
 //     //     assert_eq!(Some(3), stack.min_height);
 //     // }
 // }
+
+#[cfg(test)]
+mod test {
+    use anathema_render::Size;
+    use anathema_values::testing::{ident, unum, TestState};
+    use anathema_values::{Attributes, Context, NodeId, Value};
+
+    use super::*;
+    use crate::contexts::PositionCtx;
+    use crate::{LayoutNodes, Nodes, Widget, WidgetStyle};
+
+    #[derive(Debug, crate::WidgetFactory)]
+    struct Gauge {
+        #[attr]
+        counter: Value<usize>,
+        #[attr("max-value")]
+        max: Value<usize>,
+        #[style]
+        style: WidgetStyle,
+        #[text]
+        label: Value<String>,
+        painted: usize,
+    }
+
+    impl Widget for Gauge {
+        fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+            self.update_attributes(context, node_id);
+        }
+
+        fn layout(&mut self, _: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+            Ok(Size::ZERO)
+        }
+
+        fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+    }
+
+    #[test]
+    fn derived_factory() {
+        let state = TestState::new();
+        let ctx = Context::root(&state);
+        let mut attributes = Attributes::new();
+        attributes.insert("counter".into(), *ident("counter"));
+        attributes.insert("max-value".into(), *unum(10));
+        attributes.insert("bold".into(), *ident("debug"));
+
+        let node_id = NodeId::from(0);
+        let text = Value::Static("gauge".to_string());
+        let factory_ctx =
+            FactoryContext::new(&ctx, node_id.clone(), "gauge".into(), &attributes, text);
+
+        let mut widget = GaugeFactory.make(factory_ctx).unwrap();
+        widget.update_any(&ctx, &node_id);
+
+        let gauge = (*widget).as_any_ref().downcast_ref::<Gauge>().unwrap();
+        assert_eq!(gauge.counter.value(), Some(3));
+        assert_eq!(gauge.max.value(), Some(10));
+        assert_eq!(gauge.style.bold.value(), Some(false));
+        assert_eq!(gauge.label.str(), "gauge");
+        assert_eq!(gauge.painted, 0);
+    }
+}
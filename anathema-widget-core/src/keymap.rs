@@ -0,0 +1,455 @@
+// -----------------------------------------------------------------------------
+//   - Keymap -
+//   Maps keys, and chords of keys, to named actions.
+//
+//   A keymap file is a list of TOML style key-value pairs:
+//
+//   # Save the file
+//   ctrl+s = "save"
+//   "ctrl+x ctrl+c" = "quit"
+//
+//   A chord is a space separated sequence of keys, where each key is
+//   any number of modifiers (`ctrl`, `alt`, `shift`, `super`)
+//   followed by a key, separated by `+`.
+//
+//   Actions can be grouped with a prefix separated by a `.`,
+//   e.g `file.save`, which is used to group the bindings in the help overlay.
+//
+//   Widgets can declare a shortcut in the template as well,
+//   which is bound for as long as the widget is shown and enabled:
+//
+//   button [key: "ctrl+s", action: "save"]: "Save"
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::path::Path;
+
+use anathema_values::{NodeId, Symbol};
+
+use crate::error::{Error, Result};
+use crate::{KeyCode, KeyModifiers};
+
+/// A single key press, e.g `ctrl+s`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // The shift modifier is part of the character
+        // (e.g `shift+a` is `A`), so it's not stored for characters.
+        match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => Self {
+                code: KeyCode::Char(c.to_ascii_uppercase()),
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            code => Self { code, modifiers },
+        }
+    }
+
+    /// Parse a key, e.g `ctrl+alt+del`
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts = input.split('+').peekable();
+        let mut key = None;
+
+        while let Some(part) = parts.next() {
+            // A trailing `+` is the plus key, e.g `ctrl++`
+            let part = match (part, parts.peek()) {
+                ("", Some(&"")) => {
+                    parts.next();
+                    "+"
+                }
+                (part, _) => part,
+            };
+
+            if parts.peek().is_none() {
+                key = Some(part);
+                break;
+            }
+
+            modifiers |= match part.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                "super" => KeyModifiers::SUPER,
+                _ => return None,
+            };
+        }
+
+        let key = key?;
+        let mut chars = key.chars();
+        let code = match (chars.next()?, chars.next()) {
+            (c, None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "del" | "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                "plus" => KeyCode::Char('+'),
+                key => match key.strip_prefix('f').map(str::parse) {
+                    Some(Ok(n @ 1..=24)) => KeyCode::F(n),
+                    _ => return None,
+                },
+            },
+        };
+
+        Some(Self::new(code, modifiers))
+    }
+}
+
+impl Display for Key {
+    /// Format the key in the same format as it's parsed.
+    /// Keys that can't be parsed, e.g media keys, are written in their debug format.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (KeyModifiers::CONTROL, "ctrl"),
+            (KeyModifiers::ALT, "alt"),
+            (KeyModifiers::SHIFT, "shift"),
+            (KeyModifiers::SUPER, "super"),
+        ];
+
+        for (modifier, name) in modifiers {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char('+') => write!(f, "plus"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::BackTab => write!(f, "backtab"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::Insert => write!(f, "insert"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// The result of feeding a key to the [`Keymap`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeymapMatch {
+    /// The key completed a chord bound to an action
+    Action(Symbol),
+    /// The key is part of one or more chords
+    Pending,
+    /// The key is not bound
+    None,
+}
+
+#[derive(Debug, Default)]
+pub struct Keymap {
+    bindings: Vec<(Vec<Key>, Symbol)>,
+    pending: Vec<Key>,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a keymap from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let src = std::fs::read_to_string(path)?;
+        Self::parse(&src)
+    }
+
+    /// Parse a keymap.
+    pub fn parse(src: &str) -> Result<Self> {
+        let mut keymap = Self::new();
+
+        for (line_no, line) in src.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let err = |msg: &str| Error::Keymap {
+                line: line_no + 1,
+                msg: msg.into(),
+            };
+
+            let (chord, rest) = match line.strip_prefix('"') {
+                Some(line) => line
+                    .split_once('"')
+                    .ok_or_else(|| err("unterminated key"))?,
+                None => line
+                    .split_once('=')
+                    .map(|(key, _)| (key, &line[key.len()..]))
+                    .ok_or_else(|| err("missing `=`"))?,
+            };
+
+            let action = rest
+                .trim_start()
+                .strip_prefix('=')
+                .ok_or_else(|| err("missing `=`"))?
+                .trim();
+
+            let action = action
+                .strip_prefix('"')
+                .and_then(|action| action.split_once('"'))
+                .filter(|(_, trailing)| {
+                    let trailing = trailing.trim();
+                    trailing.is_empty() || trailing.starts_with('#')
+                })
+                .map(|(action, _)| action)
+                .ok_or_else(|| err("the action has to be a string"))?;
+
+            keymap
+                .bind(chord.trim(), action)
+                .ok_or_else(|| err("invalid key"))?;
+        }
+
+        Ok(keymap)
+    }
+
+    /// Bind a chord to an action.
+    /// If the chord is already bound the action is replaced.
+    ///
+    /// Returns `None` if the chord is invalid.
+    pub fn bind(&mut self, chord: &str, action: impl Into<Symbol>) -> Option<()> {
+        let chord = chord
+            .split_whitespace()
+            .map(Key::parse)
+            .collect::<Option<Vec<_>>>()
+            .filter(|chord| !chord.is_empty())?;
+
+        let action = action.into();
+        match self.bindings.iter_mut().find(|(keys, _)| *keys == chord) {
+            Some((_, existing)) => *existing = action,
+            None => self.bindings.push((chord, action)),
+        }
+
+        Some(())
+    }
+
+    /// Get the action bound to the chord
+    pub fn action(&self, chord: &[Key]) -> Option<Symbol> {
+        self.bindings
+            .iter()
+            .find(|(keys, _)| keys == chord)
+            .map(|(_, action)| *action)
+    }
+
+    /// All the bindings, in the order they were bound
+    pub fn bindings(&self) -> impl Iterator<Item = (&[Key], Symbol)> + '_ {
+        self.bindings
+            .iter()
+            .map(|(keys, action)| (keys.as_slice(), *action))
+    }
+
+    /// Replace all the bindings with the bindings from another keymap,
+    /// e.g when the keymap file is reloaded.
+    pub fn replace(&mut self, other: Keymap) {
+        self.bindings = other.bindings;
+        self.pending.clear();
+    }
+
+    /// Feed a key press to the keymap.
+    pub fn feed(&mut self, code: KeyCode, modifiers: KeyModifiers) -> KeymapMatch {
+        self.pending.push(Key::new(code, modifiers));
+
+        if let Some(action) = self.action(&self.pending) {
+            self.pending.clear();
+            return KeymapMatch::Action(action);
+        }
+
+        let is_prefix = self
+            .bindings
+            .iter()
+            .any(|(keys, _)| keys.starts_with(&self.pending));
+
+        match is_prefix {
+            true => KeymapMatch::Pending,
+            false => {
+                self.pending.clear();
+                KeymapMatch::None
+            }
+        }
+    }
+}
+
+thread_local! {
+    // Shortcuts declared by widgets, in the order they were registered
+    static SHORTCUTS: RefCell<Vec<(NodeId, Key, Symbol)>> = const { RefCell::new(vec![]) };
+}
+
+/// Bind the shortcut of a widget, replacing the previous shortcut of the widget.
+/// `None` removes the shortcut.
+pub(crate) fn register_shortcut(node_id: &NodeId, shortcut: Option<(Key, Symbol)>) {
+    SHORTCUTS.with_borrow_mut(|shortcuts| {
+        shortcuts.retain(|(id, ..)| id != node_id);
+        if let Some((key, action)) = shortcut {
+            shortcuts.push((node_id.clone(), key, action));
+        }
+    });
+}
+
+/// The action of the widget shortcut bound to the key.
+/// If more than one widget binds the key, the last widget to bind it wins.
+pub fn shortcut(key: Key) -> Option<Symbol> {
+    SHORTCUTS.with_borrow(|shortcuts| {
+        shortcuts
+            .iter()
+            .rev()
+            .find(|(_, k, _)| *k == key)
+            .map(|(.., action)| *action)
+    })
+}
+
+/// All the shortcuts declared by widgets
+pub fn shortcuts() -> Vec<(Key, Symbol)> {
+    SHORTCUTS.with_borrow(|shortcuts| {
+        shortcuts
+            .iter()
+            .map(|(_, key, action)| (*key, *action))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_keys() {
+        let key = Key::parse("ctrl+s").unwrap();
+        assert_eq!(key, Key::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+
+        let key = Key::parse("shift+a").unwrap();
+        assert_eq!(key, Key::new(KeyCode::Char('A'), KeyModifiers::NONE));
+
+        let key = Key::parse("ctrl+alt+F5").unwrap();
+        assert_eq!(
+            key,
+            Key::new(KeyCode::F(5), KeyModifiers::CONTROL | KeyModifiers::ALT)
+        );
+
+        let key = Key::parse("ctrl++").unwrap();
+        assert_eq!(key, Key::new(KeyCode::Char('+'), KeyModifiers::CONTROL));
+
+        assert!(Key::parse("hyper+a").is_none());
+        assert!(Key::parse("ctrl+nope").is_none());
+    }
+
+    #[test]
+    fn display_keys() {
+        for input in [
+            "ctrl+s",
+            "A",
+            "ctrl+alt+f5",
+            "space",
+            "ctrl+plus",
+            "pagedown",
+        ] {
+            let key = Key::parse(input).unwrap();
+            assert_eq!(key.to_string(), input);
+            assert_eq!(Key::parse(&key.to_string()), Some(key));
+        }
+    }
+
+    #[test]
+    fn parse_keymap() {
+        let src = r#"
+            # Comment
+            ctrl+s = "save"
+            "ctrl+x ctrl+c" = "quit" # trailing comment
+            q = "quit"
+        "#;
+
+        let keymap = Keymap::parse(src).unwrap();
+        let ctrl = |c| Key::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(keymap.action(&[ctrl('s')]), Some("save".into()));
+        assert_eq!(keymap.action(&[ctrl('x'), ctrl('c')]), Some("quit".into()));
+        assert_eq!(
+            keymap.action(&[Key::new(KeyCode::Char('q'), KeyModifiers::NONE)]),
+            Some("quit".into())
+        );
+    }
+
+    #[test]
+    fn parse_keymap_errors() {
+        let err = Keymap::parse("ctrl+s = save").unwrap_err();
+        assert!(matches!(err, Error::Keymap { line: 1, .. }));
+
+        let err = Keymap::parse("\nctrl+nope = \"save\"").unwrap_err();
+        assert!(matches!(err, Error::Keymap { line: 2, .. }));
+
+        let err = Keymap::parse("ctrl+s \"save\"").unwrap_err();
+        assert!(matches!(err, Error::Keymap { line: 1, .. }));
+    }
+
+    #[test]
+    fn feed_chords() {
+        let mut keymap = Keymap::new();
+        keymap.bind("ctrl+x ctrl+c", "quit").unwrap();
+        keymap.bind("ctrl+s", "save").unwrap();
+
+        let ctrl = KeyModifiers::CONTROL;
+        assert_eq!(keymap.feed(KeyCode::Char('x'), ctrl), KeymapMatch::Pending);
+        assert_eq!(
+            keymap.feed(KeyCode::Char('c'), ctrl),
+            KeymapMatch::Action("quit".into())
+        );
+
+        // Breaking the chord resets it
+        assert_eq!(keymap.feed(KeyCode::Char('x'), ctrl), KeymapMatch::Pending);
+        assert_eq!(keymap.feed(KeyCode::Char('a'), ctrl), KeymapMatch::None);
+        assert_eq!(
+            keymap.feed(KeyCode::Char('s'), ctrl),
+            KeymapMatch::Action("save".into())
+        );
+
+        // Shift is part of the character
+        keymap.bind("G", "bottom").unwrap();
+        assert_eq!(
+            keymap.feed(KeyCode::Char('G'), KeyModifiers::SHIFT),
+            KeymapMatch::Action("bottom".into())
+        );
+    }
+
+    #[test]
+    fn widget_shortcuts() {
+        let save = NodeId::from(vec![0, 1]);
+        let ctrl_s = Key::parse("ctrl+s").unwrap();
+        register_shortcut(&save, Some((ctrl_s, "save".into())));
+        assert_eq!(shortcut(ctrl_s), Some("save".into()));
+
+        // Replace the shortcut of the widget
+        let ctrl_w = Key::parse("ctrl+w").unwrap();
+        register_shortcut(&save, Some((ctrl_w, "save".into())));
+        assert_eq!(shortcut(ctrl_s), None);
+        assert_eq!(shortcuts(), [(ctrl_w, "save".into())]);
+
+        register_shortcut(&save, None);
+        assert_eq!(shortcut(ctrl_w), None);
+    }
+}
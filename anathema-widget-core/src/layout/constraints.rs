@@ -109,6 +109,64 @@ impl Constraints {
         self.min_height = self.max_height;
     }
 
+    /// Shrink the maximum size, e.g to make room for a border or padding.
+    /// Unbounded constraints stay unbounded, and the minimum size
+    /// is reduced if it's larger than the new maximum size.
+    /// ```
+    /// # use anathema_render::Size;
+    /// # use anathema_widget_core::layout::Constraints;
+    /// let constraints = Constraints::new(10, None).shrink_by(Size::new(12, 2));
+    /// assert_eq!(constraints.max_width, 0);
+    /// assert!(constraints.is_height_unbounded());
+    /// ```
+    pub fn shrink_by(mut self, size: Size) -> Self {
+        if !self.is_width_unbounded() {
+            self.max_width = self.max_width.saturating_sub(size.width);
+        }
+        if !self.is_height_unbounded() {
+            self.max_height = self.max_height.saturating_sub(size.height);
+        }
+        self.min_width = self.min_width.min(self.max_width);
+        self.min_height = self.min_height.min(self.max_height);
+        self
+    }
+
+    /// Raise the minimum width and / or height.
+    /// The minimum size never exceeds the maximum size.
+    /// ```
+    /// # use anathema_widget_core::layout::Constraints;
+    /// let constraints = Constraints::new(10, 10).clamp_min(Some(4), Some(20));
+    /// assert_eq!(constraints.min_width, 4);
+    /// assert_eq!(constraints.min_height, 10);
+    /// ```
+    pub fn clamp_min(mut self, min_width: Option<usize>, min_height: Option<usize>) -> Self {
+        if let Some(min_width) = min_width {
+            self.min_width = self.min_width.max(min_width).min(self.max_width);
+        }
+        if let Some(min_height) = min_height {
+            self.min_height = self.min_height.max(min_height).min(self.max_height);
+        }
+        self
+    }
+
+    /// Make the width and / or height tight, if there is a value.
+    /// See [`make_width_tight`](Self::make_width_tight).
+    /// ```
+    /// # use anathema_widget_core::layout::Constraints;
+    /// let constraints = Constraints::new(10, 10).tighten_if(Some(4), None);
+    /// assert!(constraints.is_width_tight());
+    /// assert!(!constraints.is_height_tight());
+    /// ```
+    pub fn tighten_if(mut self, width: Option<usize>, height: Option<usize>) -> Self {
+        if let Some(width) = width {
+            self.make_width_tight(width);
+        }
+        if let Some(height) = height {
+            self.make_height_tight(height);
+        }
+        self
+    }
+
     pub fn expand_horz(&mut self, mut size: Size) -> Size {
         size.width = self.max_width;
         size
@@ -126,4 +184,37 @@ impl Constraints {
 }
 
 #[cfg(test)]
-mod test {}
+mod test {
+    use super::*;
+
+    #[test]
+    fn shrink_min_with_max() {
+        let mut constraints = Constraints::new(10, 5);
+        constraints.min_width = 10;
+        constraints.min_height = 2;
+
+        let constraints = constraints.shrink_by(Size::new(2, 2));
+        assert_eq!(constraints.min_width, 8);
+        assert_eq!(constraints.max_width, 8);
+        assert_eq!(constraints.min_height, 2);
+        assert_eq!(constraints.max_height, 3);
+    }
+
+    #[test]
+    fn compose() {
+        let constraints = Constraints::new(10, 10)
+            .clamp_min(Some(8), None)
+            .tighten_if(Some(6), Some(20))
+            .shrink_by(Size::new(2, 2));
+
+        assert_eq!(
+            constraints,
+            Constraints {
+                min_width: 4,
+                min_height: 8,
+                max_width: 4,
+                max_height: 8,
+            }
+        );
+    }
+}
@@ -30,11 +30,18 @@ impl<'nodes, 'state, 'expr> LayoutNodes<'nodes, 'state, 'expr> {
         self.constraints = constraints;
     }
 
+    /// Start over from the first child,
+    /// e.g to lay out the children again after measuring them.
+    pub fn reset(&mut self) {
+        self.nodes.reset_cache();
+    }
+
     pub fn next<F>(&mut self, mut f: F) -> Result<()>
     where
         F: FnMut(LayoutNode<'_, '_, 'expr>) -> Result<()>,
     {
-        self.nodes
+        let _ = self
+            .nodes
             .next(self.context, &mut |widget, children, context| {
                 let node = LayoutNode {
                     widget,
@@ -95,6 +102,17 @@ impl<'widget, 'state, 'expr> LayoutNode<'widget, 'state, 'expr> {
     pub fn layout(&mut self, constraints: Constraints) -> Result<Size> {
         self.widget.layout(self.children, constraints, self.context)
     }
+
+    /// The baseline of the widget, see [`Widget::baseline`](crate::Widget::baseline).
+    pub fn baseline(&mut self) -> Option<usize> {
+        self.widget.baseline(self.children)
+    }
+
+    /// The children of the widget, for widgets that lay out the
+    /// children of their children (e.g nested spans of a text).
+    pub fn children(&mut self, constraints: Constraints) -> LayoutNodes<'_, '_, 'expr> {
+        LayoutNodes::new(self.children, constraints, self.context)
+    }
 }
 
 impl<'widget, 'state, 'expr> Deref for LayoutNode<'widget, 'state, 'expr> {
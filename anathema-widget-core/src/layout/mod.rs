@@ -8,13 +8,14 @@ use anathema_values::{
 pub use layoutnodes::{LayoutNode, LayoutNodes};
 
 pub use self::constraints::Constraints;
+pub use self::padding::Padding;
 use crate::contexts::LayoutCtx;
 use crate::error::Result;
 use crate::nodes::Nodes;
 
 mod constraints;
 mod layoutnodes;
-// mod padding;
+mod padding;
 
 pub trait Layout {
     fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size>;
@@ -176,6 +177,30 @@ impl TryFrom<ValueRef<'_>> for Display {
 
 impl_dyn_value!(Display);
 
+/// Pin a child of a scrolling viewport to the edge of the viewport,
+/// e.g `text [sticky: "top"] "Name  Age"`.
+///
+/// The child scrolls with the rest of the content until it reaches the edge,
+/// where it stays until the next sticky child pushes it out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sticky {
+    /// Pin the child to the top (or the left of a horizontal viewport)
+    Top,
+}
+
+impl TryFrom<ValueRef<'_>> for Sticky {
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ValueRef::Str("top") => Ok(Self::Top),
+            _ => Err(()),
+        }
+    }
+}
+
+impl_dyn_value!(Sticky);
+
 #[derive(Debug)]
 pub enum HorzEdge {
     /// Position to the left
@@ -256,6 +281,32 @@ impl TryFrom<ValueRef<'_>> for Direction {
 
 impl_dyn_value!(Direction);
 
+/// How the children of a stack are aligned on the axis
+/// opposite to the stack's axis.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum CrossAlign {
+    /// Align the top (or left) edges
+    #[default]
+    Start,
+    /// Align the first line of text of the children.
+    /// See [`Widget::baseline`](crate::Widget::baseline).
+    Baseline,
+}
+
+impl TryFrom<ValueRef<'_>> for CrossAlign {
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ValueRef::Str("start") => Ok(Self::Start),
+            ValueRef::Str("baseline") => Ok(Self::Baseline),
+            _ => Err(()),
+        }
+    }
+}
+
+impl_dyn_value!(CrossAlign);
+
 // -----------------------------------------------------------------------------
 //     - Pos -
 // -----------------------------------------------------------------------------
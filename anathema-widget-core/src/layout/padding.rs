@@ -1,5 +1,5 @@
 use anathema_render::Size;
-use anathema_values::{Context, DynValue, Expressions, Immediate, Owned, Value, ValueRef};
+use anathema_values::{Context, DynValue, Immediate, Owned, Value, ValueRef};
 
 /// Represents the padding of a widget.
 /// Padding is not applicable to `text:` widgets.
@@ -97,6 +97,21 @@ impl FromIterator<u16> for Padding {
     }
 }
 
+impl Padding {
+    fn from_value<'a>(value: ValueRef<'a>, resolver: &mut Immediate<'a>) -> Option<Self> {
+        match value {
+            ValueRef::Owned(Owned::Num(n)) => Some(Self::new(n.to_u16())),
+            value => {
+                let padding = value.to_vec(resolver)?.into_iter().map(|val| match val {
+                    ValueRef::Owned(Owned::Num(n)) => n.to_u16(),
+                    _ => 0,
+                });
+                Some(Padding::from_iter(padding))
+            }
+        }
+    }
+}
+
 impl DynValue for Padding {
     fn init_value(
         context: &Context<'_, '_>,
@@ -106,25 +121,9 @@ impl DynValue for Padding {
     where
         Self: Sized,
     {
-        // TODO: smells like copy and past in here!
         let mut resolver = Immediate::new(context.lookup(), node_id);
         let value = expr.eval(&mut resolver);
-
-        let inner = match value {
-            ValueRef::Owned(Owned::Num(n)) => Some(Self::new(n.to_u16())),
-            ValueRef::Expressions(Expressions(values)) => {
-                let padding = values
-                    .iter()
-                    .map(|expr| expr.eval(&mut Immediate::new(context.lookup(), node_id)))
-                    .map(|val| match val {
-                        ValueRef::Owned(Owned::Num(n)) => n.to_u16(),
-                        _ => 0,
-                    });
-
-                Some(Padding::from_iter(padding))
-            }
-            _ => None,
-        };
+        let inner = Self::from_value(value, &mut resolver);
 
         match resolver.is_deferred() {
             true => Value::Dyn {
@@ -148,21 +147,7 @@ impl DynValue for Padding {
         if let Value::Dyn { inner, expr } = value {
             let mut resolver = Immediate::new(context.lookup(), node_id);
             let value = expr.eval(&mut resolver);
-            *inner = match value {
-                ValueRef::Owned(Owned::Num(n)) => Some(Self::new(n.to_u16())),
-                ValueRef::Expressions(Expressions(values)) => {
-                    let padding = values
-                        .iter()
-                        .map(|expr| expr.eval(&mut Immediate::new(context.lookup(), node_id)))
-                        .map(|val| match val {
-                            ValueRef::Owned(Owned::Num(n)) => n.to_u16(),
-                            _ => 0,
-                        });
-
-                    Some(Padding::from_iter(padding))
-                }
-                _ => None,
-            };
+            *inner = Self::from_value(value, &mut resolver);
         }
     }
 }
@@ -170,7 +155,8 @@ impl DynValue for Padding {
 #[cfg(feature = "testing")]
 #[cfg(test)]
 mod test {
-    use anathema_values::testing::{unum, TestState};
+    use anathema_values::testing::{ident, unum, TestState};
+    use anathema_values::{drain_dirty_nodes, Change, NodeId};
 
     use super::*;
 
@@ -225,4 +211,38 @@ mod test {
         let expected = Padding::new(2);
         assert_eq!(&expected, actual.value_ref().unwrap());
     }
+
+    #[test]
+    fn resolve_padding_from_state_list() {
+        let node_id: NodeId = 0.into();
+        let mut state = TestState::new();
+
+        let e = ident("generic_list");
+        let mut actual = {
+            let ctx = Context::root(&state);
+            Padding::init_value(&ctx, &node_id, &e)
+        };
+
+        let expected = Padding {
+            top: 1,
+            right: 2,
+            bottom: 3,
+            left: 2,
+        };
+        assert_eq!(&expected, actual.value_ref().unwrap());
+
+        // Mutating the list should notify the node
+        state.generic_list.push_back(4);
+        assert_eq!(vec![(node_id.clone(), Change::Push)], drain_dirty_nodes());
+
+        let ctx = Context::root(&state);
+        Padding::resolve(&mut actual, &ctx, &node_id);
+        let expected = Padding {
+            top: 1,
+            right: 2,
+            bottom: 3,
+            left: 4,
+        };
+        assert_eq!(&expected, actual.value_ref().unwrap());
+    }
 }
@@ -3,9 +3,17 @@ pub mod error;
 mod event;
 pub mod expressions;
 mod factory;
+pub mod keymap;
 pub mod layout;
 pub mod nodes;
+pub mod overflow;
+pub mod overlay;
+mod paint_cache;
+#[cfg(feature = "profile")]
+pub mod profile;
+mod reorder;
 mod style;
+pub mod tooltip;
 pub mod views;
 mod widget;
 
@@ -13,13 +21,27 @@ mod widget;
 pub mod testing;
 
 pub use anathema_render::Color;
+pub use anathema_value_derive::WidgetFactory;
 pub use nodes::{Node, Nodes};
 
-pub use crate::event::{Event, Events, KeyCode, KeyModifiers};
-pub use crate::factory::{Factory, FactoryContext, WidgetFactory};
+pub use crate::event::{
+    drain_actions, drain_changes, drain_inputs, send_action, send_change, send_input, ClickCounter,
+    Event, EventResult, Events, KeyCode, KeyEventState, KeyModifiers, MouseButton,
+};
+pub use crate::factory::{Factory, FactoryContext, WidgetFactory, WidgetKind, ERRORS};
 pub use crate::layout::{
-    Align, Axis, Direction, Display, LayoutNode, LayoutNodes, LocalPos, Pos, Region,
+    Align, Axis, CrossAlign, Direction, Display, LayoutNode, LayoutNodes, LocalPos, Pos, Region,
+    Sticky,
 };
+pub use crate::reorder::Reorder;
 pub use crate::style::WidgetStyle;
 pub use crate::views::View;
 pub use crate::widget::{AnyWidget, Widget, WidgetContainer};
+
+// -----------------------------------------------------------------------------
+//   - Macro requirements -
+// -----------------------------------------------------------------------------
+#[cfg(test)]
+extern crate self as anathema;
+#[cfg(test)]
+use {crate as core, anathema_values as values};
@@ -70,7 +70,7 @@ impl<'e> IfElse<'e> {
         None
     }
 
-    fn body(&self) -> Option<&Nodes<'e>> {
+    pub(super) fn body(&self) -> Option<&Nodes<'e>> {
         if self.if_node.is_true() {
             return Some(&self.if_node.body);
         }
@@ -101,7 +101,7 @@ impl<'e> IfElse<'e> {
         self.body().map(|nodes| nodes.count()).unwrap_or(0)
     }
 
-    pub(super) fn update(&mut self, node_id: &[usize], change: &Change, context: &Context<'_, '_>) {
+    pub(super) fn update(&mut self, node_id: &[usize], change: &Change, context: &Context<'_, 'e>) {
         // If
         if self.if_node.node_id.contains(node_id) {
             if self.if_node.node_id.eq(node_id) {
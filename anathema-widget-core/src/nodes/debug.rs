@@ -0,0 +1,189 @@
+// -----------------------------------------------------------------------------
+//   - Debug tree -
+//   A snapshot of the widget tree, detached from the nodes,
+//   for external tooling such as an out-of-process inspector.
+//
+//   Loops, control flow and views are transparent: only widgets
+//   are part of the tree.
+// -----------------------------------------------------------------------------
+use std::fmt::Write;
+
+use anathema_render::Size;
+
+use super::{NodeKind, Nodes, Single, View};
+use crate::Pos;
+
+/// A single widget in the debug tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugNode {
+    /// The widget kind, e.g `text`
+    pub kind: &'static str,
+    /// The ident used in the template
+    pub ident: &'static str,
+    pub id: Vec<usize>,
+    pub size: Size,
+    pub pos: Pos,
+    /// Attribute expressions as they appear in the template, sorted by name
+    pub attributes: Vec<(&'static str, String)>,
+    /// The resolved `role` attribute
+    pub role: Option<String>,
+    /// The resolved `label` attribute
+    pub label: Option<String>,
+    pub children: Vec<DebugNode>,
+}
+
+// Roles describing a collection, where the number of children is the number of items
+const COLLECTION_ROLES: &[&str] = &["grid", "list", "listbox", "menu", "tablist", "tree"];
+
+impl DebugNode {
+    /// Describe the node by its role (or kind if it has no role) and label,
+    /// e.g `button: Save` or `list: Results, 14 items`.
+    pub fn description(&self) -> String {
+        let role = self.role.as_deref().unwrap_or(self.kind);
+        let mut description = match self.label.as_deref() {
+            Some(label) => format!("{role}: {label}"),
+            None => role.to_string(),
+        };
+
+        if COLLECTION_ROLES.contains(&role) {
+            let sep = if self.label.is_some() { ", " } else { ": " };
+            let items = self.children.len();
+            let plural = if items == 1 { "" } else { "s" };
+            let _ = write!(description, "{sep}{items} item{plural}");
+        }
+
+        description
+    }
+
+    /// Serialize the node and its children as JSON
+    pub fn to_json(&self) -> String {
+        let mut output = String::new();
+        self.write_json(&mut output);
+        output
+    }
+
+    fn write_json(&self, output: &mut String) {
+        output.push_str("{\"kind\":");
+        write_str(output, self.kind);
+        output.push_str(",\"ident\":");
+        write_str(output, self.ident);
+
+        output.push_str(",\"id\":[");
+        for (i, id) in self.id.iter().enumerate() {
+            if i > 0 {
+                output.push(',');
+            }
+            let _ = write!(output, "{id}");
+        }
+
+        let _ = write!(
+            output,
+            "],\"size\":{{\"width\":{},\"height\":{}}},\"pos\":{{\"x\":{},\"y\":{}}}",
+            self.size.width, self.size.height, self.pos.x, self.pos.y
+        );
+
+        output.push_str(",\"attributes\":{");
+        for (i, (key, value)) in self.attributes.iter().enumerate() {
+            if i > 0 {
+                output.push(',');
+            }
+            write_str(output, key);
+            output.push(':');
+            write_str(output, value);
+        }
+
+        output.push_str("},\"role\":");
+        write_opt_str(output, self.role.as_deref());
+        output.push_str(",\"label\":");
+        write_opt_str(output, self.label.as_deref());
+
+        output.push_str(",\"children\":");
+        write_json_list(&self.children, output);
+        output.push('}');
+    }
+}
+
+/// Serialize a list of nodes as a JSON array
+pub fn to_json(nodes: &[DebugNode]) -> String {
+    let mut output = String::new();
+    write_json_list(nodes, &mut output);
+    output
+}
+
+fn write_json_list(nodes: &[DebugNode], output: &mut String) {
+    output.push('[');
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 {
+            output.push(',');
+        }
+        node.write_json(output);
+    }
+    output.push(']');
+}
+
+/// Write `s` as a quoted and escaped JSON string
+pub fn write_str(output: &mut String, s: &str) {
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+fn write_opt_str(output: &mut String, s: Option<&str>) {
+    match s {
+        Some(s) => write_str(output, s),
+        None => output.push_str("null"),
+    }
+}
+
+pub(super) fn debug_tree(nodes: &Nodes<'_>, tree: &mut Vec<DebugNode>) {
+    for node in &nodes.inner {
+        match &node.kind {
+            NodeKind::Single(Single {
+                widget,
+                children,
+                ident,
+            }) => {
+                let mut attributes = widget
+                    .attributes
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.to_string()))
+                    .collect::<Vec<_>>();
+                attributes.sort_by(|a, b| a.0.cmp(b.0));
+
+                let mut node = DebugNode {
+                    kind: widget.kind(),
+                    ident: ident.as_str(),
+                    id: widget.node_id.0.to_vec(),
+                    size: widget.size,
+                    pos: widget.pos,
+                    attributes,
+                    role: widget.role().map(str::to_string),
+                    label: widget.label().map(str::to_string),
+                    children: vec![],
+                };
+                debug_tree(children, &mut node.children);
+                tree.push(node);
+            }
+            NodeKind::Loop(loop_state) => loop_state
+                .iterations
+                .iter()
+                .for_each(|iteration| debug_tree(&iteration.body, tree)),
+            NodeKind::ControlFlow(if_else) => {
+                if let Some(body) = if_else.body() {
+                    debug_tree(body, tree);
+                }
+            }
+            NodeKind::View(View { nodes, .. }) => debug_tree(nodes, tree),
+        }
+    }
+}
@@ -1,7 +1,10 @@
+use std::cmp::Ordering;
 use std::ops::ControlFlow;
 
+use anathema_values::fuzzy::fuzzy_match;
 use anathema_values::{
-    Change, Context, Deferred, NextNodeId, NodeId, Path, ScopeStorage, ScopeValue, ValueRef,
+    Change, Context, Deferred, Immediate, NextNodeId, NodeId, Num, Owned, Path, Resolver,
+    ScopeStorage, ScopeValue, ValueExpr, ValueRef,
 };
 
 use super::Nodes;
@@ -24,6 +27,75 @@ impl<'e> Iteration<'e> {
     }
 }
 
+// -----------------------------------------------------------------------------
+//   - Transforms -
+//   Transforms are applied to the collection in the order they are written,
+//   inner most first, so `sort_by(filter(items, ..), ..)` filters
+//   before sorting.
+// -----------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum Transform<'e> {
+    Filter(&'e ValueExpr),
+    SortBy(&'e ValueExpr),
+    Paginate {
+        page: &'e ValueExpr,
+        per_page: &'e ValueExpr,
+    },
+    Fuzzy(&'e ValueExpr),
+}
+
+impl<'e> Transform<'e> {
+    pub(crate) fn from_expr(mut expr: &'e ValueExpr) -> Vec<Self> {
+        let mut transforms = vec![];
+        loop {
+            match expr {
+                ValueExpr::Filter(collection, predicate) => {
+                    transforms.push(Self::Filter(predicate));
+                    expr = collection;
+                }
+                ValueExpr::SortBy(collection, key) => {
+                    transforms.push(Self::SortBy(key));
+                    expr = collection;
+                }
+                ValueExpr::Paginate(collection, page, per_page) => {
+                    transforms.push(Self::Paginate { page, per_page });
+                    expr = collection;
+                }
+                ValueExpr::Fuzzy(collection, query) => {
+                    transforms.push(Self::Fuzzy(query));
+                    expr = collection;
+                }
+                _ => break,
+            }
+        }
+        transforms.reverse();
+        transforms
+    }
+}
+
+#[derive(Debug, PartialEq, PartialOrd)]
+enum SortKey {
+    Empty,
+    Bool(bool),
+    Num(f64),
+    Char(char),
+    Str(String),
+}
+
+impl From<ValueRef<'_>> for SortKey {
+    fn from(value: ValueRef<'_>) -> Self {
+        match value {
+            ValueRef::Str(s) => Self::Str(s.into()),
+            ValueRef::Owned(Owned::Bool(b)) => Self::Bool(b),
+            ValueRef::Owned(Owned::Char(c)) => Self::Char(c),
+            ValueRef::Owned(Owned::Num(Num::Signed(n))) => Self::Num(n as f64),
+            ValueRef::Owned(Owned::Num(Num::Unsigned(n))) => Self::Num(n as f64),
+            ValueRef::Owned(Owned::Num(Num::Float(n))) => Self::Num(n),
+            _ => Self::Empty,
+        }
+    }
+}
+
 // -----------------------------------------------------------------------------
 //   - Loop -
 // -----------------------------------------------------------------------------
@@ -37,6 +109,10 @@ pub struct LoopNode<'e> {
     pub(super) value_index: usize,
     node_id: NodeId,
     next_node_id: NextNodeId,
+    transforms: Vec<Transform<'e>>,
+    // Indices into the collection after the transforms are applied.
+    // This is `None` if there are no transforms.
+    indices: Option<Vec<usize>>,
 }
 
 impl<'e> LoopNode<'e> {
@@ -44,6 +120,7 @@ impl<'e> LoopNode<'e> {
         expressions: &'e [Expression],
         binding: Path,
         collection: Collection<'e>,
+        transforms: Vec<Transform<'e>>,
         node_id: NodeId,
     ) -> Self {
         let next_node_id = NextNodeId::new(node_id.last());
@@ -56,9 +133,125 @@ impl<'e> LoopNode<'e> {
             current_iteration: 0,
             node_id,
             next_node_id,
+            transforms,
+            indices: None,
         }
     }
 
+    pub(super) fn has_transforms(&self) -> bool {
+        !self.transforms.is_empty()
+    }
+
+    /// Apply the transforms (filter, sort etc.) to the collection.
+    /// Every value accessed by a transform is subscribed to by the loop,
+    /// so a change to any of them will cause the transforms to be re-applied.
+    pub(crate) fn apply_transforms(&mut self, context: &Context<'_, 'e>, loop_id: &NodeId) {
+        if self.transforms.is_empty() {
+            return;
+        }
+
+        let len = match self.collection {
+            Collection::Static(expressions) => expressions.len(),
+            Collection::State { len, .. } => len,
+            Collection::Empty => 0,
+        };
+
+        let mut indices = (0..len).collect::<Vec<_>>();
+
+        for transform in &self.transforms {
+            match transform {
+                Transform::Filter(predicate) => indices.retain(|&index| {
+                    self.eval_binding(index, predicate, context, loop_id, |val| val.is_true())
+                }),
+                Transform::SortBy(key) => {
+                    let mut keyed = indices
+                        .drain(..)
+                        .map(|index| {
+                            let key = self.eval_binding(index, key, context, loop_id, |val| {
+                                SortKey::from(val)
+                            });
+                            (key, index)
+                        })
+                        .collect::<Vec<_>>();
+                    keyed.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                    indices.extend(keyed.into_iter().map(|(_, index)| index));
+                }
+                Transform::Paginate { page, per_page } => {
+                    let mut resolver = Immediate::new(context.lookup(), loop_id);
+                    let page = page.eval(&mut resolver).try_into().unwrap_or(0usize);
+                    let per_page = per_page.eval(&mut resolver).try_into().unwrap_or(0usize);
+                    let start = page.saturating_mul(per_page).min(indices.len());
+                    let end = start.saturating_add(per_page).min(indices.len());
+                    indices = indices.drain(start..end).collect();
+                }
+                Transform::Fuzzy(query) => {
+                    let mut resolver = Immediate::new(context.lookup(), loop_id);
+                    let query = query.eval_string(&mut resolver).unwrap_or_default();
+
+                    let mut scored = indices
+                        .drain(..)
+                        .filter_map(|index| {
+                            let score = self.resolve_binding(index, context, loop_id, |val| {
+                                let item = match val {
+                                    ValueRef::Str(s) => s.to_string(),
+                                    ValueRef::Owned(val) => val.to_string(),
+                                    _ => return None,
+                                };
+                                fuzzy_match(&query, &item).map(|found| found.score)
+                            })?;
+                            Some((score, index))
+                        })
+                        .collect::<Vec<_>>();
+
+                    // Stable sort keeps the order of the collection for equal scores
+                    scored.sort_by_key(|(score, _)| -score);
+                    indices.extend(scored.into_iter().map(|(_, index)| index));
+                }
+            }
+        }
+
+        self.indices = Some(indices);
+    }
+
+    // Evaluate an expression with the loop binding set to the value at `index`
+    fn eval_binding<T>(
+        &self,
+        index: usize,
+        expr: &'e ValueExpr,
+        context: &Context<'_, 'e>,
+        loop_id: &NodeId,
+        f: impl FnOnce(ValueRef<'_>) -> T,
+    ) -> T {
+        let mut scope = ScopeStorage::new();
+        if let Some(value) = self.scope_value(index, context) {
+            scope.insert(self.binding.clone(), value);
+        }
+
+        let scope = context.new_scope(&scope);
+        let context = context.with_scope(&scope);
+        let mut resolver = Immediate::new(context.lookup(), loop_id);
+        f(expr.eval(&mut resolver))
+    }
+
+    // Resolve the loop binding, set to the value at `index`.
+    fn resolve_binding<T>(
+        &self,
+        index: usize,
+        context: &Context<'_, 'e>,
+        loop_id: &NodeId,
+        f: impl FnOnce(ValueRef<'_>) -> T,
+    ) -> T {
+        let mut scope = ScopeStorage::new();
+        if let Some(value) = self.scope_value(index, context) {
+            scope.insert(self.binding.clone(), value);
+        }
+
+        let scope = context.new_scope(&scope);
+        let context = context.with_scope(&scope);
+        let mut resolver = Immediate::new(context.lookup(), loop_id);
+        f(resolver.resolve(&self.binding))
+    }
+
     pub(super) fn next<F>(
         &mut self,
         scope: &mut ScopeStorage<'e>,
@@ -120,9 +313,17 @@ impl<'e> LoopNode<'e> {
     }
 
     fn scope_next_value(&mut self, context: &Context<'_, 'e>) -> Option<ScopeValue<'e>> {
+        let index = match &self.indices {
+            Some(indices) => *indices.get(self.value_index)?,
+            None => self.value_index,
+        };
+        self.scope_value(index, context)
+    }
+
+    fn scope_value(&self, index: usize, context: &Context<'_, 'e>) -> Option<ScopeValue<'e>> {
         match self.collection {
             Collection::Static(expressions) => {
-                let expr = expressions.get(self.value_index)?;
+                let expr = expressions.get(index)?;
                 let mut resolver = Deferred::new(context.lookup());
                 let val = match expr.eval(&mut resolver) {
                     ValueRef::Deferred => ScopeValue::Deferred(expr),
@@ -130,10 +331,8 @@ impl<'e> LoopNode<'e> {
                 };
                 Some(val)
             }
-            Collection::State { len, .. } if len == self.value_index => None,
-            Collection::State { expr, .. } => {
-                Some(ScopeValue::DeferredList(self.value_index, expr))
-            }
+            Collection::State { len, .. } if index >= len => None,
+            Collection::State { expr, .. } => Some(ScopeValue::DeferredList(index, expr)),
             Collection::Empty => None,
         }
     }
@@ -152,7 +351,7 @@ impl<'e> LoopNode<'e> {
         self.iterations.iter_mut().flat_map(|i| i.body.iter_mut())
     }
 
-    pub(super) fn update(&mut self, node_id: &[usize], change: &Change, context: &Context<'_, '_>) {
+    pub(super) fn update(&mut self, node_id: &[usize], change: &Change, context: &Context<'_, 'e>) {
         for iter in &mut self.iterations {
             if iter.node_id.contains(node_id) {
                 iter.body.update(node_id, change, context);
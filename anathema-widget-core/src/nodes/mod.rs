@@ -1,21 +1,25 @@
+use std::collections::HashMap;
 use std::fmt;
 use std::iter::once;
 use std::ops::ControlFlow;
 
 use anathema_values::{
-    Change, Context, Deferred, Immediate, NextNodeId, NodeId, ScopeStorage, Value, ValueRef,
+    remove_node, Change, Context, Deferred, Immediate, NextNodeId, NodeId, ScopeStorage, State,
+    Symbol, Value, ValueRef,
 };
 
 pub(crate) use self::controlflow::IfElse;
-pub(crate) use self::loops::LoopNode;
+pub(crate) use self::loops::{LoopNode, Transform};
 use self::query::Query;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::expressions::{Collection, Expression, ViewState};
 use crate::views::{AnyView, Views};
-use crate::{Event, WidgetContainer};
+use crate::{tooltip, Event, EventResult, Pos, WidgetContainer};
 
 mod controlflow;
+pub mod debug;
 mod loops;
+pub mod preserve;
 mod query;
 pub mod visitor;
 
@@ -32,10 +36,14 @@ fn c_and_b<'expr, F>(
 where
     F: FnMut(&mut WidgetContainer<'expr>, &mut Nodes<'expr>, &Context<'_, 'expr>) -> Result<()>,
 {
-    while let Ok(res) = nodes.next(context, f) {
-        match res {
-            ControlFlow::Continue(()) => continue,
-            ControlFlow::Break(()) => break,
+    loop {
+        match nodes.next(context, f) {
+            Ok(ControlFlow::Continue(())) => continue,
+            Ok(ControlFlow::Break(())) => break,
+            // Running out of space only ends the layout of the body,
+            // any other error is passed on so it can reach an error boundary
+            Err(Error::InsufficientSpaceAvailble) => break,
+            Err(e) => return Err(e),
         }
     }
 
@@ -132,7 +140,7 @@ impl<'e> Node<'e> {
     // Update this node.
     // This means that the update was specifically for this node,
     // and not one of its children
-    fn update(&mut self, change: &Change, context: &Context<'_, '_>) {
+    fn update(&mut self, change: &Change, context: &Context<'_, 'e>) {
         let scope = context.new_scope(&self.scope);
         let context = context.with_scope(&scope);
 
@@ -149,10 +157,15 @@ impl<'e> Node<'e> {
                     }
                 }
 
+                loop_node.apply_transforms(&context, &self.node_id);
+
                 match change {
                     Change::InsertIndex(_index) => loop_node.smush(),
                     Change::RemoveIndex(_index) => loop_node.smush(),
                     Change::Push => loop_node.smush(),
+                    Change::Swap(..) | Change::Move(..) => loop_node.smush(),
+                    // A value used by one of the transforms changed
+                    Change::Update if loop_node.has_transforms() => loop_node.smush(),
                     _ => (),
                 }
             }
@@ -170,11 +183,26 @@ impl<'e> Node<'e> {
     }
 }
 
+// Removing a node will remove all the children as well.
+impl Drop for Node<'_> {
+    fn drop(&mut self) {
+        match &mut self.kind {
+            NodeKind::Single(Single { widget, .. }) => {
+                preserve::retain(widget);
+                widget.on_remove();
+            }
+            NodeKind::View(_) => Views::remove(&self.node_id),
+            NodeKind::Loop(_) | NodeKind::ControlFlow(_) => {}
+        }
+        remove_node(self.node_id.clone());
+    }
+}
+
 #[derive(Debug)]
 pub struct Single<'e> {
     pub(crate) widget: WidgetContainer<'e>,
     pub(crate) children: Nodes<'e>,
-    pub(crate) ident: &'e str,
+    pub(crate) ident: Symbol,
 }
 
 pub struct View<'e> {
@@ -194,8 +222,16 @@ impl fmt::Debug for View<'_> {
 }
 
 impl View<'_> {
-    pub fn on_event(&mut self, event: Event) {
+    /// Route the event to the widgets of the view first,
+    /// and to the view if no widget consumed it.
+    ///
+    /// Returns [`EventResult::Consumed`] if a widget consumed the event.
+    pub fn on_event(&mut self, event: Event) -> EventResult {
+        if self.nodes.on_event(&event).is_consumed() {
+            return EventResult::Consumed;
+        }
         self.view.on_any_event(event, &mut self.nodes);
+        EventResult::Ignored
     }
 
     pub fn tick(&mut self) {
@@ -209,8 +245,16 @@ impl View<'_> {
     pub fn blur(&mut self) {
         self.view.blur_any();
     }
+
+    /// The internal state of the view
+    pub fn state(&self) -> &dyn State {
+        self.view.get_any_state()
+    }
 }
 
+// Most nodes are widgets, so boxing the widget would cost
+// an allocation for nearly every node
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum NodeKind<'e> {
     Single(Single<'e>),
@@ -230,9 +274,9 @@ pub struct Nodes<'expr> {
 }
 
 impl<'expr> Nodes<'expr> {
-    pub fn with_view<F>(&mut self, node_id: &NodeId, mut f: F)
+    pub fn with_view<F>(&mut self, node_id: &NodeId, f: F)
     where
-        F: FnMut(&mut View<'_>),
+        F: FnOnce(&mut View<'_>),
     {
         if let Some(Node {
             kind: NodeKind::View(view),
@@ -294,7 +338,8 @@ impl<'expr> Nodes<'expr> {
     /// Update and apply the change to the specific node.
     /// This is currently done by the runtime
     #[doc(hidden)]
-    pub fn update(&mut self, node_id: &[usize], change: &Change, context: &Context<'_, '_>) {
+    pub fn update(&mut self, node_id: &[usize], change: &Change, context: &Context<'_, 'expr>) {
+        crate::paint_cache::invalidate(node_id);
         update(&mut self.inner, node_id, change, context);
     }
 
@@ -314,6 +359,78 @@ impl<'expr> Nodes<'expr> {
         count_widgets(self.inner.iter())
     }
 
+    /// The number of widgets in the node tree per widget ident (`text`, `hstack` etc.)
+    pub fn count_by_ident(&self) -> HashMap<Symbol, usize> {
+        let mut counts = HashMap::new();
+        count_idents(self, &mut counts);
+        counts
+    }
+
+    /// The approximate number of bytes used by the node tree.
+    /// This includes the nodes and the widgets, but not what the widgets allocate.
+    pub fn memory_usage(&self) -> usize {
+        memory_usage(self)
+    }
+
+    /// Snapshot the widget tree for external tooling.
+    /// See [`DebugNode`](debug::DebugNode) for more information
+    pub fn to_debug_tree(&self) -> Vec<debug::DebugNode> {
+        let mut tree = vec![];
+        debug::debug_tree(self, &mut tree);
+        tree
+    }
+
+    /// Save the local state of every widget, see [`Widget::save_state`](crate::Widget::save_state).
+    /// Restore it to a new node tree with [`Nodes::restore_widget_states`].
+    pub fn save_widget_states(&mut self) -> preserve::WidgetStates {
+        let mut states = preserve::WidgetStates::default();
+        preserve::save(self, &mut states);
+        states
+    }
+
+    /// Restore saved state to the widgets with the same kind and identity:
+    /// the same `id` attribute, or the same node path for widgets without an `id`.
+    /// Restored states are removed from `states`.
+    pub fn restore_widget_states(&mut self, states: &mut preserve::WidgetStates) {
+        preserve::restore(self, states);
+    }
+
+    /// Pass an event to every widget, children before their parents,
+    /// until a widget consumes it.
+    /// Nested views, and the children of disabled widgets, are skipped.
+    pub fn on_event(&mut self, event: &Event) -> EventResult {
+        for node in &mut self.inner {
+            let result = match &mut node.kind {
+                NodeKind::Single(Single { widget, .. }) if widget.is_disabled() => {
+                    EventResult::Ignored
+                }
+                NodeKind::Single(Single {
+                    widget, children, ..
+                }) => match children.on_event(event) {
+                    EventResult::Consumed => EventResult::Consumed,
+                    EventResult::Ignored => widget.on_event(event),
+                },
+                NodeKind::Loop(loop_state) => loop_state
+                    .iterations
+                    .iter_mut()
+                    .map(|iteration| iteration.body.on_event(event))
+                    .find(EventResult::is_consumed)
+                    .unwrap_or_default(),
+                NodeKind::ControlFlow(if_else) => if_else
+                    .body_mut()
+                    .map(|body| body.on_event(event))
+                    .unwrap_or_default(),
+                NodeKind::View(_) => EventResult::Ignored,
+            };
+
+            if result.is_consumed() {
+                return result;
+            }
+        }
+
+        EventResult::Ignored
+    }
+
     /// Reset the widget cache.
     /// This should be done per frame
     #[doc(hidden)]
@@ -333,6 +450,25 @@ impl<'expr> Nodes<'expr> {
         }
     }
 
+    /// The id of the top most widget at a screen position
+    pub fn widget_at(&mut self, pos: Pos) -> Option<NodeId> {
+        let mut node_id = None;
+
+        for (widget, children) in self.iter_mut() {
+            if !tooltip::is_visible(widget) || !tooltip::contains(widget, pos) {
+                continue;
+            }
+
+            // Widgets painted later are painted on top,
+            // and children are painted on top of their parents
+            node_id = children
+                .widget_at(pos)
+                .or_else(|| Some(widget.node_id.clone()));
+        }
+
+        node_id
+    }
+
     /// A mutable iterator over [`WidgetContainer`]s and their children
     pub fn iter_mut(
         &mut self,
@@ -368,8 +504,60 @@ fn count_widgets<'a>(nodes: impl Iterator<Item = &'a Node<'a>>) -> usize {
         .sum()
 }
 
+fn count_idents(nodes: &Nodes<'_>, counts: &mut HashMap<Symbol, usize>) {
+    for node in &nodes.inner {
+        match &node.kind {
+            NodeKind::Single(Single {
+                ident, children, ..
+            }) => {
+                *counts.entry(*ident).or_default() += 1;
+                count_idents(children, counts);
+            }
+            NodeKind::Loop(loop_state) => loop_state
+                .iterations
+                .iter()
+                .for_each(|iteration| count_idents(&iteration.body, counts)),
+            NodeKind::ControlFlow(if_else) => {
+                if let Some(body) = if_else.body() {
+                    count_idents(body, counts);
+                }
+            }
+            NodeKind::View(View { nodes, .. }) => count_idents(nodes, counts),
+        }
+    }
+}
+
+fn memory_usage(nodes: &Nodes<'_>) -> usize {
+    let mut bytes = nodes.inner.capacity() * std::mem::size_of::<Node<'_>>();
+    for node in &nodes.inner {
+        bytes += match &node.kind {
+            NodeKind::Single(Single {
+                widget, children, ..
+            }) => std::mem::size_of_val(&*widget.inner) + memory_usage(children),
+            NodeKind::Loop(loop_state) => {
+                let iterations = &loop_state.iterations;
+                iterations.capacity() * std::mem::size_of::<loops::Iteration<'_>>()
+                    + iterations
+                        .iter()
+                        .map(|iteration| memory_usage(&iteration.body))
+                        .sum::<usize>()
+            }
+            NodeKind::ControlFlow(if_else) => if_else.body().map(memory_usage).unwrap_or(0),
+            NodeKind::View(View { view, nodes, .. }) => {
+                std::mem::size_of_val(&**view) + memory_usage(nodes)
+            }
+        };
+    }
+    bytes
+}
+
 // Apply change / update to relevant nodes
-fn update(nodes: &mut [Node<'_>], node_id: &[usize], change: &Change, context: &Context<'_, '_>) {
+fn update<'e>(
+    nodes: &mut [Node<'e>],
+    node_id: &[usize],
+    change: &Change,
+    context: &Context<'_, 'e>,
+) {
     for node in nodes {
         if !node.node_id.contains(node_id) {
             continue;
@@ -425,11 +613,15 @@ fn update(nodes: &mut [Node<'_>], node_id: &[usize], change: &Change, context: &
 #[cfg(test)]
 mod test {
     use anathema_render::Size;
-    use anathema_values::testing::{ident, list};
-    use anathema_values::ValueExpr;
+    use anathema_values::testing::{
+        boolean, filter, fuzzy, greater_than, ident, list, neg, paginate, sort_by, strlit, unum,
+    };
+    use anathema_values::{drain_removed_nodes, ValueExpr};
 
+    use super::*;
     use crate::testing::expressions::{expression, for_expression, if_expression};
     use crate::testing::nodes::*;
+    use crate::{KeyCode, KeyEventState, KeyModifiers};
 
     #[test]
     fn generate_a_single_widget() {
@@ -439,6 +631,54 @@ mod test {
         assert_eq!(widget.kind(), "text");
     }
 
+    #[test]
+    fn cast_widget_to_wrong_type() {
+        let test = expression("test", None, [], []).test();
+        let mut node = test.eval().unwrap();
+        let (widget, _nodes) = node.single();
+        assert!(widget.try_to::<TestWidget>().is_ok());
+
+        let err = widget.try_to::<String>().unwrap_err();
+        assert_eq!(err.expected, "alloc::string::String");
+        assert_eq!(err.found, "text");
+    }
+
+    #[test]
+    fn widgets_consume_events() {
+        let exprs = vec![
+            expression("test", Some("abc".into()), [], []),
+            for_expression(
+                "item",
+                list([1, 2]),
+                [expression("test", Some("xyz".into()), [], [])],
+            ),
+        ];
+        let mut runtime = test_runtime(&exprs);
+        runtime.layout().unwrap();
+
+        let key = |c| Event::KeyPress(KeyCode::Char(c), KeyModifiers::NONE, KeyEventState::NONE);
+        assert_eq!(runtime.nodes.on_event(&key('b')), EventResult::Consumed);
+        assert_eq!(runtime.nodes.on_event(&key('y')), EventResult::Consumed);
+        assert_eq!(runtime.nodes.on_event(&key('q')), EventResult::Ignored);
+    }
+
+    #[test]
+    fn disabled_widgets_ignore_events() {
+        let disabled = ("disabled".to_string(), *boolean(true));
+        let exprs = vec![expression(
+            "test",
+            Some("abc".into()),
+            [disabled],
+            [expression("test", Some("xyz".into()), [], [])],
+        )];
+        let mut runtime = test_runtime(&exprs);
+        runtime.layout().unwrap();
+
+        let key = |c| Event::KeyPress(KeyCode::Char(c), KeyModifiers::NONE, KeyEventState::NONE);
+        assert_eq!(runtime.nodes.on_event(&key('b')), EventResult::Ignored);
+        assert_eq!(runtime.nodes.on_event(&key('y')), EventResult::Ignored);
+    }
+
     #[test]
     fn for_loop() {
         let string = "hello".into();
@@ -450,6 +690,83 @@ mod test {
         assert_eq!(runtime.nodes.count(), 3);
     }
 
+    #[test]
+    fn count_widgets_by_ident() {
+        let string = "hello".into();
+        let body = expression("test", Some(string), [], []);
+        let exprs = vec![for_expression("item", list([1, 2, 3]), [body])];
+        let mut runtime = test_runtime(&exprs);
+        let _ = runtime.layout().unwrap();
+
+        let counts = runtime.nodes.count_by_ident();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[&Symbol::from("test")], 3);
+
+        let kinds = crate::Factory::kinds(&runtime.nodes);
+        let test = kinds.iter().find(|kind| kind.ident == "test").unwrap();
+        assert_eq!(test.instances, 3);
+        assert!(test.factory.ends_with("TestWidgetFactory"));
+    }
+
+    #[test]
+    fn memory_usage() {
+        let body = expression("test", Some("hello".into()), [], []);
+        let exprs = vec![for_expression("item", list([1, 2, 3]), [body])];
+        let mut runtime = test_runtime(&exprs);
+        let before = runtime.nodes.memory_usage();
+        let _ = runtime.layout().unwrap();
+        assert!(runtime.nodes.memory_usage() > before + 3 * std::mem::size_of::<Node<'_>>());
+    }
+
+    #[test]
+    fn debug_tree() {
+        let body = expression("test", Some(ValueExpr::Ident("item".into())), [], []);
+        let exprs = vec![expression(
+            "list",
+            None,
+            [("axis".into(), ValueExpr::String("vertical".into()))],
+            [for_expression("item", list([1, 2]), [body])],
+        )];
+        let mut runtime = test_runtime(&exprs);
+        let _ = runtime.layout().unwrap();
+
+        let tree = runtime.nodes.to_debug_tree();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].ident, "list");
+        assert_eq!(tree[0].attributes, [("axis", "vertical".to_string())]);
+        assert_eq!(tree[0].children.len(), 2);
+        assert_eq!(tree[0].children[1].size, Size::new(1, 1));
+
+        let json = debug::to_json(&tree);
+        assert!(json.starts_with(r#"[{"kind":"#));
+        assert!(json.contains(r#""attributes":{"axis":"vertical"}"#));
+        assert!(json.contains(r#""role":null,"label":null"#));
+    }
+
+    #[test]
+    fn debug_tree_roles() {
+        let body = expression("test", Some(ValueExpr::Ident("item".into())), [], []);
+        let exprs = vec![expression(
+            "list",
+            None,
+            [
+                ("role".into(), ValueExpr::String("list".into())),
+                ("label".into(), ValueExpr::String("Results".into())),
+            ],
+            [for_expression("item", list([1, 2]), [body])],
+        )];
+        let mut runtime = test_runtime(&exprs);
+        let _ = runtime.layout().unwrap();
+
+        let tree = runtime.nodes.to_debug_tree();
+        assert_eq!(tree[0].role.as_deref(), Some("list"));
+        assert_eq!(tree[0].description(), "list: Results, 2 items");
+        assert_eq!(tree[0].children[0].description(), "text");
+
+        let json = debug::to_json(&tree);
+        assert!(json.contains(r#""role":"list","label":"Results""#));
+    }
+
     #[test]
     fn for_loop_from_state() {
         let string = ValueExpr::Ident("item".into());
@@ -461,6 +778,91 @@ mod test {
         assert_eq!(runtime.nodes.count(), 3);
     }
 
+    #[test]
+    fn filtered_loop() {
+        let string = ValueExpr::Ident("item".into());
+        let body = expression("test", Some(string), [], []);
+        let collection = filter(ident("generic_list"), greater_than(ident("item"), unum(1)));
+        let exprs = vec![for_expression("item", collection, [body])];
+        let mut runtime = test_runtime(&exprs);
+        let _ = runtime.layout().unwrap();
+        assert_eq!(runtime.nodes.count(), 2);
+
+        let (node, _) = runtime.nodes.first_mut().unwrap();
+        let widget = node.to_ref::<TestWidget>();
+        assert_eq!(widget.0.value_ref().unwrap(), "2");
+    }
+
+    #[test]
+    fn sorted_loop() {
+        let string = ValueExpr::Ident("item".into());
+        let body = expression("test", Some(string), [], []);
+        let collection = sort_by(ident("generic_list"), neg(ident("item")));
+        let exprs = vec![for_expression("item", collection, [body])];
+        let mut runtime = test_runtime(&exprs);
+        let _ = runtime.layout().unwrap();
+
+        let values = runtime
+            .nodes
+            .iter_mut()
+            .map(|(node, _)| node.to_ref::<TestWidget>().0.str().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(values, ["3", "2", "1"]);
+    }
+
+    #[test]
+    fn paginated_loop() {
+        let string = ValueExpr::Ident("item".into());
+        let body = expression("test", Some(string), [], []);
+        let collection = paginate(list([1, 2, 3, 4, 5]), unum(1), unum(2));
+        let exprs = vec![for_expression("item", collection, [body])];
+        let mut runtime = test_runtime(&exprs);
+        let _ = runtime.layout().unwrap();
+
+        let values = runtime
+            .nodes
+            .iter_mut()
+            .map(|(node, _)| node.to_ref::<TestWidget>().0.str().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(values, ["3", "4"]);
+    }
+
+    #[test]
+    fn fuzzy_loop() {
+        let string = ValueExpr::Ident("item".into());
+        let body = expression("test", Some(string), [], []);
+        let items = list(["close project", "open", "save", "open recent"]);
+        let collection = fuzzy(items, strlit("op"));
+        let exprs = vec![for_expression("item", collection, [body])];
+        let mut runtime = test_runtime(&exprs);
+        let _ = runtime.layout().unwrap();
+
+        let values = runtime
+            .nodes
+            .iter_mut()
+            .map(|(node, _)| node.to_ref::<TestWidget>().0.str().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(values, ["open", "open recent", "close project"]);
+    }
+
+    #[test]
+    fn remove_nodes() {
+        let body = expression("test", Some("hello".into()), [], []);
+        let exprs = vec![for_expression("item", list([1, 2, 3]), [body])];
+        let mut runtime = test_runtime(&exprs);
+        let _ = runtime.layout().unwrap();
+        let _ = drain_removed_nodes();
+
+        runtime
+            .nodes
+            .query()
+            .filter(|node| matches!(node.kind, NodeKind::Single(_)))
+            .remove();
+
+        assert_eq!(runtime.nodes.count(), 0);
+        assert_eq!(drain_removed_nodes().len(), 3);
+    }
+
     fn test_if_else(is_true: bool, else_cond: Option<bool>, expected: &str) {
         let is_true = is_true.into();
         let is_else = else_cond.map(|val| val.into());
@@ -0,0 +1,237 @@
+// -----------------------------------------------------------------------------
+//   - Widget state preservation -
+//   Widget-local state (scroll offsets, input contents, selections)
+//   is lost when the node tree is rebuilt from new templates.
+//
+//   The state is saved from the old node tree and restored to the
+//   widgets of the new node tree with the same identity and kind.
+//   A widget is identified by its `id` attribute, or by the
+//   path of the node if it has no `id`.
+// -----------------------------------------------------------------------------
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+
+use anathema_values::{NodeId, Symbol, ValueExpr};
+
+use super::Nodes;
+use crate::{Widget, WidgetContainer};
+
+/// The identity of a widget across node trees
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WidgetKey {
+    /// The `id` attribute of the widget
+    Id(String),
+    /// The path of the node
+    Path(NodeId),
+}
+
+impl WidgetKey {
+    fn new(widget: &WidgetContainer<'_>) -> Self {
+        match widget.attributes.get(&Symbol::new("id")) {
+            Some(ValueExpr::String(id)) => Self::Id(id.to_string()),
+            _ => Self::Path(widget.node_id.clone()),
+        }
+    }
+}
+
+/// Widget state saved from a node tree.
+/// See [`Nodes::save_widget_states`].
+#[derive(Default)]
+pub struct WidgetStates {
+    states: HashMap<WidgetKey, (&'static str, Box<dyn Any>)>,
+}
+
+impl WidgetStates {
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Retained state -
+//   With retention enabled, the state of a widget removed from the node tree
+//   is kept, and restored to the next widget created with the same identity
+//   and kind, e.g once a loop is rebuilt.
+//
+//   Node paths inside loops change when the loop is rebuilt,
+//   so those widgets need an `id` to be restored.
+// -----------------------------------------------------------------------------
+
+// Evict the oldest state beyond this
+const MAX_RETAINED: usize = 1024;
+
+#[derive(Default)]
+struct Retained {
+    states: WidgetStates,
+    order: VecDeque<WidgetKey>,
+}
+
+thread_local! {
+    static RETAINED: RefCell<Option<Retained>> = const { RefCell::new(None) };
+}
+
+/// Keep the state of removed widgets (scroll offsets, selections etc.)
+/// and restore it when a widget with the same identity is created again.
+/// Disabling this discards all retained state.
+pub fn retain_removed_states(enable: bool) {
+    RETAINED.with_borrow_mut(|retained| match enable {
+        true => {
+            retained.get_or_insert_with(Retained::default);
+        }
+        false => *retained = None,
+    });
+}
+
+/// The number of states kept from removed widgets
+pub fn retained_count() -> usize {
+    RETAINED.with_borrow(|retained| retained.as_ref().map_or(0, |r| r.states.len()))
+}
+
+pub(crate) fn retain(widget: &WidgetContainer<'_>) {
+    RETAINED.with_borrow_mut(|retained| {
+        let Some(retained) = retained else { return };
+        let Some(state) = widget.inner.save_state() else {
+            return;
+        };
+
+        let key = WidgetKey::new(widget);
+        let entry = (widget.kind(), state);
+        if retained.states.states.insert(key.clone(), entry).is_none() {
+            retained.order.push_back(key);
+        }
+
+        while retained.states.len() > MAX_RETAINED {
+            let Some(oldest) = retained.order.pop_front() else {
+                break;
+            };
+            retained.states.states.remove(&oldest);
+        }
+    });
+}
+
+pub(crate) fn restore_retained(widget: &mut WidgetContainer<'_>) {
+    RETAINED.with_borrow_mut(|retained| {
+        let Some(retained) = retained.as_mut().filter(|r| !r.states.is_empty()) else {
+            return;
+        };
+
+        let key = WidgetKey::new(widget);
+        let same_kind = retained
+            .states
+            .states
+            .get(&key)
+            .is_some_and(|(kind, _)| *kind == widget.kind());
+
+        if !same_kind {
+            return;
+        }
+
+        if let Some((_, state)) = retained.states.states.remove(&key) {
+            retained.order.retain(|k| *k != key);
+            widget.inner.restore_state(state);
+        }
+    });
+}
+
+pub(super) fn save(nodes: &mut Nodes<'_>, states: &mut WidgetStates) {
+    for (widget, children) in nodes.iter_mut() {
+        if let Some(state) = widget.inner.save_state() {
+            let key = WidgetKey::new(widget);
+            states.states.insert(key, (widget.kind(), state));
+        }
+        save(children, states);
+    }
+}
+
+pub(super) fn restore(nodes: &mut Nodes<'_>, states: &mut WidgetStates) {
+    for (widget, children) in nodes.iter_mut() {
+        if states.is_empty() {
+            return;
+        }
+
+        let key = WidgetKey::new(widget);
+        let same_kind = states
+            .states
+            .get(&key)
+            .is_some_and(|(kind, _)| *kind == widget.kind());
+
+        if same_kind {
+            if let Some((_, state)) = states.states.remove(&key) {
+                widget.inner.restore_state(state);
+            }
+        }
+
+        restore(children, states);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_values::testing::strlit;
+
+    use anathema_values::Value;
+
+    use super::*;
+    use crate::testing::expressions::expression;
+    use crate::testing::nodes::{test_runtime, TestWidget};
+
+    fn texts(nodes: &mut crate::Nodes<'_>) -> Vec<String> {
+        nodes
+            .iter_mut()
+            .map(|(widget, _)| widget.to_ref::<TestWidget>().0.value_ref().unwrap().clone())
+            .collect()
+    }
+
+    #[test]
+    fn restore_by_id_and_path() {
+        let id = || ("id".to_string(), *strlit("keep"));
+        let old = vec![
+            expression("test", *strlit("a"), [], []),
+            expression("test", *strlit("b"), [id()], []),
+        ];
+        let new = vec![
+            expression("test", *strlit("x"), [], []),
+            expression("test", *strlit("y"), [id()], []),
+            expression("test", *strlit("z"), [], []),
+        ];
+
+        let mut old = test_runtime(&old);
+        old.layout().unwrap();
+        let mut states = old.nodes.save_widget_states();
+        assert_eq!(states.len(), 2);
+
+        let mut new = test_runtime(&new);
+        new.layout().unwrap();
+        new.nodes.restore_widget_states(&mut states);
+
+        assert!(states.is_empty());
+        assert_eq!(texts(&mut new.nodes), vec!["a", "b", "z"]);
+    }
+
+    #[test]
+    fn restore_removed_widget() {
+        retain_removed_states(true);
+
+        let exprs = vec![expression("test", *strlit("new"), [], [])];
+        let mut runtime = test_runtime(&exprs);
+        runtime.layout().unwrap();
+        let (widget, _) = runtime.nodes.first_mut().unwrap();
+        widget.to_mut::<TestWidget>().0 = Value::Static("changed".into());
+
+        // Dropping the nodes retains the state,
+        // the next widget with the same path gets it back
+        drop(runtime);
+        assert_eq!(retained_count(), 1);
+        let mut runtime = test_runtime(&exprs);
+        runtime.layout().unwrap();
+        assert_eq!(texts(&mut runtime.nodes), vec!["changed"]);
+        assert_eq!(retained_count(), 0);
+
+        retain_removed_states(false);
+    }
+}
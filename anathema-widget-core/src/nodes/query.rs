@@ -1,4 +1,4 @@
-use anathema_values::{NodeId, ValueExpr};
+use anathema_values::{NodeId, Symbol, ValueExpr};
 
 use super::{LoopNode, Node, Single, View};
 use crate::nodes::NodeKind;
@@ -226,7 +226,7 @@ where
 
 impl Filter for () {}
 
-struct ByAttribute(String, ValueExpr);
+struct ByAttribute(Symbol, ValueExpr);
 
 // TODO: attributes are not resolved at this point.
 //       Alternatively we can resolve all attributes upon creation,
@@ -244,7 +244,7 @@ impl Filter for ByAttribute {
     }
 }
 
-struct ByTag(String);
+struct ByTag(Symbol);
 
 impl Filter for ByTag {
     fn filter(&self, node: &Node<'_>) -> bool {
@@ -0,0 +1,231 @@
+// -----------------------------------------------------------------------------
+//   - Overflow checks -
+//   Detect widgets that size themselves outside of their constraints,
+//   or paint outside of their own size.
+//
+//   Both are otherwise silent: the output is clipped or painted on top
+//   of the neighbouring widgets.
+//   The checks are off by default.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+
+use anathema_render::{Color, Size};
+use anathema_values::NodeId;
+
+use crate::layout::Constraints;
+use crate::LocalPos;
+
+thread_local! {
+    static CHECKS: RefCell<Checks> = const { RefCell::new(Checks::new()) };
+}
+
+struct Checks {
+    enabled: bool,
+    highlight: bool,
+    // The widgets currently painting, the last one is the innermost widget
+    painting: Vec<(&'static str, NodeId)>,
+    reports: Vec<Overflow>,
+}
+
+impl Checks {
+    const fn new() -> Self {
+        Self {
+            enabled: false,
+            highlight: false,
+            painting: vec![],
+            reports: vec![],
+        }
+    }
+
+    fn report(&mut self, overflow: Overflow) {
+        // Only report a widget once per kind of overflow,
+        // as painting outside the widget is usually done more than once
+        let reported = self.reports.iter().any(|report| {
+            report.node_id == overflow.node_id
+                && std::mem::discriminant(&report.reason)
+                    == std::mem::discriminant(&overflow.reason)
+        });
+
+        if !reported {
+            self.reports.push(overflow);
+        }
+    }
+}
+
+/// Why a widget overflowed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reason {
+    /// The size returned from layout is outside of the constraints
+    Layout {
+        size: Size,
+        constraints: Constraints,
+    },
+    /// A character was painted outside of the widget's size
+    Paint { pos: LocalPos, size: Size },
+}
+
+/// A widget that overflowed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overflow {
+    /// The widget kind
+    pub kind: &'static str,
+    pub node_id: NodeId,
+    pub reason: Reason,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bound = |n: usize| match n {
+            usize::MAX => "∞".to_string(),
+            n => n.to_string(),
+        };
+
+        write!(f, "{} {:?}: ", self.kind, self.node_id.as_slice())?;
+        match &self.reason {
+            Reason::Layout { size, constraints } => write!(
+                f,
+                "size {}x{} is outside of the constraints {}..{}x{}..{}",
+                size.width,
+                size.height,
+                constraints.min_width,
+                bound(constraints.max_width),
+                constraints.min_height,
+                bound(constraints.max_height),
+            ),
+            Reason::Paint { pos, size } => write!(
+                f,
+                "painting at {},{} is outside of the size {}x{}",
+                pos.x, pos.y, size.width, size.height
+            ),
+        }
+    }
+}
+
+/// Check the size and painting of every widget.
+/// If `highlight` is true widgets that overflow are painted with a red background.
+///
+/// Reports are collected until [`drain_reports`] is called.
+pub fn enable(highlight: bool) {
+    CHECKS.with_borrow_mut(|checks| {
+        checks.enabled = true;
+        checks.highlight = highlight;
+    });
+}
+
+pub fn disable() {
+    CHECKS.with_borrow_mut(|checks| *checks = Checks::new());
+}
+
+pub fn is_enabled() -> bool {
+    CHECKS.with_borrow(|checks| checks.enabled)
+}
+
+/// Drain all overflow reports
+pub fn drain_reports() -> Vec<Overflow> {
+    CHECKS.with_borrow_mut(|checks| checks.reports.drain(..).collect())
+}
+
+pub(crate) fn check_layout(
+    kind: &'static str,
+    node_id: &NodeId,
+    size: Size,
+    constraints: Constraints,
+) {
+    let fits = size.width >= constraints.min_width
+        && size.width <= constraints.max_width
+        && size.height >= constraints.min_height
+        && size.height <= constraints.max_height;
+
+    if fits || !is_enabled() {
+        return;
+    }
+
+    CHECKS.with_borrow_mut(|checks| {
+        checks.report(Overflow {
+            kind,
+            node_id: node_id.clone(),
+            reason: Reason::Layout { size, constraints },
+        })
+    });
+}
+
+pub(crate) fn enter_paint(kind: &'static str, node_id: &NodeId) {
+    CHECKS.with_borrow_mut(|checks| {
+        if checks.enabled {
+            checks.painting.push((kind, node_id.clone()));
+        }
+    });
+}
+
+pub(crate) fn exit_paint() {
+    CHECKS.with_borrow_mut(|checks| checks.painting.pop());
+}
+
+// Painting outside of the overlays painted by the runtime is not reported,
+// as there is no widget painting
+pub(crate) fn report_paint(pos: LocalPos, size: Size) {
+    CHECKS.with_borrow_mut(|checks| {
+        let Some((kind, node_id)) = checks.painting.last().cloned() else {
+            return;
+        };
+
+        checks.report(Overflow {
+            kind,
+            node_id,
+            reason: Reason::Paint { pos, size },
+        })
+    });
+}
+
+// The background color of an overflowing widget, if it should be highlighted
+pub(crate) fn highlight(node_id: &NodeId) -> Option<Color> {
+    CHECKS.with_borrow(|checks| {
+        let overflowed = checks.highlight
+            && checks
+                .reports
+                .iter()
+                .any(|report| report.node_id == *node_id);
+        overflowed.then_some(Color::Red)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn report_layout_overflow() {
+        enable(false);
+        let constraints = Constraints::new(4, 1);
+        check_layout("text", &1.into(), Size::new(3, 1), constraints);
+        assert!(drain_reports().is_empty());
+
+        check_layout("text", &1.into(), Size::new(5, 1), constraints);
+        check_layout("text", &1.into(), Size::new(6, 1), constraints);
+        let reports = drain_reports();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(
+            reports[0].to_string(),
+            "text [1]: size 5x1 is outside of the constraints 0..4x0..1"
+        );
+    }
+
+    #[test]
+    fn report_paint_overflow() {
+        enable(true);
+
+        // Outside of any widget
+        report_paint(LocalPos::new(2, 0), Size::new(1, 1));
+        assert!(drain_reports().is_empty());
+
+        enter_paint("border", &vec![0, 1].into());
+        report_paint(LocalPos::new(2, 0), Size::new(1, 1));
+        exit_paint();
+        assert_eq!(highlight(&vec![0, 1].into()), Some(Color::Red));
+        assert_eq!(
+            drain_reports()[0].to_string(),
+            "border [0, 1]: painting at 2,0 is outside of the size 1x1"
+        );
+    }
+}
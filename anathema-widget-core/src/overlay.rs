@@ -0,0 +1,100 @@
+// -----------------------------------------------------------------------------
+//   - Overlays -
+//   Widgets can paint on top of all the other widgets, e.g a dropdown menu.
+//
+//   Overlays are queued while painting and painted by the runtime
+//   once every widget is painted, in the order they were queued.
+//   The position of an overlay is a screen position.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+
+use anathema_render::{Screen, Size, Style};
+use unicode_width::UnicodeWidthChar;
+
+use crate::contexts::PaintCtx;
+use crate::{LocalPos, Pos};
+
+thread_local! {
+    static OVERLAYS: RefCell<Vec<Overlay>> = const { RefCell::new(vec![]) };
+}
+
+/// An area painted on top of the widgets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlay {
+    pub pos: Pos,
+    pub size: Size,
+    cells: Vec<Option<(char, Style)>>,
+}
+
+impl Overlay {
+    pub fn new(pos: Pos, size: Size) -> Self {
+        Self {
+            pos,
+            size,
+            cells: vec![None; size.width * size.height],
+        }
+    }
+
+    /// Put a character at a position relative to the overlay.
+    /// Characters outside of the overlay are ignored.
+    pub fn put(&mut self, c: char, style: Style, pos: LocalPos) {
+        if pos.x < self.size.width && pos.y < self.size.height {
+            self.cells[pos.y * self.size.width + pos.x] = Some((c, style));
+        }
+    }
+
+    /// Print a string, returns the position after the last character.
+    pub fn print(&mut self, s: &str, style: Style, mut pos: LocalPos) -> LocalPos {
+        for c in s.chars() {
+            self.put(c, style, pos);
+            pos.x += c.width().unwrap_or(0);
+        }
+        pos
+    }
+
+    /// Queue the overlay to be painted on top of the widgets
+    pub fn show(self) {
+        OVERLAYS.with_borrow_mut(|overlays| overlays.push(self));
+    }
+}
+
+/// Paint the queued overlays and clear the queue.
+pub fn paint(screen: &mut Screen) {
+    for overlay in OVERLAYS.take() {
+        let mut ctx = PaintCtx::new(screen, None).into_sized(overlay.size, overlay.pos);
+        for (i, cell) in overlay.cells.iter().enumerate() {
+            if let Some((c, style)) = cell {
+                let pos = LocalPos::new(i % overlay.size.width, i / overlay.size.width);
+                ctx.put(*c, *style, pos);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::ScreenPos;
+
+    use super::*;
+
+    #[test]
+    fn paint_on_top() {
+        let mut screen = Screen::new(Size::new(5, 2));
+        let mut overlay = Overlay::new(Pos::new(1, 1), Size::new(3, 1));
+        let end = overlay.print("abcd", Style::new(), LocalPos::ZERO);
+        assert_eq!(end, LocalPos::new(4, 0));
+        overlay.show();
+
+        paint(&mut screen);
+        let c = |x, y| screen.get(ScreenPos::new(x, y)).map(|(c, _)| c);
+        assert_eq!(c(0, 1), None);
+        assert_eq!(c(1, 1), Some('a'));
+        assert_eq!(c(3, 1), Some('c'));
+        assert_eq!(c(4, 1), None);
+
+        // The queue is cleared
+        let mut screen = Screen::new(Size::new(5, 2));
+        paint(&mut screen);
+        assert_eq!(screen.get(ScreenPos::new(1, 1)), None);
+    }
+}
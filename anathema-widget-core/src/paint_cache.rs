@@ -0,0 +1,100 @@
+// -----------------------------------------------------------------------------
+//   - Paint cache -
+//   Widgets with the `cache-paint` attribute paint their subtree into an
+//   offscreen buffer once, and copy the cells onto the screen on the
+//   following frames.
+//
+//   The cache is painted again when a node in the subtree changes
+//   or consumes an event, or when the widget moves, resizes or is clipped
+//   differently.
+//   Overlays and the cursor are not part of the cache.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anathema_render::{Size, Style};
+use anathema_values::NodeId;
+
+use crate::{Pos, Region};
+
+thread_local! {
+    // The widgets with a paint cache, and whether the cache is outdated
+    static DIRTY: RefCell<HashMap<NodeId, bool>> = RefCell::new(HashMap::new());
+}
+
+/// Mark the paint cache of the node and its ancestors as outdated.
+pub(crate) fn invalidate(node_id: &[usize]) {
+    DIRTY.with_borrow_mut(|dirty| {
+        dirty
+            .iter_mut()
+            .filter(|(id, _)| id.contains(node_id))
+            .for_each(|(_, dirty)| *dirty = true);
+    });
+}
+
+/// Returns true if the cache of the node is outdated or unknown,
+/// and tracks the node as up to date from here on.
+pub(crate) fn take_dirty(node_id: &NodeId) -> bool {
+    DIRTY.with_borrow_mut(|dirty| dirty.insert(node_id.clone(), false).unwrap_or(true))
+}
+
+/// Stop tracking a node, e.g when the node is removed
+pub(crate) fn forget(node_id: &NodeId) {
+    DIRTY.with_borrow_mut(|dirty| dirty.remove(node_id));
+}
+
+/// A cell painted by a cached subtree
+pub(crate) type CachedCell = Option<(char, Style, Option<Arc<str>>)>;
+
+/// The cells of the region of a widget, row by row
+#[derive(Debug)]
+pub(crate) struct PaintCache {
+    pos: Pos,
+    size: Size,
+    screen: Size,
+    clip: Option<(Pos, Pos)>,
+    pub(crate) cells: Vec<CachedCell>,
+}
+
+impl PaintCache {
+    pub(crate) fn new(pos: Pos, size: Size, screen: Size, clip: Option<&Region>) -> Self {
+        Self {
+            pos,
+            size,
+            screen,
+            clip: clip.map(|clip| (clip.from, clip.to)),
+            cells: vec![],
+        }
+    }
+
+    /// Returns true if the cache was painted in the same place
+    pub(crate) fn matches(&self, other: &Self) -> bool {
+        self.pos == other.pos
+            && self.size == other.size
+            && self.screen == other.screen
+            && self.clip == other.clip
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn invalidate_ancestors() {
+        let parent = NodeId::from(vec![0, 1]);
+        let sibling = NodeId::from(vec![0, 2]);
+        assert!(take_dirty(&parent));
+        assert!(take_dirty(&sibling));
+        assert!(!take_dirty(&parent));
+
+        invalidate(&[0, 1, 4, 0]);
+        assert!(take_dirty(&parent));
+        assert!(!take_dirty(&parent));
+        assert!(!take_dirty(&sibling));
+
+        forget(&parent);
+        assert!(take_dirty(&parent));
+    }
+}
@@ -0,0 +1,139 @@
+// -----------------------------------------------------------------------------
+//   - Profiling -
+//   Records the time spent in layout, position and paint for every widget.
+//
+//   The spans are written as a chrome://tracing compatible JSON file,
+//   which can be opened in chrome://tracing or https://ui.perfetto.dev
+//   to see a flame view of the widget tree.
+// -----------------------------------------------------------------------------
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static SPANS: RefCell<Vec<Span>> = const { RefCell::new(Vec::new()) };
+}
+
+fn epoch() -> Instant {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    *EPOCH.get_or_init(Instant::now)
+}
+
+/// A completed span
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    /// Name of the span, e.g the widget kind
+    pub name: &'static str,
+    /// Category of the span, e.g `layout`, `position` or `paint`
+    pub category: &'static str,
+    /// Start of the span, relative to the first recorded span
+    pub start: Duration,
+    pub duration: Duration,
+}
+
+/// Records a span when dropped.
+pub struct SpanGuard {
+    name: &'static str,
+    category: &'static str,
+    start: Instant,
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        let span = Span {
+            name: self.name,
+            category: self.category,
+            start: self.start.duration_since(epoch()),
+            duration: self.start.elapsed(),
+        };
+        SPANS.with_borrow_mut(|spans| spans.push(span));
+    }
+}
+
+/// Start a new span that ends when the guard is dropped.
+pub fn span(name: &'static str, category: &'static str) -> SpanGuard {
+    // Make sure the epoch is set before the start of the first span
+    let _ = epoch();
+
+    SpanGuard {
+        name,
+        category,
+        start: Instant::now(),
+    }
+}
+
+/// Take all spans recorded so far on the current thread.
+pub fn drain_spans() -> Vec<Span> {
+    SPANS.with_borrow_mut(std::mem::take)
+}
+
+/// Write the spans as chrome://tracing JSON.
+pub fn write_chrome_trace(spans: &[Span], mut output: impl Write) -> io::Result<()> {
+    write!(output, "{{\"traceEvents\":[")?;
+    for (i, span) in spans.iter().enumerate() {
+        if i > 0 {
+            write!(output, ",")?;
+        }
+
+        write!(output, "{{\"name\":\"")?;
+        write_escaped(&mut output, span.name)?;
+        write!(output, "\",\"cat\":\"")?;
+        write_escaped(&mut output, span.category)?;
+        write!(
+            output,
+            "\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":1}}",
+            span.start.as_micros(),
+            span.duration.as_micros()
+        )?;
+    }
+    write!(output, "]}}")
+}
+
+fn write_escaped(output: &mut impl Write, s: &str) -> io::Result<()> {
+    for c in s.chars() {
+        match c {
+            '"' => write!(output, "\\\"")?,
+            '\\' => write!(output, "\\\\")?,
+            c if c.is_control() => write!(output, "\\u{:04x}", c as u32)?,
+            c => write!(output, "{c}")?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn record_nested_spans() {
+        {
+            let _outer = span("vstack", "layout");
+            let _inner = span("text", "layout");
+        }
+
+        let spans = drain_spans();
+        assert_eq!(spans.len(), 2);
+        // The inner span ends first
+        assert_eq!(spans[0].name, "text");
+        assert_eq!(spans[1].name, "vstack");
+        assert!(spans[1].start <= spans[0].start);
+        assert!(drain_spans().is_empty());
+    }
+
+    #[test]
+    fn chrome_trace() {
+        let spans = [Span {
+            name: "a \"widget\"",
+            category: "paint",
+            start: Duration::from_micros(10),
+            duration: Duration::from_micros(5),
+        }];
+
+        let mut output = vec![];
+        write_chrome_trace(&spans, &mut output).unwrap();
+        let expected = r#"{"traceEvents":[{"name":"a \"widget\"","cat":"paint","ph":"X","ts":10,"dur":5,"pid":1,"tid":1}]}"#;
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+}
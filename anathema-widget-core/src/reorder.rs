@@ -0,0 +1,137 @@
+use crate::{Event, KeyCode, MouseButton};
+
+/// Track dragging an item to a new position using the mouse.
+///
+/// Mouse events are mapped to item indices by the closure passed to
+/// [`Reorder::event`], e.g the row of a list.
+/// Once the mouse button is released the move is returned,
+/// and can be applied with `List::move_index`:
+///
+/// ```
+/// # use anathema_widget_core::{Event, KeyModifiers, MouseButton, Reorder};
+/// # let mut items = vec!["a", "b", "c"];
+/// let mut reorder = Reorder::new();
+/// // One item per row, starting from the second row
+/// let row = |_x, y: u16| (y as usize).checked_sub(1).filter(|i| *i < 3);
+///
+/// let mods = KeyModifiers::NONE;
+/// reorder.event(&Event::MouseDown(0, 1, MouseButton::Left, mods, 1), row);
+/// reorder.event(&Event::MouseDrag(0, 3, MouseButton::Left, mods), row);
+/// // Paint an insertion indicator at `reorder.target()` while dragging
+/// assert_eq!(reorder.target(), Some(2));
+///
+/// if let Some((from, to)) = reorder.event(&Event::MouseUp(0, 3, MouseButton::Left, mods), row) {
+///     let item = items.remove(from);
+///     items.insert(to, item);
+/// }
+/// assert_eq!(items, ["b", "c", "a"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct Reorder {
+    origin: Option<usize>,
+    target: Option<usize>,
+}
+
+impl Reorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The index of the item being dragged
+    pub fn dragging(&self) -> Option<usize> {
+        self.origin
+    }
+
+    /// The index the dragged item would be moved to if it was dropped
+    pub fn target(&self) -> Option<usize> {
+        self.target.filter(|_| self.origin.is_some())
+    }
+
+    /// Cancel the current drag
+    pub fn cancel(&mut self) {
+        self.origin = None;
+        self.target = None;
+    }
+
+    /// Feed an event to the tracker.
+    /// Returns the origin and target index when an item is dropped on a new index.
+    pub fn event(
+        &mut self,
+        event: &Event,
+        index_at: impl Fn(u16, u16) -> Option<usize>,
+    ) -> Option<(usize, usize)> {
+        match *event {
+            Event::MouseDown(x, y, MouseButton::Left, ..) => {
+                self.origin = index_at(x, y);
+                self.target = self.origin;
+                None
+            }
+            Event::MouseDrag(x, y, MouseButton::Left, _) => {
+                if self.origin.is_some() {
+                    if let Some(index) = index_at(x, y) {
+                        self.target = Some(index);
+                    }
+                }
+                None
+            }
+            Event::MouseUp(_, _, MouseButton::Left, _) => {
+                let origin = self.origin.take()?;
+                let target = self.target.take()?;
+                (origin != target).then_some((origin, target))
+            }
+            Event::KeyPress(KeyCode::Esc, ..) => {
+                self.cancel();
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::KeyEventState;
+    use crate::KeyModifiers;
+
+    const MODS: KeyModifiers = KeyModifiers::NONE;
+
+    fn row(_: u16, y: u16) -> Option<usize> {
+        (y < 5).then_some(y as usize)
+    }
+
+    #[test]
+    fn drag_and_drop() {
+        let mut reorder = Reorder::new();
+        reorder.event(&Event::MouseDown(0, 4, MouseButton::Left, MODS, 1), row);
+        assert_eq!(reorder.dragging(), Some(4));
+
+        reorder.event(&Event::MouseDrag(0, 1, MouseButton::Left, MODS), row);
+        // Dragging outside of the items keeps the last target
+        reorder.event(&Event::MouseDrag(0, 10, MouseButton::Left, MODS), row);
+        assert_eq!(reorder.target(), Some(1));
+
+        let dropped = reorder.event(&Event::MouseUp(0, 10, MouseButton::Left, MODS), row);
+        assert_eq!(dropped, Some((4, 1)));
+        assert!(reorder.dragging().is_none());
+    }
+
+    #[test]
+    fn drop_in_place() {
+        let mut reorder = Reorder::new();
+        reorder.event(&Event::MouseDown(0, 2, MouseButton::Left, MODS, 1), row);
+        let dropped = reorder.event(&Event::MouseUp(0, 2, MouseButton::Left, MODS), row);
+        assert!(dropped.is_none());
+    }
+
+    #[test]
+    fn cancel_drag() {
+        let mut reorder = Reorder::new();
+        reorder.event(&Event::MouseDown(0, 2, MouseButton::Left, MODS, 1), row);
+        reorder.event(&Event::MouseDrag(0, 3, MouseButton::Left, MODS), row);
+        let esc = Event::KeyPress(KeyCode::Esc, MODS, KeyEventState::empty());
+        reorder.event(&esc, row);
+        let dropped = reorder.event(&Event::MouseUp(0, 3, MouseButton::Left, MODS), row);
+        assert!(dropped.is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use anathema_render::{Attributes, Color, Style as RenderStyle};
 use anathema_values::{Context, NodeId, Value};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct WidgetStyle {
     pub(crate) fg: Value<Color>,
     pub(crate) bg: Value<Color>,
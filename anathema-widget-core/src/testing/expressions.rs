@@ -1,11 +1,11 @@
-use anathema_values::{Attributes, Path, ValueExpr};
+use anathema_values::{Attributes, Path, Symbol, ValueExpr};
 
 use crate::expressions::{
     ControlFlow, ElseExpr, Expression, IfExpr, LoopExpr, SingleNodeExpr, ViewExpr,
 };
 
 pub fn expression(
-    ident: impl Into<String>,
+    ident: impl Into<Symbol>,
     text: impl Into<Option<ValueExpr>>,
     attributes: impl IntoIterator<Item = (String, ValueExpr)>,
     children: impl Into<Vec<Expression>>,
@@ -14,7 +14,10 @@ pub fn expression(
     Expression::Node(SingleNodeExpr {
         ident: ident.into(),
         text: text.into(),
-        attributes: Attributes::from_iter(attributes),
+        attributes: attributes
+            .into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect(),
         children,
     })
 }
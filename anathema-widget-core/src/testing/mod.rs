@@ -8,7 +8,7 @@ use crate::contexts::PaintCtx;
 use crate::expressions::Expression;
 use crate::layout::Constraints;
 use crate::nodes::{NodeKind, Single};
-use crate::{Node, Nodes, Pos};
+use crate::{Event, Node, Nodes, Pos};
 
 pub mod expressions;
 pub mod nodes;
@@ -47,6 +47,11 @@ pub struct FakeTerm {
 }
 
 impl FakeTerm {
+    /// The screen painted by the widget under test
+    pub fn screen(&self) -> &Screen {
+        &self.screen
+    }
+
     pub fn from_str(s: &str) -> Self {
         let mut size = Size::ZERO;
 
@@ -97,7 +102,7 @@ impl FakeTerm {
     }
 }
 
-pub fn test_widget(expr: Expression, expected: FakeTerm) {
+pub fn test_widget(expr: Expression, expected: FakeTerm) -> FakeTerm {
     let state = TestState::new();
     let context = Context::root(&state);
     let mut node = expr.eval(&context, 0.into()).unwrap();
@@ -106,12 +111,46 @@ pub fn test_widget(expr: Expression, expected: FakeTerm) {
     test_widget_container(widget, nodes, &context, expected)
 }
 
+/// Change the widget before it's laid out and then compare the output.
+pub fn test_widget_with(
+    expr: Expression,
+    f: impl FnOnce(&mut WidgetContainer<'_>),
+    expected: FakeTerm,
+) -> FakeTerm {
+    let state = TestState::new();
+    let context = Context::root(&state);
+    let mut node = expr.eval(&context, 0.into()).unwrap();
+    let (widget, children) = node.single();
+    f(widget);
+
+    test_widget_container(widget, children, &context, expected)
+}
+
+/// Lay out the widget, pass it the events and then compare the output.
+/// The events are passed to the widget itself, not to its children.
+pub fn test_widget_events(expr: Expression, events: &[Event], expected: FakeTerm) -> FakeTerm {
+    let state = TestState::new();
+    let context = Context::root(&state);
+    let mut node = expr.eval(&context, 0.into()).unwrap();
+    let (widget, children) = node.single();
+
+    let constraints = Constraints::new(Some(expected.size.width), Some(expected.size.height));
+    widget.layout(children, constraints, &context).unwrap();
+    widget.position(children, Pos::ZERO);
+    for event in events {
+        widget.on_event(event);
+    }
+    children.reset_cache();
+
+    test_widget_container(widget, children, &context, expected)
+}
+
 pub fn test_widget_container<'e>(
     widget: &mut WidgetContainer<'e>,
     children: &mut Nodes<'e>,
     context: &Context<'_, 'e>,
     mut expected: FakeTerm,
-) {
+) -> FakeTerm {
     // Layout
     let constraints = Constraints::new(Some(expected.size.width), Some(expected.size.height));
     widget.layout(children, constraints, context).unwrap();
@@ -122,6 +161,8 @@ pub fn test_widget_container<'e>(
     // Paint
     let ctx = PaintCtx::new(&mut expected.screen, None);
     widget.paint(children, ctx);
+    crate::overlay::paint(&mut expected.screen);
+    expected.screen.apply_highlights();
 
     let expected_rows = expected.rows.iter();
     for (y, row) in expected_rows.enumerate() {
@@ -144,4 +185,6 @@ pub fn test_widget_container<'e>(
             }
         }
     }
+
+    expected
 }
@@ -2,12 +2,15 @@ use anathema_render::Size;
 use anathema_values::testing::TestState;
 use anathema_values::{Context, State, Value};
 
-use crate::contexts::{LayoutCtx, PositionCtx};
+use crate::contexts::{EventCtx, LayoutCtx, PositionCtx};
 use crate::error::Result;
 use crate::expressions::Expression;
 use crate::layout::{Constraints, Layout};
 use crate::nodes::{make_it_so, Node};
-use crate::{AnyWidget, Factory, FactoryContext, LayoutNodes, Nodes, Widget, WidgetFactory};
+use crate::{
+    AnyWidget, Event, EventResult, Factory, FactoryContext, LayoutNodes, Nodes, Widget,
+    WidgetFactory,
+};
 
 // -----------------------------------------------------------------------------
 //   - Layouts -
@@ -51,6 +54,24 @@ impl Widget for TestWidget {
     }
 
     fn position<'tpl>(&mut self, _children: &mut Nodes<'_>, _ctx: PositionCtx) {}
+
+    fn save_state(&self) -> Option<Box<dyn std::any::Any>> {
+        Some(Box::new(self.0.value_ref()?.clone()))
+    }
+
+    fn restore_state(&mut self, state: Box<dyn std::any::Any>) {
+        if let Ok(text) = state.downcast::<String>() {
+            self.0 = Value::Static(*text);
+        }
+    }
+
+    // Consume any key press for a character in the text
+    fn on_event(&mut self, event: &Event, _ctx: EventCtx<'_>) -> EventResult {
+        match (event.get_char(), self.0.value_ref()) {
+            (Some(c), Some(s)) if s.contains(c) => EventResult::Consumed,
+            _ => EventResult::Ignored,
+        }
+    }
 }
 
 struct TestWidgetFactory;
@@ -0,0 +1,168 @@
+use anathema_render::{Screen, Size, Style};
+use anathema_values::NodeId;
+use unicode_width::UnicodeWidthStr;
+
+use crate::contexts::PaintCtx;
+use crate::nodes::Nodes;
+use crate::{Display, LocalPos, Pos, WidgetContainer};
+
+/// The text of a widget's `tooltip` attribute, and the area of the widget.
+///
+/// ```text
+/// text [tooltip: "Save the current file"] "save"
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tooltip {
+    pub text: String,
+    /// Position of the widget
+    pub anchor_pos: Pos,
+    /// Size of the widget
+    pub anchor_size: Size,
+}
+
+impl Tooltip {
+    fn from_widget(widget: &WidgetContainer<'_>) -> Option<Self> {
+        let text = widget.tooltip()?;
+        Some(Self {
+            text: text.to_string(),
+            anchor_pos: widget.pos,
+            anchor_size: widget.size,
+        })
+    }
+
+    /// Find the tooltip of the top most widget at a screen position.
+    pub fn at_position(nodes: &mut Nodes<'_>, pos: Pos) -> Option<Self> {
+        let mut tooltip = None;
+
+        for (widget, children) in nodes.iter_mut() {
+            if !is_visible(widget) || !contains(widget, pos) {
+                continue;
+            }
+
+            // Widgets painted later are painted on top,
+            // and children are painted on top of their parents
+            if let Some(child) = Self::at_position(children, pos) {
+                tooltip = Some(child);
+            } else if let Some(own) = Self::from_widget(widget) {
+                tooltip = Some(own);
+            }
+        }
+
+        tooltip
+    }
+
+    /// Find the first tooltip of the widgets inside a view.
+    pub fn in_view(nodes: &mut Nodes<'_>, view_id: &NodeId) -> Option<Self> {
+        let mut tooltip = None;
+        nodes.with_view(view_id, |view| tooltip = first(&mut view.nodes));
+        tooltip
+    }
+
+    /// The size of the tooltip, including padding
+    pub fn size(&self) -> Size {
+        Size::new(self.text.width() + 2, 1)
+    }
+
+    /// Place the tooltip below the widget, or above it if there is no room below.
+    /// The tooltip is moved inside the screen if it would cross the edges.
+    pub fn placement(&self, screen: Size) -> Pos {
+        let size = self.size();
+        let (width, height) = (size.width as i32, size.height as i32);
+        let (screen_width, screen_height) = (screen.width as i32, screen.height as i32);
+
+        let below = self.anchor_pos.y + self.anchor_size.height as i32;
+        let above = self.anchor_pos.y - height;
+        let y = if below + height <= screen_height || above < 0 {
+            below
+        } else {
+            above
+        };
+
+        let x = self.anchor_pos.x.min(screen_width - width).max(0);
+        let y = y.min(screen_height - height).max(0);
+
+        Pos::new(x, y)
+    }
+
+    /// Paint the tooltip on top of everything else on the screen
+    pub fn paint(&self, screen: &mut Screen) {
+        let pos = self.placement(screen.size());
+        let mut ctx = PaintCtx::new(screen, None).into_sized(self.size(), pos);
+
+        let mut style = Style::new();
+        style.set_inverse(true);
+        ctx.print(&format!(" {} ", self.text), style, LocalPos::ZERO);
+    }
+}
+
+fn first(nodes: &mut Nodes<'_>) -> Option<Tooltip> {
+    nodes
+        .iter_mut()
+        .filter(|(widget, _)| is_visible(widget))
+        .find_map(|(widget, children)| Tooltip::from_widget(widget).or_else(|| first(children)))
+}
+
+pub(crate) fn is_visible(widget: &WidgetContainer<'_>) -> bool {
+    matches!(widget.display.value_or_default(), Display::Show)
+}
+
+pub(crate) fn contains(widget: &WidgetContainer<'_>, pos: Pos) -> bool {
+    let from = widget.pos;
+    let to = Pos::new(
+        from.x + widget.size.width as i32,
+        from.y + widget.size.height as i32,
+    );
+    pos.x >= from.x && pos.x < to.x && pos.y >= from.y && pos.y < to.y
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::{Attributes, ScreenPos};
+
+    use super::*;
+
+    fn anchored(text: &str, x: i32, y: i32, width: usize) -> Tooltip {
+        Tooltip {
+            text: text.into(),
+            anchor_pos: Pos::new(x, y),
+            anchor_size: Size::new(width, 1),
+        }
+    }
+
+    #[test]
+    fn place_below() {
+        let tooltip = anchored("hello", 2, 3, 4);
+        assert_eq!(tooltip.placement(Size::new(20, 10)), Pos::new(2, 4));
+    }
+
+    #[test]
+    fn place_above_at_bottom_edge() {
+        let tooltip = anchored("hello", 2, 9, 4);
+        assert_eq!(tooltip.placement(Size::new(20, 10)), Pos::new(2, 8));
+    }
+
+    #[test]
+    fn avoid_right_edge() {
+        let tooltip = anchored("hello", 17, 0, 3);
+        assert_eq!(tooltip.placement(Size::new(20, 10)), Pos::new(13, 1));
+
+        // Wider than the screen
+        let tooltip = anchored("a very long tooltip", 5, 0, 3);
+        assert_eq!(tooltip.placement(Size::new(10, 10)), Pos::new(0, 1));
+    }
+
+    #[test]
+    fn paint_tooltip() {
+        let mut screen = Screen::new((10usize, 3usize));
+        anchored("hi", 8, 0, 2).paint(&mut screen);
+
+        let row = (6..10)
+            .map(|x| screen.get(ScreenPos::new(x, 1)).unwrap())
+            .collect::<Vec<_>>();
+        let text = row.iter().map(|(c, _)| *c).collect::<String>();
+        assert_eq!(text, " hi ");
+        assert!(row
+            .iter()
+            .all(|(_, style)| style.attributes.contains(Attributes::INVERSE)));
+    }
+}
@@ -20,7 +20,11 @@ enum ViewFactory {
 static REGISTERED_VIEWS: OnceLock<Mutex<HashMap<usize, ViewFactory>>> = OnceLock::new();
 
 thread_local! {
-    static VIEWS: RefCell<Map<NodeId, Option<u32>>> = RefCell::new(Map::new());
+    static VIEWS: RefCell<Map<NodeId, Option<u32>>> = const { RefCell::new(Map::new()) };
+    // Widgets with the `disabled` attribute
+    static DISABLED: RefCell<Map<NodeId, ()>> = const { RefCell::new(Map::new()) };
+    // The view receiving the events
+    static FOCUSED: RefCell<Option<NodeId>> = const { RefCell::new(None) };
 }
 
 pub struct RegisteredViews;
@@ -88,6 +92,10 @@ impl Views {
         VIEWS.with_borrow_mut(|views| views.insert(node_id, tabindex));
     }
 
+    pub(crate) fn remove(node_id: &NodeId) {
+        VIEWS.with_borrow_mut(|views| views.remove(node_id));
+    }
+
     pub(crate) fn update(node_id: &NodeId, tabindex: Option<u32>) {
         VIEWS.with_borrow_mut(|views| {
             if let Some(old_index) = views.get_mut(node_id) {
@@ -96,14 +104,58 @@ impl Views {
         });
     }
 
+    /// Returns true if the view is inside of a disabled widget,
+    /// in which case it should not be focused.
+    pub fn is_disabled(node_id: &NodeId) -> bool {
+        DISABLED.with_borrow(|disabled| {
+            disabled
+                .iter()
+                .any(|field| field.key().contains(node_id.as_slice()))
+        })
+    }
+
+    pub(crate) fn set_disabled(node_id: &NodeId, is_disabled: bool) {
+        DISABLED.with_borrow_mut(|disabled| match is_disabled {
+            true => {
+                disabled.insert(node_id.clone(), ());
+            }
+            false => {
+                disabled.remove(node_id);
+            }
+        });
+    }
+
+    /// Set the view that has focus, and receives the events.
+    /// This is done by the runtime when tabbing between the views.
+    pub fn set_focused(node_id: Option<NodeId>) {
+        FOCUSED.with_borrow_mut(|focused| *focused = node_id);
+    }
+
+    /// Returns true if the node is inside of the focused view,
+    /// or if no view has focus, in which case the events go to the root view.
+    /// Widgets taking text input only show the cursor while they have focus.
+    pub fn has_focus(node_id: &NodeId) -> bool {
+        FOCUSED.with_borrow(|focused| match focused {
+            Some(focused) => focused.contains(node_id.as_slice()),
+            None => true,
+        })
+    }
+
     #[cfg(feature = "testing")]
     pub fn test_insert(node_id: impl Into<NodeId>, tab_index: Option<u32>) {
         Self::insert(node_id.into(), tab_index)
     }
 
+    #[cfg(feature = "testing")]
+    pub fn test_disable(node_id: impl Into<NodeId>) {
+        Self::set_disabled(&node_id.into(), true)
+    }
+
     #[cfg(feature = "testing")]
     pub fn test_clear() {
         VIEWS.with_borrow_mut(|views| views.clear());
+        DISABLED.with_borrow_mut(|disabled| disabled.clear());
+        FOCUSED.with_borrow_mut(|focused| *focused = None);
     }
 }
 
@@ -2,15 +2,19 @@ use std::fmt::Debug;
 use std::ops::{Deref, DerefMut};
 
 use anathema_render::{Color, ScreenPos, Size, Style};
-use anathema_values::{Attributes, Context, NodeId, Value};
+use anathema_values::{Attributes, Context, NodeId, Symbol, Value};
 
 use super::{AnyWidget, Widget};
-use crate::contexts::{PaintCtx, PositionCtx, Unsized, WithSize};
-use crate::error::Result;
+use crate::contexts::{EventCtx, PaintCtx, PositionCtx, Unsized, WithSize};
+use crate::error::{Result, WidgetCastError};
 use crate::expressions::Expression;
+use crate::keymap::{self, Key};
 use crate::layout::Constraints;
 use crate::nodes::Nodes;
-use crate::{Display, LayoutNodes, LocalPos, Pos, Region};
+use crate::overflow;
+use crate::paint_cache::{self, PaintCache};
+use crate::views::Views;
+use crate::{Display, Event, EventResult, LayoutNodes, LocalPos, Pos, Region, Sticky};
 
 /// The `WidgetContainer` has to go through three steps before it can be displayed:
 /// * [`layout`](Self::layout)
@@ -20,11 +24,23 @@ use crate::{Display, LayoutNodes, LocalPos, Pos, Region};
 pub struct WidgetContainer<'e> {
     pub(crate) background: Value<Color>,
     pub(crate) display: Value<Display>,
+    pub(crate) tooltip: Value<String>,
+    pub(crate) sticky: Value<Sticky>,
+    pub(crate) disabled: Value<bool>,
+    pub(crate) role: Value<String>,
+    pub(crate) label: Value<String>,
+    pub(crate) shortcut: Value<String>,
+    pub(crate) action: Value<String>,
+    pub(crate) error: Value<String>,
+    pub(crate) culled: bool,
+    pub(crate) cache_paint: Value<bool>,
+    pub(crate) paint_cache: Option<Box<PaintCache>>,
     pub(crate) inner: Box<dyn AnyWidget>,
     pub pos: Pos,
     pub size: Size,
     pub expr: Option<&'e Expression>,
     pub attributes: &'e Attributes,
+    pub node_id: NodeId,
 }
 
 impl WidgetContainer<'_> {
@@ -32,27 +48,53 @@ impl WidgetContainer<'_> {
         self.inner.kind()
     }
 
+    /// Cast the widget to `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widget is not a `T`, see [`WidgetContainer::try_to`]
     pub fn to_ref<T: 'static>(&self) -> &T {
-        let kind = self.inner.kind();
-
-        match self.try_to_ref() {
-            Some(t) => t,
-            None => panic!("invalid widget type, found `{kind}`"),
+        match self.try_to() {
+            Ok(t) => t,
+            Err(err) => panic!("{err}"),
         }
     }
 
+    /// Cast the widget to `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widget is not a `T`, see [`WidgetContainer::try_to_mut`]
     pub fn to_mut<T: 'static>(&mut self) -> &mut T {
-        let kind = self.inner.kind();
-
         match self.try_to_mut() {
-            Some(t) => t,
-            None => panic!("invalid widget type, found `{kind}`"),
+            Ok(t) => t,
+            Err(err) => panic!("{err}"),
         }
     }
 
-    pub fn try_to_ref<T: 'static>(&self) -> Option<&T> {
-        let _kind = self.inner.kind();
+    /// Cast the widget to `T`, or return an error with the
+    /// expected type and the actual kind of the widget.
+    pub fn try_to<T: 'static>(&self) -> std::result::Result<&T, WidgetCastError> {
+        let err = self.cast_error::<T>();
+        self.try_to_ref().ok_or(err)
+    }
+
+    /// Cast the widget to `T`, or return an error with the
+    /// expected type and the actual kind of the widget.
+    pub fn try_to_mut<T: 'static>(&mut self) -> std::result::Result<&mut T, WidgetCastError> {
+        let err = self.cast_error::<T>();
+        self.downcast_mut().ok_or(err)
+    }
+
+    fn cast_error<T: 'static>(&self) -> WidgetCastError {
+        WidgetCastError {
+            expected: std::any::type_name::<T>(),
+            found: self.inner.kind(),
+            node_id: self.node_id.clone(),
+        }
+    }
 
+    pub fn try_to_ref<T: 'static>(&self) -> Option<&T> {
         let any = self
             .inner
             .deref()
@@ -63,7 +105,7 @@ impl WidgetContainer<'_> {
         any.deref().as_any_ref().downcast_ref::<T>()
     }
 
-    pub fn try_to_mut<T: 'static>(&mut self) -> Option<&mut T> {
+    fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
         let any = self
             .inner
             .deref_mut()
@@ -74,6 +116,70 @@ impl WidgetContainer<'_> {
         any.deref_mut().as_any_mut().downcast_mut::<T>()
     }
 
+    /// The text of the `tooltip` attribute
+    pub fn tooltip(&self) -> Option<&str> {
+        self.tooltip.value_ref().map(String::as_str)
+    }
+
+    /// What the widget is to assistive tooling, e.g `button` or `list`,
+    /// from the `role` attribute
+    pub fn role(&self) -> Option<&str> {
+        self.role.value_ref().map(String::as_str)
+    }
+
+    /// The name of the widget for assistive tooling, e.g `Save`,
+    /// from the `label` attribute
+    pub fn label(&self) -> Option<&str> {
+        self.label.value_ref().map(String::as_str)
+    }
+
+    /// The edge of a scrolling viewport the widget sticks to, see [`Sticky`]
+    pub fn sticky(&self) -> Option<Sticky> {
+        self.sticky.value()
+    }
+
+    /// Returns true if the `disabled` attribute is set.
+    /// A disabled widget is painted dimmed, and neither the widget nor its children
+    /// receive events. Views inside of it are skipped by the tab index.
+    pub fn is_disabled(&self) -> bool {
+        self.disabled.value_or_default()
+    }
+
+    // Keep the views inside of a disabled widget from being focused
+    pub(crate) fn track_disabled(&self) {
+        Views::set_disabled(&self.node_id, self.is_disabled());
+    }
+
+    /// The validation error of the field the widget is bound to with the `field` attribute.
+    /// A widget with an error is painted red.
+    /// See [`FactoryContext::field_error`](crate::FactoryContext::field_error).
+    pub fn error(&self) -> Option<&str> {
+        self.error.value_ref().map(String::as_str)
+    }
+
+    /// The key bound to the `action` of the widget, from the `key` attribute.
+    /// Returns `None` if the key is invalid.
+    pub fn shortcut(&self) -> Option<Key> {
+        self.shortcut.value_ref().and_then(|key| Key::parse(key))
+    }
+
+    // Bind the shortcut while the widget is shown and enabled
+    pub(crate) fn track_shortcut(&self) {
+        let shown = matches!(self.display.value_or_default(), Display::Show);
+        let shortcut = self
+            .shortcut()
+            .zip(self.action.value_ref())
+            .filter(|_| shown && !self.is_disabled())
+            .map(|(key, action)| (key, Symbol::new(action)));
+        keymap::register_shortcut(&self.node_id, shortcut);
+    }
+
+    /// Returns true if the widget was outside of the screen, or the clipping region
+    /// of its ancestors, the last time it was painted, and the paint was skipped.
+    pub fn is_culled(&self) -> bool {
+        self.culled
+    }
+
     pub fn pos(&self) -> Pos {
         self.pos
     }
@@ -108,8 +214,12 @@ impl WidgetContainer<'_> {
         match self.display.value_or_default() {
             Display::Exclude => self.size = Size::ZERO,
             _ => {
+                #[cfg(feature = "profile")]
+                let _span = crate::profile::span(self.kind(), "layout");
+
                 let mut nodes = LayoutNodes::new(children, constraints, data);
                 let size = self.inner.layout(&mut nodes)?;
+                overflow::check_layout(self.kind(), &self.node_id, size, constraints);
 
                 // TODO: we should compare the new size with the old size
                 //       to determine if the layout needs to propagate outwards
@@ -122,7 +232,19 @@ impl WidgetContainer<'_> {
         Ok(self.size)
     }
 
+    /// The number of rows from the top of the widget to its first line of text.
+    /// See [`Widget::baseline`].
+    pub fn baseline(&self, children: &mut Nodes<'_>) -> Option<usize> {
+        match self.display.value_or_default() {
+            Display::Exclude => None,
+            _ => self.inner.baseline(children),
+        }
+    }
+
     pub fn position(&mut self, children: &mut Nodes<'_>, pos: Pos) {
+        #[cfg(feature = "profile")]
+        let _span = crate::profile::span(self.kind(), "position");
+
         self.pos = pos;
 
         let pos = Pos::new(self.pos.x, self.pos.y);
@@ -136,14 +258,55 @@ impl WidgetContainer<'_> {
             return;
         }
 
+        #[cfg(feature = "profile")]
+        let _span = crate::profile::span(self.kind(), "paint");
+
+        let mut ctx = ctx.into_sized(self.size, self.pos);
+
+        // Skip the widget and its children if none of it would show
+        self.culled = !ctx.is_visible();
+        if self.culled {
+            return;
+        }
+
+        if !self.cache_paint.value_or_default() {
+            self.paint_cache = None;
+            return self.paint_widget(children, &mut ctx);
+        }
+
+        let outdated = paint_cache::take_dirty(&self.node_id);
+        let cache = match self.paint_cache.take() {
+            Some(cache) if !outdated && cache.matches(&ctx.paint_cache()) => cache,
+            _ => Box::new(ctx.capture(|ctx| self.paint_widget(children, ctx))),
+        };
+        ctx.blit(&cache);
+        self.paint_cache = Some(cache);
+    }
+
+    fn paint_widget(&mut self, children: &mut Nodes<'_>, ctx: &mut PaintCtx<'_, WithSize>) {
         // Paint the background without the padding,
         // using the outer size and current pos.
-        let mut ctx = ctx.into_sized(self.size, self.pos);
-        self.paint_background(&mut ctx);
+        self.paint_background(ctx);
 
         let pos = Pos::new(self.pos.x, self.pos.y);
         ctx.update(self.size, pos);
-        self.inner.paint(children, ctx);
+
+        overflow::enter_paint(self.kind(), &self.node_id);
+        self.inner
+            .paint(children, ctx.to_unsized().into_sized(self.size, pos));
+        overflow::exit_paint();
+
+        if self.is_disabled() {
+            ctx.restyle(|style| style.set_dim(true));
+        }
+
+        if self.error().is_some() {
+            ctx.restyle(|style| style.set_fg(Color::Red));
+        }
+
+        if let Some(color) = overflow::highlight(&self.node_id) {
+            ctx.set_background(color);
+        }
     }
 
     fn paint_background(&self, ctx: &mut PaintCtx<'_, WithSize>) -> Option<()> {
@@ -165,6 +328,42 @@ impl WidgetContainer<'_> {
     pub fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
         self.background.resolve(context, node_id);
         self.display.resolve(context, node_id);
+        self.tooltip.resolve(context, node_id);
+        self.sticky.resolve(context, node_id);
+        self.disabled.resolve(context, node_id);
+        self.role.resolve(context, node_id);
+        self.label.resolve(context, node_id);
+        self.shortcut.resolve(context, node_id);
+        self.action.resolve(context, node_id);
+        self.error.resolve(context, node_id);
+        self.track_shortcut();
+        self.track_disabled();
+        self.cache_paint.resolve(context, node_id);
         self.inner.update(context, node_id);
     }
+
+    /// Let the widget handle an event.
+    /// Widgets that are not shown, or are disabled, never consume events.
+    pub fn on_event(&mut self, event: &Event) -> EventResult {
+        if !matches!(self.display.value_or_default(), Display::Show) || self.is_disabled() {
+            return EventResult::Ignored;
+        }
+
+        let ctx = EventCtx::new(&self.node_id, self.pos, self.size);
+        let result = self.inner.on_event(event, ctx);
+
+        // The widget could look different after handling the event
+        if let EventResult::Consumed = result {
+            paint_cache::invalidate(self.node_id.as_slice());
+        }
+
+        result
+    }
+
+    pub(crate) fn on_remove(&mut self) {
+        paint_cache::forget(&self.node_id);
+        Views::set_disabled(&self.node_id, false);
+        keymap::register_shortcut(&self.node_id, None);
+        self.inner.on_remove();
+    }
 }
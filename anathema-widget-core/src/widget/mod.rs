@@ -6,10 +6,10 @@ use anathema_render::Size;
 use anathema_values::{Context, NodeId};
 
 pub use self::container::WidgetContainer;
-use super::contexts::{PaintCtx, PositionCtx, WithSize};
+use super::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
 use crate::error::Result;
 use crate::nodes::Nodes;
-use crate::LayoutNodes;
+use crate::{Event, EventResult, LayoutNodes};
 
 mod container;
 
@@ -38,6 +38,15 @@ pub trait Widget {
     /// of the children.
     fn position(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx);
 
+    /// The number of rows from the top of the widget to its first line of text.
+    /// This is used to align widgets on their text rather than their top edge,
+    /// e.g a `text` next to a `border` containing text.
+    ///
+    /// This is called after layout, and returns `None` if the widget has no text.
+    fn baseline(&self, _children: &mut Nodes<'_>) -> Option<usize> {
+        None
+    }
+
     fn paint(&mut self, children: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
         for (widget, children) in children.iter_mut() {
             let ctx = ctx.to_unsized();
@@ -47,6 +56,31 @@ pub trait Widget {
 
     /// Called when a value the widget subscribes to has changed.
     fn update(&mut self, _context: &Context<'_, '_>, _node_id: &NodeId) {}
+
+    /// Called with events routed to the view the widget belongs to,
+    /// before the view itself receives the event.
+    /// Children receive the event before their parent.
+    ///
+    /// Return [`EventResult::Consumed`] to stop the event from reaching
+    /// any other widget and the view.
+    fn on_event(&mut self, _event: &Event, _ctx: EventCtx<'_>) -> EventResult {
+        EventResult::Ignored
+    }
+
+    /// Save widget-local state, such as a scroll offset or the contents of an input,
+    /// before the node tree is rebuilt (e.g when the templates are reloaded).
+    /// See [`Nodes::save_widget_states`].
+    fn save_state(&self) -> Option<Box<dyn Any>> {
+        None
+    }
+
+    /// Restore the state saved by a widget of the same kind and identity
+    /// in the previous node tree.
+    fn restore_state(&mut self, _state: Box<dyn Any>) {}
+
+    /// Called when the widget is removed from the node tree.
+    /// Release any resources held by the widget here (file handles, processes etc.)
+    fn on_remove(&mut self) {}
 }
 
 impl Widget for Box<dyn Widget> {
@@ -62,6 +96,10 @@ impl Widget for Box<dyn Widget> {
         self.as_mut().position(children, ctx)
     }
 
+    fn baseline(&self, children: &mut Nodes<'_>) -> Option<usize> {
+        self.as_ref().baseline(children)
+    }
+
     fn paint(&mut self, children: &mut Nodes<'_>, ctx: PaintCtx<'_, WithSize>) {
         self.as_mut().paint(children, ctx)
     }
@@ -69,6 +107,22 @@ impl Widget for Box<dyn Widget> {
     fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
         self.as_mut().update(context, node_id)
     }
+
+    fn on_event(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult {
+        self.as_mut().on_event(event, ctx)
+    }
+
+    fn save_state(&self) -> Option<Box<dyn Any>> {
+        self.as_ref().save_state()
+    }
+
+    fn restore_state(&mut self, state: Box<dyn Any>) {
+        self.as_mut().restore_state(state)
+    }
+
+    fn on_remove(&mut self) {
+        self.as_mut().on_remove()
+    }
 }
 
 pub trait AnyWidget: Debug {
@@ -82,6 +136,8 @@ pub trait AnyWidget: Debug {
 
     fn position_any(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx);
 
+    fn baseline_any(&self, children: &mut Nodes<'_>) -> Option<usize>;
+
     fn paint_any<'gen: 'ctx, 'ctx>(
         &mut self,
         children: &mut Nodes<'_>,
@@ -89,6 +145,14 @@ pub trait AnyWidget: Debug {
     );
 
     fn update_any(&mut self, context: &Context<'_, '_>, node_id: &NodeId);
+
+    fn on_event_any(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult;
+
+    fn save_state_any(&self) -> Option<Box<dyn Any>>;
+
+    fn restore_state_any(&mut self, state: Box<dyn Any>);
+
+    fn on_remove_any(&mut self);
 }
 
 impl Widget for Box<dyn AnyWidget> {
@@ -104,6 +168,10 @@ impl Widget for Box<dyn AnyWidget> {
         self.deref_mut().position_any(children, ctx)
     }
 
+    fn baseline(&self, children: &mut Nodes<'_>) -> Option<usize> {
+        self.deref().baseline_any(children)
+    }
+
     fn paint(&mut self, children: &mut Nodes<'_>, ctx: PaintCtx<'_, WithSize>) {
         self.deref_mut().paint_any(children, ctx)
     }
@@ -111,6 +179,22 @@ impl Widget for Box<dyn AnyWidget> {
     fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
         self.deref_mut().update_any(context, node_id)
     }
+
+    fn on_event(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult {
+        self.deref_mut().on_event_any(event, ctx)
+    }
+
+    fn save_state(&self) -> Option<Box<dyn Any>> {
+        self.deref().save_state_any()
+    }
+
+    fn restore_state(&mut self, state: Box<dyn Any>) {
+        self.deref_mut().restore_state_any(state)
+    }
+
+    fn on_remove(&mut self) {
+        self.deref_mut().on_remove_any()
+    }
 }
 
 impl<T: Debug + Widget + 'static> AnyWidget for T {
@@ -134,6 +218,10 @@ impl<T: Debug + Widget + 'static> AnyWidget for T {
         self.position(children, ctx)
     }
 
+    fn baseline_any(&self, children: &mut Nodes<'_>) -> Option<usize> {
+        self.baseline(children)
+    }
+
     fn paint_any<'gen: 'ctx, 'ctx>(
         &mut self,
         children: &mut Nodes<'_>,
@@ -145,4 +233,20 @@ impl<T: Debug + Widget + 'static> AnyWidget for T {
     fn update_any(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
         self.update(context, node_id)
     }
+
+    fn on_event_any(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult {
+        self.on_event(event, ctx)
+    }
+
+    fn save_state_any(&self) -> Option<Box<dyn Any>> {
+        self.save_state()
+    }
+
+    fn restore_state_any(&mut self, state: Box<dyn Any>) {
+        self.restore_state(state)
+    }
+
+    fn on_remove_any(&mut self) {
+        self.on_remove()
+    }
 }
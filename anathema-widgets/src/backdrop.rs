@@ -0,0 +1,135 @@
+use anathema_render::{Color, Size};
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::{AnyWidget, FactoryContext, LayoutNodes, Nodes, Widget, WidgetFactory};
+
+/// Dim everything painted beneath the backdrop, to set a dialog apart from
+/// the rest of the ui.
+///
+/// The backdrop fills the space available to it, and restyles the cells that
+/// were painted before it, so it goes between the ui and the dialog in a
+/// [`ZStack`](crate::ZStack):
+///
+/// ```text
+/// zstack
+///     vstack
+///         ...
+///     backdrop [tint: "dark_grey"]
+///     alignment
+///         border
+///             text "Quit without saving?"
+/// ```
+///
+/// Attributes:
+/// * `dim`: dim the cells beneath the backdrop, defaults to `true`
+/// * `foreground`: replace the foreground color of the cells
+/// * `tint`: replace the background color of the cells.
+///   Unlike `background`, which covers the cells, the characters are kept
+#[derive(Debug)]
+pub struct Backdrop {
+    pub dim: Value<bool>,
+    pub foreground: Value<Color>,
+    pub tint: Value<Color>,
+}
+
+impl Backdrop {
+    /// Widget name.
+    pub const KIND: &'static str = "Backdrop";
+}
+
+impl Widget for Backdrop {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.dim.resolve(context, node_id);
+        self.foreground.resolve(context, node_id);
+        self.tint.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        Ok(Size::new(constraints.max_width, constraints.max_height))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let dim = self.dim.value_or(true);
+        let foreground = self.foreground.value();
+        let tint = self.tint.value();
+
+        ctx.restyle(|style| {
+            if dim {
+                style.set_dim(true);
+            }
+            if let Some(color) = foreground {
+                style.set_fg(color);
+            }
+            if let Some(color) = tint {
+                style.set_bg(color);
+            }
+        });
+    }
+}
+
+pub(crate) struct BackdropFactory;
+
+impl WidgetFactory for BackdropFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Backdrop {
+            dim: ctx.get("dim"),
+            foreground: ctx.get("foreground"),
+            tint: ctx.get("tint"),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::{Attributes, ScreenPos};
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use super::*;
+    use crate::testing::test_widget;
+
+    #[test]
+    fn dim_beneath() {
+        let zstack = expression(
+            "zstack",
+            None,
+            [],
+            [
+                expression("text", Some("beneath".into()), [], []),
+                expression("backdrop", None, [("tint".to_string(), "blue".into())], []),
+                expression("text", Some("top".into()), [], []),
+            ],
+        );
+
+        let term = test_widget(
+            zstack,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║topeath        ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+
+        let style = |x, y| term.screen().get(ScreenPos::new(x, y)).unwrap().1;
+        assert!(!style(0, 0).attributes.contains(Attributes::DIM));
+        assert_eq!(style(0, 0).bg, None);
+        assert!(style(3, 0).attributes.contains(Attributes::DIM));
+        assert_eq!(style(3, 0).bg, Some(Color::Blue));
+
+        // Empty cells are tinted
+        assert_eq!(term.screen().get(ScreenPos::new(5, 1)).unwrap().0, ' ');
+        assert_eq!(style(5, 1).bg, Some(Color::Blue));
+    }
+}
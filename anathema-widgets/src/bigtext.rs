@@ -0,0 +1,343 @@
+use std::fmt;
+
+use anathema_render::{Color, Size};
+use anathema_values::{
+    impl_dyn_value, Context, DynValue, Immediate, NodeId, Value, ValueExpr, ValueRef,
+};
+use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::{
+    AnyWidget, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget, WidgetFactory, WidgetStyle,
+};
+
+use crate::heatmap::blend;
+
+const GLYPH_HEIGHT: usize = 5;
+const SPACING: usize = 1;
+
+// Each glyph is five rows of pixels, where `#` is set
+#[rustfmt::skip]
+const GLYPHS: &[(char, [&str; GLYPH_HEIGHT])] = &[
+    ('A', [".#.", "#.#", "###", "#.#", "#.#"]),
+    ('B', ["##.", "#.#", "##.", "#.#", "##."]),
+    ('C', [".##", "#..", "#..", "#..", ".##"]),
+    ('D', ["##.", "#.#", "#.#", "#.#", "##."]),
+    ('E', ["###", "#..", "##.", "#..", "###"]),
+    ('F', ["###", "#..", "##.", "#..", "#.."]),
+    ('G', [".##", "#..", "#.#", "#.#", ".##"]),
+    ('H', ["#.#", "#.#", "###", "#.#", "#.#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..#", "..#", "..#", "#.#", ".#."]),
+    ('K', ["#.#", "#.#", "##.", "#.#", "#.#"]),
+    ('L', ["#..", "#..", "#..", "#..", "###"]),
+    ('M', ["#...#", "##.##", "#.#.#", "#...#", "#...#"]),
+    ('N', ["#..#", "##.#", "#.##", "#..#", "#..#"]),
+    ('O', ["###", "#.#", "#.#", "#.#", "###"]),
+    ('P', ["##.", "#.#", "##.", "#..", "#.."]),
+    ('Q', [".#.", "#.#", "#.#", "##.", ".##"]),
+    ('R', ["##.", "#.#", "##.", "#.#", "#.#"]),
+    ('S', [".##", "#..", ".#.", "..#", "##."]),
+    ('T', ["###", ".#.", ".#.", ".#.", ".#."]),
+    ('U', ["#.#", "#.#", "#.#", "#.#", "###"]),
+    ('V', ["#.#", "#.#", "#.#", "#.#", ".#."]),
+    ('W', ["#...#", "#...#", "#.#.#", "##.##", "#...#"]),
+    ('X', ["#.#", "#.#", ".#.", "#.#", "#.#"]),
+    ('Y', ["#.#", "#.#", ".#.", ".#.", ".#."]),
+    ('Z', ["###", "..#", ".#.", "#..", "###"]),
+    ('0', [".#.", "#.#", "#.#", "#.#", ".#."]),
+    ('1', [".#.", "##.", ".#.", ".#.", "###"]),
+    ('2', ["##.", "..#", ".#.", "#..", "###"]),
+    ('3', ["##.", "..#", ".#.", "..#", "##."]),
+    ('4', ["#.#", "#.#", "###", "..#", "..#"]),
+    ('5', ["###", "#..", "##.", "..#", "##."]),
+    ('6', [".##", "#..", "##.", "#.#", ".#."]),
+    ('7', ["###", "..#", ".#.", ".#.", ".#."]),
+    ('8', [".#.", "#.#", ".#.", "#.#", ".#."]),
+    ('9', [".#.", "#.#", ".##", "..#", "##."]),
+    (' ', ["..", "..", "..", "..", ".."]),
+    ('.', [".", ".", ".", ".", "#"]),
+    (',', ["..", "..", "..", ".#", "#."]),
+    (':', [".", "#", ".", "#", "."]),
+    ('!', ["#", "#", "#", ".", "#"]),
+    ('?', ["##.", "..#", ".#.", "...", ".#."]),
+    ('-', ["...", "...", "###", "...", "..."]),
+    ('\'', ["#", "#", ".", ".", "."]),
+    ('/', ["..#", "..#", ".#.", "#..", "#.."]),
+];
+
+fn glyph(c: char) -> &'static [&'static str; GLYPH_HEIGHT] {
+    let c = c.to_ascii_uppercase();
+    GLYPHS
+        .iter()
+        .find(|(g, _)| *g == c)
+        .or_else(|| GLYPHS.iter().find(|(g, _)| *g == '?'))
+        .map(|(_, rows)| rows)
+        .expect("the question mark glyph exists")
+}
+
+/// The font of a [`BigText`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Font {
+    /// Five rows of full blocks
+    #[default]
+    Block,
+    /// Three rows of half blocks
+    Small,
+}
+
+impl Font {
+    fn height(&self) -> usize {
+        match self {
+            Self::Block => GLYPH_HEIGHT,
+            Self::Small => GLYPH_HEIGHT.div_ceil(2),
+        }
+    }
+
+    // The character of a cell, given the pixels of a glyph
+    fn cell(&self, pixels: &[&str; GLYPH_HEIGHT], x: usize, y: usize) -> Option<char> {
+        let set = |y: usize| pixels.get(y).and_then(|row| row.as_bytes().get(x)) == Some(&b'#');
+        match self {
+            Self::Block => set(y).then_some('█'),
+            Self::Small => match (set(y * 2), set(y * 2 + 1)) {
+                (true, true) => Some('█'),
+                (true, false) => Some('▀'),
+                (false, true) => Some('▄'),
+                (false, false) => None,
+            },
+        }
+    }
+}
+
+impl_dyn_value!(Font);
+
+impl TryFrom<ValueRef<'_>> for Font {
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ValueRef::Str("block") => Ok(Self::Block),
+            ValueRef::Str("small") => Ok(Self::Small),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Block => write!(f, "block"),
+            Self::Small => write!(f, "small"),
+        }
+    }
+}
+
+/// Large text for headers and splash screens, drawn with block characters.
+///
+/// The font covers letters, digits and some punctuation; letters are upper case
+/// and any other character is drawn as a question mark.
+///
+/// With a `gradient` the text goes from the foreground color on the left to the
+/// gradient color on the right. Rgb colors are blended, other colors switch halfway.
+///
+/// Attributes:
+/// * `font`: `"block"` (default) or `"small"`
+/// * `gradient`: the color of the right edge of the text
+///
+/// ```text
+/// bigtext [font: "small", foreground: start, gradient: end] "hi"
+/// ```
+///
+/// ```text
+/// █ █ ▀█▀
+/// █▀█  █
+/// ▀ ▀ ▀▀▀
+/// ```
+#[derive(Debug)]
+pub struct BigText {
+    /// The text
+    pub text: Value<String>,
+    /// The font
+    pub font: Value<Font>,
+    /// The color of the right edge of the text
+    pub gradient: Value<Color>,
+    /// The style of the text
+    pub style: WidgetStyle,
+}
+
+impl BigText {
+    /// Widget name.
+    pub const KIND: &'static str = "BigText";
+
+    fn glyphs(&self) -> impl Iterator<Item = &'static [&'static str; GLYPH_HEIGHT]> + '_ {
+        self.text.str().chars().map(glyph)
+    }
+
+    fn width(&self) -> usize {
+        self.glyphs()
+            .map(|glyph| glyph[0].len() + SPACING)
+            .sum::<usize>()
+            .saturating_sub(SPACING)
+    }
+}
+
+impl Widget for BigText {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.text.resolve(context, node_id);
+        self.font.resolve(context, node_id);
+        self.gradient.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        let height = match self.text.str().is_empty() {
+            true => 0,
+            false => self.font.value_or_default().height(),
+        };
+
+        Ok(Size::new(
+            self.width().min(constraints.max_width),
+            height.min(constraints.max_height),
+        ))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let font = self.font.value_or_default();
+        let style = self.style.style();
+        let width = self.width();
+        let gradient = match (style.fg, self.gradient.value()) {
+            (Some(from), Some(to)) if width > 1 => Some((from, to)),
+            _ => None,
+        };
+
+        let mut offset = 0;
+        for pixels in self.glyphs() {
+            let glyph_width = pixels[0].len();
+            for x in 0..glyph_width {
+                let mut style = style;
+                if let Some((from, to)) = gradient {
+                    let t = (offset + x) as f64 / (width - 1) as f64;
+                    style.fg = Some(blend(from, to, t));
+                }
+
+                for y in 0..font.height() {
+                    if let Some(c) = font.cell(pixels, x, y) {
+                        ctx.put(c, style, LocalPos::new(offset + x, y));
+                    }
+                }
+            }
+            offset += glyph_width + SPACING;
+        }
+    }
+}
+
+pub(crate) struct BigTextFactory;
+
+impl WidgetFactory for BigTextFactory {
+    fn make(&self, mut ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = BigText {
+            font: ctx.get("font"),
+            gradient: ctx.get("gradient"),
+            style: ctx.style(),
+            text: ctx.text.take(),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::ScreenPos;
+    use anathema_values::{Owned, ValueExpr};
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use super::*;
+    use crate::testing::test_widget;
+
+    #[test]
+    fn block_font() {
+        test_widget(
+            expression("bigtext", Some("hi!".into()), [], []),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║█ █ ███ █      ║
+            ║█ █  █  █      ║
+            ║███  █  █      ║
+            ║█ █  █         ║
+            ║█ █ ███ █      ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn small_font() {
+        test_widget(
+            expression(
+                "bigtext",
+                Some("hi".into()),
+                [("font".to_string(), "small".into())],
+                [],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║█ █ ▀█▀        ║
+            ║█▀█  █         ║
+            ║▀ ▀ ▀▀▀        ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn gradient() {
+        let grey = |c| Color::Rgb { r: c, g: c, b: c };
+        let term = test_widget(
+            expression(
+                "bigtext",
+                Some("hi".into()),
+                [
+                    (
+                        "foreground".to_string(),
+                        ValueExpr::Owned(Owned::Color(grey(0))),
+                    ),
+                    (
+                        "gradient".to_string(),
+                        ValueExpr::Owned(Owned::Color(grey(60))),
+                    ),
+                ],
+                [],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║█ █ ███        ║
+            ║█ █  █         ║
+            ║███  █         ║
+            ║█ █  █         ║
+            ║█ █ ███        ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+
+        let fg = |x| {
+            let (_, style) = term.screen().get(ScreenPos::new(x, 0)).unwrap();
+            style.fg.unwrap()
+        };
+        assert_eq!(fg(0), grey(0));
+        assert_eq!(fg(2), grey(20));
+        assert_eq!(fg(6), grey(60));
+    }
+}
@@ -1,9 +1,7 @@
 use std::fmt::Display;
 
 use anathema_render::Size;
-use anathema_values::{
-    impl_dyn_value, Context, DynValue, Expressions, Immediate, NodeId, Value, ValueExpr, ValueRef,
-};
+use anathema_values::{Context, DynValue, Immediate, NodeId, Value, ValueExpr, ValueRef};
 use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
 use anathema_widget_core::error::Result;
 use anathema_widget_core::layout::Layout;
@@ -59,30 +57,29 @@ impl Default for Sides {
     }
 }
 
+impl Sides {
+    fn from_value<'a>(value: ValueRef<'a>, resolver: &mut Immediate<'a>) -> Self {
+        match value {
+            ValueRef::Str(s) => s.into(),
+            value => {
+                value
+                    .to_vec(resolver)
+                    .into_iter()
+                    .flatten()
+                    .fold(Sides::EMPTY, |sides, val| match val {
+                        ValueRef::Str(s) => sides | s.into(),
+                        _ => sides,
+                    })
+            }
+        }
+    }
+}
+
 impl DynValue for Sides {
     fn init_value(context: &Context<'_, '_>, node_id: &NodeId, expr: &ValueExpr) -> Value<Self> {
-        // TODO: smells like copy and past in here!
         let mut resolver = Immediate::new(context.lookup(), node_id);
         let value = expr.eval(&mut resolver);
-
-        let inner = match value {
-            ValueRef::Str(s) => s.into(),
-            ValueRef::Expressions(Expressions(values)) => {
-                let mut sides = Sides::EMPTY;
-
-                values
-                    .iter()
-                    .map(|expr| expr.eval(&mut Immediate::new(context.lookup(), node_id)))
-                    .for_each(|val| {
-                        if let ValueRef::Str(s) = val {
-                            sides |= s.into();
-                        }
-                    });
-
-                sides
-            }
-            _ => Sides::EMPTY,
-        };
+        let inner = Self::from_value(value, &mut resolver);
 
         match resolver.is_deferred() {
             true => Value::Dyn {
@@ -97,26 +94,7 @@ impl DynValue for Sides {
         if let Value::Dyn { inner, expr } = value {
             let mut resolver = Immediate::new(context.lookup(), node_id);
             let value = expr.eval(&mut resolver);
-
-            *inner = match value {
-                ValueRef::Str(s) => s.into(),
-                ValueRef::Expressions(Expressions(values)) => {
-                    let mut sides = Sides::EMPTY;
-
-                    values
-                        .iter()
-                        .map(|expr| expr.eval(&mut Immediate::new(context.lookup(), node_id)))
-                        .for_each(|val| {
-                            if let ValueRef::Str(s) = val {
-                                sides |= s.into();
-                            }
-                        });
-
-                    sides
-                }
-                _ => Sides::EMPTY,
-            }
-            .into();
+            *inner = Some(Self::from_value(value, &mut resolver));
         }
     }
 }
@@ -202,18 +180,52 @@ pub enum BorderStyle {
     Custom(String),
 }
 
-impl_dyn_value!(BorderStyle);
+impl BorderStyle {
+    fn from_value<'a>(value: ValueRef<'a>, resolver: &mut Immediate<'a>) -> Option<Self> {
+        match value {
+            ValueRef::Str("thin") => Some(Self::Thin),
+            ValueRef::Str("thick") => Some(Self::Thick),
+            ValueRef::Str(raw) => Some(Self::Custom(raw.to_string())),
+            // A list of edges, e.g `["+", "-", "+", "|", "+", "-", "+", "|"]`
+            value => {
+                let edges = value
+                    .to_vec(resolver)?
+                    .into_iter()
+                    .map(|val| match val {
+                        ValueRef::Str(s) => s.chars().next().unwrap_or(' '),
+                        _ => ' ',
+                    })
+                    .collect();
+                Some(Self::Custom(edges))
+            }
+        }
+    }
+}
 
-impl TryFrom<ValueRef<'_>> for BorderStyle {
-    type Error = ();
+impl DynValue for BorderStyle {
+    fn init_value(context: &Context<'_, '_>, node_id: &NodeId, expr: &ValueExpr) -> Value<Self> {
+        let mut resolver = Immediate::new(context.lookup(), node_id);
+        let value = expr.eval(&mut resolver);
+        let inner = Self::from_value(value, &mut resolver);
 
-    fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
-        Ok(match value {
-            ValueRef::Str("thin") => Self::Thin,
-            ValueRef::Str("thick") => Self::Thick,
-            ValueRef::Str(raw) => Self::Custom(raw.to_string()),
-            _ => Self::default(),
-        })
+        match resolver.is_deferred() {
+            true => Value::Dyn {
+                inner,
+                expr: expr.clone(),
+            },
+            false => match inner {
+                Some(val) => Value::Static(val),
+                None => Value::Empty,
+            },
+        }
+    }
+
+    fn resolve(value: &mut Value<Self>, context: &Context<'_, '_>, node_id: &NodeId) {
+        if let Value::Dyn { inner, expr } = value {
+            let mut resolver = Immediate::new(context.lookup(), node_id);
+            let value = expr.eval(&mut resolver);
+            *inner = Self::from_value(value, &mut resolver);
+        }
     }
 }
 
@@ -389,6 +401,12 @@ impl Widget for Border {
         child.position(children, ctx.pos);
     }
 
+    fn baseline(&self, children: &mut Nodes<'_>) -> Option<usize> {
+        let (child, children) = children.first_mut()?;
+        let top = self.sides.value_or_default().contains(Sides::TOP) as usize;
+        Some(child.baseline(children)? + top)
+    }
+
     fn paint(&mut self, children: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
         // Draw the child
         if let Some((child, children)) = children.first_mut() {
@@ -510,6 +528,7 @@ impl WidgetFactory for BorderFactory {
 
 #[cfg(test)]
 mod test {
+    use anathema_values::testing::list;
     use anathema_widget_core::expressions::Expression;
     use anathema_widget_core::testing::{expression, FakeTerm};
 
@@ -605,6 +624,36 @@ mod test {
         );
     }
 
+    #[test]
+    fn custom_border_from_list() {
+        let edges = ["0", "1", "2", "3", "4", "5", "6", "7"].map(String::from);
+        let attribs = vec![
+            ("border-style".to_string(), *list(edges)),
+            ("width".to_string(), 5.into()),
+            ("height".to_string(), 4.into()),
+            (
+                "sides".to_string(),
+                *list(["top", "bottom"].map(String::from)),
+            ),
+        ];
+
+        test_widget(
+            expression("border", None, attribs, []),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══════╗
+            ║11111               ║
+            ║                    ║
+            ║                    ║
+            ║55555               ║
+            ║                    ║
+            ║                    ║
+            ╚════════════════════╝
+            "#,
+            ),
+        );
+    }
+
     #[test]
     fn border_top() {
         test_widget(
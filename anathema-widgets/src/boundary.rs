@@ -0,0 +1,172 @@
+use anathema_render::Size;
+use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::nodes::Nodes;
+use anathema_widget_core::{
+    AnyWidget, FactoryContext, LayoutNodes, Widget, WidgetContainer, WidgetFactory,
+};
+
+/// An error boundary.
+///
+/// The first child is the content and the second child is the fallback.
+/// If the content fails to build or to lay out, the fallback is shown
+/// in its place and the rest of the application keeps running.
+///
+/// ```text
+/// boundary
+///     border
+///         @chart
+///     text "The chart could not be shown"
+/// ```
+///
+/// Once the content has failed the fallback is shown until the boundary
+/// itself is removed, e.g by an `if` around it.
+#[derive(Debug, Default)]
+pub struct Boundary {
+    error: Option<String>,
+    // The content node was created before it failed,
+    // and has to be skipped to reach the fallback
+    content_built: bool,
+}
+
+impl Boundary {
+    pub const KIND: &'static str = "Boundary";
+
+    /// The error of the content, if the content failed
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    // The content, or the fallback if the content failed
+    fn child<'a, 'e>(
+        &self,
+        children: &'a mut Nodes<'e>,
+    ) -> Option<(&'a mut WidgetContainer<'e>, &'a mut Nodes<'e>)> {
+        match self.error {
+            None => children.first_mut(),
+            Some(_) => children.iter_mut().last(),
+        }
+    }
+}
+
+impl Widget for Boundary {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        let mut size = Size::ZERO;
+
+        if self.error.is_none() {
+            let mut built = false;
+            let res = nodes.next(|mut node| {
+                built = true;
+                size = node.layout(constraints)?;
+                Ok(())
+            });
+
+            match res {
+                Ok(()) => return Ok(size),
+                Err(err) => {
+                    self.error = Some(err.to_string());
+                    self.content_built = built;
+                }
+            }
+        } else if self.content_built {
+            // The content already failed, so any error is ignored
+            let _ = nodes.next(|_| Ok(()));
+        }
+
+        nodes.next(|mut node| {
+            size = node.layout(constraints)?;
+            Ok(())
+        })?;
+
+        Ok(size)
+    }
+
+    fn position(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx) {
+        if let Some((widget, children)) = self.child(children) {
+            widget.position(children, ctx.pos);
+        }
+    }
+
+    fn paint(&mut self, children: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        if let Some((widget, children)) = self.child(children) {
+            widget.paint(children, ctx.to_unsized());
+        }
+    }
+}
+
+pub(crate) struct BoundaryFactory;
+
+impl WidgetFactory for BoundaryFactory {
+    fn make(&self, _: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        Ok(Box::new(Boundary::default()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_values::ValueExpr;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use crate::testing::test_widget;
+
+    fn text(s: &str) -> anathema_widget_core::expressions::Expression {
+        expression("text", ValueExpr::String(s.into()), [], [])
+    }
+
+    #[test]
+    fn show_content() {
+        let boundary = expression("boundary", None, [], [text("content"), text("fallback")]);
+        test_widget(
+            boundary,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║content        ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn show_fallback() {
+        let missing = expression("not-a-widget", None, [], []);
+        let boundary = expression("boundary", None, [], [missing, text("fallback")]);
+        test_widget(
+            boundary,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║fallback       ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn show_fallback_for_nested_error() {
+        let missing = expression("not-a-widget", None, [], []);
+        let content = expression("border", None, [], [text("content"), missing]);
+        let boundary = expression("boundary", None, [], [content, text("fallback")]);
+        let vstack = expression("vstack", None, [], [boundary, text("still running")]);
+        test_widget(
+            vstack,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║fallback       ║
+            ║still running  ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use anathema_render::Size;
+use anathema_values::{Context, NodeId, Symbol, Value};
+use anathema_widget_core::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::layout::Constraints;
+use anathema_widget_core::{
+    send_action, AnyWidget, Event, EventResult, FactoryContext, LayoutNodes, LocalPos, MouseButton,
+    Nodes, Widget, WidgetFactory, WidgetStyle,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::menu::MenuItem;
+
+const DEFAULT_SEPARATOR: &str = " › ";
+const ELLIPSIS: &str = "…";
+
+/// The path to the current location, where clicking a crumb runs its action.
+///
+/// The crumbs are `menuitem`s, and the last crumb is the current location.
+/// If the crumbs don't fit, the first crumbs are replaced with `…`.
+///
+/// ```text
+/// breadcrumbs [separator: " / "]
+///     menuitem [action: "nav.home"] "Home"
+///     menuitem [action: "nav.docs"] "Docs"
+///     menuitem "Widgets"
+/// ```
+///
+/// ```text
+/// Home / Docs / Widgets
+/// ```
+#[derive(Debug)]
+pub struct Breadcrumbs {
+    /// The text between the crumbs. Defaults to `" › "`
+    pub separator: Value<String>,
+    /// The style of the crumbs, the last crumb is bold
+    pub style: WidgetStyle,
+    crumbs: Vec<(String, Option<Symbol>)>,
+    // The number of crumbs replaced by the ellipsis
+    hidden: usize,
+}
+
+impl Breadcrumbs {
+    /// Widget name.
+    pub const KIND: &'static str = "Breadcrumbs";
+
+    fn separator(&self) -> &str {
+        self.separator
+            .value_ref()
+            .map_or(DEFAULT_SEPARATOR, String::as_str)
+    }
+
+    // The offset, width and index of each visible crumb
+    fn visible(&self) -> Vec<(usize, usize, usize)> {
+        let separator = self.separator().width();
+        let mut x = match self.hidden {
+            0 => 0,
+            _ => ELLIPSIS.width() + separator,
+        };
+
+        let mut visible = vec![];
+        for (index, (label, _)) in self.crumbs.iter().enumerate().skip(self.hidden) {
+            visible.push((x, label.width(), index));
+            x += label.width() + separator;
+        }
+        visible
+    }
+
+    // The width of the visible crumbs
+    fn width(&self) -> usize {
+        let separator = self.separator().width();
+        self.visible()
+            .last()
+            .map_or(0, |&(x, width, _)| x + width)
+            .max(match self.hidden {
+                0 => 0,
+                _ => ELLIPSIS.width() + separator,
+            })
+    }
+}
+
+impl Widget for Breadcrumbs {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.separator.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        nodes.for_each(|mut node| {
+            node.layout(Constraints::ZERO)?;
+            Ok(())
+        })?;
+
+        self.crumbs = nodes
+            .filter(|_| true)
+            .filter_map(|node| node.try_to_ref::<MenuItem>().map(MenuItem::crumb))
+            .collect();
+
+        // Hide crumbs from the start until the rest fits, always keeping the last one
+        let max_width = nodes.constraints.max_width;
+        self.hidden = 0;
+        while self.hidden + 1 < self.crumbs.len() && self.width() > max_width {
+            self.hidden += 1;
+        }
+
+        let height = match self.crumbs.is_empty() {
+            true => 0,
+            false => nodes.constraints.max_height.min(1),
+        };
+        Ok(Size::new(self.width().min(max_width), height))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let style = self.style.style();
+        let separator = self.separator();
+        let last = self.crumbs.len().saturating_sub(1);
+
+        if self.hidden > 0 {
+            let pos = ctx.print(ELLIPSIS, style, LocalPos::ZERO);
+            if let Some(pos) = pos {
+                ctx.print(separator, style, pos);
+            }
+        }
+
+        for (x, _, index) in self.visible() {
+            let mut style = style;
+            style.set_bold(index == last);
+            let (label, _) = &self.crumbs[index];
+
+            let pos = ctx.print(label, style, LocalPos::new(x, 0));
+            if let (Some(pos), false) = (pos, index == last) {
+                ctx.print(separator, self.style.style(), pos);
+            }
+        }
+    }
+
+    fn on_event(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult {
+        let Event::MouseDown(x, y, MouseButton::Left, ..) = *event else {
+            return EventResult::Ignored;
+        };
+        let Some(pos) = ctx.local_pos(x, y) else {
+            return EventResult::Ignored;
+        };
+
+        let action = self
+            .visible()
+            .into_iter()
+            .find(|&(x, width, _)| (x..x + width).contains(&pos.x))
+            .and_then(|(_, _, index)| self.crumbs[index].1);
+
+        match action {
+            Some(action) => {
+                send_action(action);
+                EventResult::Consumed
+            }
+            None => EventResult::Ignored,
+        }
+    }
+}
+
+pub(crate) struct BreadcrumbsFactory;
+
+impl WidgetFactory for BreadcrumbsFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Breadcrumbs {
+            separator: ctx.get("separator"),
+            style: ctx.style(),
+            crumbs: vec![],
+            hidden: 0,
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::expressions::Expression;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+    use anathema_widget_core::{drain_actions, KeyModifiers};
+
+    use super::*;
+    use crate::testing::{test_widget, test_widget_events};
+
+    fn breadcrumbs() -> Expression {
+        let crumb = |label: &str, action: Option<&str>| {
+            let attributes = action.map(|action| ("action".to_string(), action.into()));
+            expression("menuitem", Some(label.into()), attributes, [])
+        };
+
+        expression(
+            "breadcrumbs",
+            None,
+            [],
+            [
+                crumb("Home", Some("nav.home")),
+                crumb("Docs", Some("nav.docs")),
+                crumb("Widgets", None),
+            ],
+        )
+    }
+
+    #[test]
+    fn crumbs() {
+        test_widget(
+            breadcrumbs(),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══════════╗
+            ║Home › Docs › Widgets   ║
+            ╚════════════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn hide_crumbs_that_do_not_fit() {
+        test_widget(
+            breadcrumbs(),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═══╗
+            ║… › Widgets      ║
+            ╚═════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn click_crumb() {
+        let click = |x| Event::MouseDown(x, 0, MouseButton::Left, KeyModifiers::NONE, 1);
+        test_widget_events(
+            breadcrumbs(),
+            // The separator, the current location and a crumb
+            &[click(5), click(16), click(8)],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══════════╗
+            ║Home › Docs › Widgets   ║
+            ╚════════════════════════╝
+            "#,
+            ),
+        );
+        assert_eq!(drain_actions(), vec![Symbol::new("nav.docs")]);
+    }
+}
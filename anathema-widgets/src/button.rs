@@ -0,0 +1,237 @@
+use anathema_render::{Attributes, Color, Size, Style};
+use anathema_values::{hover, Context, NodeId, Value};
+use anathema_widget_core::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::{
+    send_action, AnyWidget, Event, EventResult, FactoryContext, KeyCode, LayoutNodes, LocalPos,
+    MouseButton, Nodes, Widget, WidgetFactory, WidgetStyle,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// The state a [`Button`] is painted in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ButtonState {
+    Normal,
+    Focused,
+    Hovered,
+    Pressed,
+}
+
+/// A button running its `action` when pressed:
+/// by clicking it, or with enter or space while it's focused.
+///
+/// Pressing the button sends an [`Event::Action`] to the views.
+///
+/// The button is painted bold while focused, underlined while hovered
+/// and inverted while pressed.
+///
+/// Attributes:
+/// * `action`: the action to send
+/// * `focused`: only a focused button is pressed with enter or space
+/// * `focus-background`, `hover-background`, `pressed-background`:
+///   the background in the given state, instead of the `background`
+///
+/// ```text
+/// button [action: "save", focused: selected == 0]: "Save"
+/// ```
+///
+/// ```text
+/// [ Save ]
+/// ```
+#[derive(Debug)]
+pub struct Button {
+    /// The label of the button
+    pub label: Value<String>,
+    /// The action sent when the button is pressed
+    pub action: Value<String>,
+    /// Whether the button is focused
+    pub focused: Value<bool>,
+    /// The style of the button
+    pub style: WidgetStyle,
+    focus_background: Value<Color>,
+    hover_background: Value<Color>,
+    pressed_background: Value<Color>,
+    node_id: NodeId,
+    pressed: bool,
+}
+
+impl Button {
+    /// Widget name.
+    pub const KIND: &'static str = "Button";
+
+    /// The state the button is painted in, the pressed state taking precedence
+    /// over the hovered state, and the hovered state over the focused state.
+    pub fn state(&self) -> ButtonState {
+        if self.pressed {
+            ButtonState::Pressed
+        } else if hover::is_hovered(&self.node_id) {
+            ButtonState::Hovered
+        } else if self.focused.value_or_default() {
+            ButtonState::Focused
+        } else {
+            ButtonState::Normal
+        }
+    }
+
+    fn text(&self) -> String {
+        format!("[ {} ]", self.label.value_ref().map_or("", String::as_str))
+    }
+
+    fn press(&self) -> EventResult {
+        if let Some(action) = self.action.value_ref() {
+            send_action(action.as_str());
+        }
+        EventResult::Consumed
+    }
+
+    fn state_style(&self) -> Style {
+        let mut style = self.style.style();
+        let (attributes, background) = match self.state() {
+            ButtonState::Normal => return style,
+            ButtonState::Focused => (Attributes::BOLD, &self.focus_background),
+            ButtonState::Hovered => (Attributes::UNDERLINED, &self.hover_background),
+            ButtonState::Pressed => (Attributes::INVERSE, &self.pressed_background),
+        };
+
+        style.attributes |= attributes;
+        if let Some(color) = background.value() {
+            style.set_bg(color);
+        }
+        style
+    }
+}
+
+impl Widget for Button {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.label.resolve(context, node_id);
+        self.action.resolve(context, node_id);
+        self.focused.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+        self.focus_background.resolve(context, node_id);
+        self.hover_background.resolve(context, node_id);
+        self.pressed_background.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        Ok(Size::new(
+            self.text().width().min(constraints.max_width),
+            constraints.max_height.min(1),
+        ))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        ctx.print(&self.text(), self.state_style(), LocalPos::ZERO);
+    }
+
+    fn on_event(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult {
+        match *event {
+            Event::KeyPress(KeyCode::Enter | KeyCode::Char(' '), ..)
+                if self.focused.value_or_default() =>
+            {
+                self.press()
+            }
+            Event::MouseDown(x, y, MouseButton::Left, ..) if ctx.local_pos(x, y).is_some() => {
+                self.pressed = true;
+                EventResult::Consumed
+            }
+            // Releasing the mouse outside of the button cancels the press
+            Event::MouseUp(x, y, MouseButton::Left, ..) if self.pressed => {
+                self.pressed = false;
+                match ctx.local_pos(x, y) {
+                    Some(_) => self.press(),
+                    None => EventResult::Consumed,
+                }
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+pub(crate) struct ButtonFactory;
+
+impl WidgetFactory for ButtonFactory {
+    fn make(&self, mut ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        hover::subscribe(ctx.node_id.clone());
+
+        let widget = Button {
+            label: ctx.text.take(),
+            action: ctx.get("action"),
+            focused: ctx.get("focused"),
+            style: ctx.style(),
+            focus_background: ctx.get("focus-background"),
+            hover_background: ctx.get("hover-background"),
+            pressed_background: ctx.get("pressed-background"),
+            node_id: ctx.node_id.clone(),
+            pressed: false,
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_values::{Symbol, ValueExpr};
+    use anathema_widget_core::expressions::Expression;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+    use anathema_widget_core::{drain_actions, KeyModifiers};
+
+    use super::*;
+    use crate::testing::{key, test_widget_events};
+
+    fn button(focused: bool) -> Expression {
+        expression(
+            "button",
+            Some("Save".into()),
+            [
+                ("action".to_string(), ValueExpr::from("save")),
+                ("focused".to_string(), focused.into()),
+            ],
+            [],
+        )
+    }
+
+    fn expected() -> FakeTerm {
+        FakeTerm::from_str(
+            r#"
+            ╔═] Fake term [═╗
+            ║[ Save ]       ║
+            ╚═══════════════╝
+            "#,
+        )
+    }
+
+    #[test]
+    fn press_with_keys() {
+        test_widget_events(
+            button(true),
+            &[key(KeyCode::Enter), key(KeyCode::Char(' '))],
+            expected(),
+        );
+        assert_eq!(drain_actions(), vec![Symbol::new("save"); 2]);
+
+        // Only a focused button is pressed
+        test_widget_events(button(false), &[key(KeyCode::Enter)], expected());
+        assert!(drain_actions().is_empty());
+    }
+
+    #[test]
+    fn press_with_mouse() {
+        let down = |x| Event::MouseDown(x, 0, MouseButton::Left, KeyModifiers::NONE, 1);
+        let up = |x| Event::MouseUp(x, 0, MouseButton::Left, KeyModifiers::NONE);
+
+        test_widget_events(button(false), &[down(2), up(3)], expected());
+        assert_eq!(drain_actions(), vec![Symbol::new("save")]);
+
+        // Releasing outside of the button
+        test_widget_events(button(false), &[down(2), up(12)], expected());
+        assert!(drain_actions().is_empty());
+    }
+}
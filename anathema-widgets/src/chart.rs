@@ -0,0 +1,494 @@
+use std::fmt;
+
+use anathema_render::{Size, Style};
+use anathema_values::{
+    impl_dyn_value, Context, DynValue, Immediate, NodeId, Value, ValueExpr, ValueRef,
+};
+use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::layout::Constraints;
+use anathema_widget_core::{
+    AnyWidget, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget, WidgetFactory, WidgetStyle,
+};
+use unicode_width::UnicodeWidthStr;
+
+const DEFAULT_TICKS: usize = 3;
+const POINT: char = '•';
+const BAR: char = '█';
+const GRIDLINE: char = '┈';
+const LEGEND_MARKER: char = '■';
+
+// -----------------------------------------------------------------------------
+//   - Series -
+// -----------------------------------------------------------------------------
+/// How a [`Series`] is drawn
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum SeriesKind {
+    /// A point per value
+    #[default]
+    Line,
+    /// A bar per value, from the bottom of the chart
+    Bar,
+}
+
+impl_dyn_value!(SeriesKind);
+
+impl TryFrom<ValueRef<'_>> for SeriesKind {
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ValueRef::Str("line") => Ok(Self::Line),
+            ValueRef::Str("bar") => Ok(Self::Bar),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for SeriesKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Line => write!(f, "line"),
+            Self::Bar => write!(f, "bar"),
+        }
+    }
+}
+
+/// The values of a [`Chart`], and the label and style of the series in the legend.
+///
+/// ```text
+/// series [values: cpu, label: "CPU", kind: "bar", foreground: "red"]
+/// ```
+#[derive(Debug)]
+pub struct Series {
+    /// A list of numbers
+    pub values: Value<Vec<f64>>,
+    /// The label in the legend
+    pub label: Value<String>,
+    /// `line` (default) or `bar`
+    pub kind: Value<SeriesKind>,
+    pub style: WidgetStyle,
+}
+
+impl Series {
+    /// Widget name.
+    pub const KIND: &'static str = "Series";
+
+    fn data(&self) -> SeriesData {
+        SeriesData {
+            values: self.values.value_ref().cloned().unwrap_or_default(),
+            label: self.label.value_ref().cloned(),
+            kind: self.kind.value_or_default(),
+            style: self.style.style(),
+        }
+    }
+}
+
+impl Widget for Series {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.values.resolve(context, node_id);
+        self.label.resolve(context, node_id);
+        self.kind.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    // A series is painted by the chart
+    fn layout(&mut self, _: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        Ok(Size::ZERO)
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, _: PaintCtx<'_, WithSize>) {}
+}
+
+pub(crate) struct SeriesFactory;
+
+impl WidgetFactory for SeriesFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Series {
+            values: ctx.get("values"),
+            label: ctx.get("label"),
+            kind: ctx.get("kind"),
+            style: ctx.style(),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[derive(Debug)]
+struct SeriesData {
+    values: Vec<f64>,
+    label: Option<String>,
+    kind: SeriesKind,
+    style: Style,
+}
+
+// -----------------------------------------------------------------------------
+//   - Chart -
+// -----------------------------------------------------------------------------
+/// Plot the [`Series`] inside it, with a y axis on the left, an x axis at the bottom
+/// and a legend below the axes.
+///
+/// The y axis goes from `min` to `max`, which default to the smallest and the largest value.
+/// The x axis goes from `x-min` to `x-max`, which default to the index of the first
+/// and the last value.
+///
+/// The values of a series are spread over the width of the chart.
+///
+/// Attributes:
+/// * `min`, `max`: the range of the y axis
+/// * `x-min`, `x-max`: the range of the x axis
+/// * `ticks`: the number of labels on each axis (default: 3)
+/// * `gridlines`: draw a line across the chart at each tick of the y axis
+/// * `legend`: show the labels of the series (default: true)
+///
+/// ```text
+/// chart [min: 0, max: 100, gridlines: true]
+///     series [values: cpu, label: "CPU", foreground: "red"]
+///     series [values: memory, label: "Memory", foreground: "blue"]
+/// ```
+///
+/// ```text
+/// 100┤┈┈┈┈┈┈•┈┈┈┈
+///    │•   •  •
+///  50┤┈•┈┈┈┈┈┈┈•┈
+///    │  •
+///   0┤┈┈┈┈┈┈┈┈┈┈┈
+///    └┬────┬────┬
+///     0    5   10
+/// ■ CPU ■ Memory
+/// ```
+#[derive(Debug)]
+pub struct Chart {
+    pub min: Value<f64>,
+    pub max: Value<f64>,
+    pub x_min: Value<f64>,
+    pub x_max: Value<f64>,
+    pub ticks: Value<usize>,
+    pub gridlines: Value<bool>,
+    pub legend: Value<bool>,
+    /// The style of the axes and the labels
+    pub style: WidgetStyle,
+    series: Vec<SeriesData>,
+}
+
+impl Chart {
+    /// Widget name.
+    pub const KIND: &'static str = "Chart";
+
+    // The range of the y axis
+    fn y_range(&self) -> (f64, f64) {
+        let values = self.series.iter().flat_map(|series| &series.values);
+        let min = self
+            .min
+            .value()
+            .unwrap_or_else(|| values.clone().copied().fold(f64::INFINITY, f64::min));
+        let max = self
+            .max
+            .value()
+            .unwrap_or_else(|| values.copied().fold(f64::NEG_INFINITY, f64::max));
+
+        match (min.is_finite(), max.is_finite()) {
+            (true, true) if max > min => (min, max),
+            (true, _) => (min, min + 1.0),
+            (false, true) => (max - 1.0, max),
+            (false, false) => (0.0, 1.0),
+        }
+    }
+
+    // The range of the x axis
+    fn x_range(&self) -> (f64, f64) {
+        let len = self
+            .series
+            .iter()
+            .map(|s| s.values.len())
+            .max()
+            .unwrap_or(0);
+        let min = self.x_min.value_or(0.0);
+        let max = self
+            .x_max
+            .value_or_else(|| min + len.saturating_sub(1) as f64);
+        (min, max)
+    }
+
+    fn has_legend(&self) -> bool {
+        self.legend.value_or(true) && self.series.iter().any(|series| series.label.is_some())
+    }
+}
+
+// The offset of the i:th of `count` items, spread evenly from the start to the end of `len`
+fn spread(i: usize, count: usize, len: usize) -> usize {
+    match count {
+        0 | 1 => 0,
+        _ => ((i * (len - 1)) as f64 / (count - 1) as f64).round() as usize,
+    }
+}
+
+// The offset of each tick
+fn ticks(count: usize, len: usize) -> impl Iterator<Item = usize> {
+    let count = count.max(2).min(len);
+    (0..count).map(move |i| spread(i, count, len))
+}
+
+// The offset of a value between `min` and `max` on an axis of `len` cells
+fn scale(value: f64, (min, max): (f64, f64), len: usize) -> usize {
+    let value = value.clamp(min, max);
+    ((value - min) / (max - min) * (len - 1) as f64).round() as usize
+}
+
+// The value at an offset on an axis of `len` cells
+fn unscale(offset: usize, (min, max): (f64, f64), len: usize) -> f64 {
+    match len {
+        0 | 1 => min,
+        _ => min + (max - min) * offset as f64 / (len - 1) as f64,
+    }
+}
+
+fn format_tick(value: f64) -> String {
+    match value.fract() == 0.0 || value.abs() >= 10.0 {
+        true => format!("{value:.0}"),
+        false => format!("{value:.1}"),
+    }
+}
+
+impl Widget for Chart {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.min.resolve(context, node_id);
+        self.max.resolve(context, node_id);
+        self.x_min.resolve(context, node_id);
+        self.x_max.resolve(context, node_id);
+        self.ticks.resolve(context, node_id);
+        self.gridlines.resolve(context, node_id);
+        self.legend.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    // A chart fills the space available to it
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        nodes.for_each(|mut node| {
+            node.layout(Constraints::ZERO)?;
+            Ok(())
+        })?;
+
+        self.series = nodes
+            .filter(|_| true)
+            .filter_map(|node| node.try_to_ref::<Series>().map(Series::data))
+            .collect();
+
+        let constraints = nodes.constraints;
+        Ok(Size::new(constraints.max_width, constraints.max_height))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let Size { width, height } = ctx.local_size;
+        let style = self.style.style();
+        let ticks_count = self.ticks.value_or(DEFAULT_TICKS);
+
+        // The x axis and its labels, and the legend, are below the plot
+        let legend = self.has_legend();
+        let plot_height = height.saturating_sub(2 + legend as usize);
+        if plot_height == 0 {
+            return;
+        }
+
+        // The labels of the y axis, from the top
+        let y_range = self.y_range();
+        let y_labels = ticks(ticks_count, plot_height)
+            .map(|row| {
+                let value = unscale(plot_height - 1 - row, y_range, plot_height);
+                (row, format_tick(value))
+            })
+            .collect::<Vec<_>>();
+
+        let axis_x = y_labels.iter().map(|(_, l)| l.width()).max().unwrap_or(0);
+        let plot_width = width.saturating_sub(axis_x + 1);
+        if plot_width == 0 {
+            return;
+        }
+        let plot_x = axis_x + 1;
+
+        // Y axis and gridlines
+        for y in 0..plot_height {
+            ctx.put('│', style, LocalPos::new(axis_x, y));
+        }
+        let mut grid_style = style;
+        grid_style.set_dim(true);
+        for (row, label) in &y_labels {
+            ctx.print(label, style, LocalPos::new(axis_x - label.width(), *row));
+            ctx.put('┤', style, LocalPos::new(axis_x, *row));
+            if self.gridlines.is_true() {
+                for x in plot_x..width {
+                    ctx.put(GRIDLINE, grid_style, LocalPos::new(x, *row));
+                }
+            }
+        }
+
+        // X axis and its labels
+        ctx.put('└', style, LocalPos::new(axis_x, plot_height));
+        for x in plot_x..width {
+            ctx.put('─', style, LocalPos::new(x, plot_height));
+        }
+        let x_range = self.x_range();
+        let mut label_end = 0;
+        for col in ticks(ticks_count, plot_width) {
+            let x = plot_x + col;
+            let label = format_tick(unscale(col, x_range, plot_width));
+            let label_x = x
+                .saturating_sub(label.width() / 2)
+                .min(width.saturating_sub(label.width()));
+
+            ctx.put('┬', style, LocalPos::new(x, plot_height));
+            // Skip labels that would overlap the previous one
+            if label_x >= label_end {
+                ctx.print(&label, style, LocalPos::new(label_x, plot_height + 1));
+                label_end = label_x + label.width() + 1;
+            }
+        }
+
+        // The series, in the order they are defined
+        for series in &self.series {
+            let len = series.values.len();
+            for (i, value) in series.values.iter().enumerate() {
+                let x = plot_x + spread(i, len, plot_width);
+                let row = plot_height - 1 - scale(*value, y_range, plot_height);
+
+                match series.kind {
+                    SeriesKind::Line => drop(ctx.put(POINT, series.style, LocalPos::new(x, row))),
+                    SeriesKind::Bar => {
+                        for y in row..plot_height {
+                            ctx.put(BAR, series.style, LocalPos::new(x, y));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Legend
+        if legend {
+            let mut pos = Some(LocalPos::new(0, height - 1));
+            for series in self.series.iter().filter(|s| s.label.is_some()) {
+                let label = series.label.as_deref().unwrap_or_default();
+                pos = pos
+                    .and_then(|pos| ctx.put(LEGEND_MARKER, series.style, pos))
+                    .and_then(|pos| ctx.print(&format!(" {label} "), style, pos));
+            }
+        }
+    }
+}
+
+pub(crate) struct ChartFactory;
+
+impl WidgetFactory for ChartFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Chart {
+            min: ctx.get("min"),
+            max: ctx.get("max"),
+            x_min: ctx.get("x-min"),
+            x_max: ctx.get("x-max"),
+            ticks: ctx.get("ticks"),
+            gridlines: ctx.get("gridlines"),
+            legend: ctx.get("legend"),
+            style: ctx.style(),
+            series: vec![],
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_values::testing::{list, unum};
+    use anathema_widget_core::expressions::Expression;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use crate::testing::test_widget;
+
+    fn series(values: &[u64], label: &str, kind: &str) -> Expression {
+        expression(
+            "series",
+            None,
+            [
+                (
+                    "values".to_string(),
+                    *list(values.iter().map(|v| *unum(*v))),
+                ),
+                ("label".to_string(), label.into()),
+                ("kind".to_string(), kind.into()),
+            ],
+            [],
+        )
+    }
+
+    #[test]
+    fn axes_and_legend() {
+        let chart = expression(
+            "chart",
+            None,
+            [
+                ("max".to_string(), 100.into()),
+                ("min".to_string(), 0.into()),
+            ],
+            [series(&[0, 50, 100, 50, 25], "CPU", "line")],
+        );
+
+        test_widget(
+            chart,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║100┤     •     ║
+            ║   │           ║
+            ║ 50┤   •    •  ║
+            ║   │          •║
+            ║  0┤•          ║
+            ║   └┬────┬────┬║
+            ║    0    2    4║
+            ║■ CPU          ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn bars_and_gridlines() {
+        let chart = expression(
+            "chart",
+            None,
+            [
+                ("gridlines".to_string(), true.into()),
+                ("legend".to_string(), false.into()),
+                ("x-min".to_string(), 1.into()),
+            ],
+            [series(&[1, 3, 2], "", "bar")],
+        );
+
+        test_widget(
+            chart,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║3┤┈┈┈┈┈┈█┈┈┈┈┈┈║
+            ║2┤┈┈┈┈┈┈█┈┈┈┈┈█║
+            ║1┤█┈┈┈┈┈█┈┈┈┈┈█║
+            ║ └┬─────┬─────┬║
+            ║  1     2     3║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+}
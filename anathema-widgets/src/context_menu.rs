@@ -0,0 +1,227 @@
+use anathema_values::state::State;
+use anathema_values::{List, NodeId, Path, StateValue, Symbol, ValueRef};
+use anathema_widget_core::{Event, KeyCode, MouseButton};
+use unicode_width::UnicodeWidthStr;
+
+/// A popup menu of named actions, opened at a screen position.
+///
+/// The menu is state owned by a view, where events are passed to [`ContextMenu::event`],
+/// which returns the name of the chosen action.
+/// A right click opens the menu at the mouse position, and
+/// escape or a click outside of the menu closes it.
+///
+/// The menu is exposed to the templates as:
+/// * `open`: true if the menu is open
+/// * `x`, `y`: the position of the menu
+/// * `items`: the labels of the menu items
+/// * `selected`: the index of the selected item
+///
+/// and should be drawn on top of the other widgets:
+///
+/// ```text
+/// zstack
+///     // ... the rest of the ui
+///     if menu.open
+///         position [left: menu.x, top: menu.y]
+///             border
+///                 vstack
+///                     for item in menu.items
+///                         if loop == menu.selected
+///                             text [inverse: true] item
+///                         else
+///                             text item
+/// ```
+#[derive(Debug)]
+pub struct ContextMenu {
+    open: StateValue<bool>,
+    x: StateValue<usize>,
+    y: StateValue<usize>,
+    items: List<String>,
+    selected: StateValue<usize>,
+    actions: Vec<Symbol>,
+    width: usize,
+}
+
+impl ContextMenu {
+    /// Create a new menu from pairs of labels and action names
+    pub fn new<'a>(items: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let (labels, actions): (Vec<_>, Vec<_>) = items
+            .into_iter()
+            .map(|(label, action)| (label.to_string(), Symbol::new(action)))
+            .unzip();
+
+        Self {
+            open: StateValue::new(false),
+            x: StateValue::new(0),
+            y: StateValue::new(0),
+            width: labels.iter().map(|l| l.width()).max().unwrap_or(0),
+            items: List::new(labels),
+            selected: StateValue::new(0),
+            actions,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        *self.open
+    }
+
+    /// Open the menu with the top left corner at the screen position
+    pub fn open_at(&mut self, x: u16, y: u16) {
+        *self.x = x as usize;
+        *self.y = y as usize;
+        *self.selected = 0;
+        *self.open = true;
+    }
+
+    pub fn close(&mut self) {
+        if self.is_open() {
+            *self.open = false;
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        if index < self.actions.len() && *self.selected != index {
+            *self.selected = index;
+        }
+    }
+
+    // Is the screen position inside the menu, including the border
+    fn contains(&self, x: u16, y: u16) -> bool {
+        let (x, y) = (x as usize, y as usize);
+        let (left, top) = (*self.x, *self.y);
+        (left..left + self.width + 2).contains(&x)
+            && (top..top + self.actions.len() + 2).contains(&y)
+    }
+
+    // The item at the screen position
+    fn item_at(&self, x: u16, y: u16) -> Option<usize> {
+        if !self.contains(x, y) || x as usize == *self.x || x as usize > *self.x + self.width {
+            return None;
+        }
+
+        let index = (y as usize).checked_sub(*self.y + 1)?;
+        (index < self.actions.len()).then_some(index)
+    }
+
+    // Close the menu and return the chosen action
+    fn choose(&mut self, index: usize) -> Option<Symbol> {
+        self.close();
+        self.actions.get(index).copied()
+    }
+
+    /// Handle an event.
+    /// Returns the name of the action if an item was chosen.
+    pub fn event(&mut self, event: &Event) -> Option<Symbol> {
+        if let Event::MouseDown(x, y, MouseButton::Right, ..) = *event {
+            self.open_at(x, y);
+            return None;
+        }
+
+        if !self.is_open() {
+            return None;
+        }
+
+        match *event {
+            Event::KeyPress(KeyCode::Esc, ..) => self.close(),
+            Event::KeyPress(KeyCode::Up, ..) => self.select(self.selected.saturating_sub(1)),
+            Event::KeyPress(KeyCode::Down, ..) => self.select(*self.selected + 1),
+            Event::KeyPress(KeyCode::Enter, ..) => return self.choose(*self.selected),
+            Event::MouseMove(x, y, _) => {
+                if let Some(index) = self.item_at(x, y) {
+                    self.select(index);
+                }
+            }
+            Event::MouseDown(x, y, MouseButton::Left, ..) => match self.item_at(x, y) {
+                Some(index) => return self.choose(index),
+                // Clicking away closes the menu
+                None if !self.contains(x, y) => self.close(),
+                None => {}
+            },
+            _ => {}
+        }
+
+        None
+    }
+}
+
+impl State for ContextMenu {
+    fn state_get(&self, key: &Path, node_id: &NodeId) -> ValueRef<'_> {
+        match key {
+            Path::Key(key) => match key.as_str() {
+                "open" => self.open.get_value(node_id),
+                "x" => self.x.get_value(node_id),
+                "y" => self.y.get_value(node_id),
+                "items" => self.items.get_value(node_id),
+                "selected" => self.selected.get_value(node_id),
+                _ => ValueRef::Empty,
+            },
+            Path::Composite(lhs, rhs) => match &**lhs {
+                Path::Key(key) if key.as_str() == "items" => self.items.state_get(rhs, node_id),
+                _ => ValueRef::Empty,
+            },
+            _ => ValueRef::Empty,
+        }
+    }
+}
+
+impl<'a> From<&'a ContextMenu> for ValueRef<'a> {
+    fn from(menu: &'a ContextMenu) -> Self {
+        ValueRef::Map(menu)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::KeyModifiers;
+
+    use super::*;
+    use crate::testing::key;
+
+    fn menu() -> ContextMenu {
+        ContextMenu::new([("Copy", "copy"), ("Paste", "paste"), ("Delete", "delete")])
+    }
+
+    fn click(button: MouseButton, x: u16, y: u16) -> Event {
+        Event::MouseDown(x, y, button, KeyModifiers::NONE, 1)
+    }
+
+    #[test]
+    fn choose_with_keys() {
+        let mut menu = menu();
+        menu.open_at(0, 0);
+        menu.event(&key(KeyCode::Down));
+        menu.event(&key(KeyCode::Down));
+        menu.event(&key(KeyCode::Down));
+        menu.event(&key(KeyCode::Up));
+        assert_eq!(menu.event(&key(KeyCode::Enter)), Some("paste".into()));
+        assert!(!menu.is_open());
+    }
+
+    #[test]
+    fn choose_with_mouse() {
+        let mut menu = menu();
+        menu.event(&click(MouseButton::Right, 10, 5));
+        assert!(menu.is_open());
+
+        // The border
+        assert_eq!(menu.event(&click(MouseButton::Left, 10, 7)), None);
+        assert!(menu.is_open());
+
+        // Third item
+        let action = menu.event(&click(MouseButton::Left, 12, 8));
+        assert_eq!(action, Some("delete".into()));
+    }
+
+    #[test]
+    fn dismiss() {
+        let mut menu = menu();
+        menu.open_at(10, 5);
+        menu.event(&click(MouseButton::Left, 0, 0));
+        assert!(!menu.is_open());
+
+        menu.open_at(10, 5);
+        menu.event(&key(KeyCode::Esc));
+        assert!(!menu.is_open());
+        assert_eq!(menu.event(&key(KeyCode::Enter)), None);
+    }
+}
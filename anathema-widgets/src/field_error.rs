@@ -0,0 +1,132 @@
+use anathema_render::{Color, Size};
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::{
+    AnyWidget, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget, WidgetFactory, WidgetStyle,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// The validation error of a field, read from the `errors` map of the state.
+/// Nothing is painted, and no space is taken up, while the field has no error.
+///
+/// The error is painted red, unless the widget has a `foreground`.
+/// Any widget with the same `field` attribute is painted red as well,
+/// so the error is shown next to the offending input:
+///
+/// ```text
+/// vstack
+///     text [field: "email"] "{{ email }}"
+///     fielderror [field: "email"]
+/// ```
+///
+/// ```text
+/// not-an-email
+/// Not an email address
+/// ```
+#[derive(Debug)]
+pub struct FieldError {
+    /// The validation error
+    pub error: Value<String>,
+    /// The style of the error
+    pub style: WidgetStyle,
+}
+
+impl FieldError {
+    /// Widget name.
+    pub const KIND: &'static str = "FieldError";
+
+    fn error(&self) -> &str {
+        self.error.value_ref().map_or("", String::as_str)
+    }
+}
+
+impl Widget for FieldError {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.error.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        let size = match self.error() {
+            "" => Size::ZERO,
+            error => Size::new(
+                error.width().min(constraints.max_width),
+                constraints.max_height.min(1),
+            ),
+        };
+        Ok(size)
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let mut style = self.style.style();
+        if style.fg.is_none() {
+            style.set_fg(Color::Red);
+        }
+        ctx.print(self.error(), style, LocalPos::ZERO);
+    }
+}
+
+pub(crate) struct FieldErrorFactory;
+
+impl WidgetFactory for FieldErrorFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = FieldError {
+            error: ctx.field_error(),
+            style: ctx.style(),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::ScreenPos;
+    use anathema_values::ValueExpr;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use super::*;
+    use crate::testing::test_widget;
+
+    #[test]
+    fn field_error() {
+        let field = |name: &str| ("field".to_string(), ValueExpr::from(name));
+        let vstack = expression(
+            "vstack",
+            None,
+            [],
+            [
+                expression("text", Some("x@".into()), [field("email")], []),
+                expression("fielderror", None, [field("email")], []),
+                expression("fielderror", None, [field("name")], []),
+                expression("text", Some("end".into()), [], []),
+            ],
+        );
+
+        let term = test_widget(
+            vstack,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═══════════╗
+            ║x@                       ║
+            ║Not an email address     ║
+            ║end                      ║
+            ╚═════════════════════════╝
+            "#,
+            ),
+        );
+
+        let fg = |y| term.screen().get(ScreenPos::new(0, y)).unwrap().1.fg;
+        assert_eq!(fg(0), Some(Color::Red));
+        assert_eq!(fg(1), Some(Color::Red));
+        assert_ne!(fg(2), Some(Color::Red));
+    }
+}
@@ -0,0 +1,232 @@
+use anathema_render::{Color, Size, Style};
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::{
+    AnyWidget, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget, WidgetFactory,
+};
+
+const DEFAULT_LOW: Color = Color::Rgb { r: 0, g: 0, b: 139 };
+const DEFAULT_HIGH: Color = Color::Rgb {
+    r: 255,
+    g: 64,
+    b: 0,
+};
+
+/// Color a grid of values, from the `low` color for the smallest value
+/// to the `high` color for the largest value.
+///
+/// The grid is a list of rows, and is stretched to fill the space available.
+/// If the space is unbounded each value is a single cell.
+///
+/// Values between `low` and `high` are blended if both are rgb colors,
+/// otherwise the lower half of the values is `low` and the upper half is `high`.
+///
+/// Attributes:
+/// * `values`: a list of lists of numbers
+/// * `min`, `max`: the range of the values, defaults to the smallest and the largest value
+/// * `low`, `high`: the colors at each end of the range, defaults to dark blue and orange
+///
+/// ```text
+/// heatmap [values: load, min: 0, max: 100]
+/// ```
+#[derive(Debug)]
+pub struct Heatmap {
+    pub values: Value<Vec<Vec<f64>>>,
+    pub min: Value<f64>,
+    pub max: Value<f64>,
+    pub low: Value<Color>,
+    pub high: Value<Color>,
+}
+
+impl Heatmap {
+    /// Widget name.
+    pub const KIND: &'static str = "Heatmap";
+
+    fn values(&self) -> &[Vec<f64>] {
+        self.values.value_ref().map_or(&[], Vec::as_slice)
+    }
+
+    fn grid_size(&self) -> Size {
+        let values = self.values();
+        let width = values.iter().map(Vec::len).max().unwrap_or(0);
+        Size::new(width, values.len())
+    }
+
+    fn range(&self) -> (f64, f64) {
+        let values = self.values().iter().flatten().filter(|v| !v.is_nan());
+        let min = self
+            .min
+            .value()
+            .unwrap_or_else(|| values.clone().copied().fold(f64::INFINITY, f64::min));
+        let max = self
+            .max
+            .value()
+            .unwrap_or_else(|| values.copied().fold(f64::NEG_INFINITY, f64::max));
+        (min, max)
+    }
+
+    /// The color of a value
+    pub fn color(&self, value: f64) -> Color {
+        let (min, max) = self.range();
+        let t = match max > min {
+            true => ((value - min) / (max - min)).clamp(0.0, 1.0),
+            false => 0.0,
+        };
+
+        let low = self.low.value_or(DEFAULT_LOW);
+        let high = self.high.value_or(DEFAULT_HIGH);
+        blend(low, high, t)
+    }
+}
+
+pub(crate) fn blend(low: Color, high: Color, t: f64) -> Color {
+    let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+
+    match (low, high) {
+        (
+            Color::Rgb { r, g, b },
+            Color::Rgb {
+                r: r2,
+                g: g2,
+                b: b2,
+            },
+        ) => Color::Rgb {
+            r: channel(r, r2),
+            g: channel(g, g2),
+            b: channel(b, b2),
+        },
+        _ if t < 0.5 => low,
+        _ => high,
+    }
+}
+
+impl Widget for Heatmap {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.values.resolve(context, node_id);
+        self.min.resolve(context, node_id);
+        self.max.resolve(context, node_id);
+        self.low.resolve(context, node_id);
+        self.high.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        let grid = self.grid_size();
+
+        let width = match constraints.is_width_unbounded() {
+            true => grid.width,
+            false => constraints.max_width,
+        };
+        let height = match constraints.is_height_unbounded() {
+            true => grid.height,
+            false => constraints.max_height,
+        };
+
+        Ok(Size::new(width, height))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let Size { width, height } = ctx.local_size;
+        let grid = self.grid_size();
+        if grid.width == 0 || grid.height == 0 {
+            return;
+        }
+
+        for y in 0..height {
+            let row = &self.values()[y * grid.height / height];
+            for x in 0..width {
+                let Some(value) = row.get(x * grid.width / width).filter(|v| !v.is_nan()) else {
+                    continue;
+                };
+
+                let mut style = Style::new();
+                style.set_bg(self.color(*value));
+                ctx.put(' ', style, LocalPos::new(x, y));
+            }
+        }
+    }
+}
+
+pub(crate) struct HeatmapFactory;
+
+impl WidgetFactory for HeatmapFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Heatmap {
+            values: ctx.get("values"),
+            min: ctx.get("min"),
+            max: ctx.get("max"),
+            low: ctx.get("low"),
+            high: ctx.get("high"),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::ScreenPos;
+    use anathema_values::testing::{list, unum};
+    use anathema_values::{Owned, ValueExpr};
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use super::*;
+    use crate::testing::test_widget;
+
+    fn grey(c: u8) -> Color {
+        Color::Rgb { r: c, g: c, b: c }
+    }
+
+    #[test]
+    fn stretch_grid_to_fill() {
+        let row = |values: [u64; 2]| *list(values.map(|v| *unum(v)));
+        let heatmap = expression(
+            "heatmap",
+            None,
+            [
+                ("values".to_string(), *list([row([0, 1]), row([2, 3])])),
+                ("low".to_string(), ValueExpr::Owned(Owned::Color(grey(0)))),
+                (
+                    "high".to_string(),
+                    ValueExpr::Owned(Owned::Color(grey(255))),
+                ),
+            ],
+            [],
+        );
+
+        let term = test_widget(
+            heatmap,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║               ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+
+        let bg = |x, y| {
+            let (_, style) = term.screen().get(ScreenPos::new(x, y)).unwrap();
+            style.bg.unwrap()
+        };
+        assert_eq!(bg(0, 0), grey(0));
+        assert_eq!(bg(7, 0), grey(0));
+        assert_eq!(bg(8, 0), grey(85));
+        assert_eq!(bg(0, 1), grey(170));
+        assert_eq!(bg(14, 1), grey(255));
+    }
+
+    #[test]
+    fn blend_named_colors() {
+        assert_eq!(blend(Color::Blue, Color::Red, 0.2), Color::Blue);
+        assert_eq!(blend(Color::Blue, Color::Red, 0.7), Color::Red);
+    }
+}
@@ -53,6 +53,10 @@ impl Widget for HStack {
     fn position<'tpl>(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx) {
         self.0.position(children, ctx)
     }
+
+    fn baseline(&self, children: &mut Nodes<'_>) -> Option<usize> {
+        self.0.baseline(children)
+    }
 }
 
 pub(crate) struct HStackFactory;
@@ -64,6 +68,7 @@ impl WidgetFactory for HStackFactory {
         let mut widget = HStack::new(width, height);
         widget.0.min_width = context.get("min-width");
         widget.0.min_height = context.get("min-height");
+        widget.0.align = context.get("align");
         Ok(Box::new(widget))
     }
 }
@@ -133,4 +138,31 @@ mod test {
             ),
         );
     }
+
+    #[test]
+    fn align_baseline() {
+        let hstack = expression(
+            "hstack",
+            None,
+            [("align".to_string(), "baseline".into())],
+            [
+                expression("text", Some("name:".into()), [], []),
+                children(1).remove(0),
+                expression("text", Some("!".into()), [], []),
+            ],
+        );
+        test_widget(
+            hstack,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║     ┌─┐       ║
+            ║name:│0│!      ║
+            ║     └─┘       ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
 }
@@ -0,0 +1,430 @@
+use anathema_render::{Size, Style};
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::views::Views;
+use anathema_widget_core::{
+    send_input, AnyWidget, Event, EventResult, FactoryContext, KeyCode, KeyModifiers, LayoutNodes,
+    LocalPos, MouseButton, Nodes, Widget, WidgetFactory, WidgetStyle,
+};
+use unicode_width::UnicodeWidthChar;
+
+const DEFAULT_INPUT_WIDTH: usize = 20;
+
+/// A single line of editable text.
+///
+/// Typed and pasted text is inserted at the cursor. The cursor is moved with
+/// the left and right arrow keys, home and end, or by clicking the text,
+/// and backspace and delete remove the character before and after the cursor.
+/// Text that is wider than the input scrolls to keep the cursor in view.
+///
+/// Editing the text sends an [`Event::Input`] with the `action` and the new text,
+/// which the view uses to update the bound state.
+///
+/// The input receives the key events of the view it is in,
+/// so only the input of the focused view is edited, and shows the cursor.
+/// Views with more than one input can set `focus` to pick the input.
+///
+/// The input is as wide as the space available to it, or 20 cells
+/// if the space is unbounded.
+///
+/// With a `mask` every character is painted as the mask character, like the
+/// `mask` of [`Text`](crate::Text), and the `text.reveal` action toggles between
+/// the mask and the text.
+///
+/// Attributes:
+/// * `value`: the text
+/// * `action`: the name of the input
+/// * `placeholder`: painted dimmed while the text is empty
+/// * `mask`: paint every character as the first character of the mask
+/// * `focus`: set to `false` to not take any key events, defaults to `true`
+///
+/// ```text
+/// input [value: name, action: "name", placeholder: "Your name"]
+/// input [value: password, action: "password", mask: "*"]
+/// ```
+#[derive(Debug)]
+pub struct Input {
+    pub value: Value<String>,
+    pub action: Value<String>,
+    pub placeholder: Value<String>,
+    /// Paint every character as the first character of the mask
+    pub mask: Value<String>,
+    pub focus: Value<bool>,
+    /// The style of the input
+    pub style: WidgetStyle,
+    text: Vec<char>,
+    // The char index of the cursor
+    cursor: usize,
+    // The char index of the first visible char
+    offset: usize,
+    width: usize,
+    revealed: bool,
+    // The bound value that `text` was last set from
+    bound: Option<String>,
+    node_id: NodeId,
+}
+
+impl Input {
+    /// Widget name.
+    pub const KIND: &'static str = "Input";
+
+    /// The current text
+    pub fn text(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    /// The char index of the cursor
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    // Replace the text if the bound value changed
+    fn sync(&mut self) {
+        let bound = self.value.value_ref();
+        if bound != self.bound.as_ref() {
+            self.bound = bound.cloned();
+            self.text = self.bound.iter().flat_map(|text| text.chars()).collect();
+            self.cursor = self.cursor.min(self.text.len());
+        }
+    }
+
+    fn has_focus(&self) -> bool {
+        self.focus.value_or(true) && Views::has_focus(&self.node_id)
+    }
+
+    fn shown(&self, c: char) -> char {
+        match self.mask.str().chars().next() {
+            Some(mask) if !self.revealed => mask,
+            _ => c,
+        }
+    }
+
+    fn width_of(&self, chars: &[char]) -> usize {
+        chars
+            .iter()
+            .map(|&c| self.shown(c).width().unwrap_or(0))
+            .sum()
+    }
+
+    // Scroll the text so the cursor, and the cell it occupies, is visible
+    fn scroll_to_cursor(&mut self) {
+        self.offset = self.offset.min(self.cursor);
+        while self.offset < self.cursor
+            && self.width_of(&self.text[self.offset..self.cursor]) + 1 > self.width
+        {
+            self.offset += 1;
+        }
+    }
+
+    // Send the new text
+    fn edited(&self) -> EventResult {
+        if let Some(action) = self.action.value_ref() {
+            send_input(action.as_str(), self.text());
+        }
+        EventResult::Consumed
+    }
+
+    fn insert(&mut self, text: impl Iterator<Item = char>) -> EventResult {
+        let text = text.filter(|c| !c.is_control()).collect::<Vec<_>>();
+        let len = text.len();
+        self.text.splice(self.cursor..self.cursor, text);
+        self.cursor += len;
+        self.edited()
+    }
+
+    fn key_press(&mut self, code: KeyCode, modifiers: KeyModifiers) -> EventResult {
+        match code {
+            KeyCode::Char(c)
+                if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.insert(std::iter::once(c))
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.cursor);
+                self.edited()
+            }
+            KeyCode::Delete if self.cursor < self.text.len() => {
+                self.text.remove(self.cursor);
+                self.edited()
+            }
+            KeyCode::Backspace | KeyCode::Delete => EventResult::Consumed,
+            KeyCode::Left => {
+                self.cursor = self.cursor.saturating_sub(1);
+                EventResult::Consumed
+            }
+            KeyCode::Right => {
+                self.cursor = (self.cursor + 1).min(self.text.len());
+                EventResult::Consumed
+            }
+            KeyCode::Home => {
+                self.cursor = 0;
+                EventResult::Consumed
+            }
+            KeyCode::End => {
+                self.cursor = self.text.len();
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    // The char index at a local x position, the end of the text if past it
+    fn index_at(&self, x: usize) -> usize {
+        let mut left = 0;
+        for (index, &c) in self.text.iter().enumerate().skip(self.offset) {
+            let width = self.shown(c).width().unwrap_or(0);
+            if x < left + width {
+                return index;
+            }
+            left += width;
+        }
+        self.text.len()
+    }
+}
+
+impl Widget for Input {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.value.resolve(context, node_id);
+        self.action.resolve(context, node_id);
+        self.placeholder.resolve(context, node_id);
+        self.mask.resolve(context, node_id);
+        self.focus.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+        self.sync();
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        self.width = match constraints.is_width_unbounded() {
+            true => DEFAULT_INPUT_WIDTH,
+            false => constraints.max_width,
+        };
+        self.scroll_to_cursor();
+
+        Ok(Size::new(self.width, constraints.max_height.min(1)))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let style = self.style.style();
+
+        if self.text.is_empty() {
+            if let Some(placeholder) = self.placeholder.value_ref() {
+                let mut placeholder_style = Style::new();
+                placeholder_style.set_dim(true);
+                placeholder_style.merge(style);
+                ctx.print(placeholder, placeholder_style, LocalPos::ZERO);
+            }
+        }
+
+        let mut x = 0;
+        for &c in &self.text[self.offset..] {
+            let c = self.shown(c);
+            let width = c.width().unwrap_or(0);
+            if x + width > self.width {
+                break;
+            }
+            ctx.put(c, style, LocalPos::new(x, 0));
+            x += width;
+        }
+
+        if self.has_focus() {
+            let x = self.width_of(&self.text[self.offset..self.cursor]);
+            ctx.set_cursor(LocalPos::new(x, 0));
+        }
+    }
+
+    fn on_event(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult {
+        if let Event::Action(action) = event {
+            if action == "text.reveal" && !self.mask.str().is_empty() {
+                self.revealed = !self.revealed;
+                return EventResult::Consumed;
+            }
+        }
+
+        if !self.focus.value_or(true) {
+            return EventResult::Ignored;
+        }
+
+        match *event {
+            Event::KeyPress(code, modifiers, _) => self.key_press(code, modifiers),
+            Event::Paste(ref text) => self.insert(text.chars()),
+            Event::MouseDown(x, y, MouseButton::Left, ..) => match ctx.local_pos(x, y) {
+                Some(pos) => {
+                    self.cursor = self.index_at(pos.x);
+                    EventResult::Consumed
+                }
+                None => EventResult::Ignored,
+            },
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+pub(crate) struct InputFactory;
+
+impl WidgetFactory for InputFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let mut widget = Input {
+            value: ctx.get("value"),
+            action: ctx.get("action"),
+            placeholder: ctx.get("placeholder"),
+            mask: ctx.get("mask"),
+            focus: ctx.get("focus"),
+            style: ctx.style(),
+            text: vec![],
+            cursor: 0,
+            offset: 0,
+            width: 0,
+            revealed: false,
+            bound: None,
+            node_id: ctx.node_id.clone(),
+        };
+        widget.sync();
+        widget.cursor = widget.text.len();
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_render::ScreenPos;
+    use anathema_values::{Symbol, ValueExpr};
+    use anathema_widget_core::drain_inputs;
+    use anathema_widget_core::expressions::Expression;
+    use anathema_widget_core::testing::FakeTerm;
+
+    use super::*;
+    use crate::testing::{key, test_widget, test_widget_events, widget};
+
+    fn input(attributes: impl IntoIterator<Item = (&'static str, ValueExpr)>) -> Expression {
+        let action = ("action", ValueExpr::from("name"));
+        widget("input", attributes.into_iter().chain([action]))
+    }
+
+    #[test]
+    fn edit_text() {
+        let term = test_widget_events(
+            input([("value", "helo".into())]),
+            &[
+                key(KeyCode::Left),
+                key(KeyCode::Char('l')),
+                key(KeyCode::End),
+                Event::Paste("!\n".into()),
+                key(KeyCode::Backspace),
+                key(KeyCode::Home),
+                key(KeyCode::Delete),
+                key(KeyCode::Right),
+            ],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║ello           ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+        assert_eq!(term.screen().cursor(), Some(ScreenPos::new(1, 0)));
+
+        let inputs = drain_inputs()
+            .into_iter()
+            .map(|(action, text)| {
+                assert_eq!(action, Symbol::new("name"));
+                text
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(inputs, vec!["hello", "hello!", "hello", "ello"]);
+    }
+
+    #[test]
+    fn scroll_to_cursor() {
+        let term = test_widget_events(
+            input([("value", "abcdefghijklmnopqrstuvwxyz".into())]),
+            &[],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║mnopqrstuvwxyz ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+        assert_eq!(term.screen().cursor(), Some(ScreenPos::new(14, 0)));
+
+        // Moving the cursor to the start scrolls back
+        let term = test_widget_events(
+            input([("value", "abcdefghijklmnopqrstuvwxyz".into())]),
+            &[key(KeyCode::Home)],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║abcdefghijklmno║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+        assert_eq!(term.screen().cursor(), Some(ScreenPos::new(0, 0)));
+        drain_inputs();
+    }
+
+    #[test]
+    fn mask_and_placeholder() {
+        test_widget(
+            input([("value", "secret".into()), ("mask", "•".into())]),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║••••••         ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+
+        test_widget_events(
+            input([("value", "secret".into()), ("mask", "*".into())]),
+            &[Event::Action(Symbol::new("text.reveal"))],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║secret         ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+
+        test_widget(
+            input([("placeholder", "Your name".into())]),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║Your name      ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn ignore_keys_without_focus() {
+        test_widget_events(
+            input([("value", "a".into()), ("focus", false.into())]),
+            &[key(KeyCode::Char('b'))],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║a              ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+        assert!(drain_inputs().is_empty());
+    }
+}
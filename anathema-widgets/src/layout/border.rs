@@ -14,27 +14,14 @@ pub struct BorderLayout {
 impl Layout for BorderLayout {
     fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
         // If there is a min width / height, make sure the minimum constraints
-        // are matching these
-        let mut constraints = nodes.constraints;
-
-        if let Some(min_width) = self.min_width {
-            constraints.min_width = constraints.min_width.max(min_width);
-        }
-
-        if let Some(min_height) = self.min_height {
-            constraints.min_height = constraints.min_height.max(min_height);
-        }
-
+        // are matching these.
         // If there is a width / height then make the constraints tight
         // around the size. This will modify the size to fit within the
         // constraints first.
-        if let Some(width) = self.width {
-            constraints.make_width_tight(width);
-        }
-
-        if let Some(height) = self.height {
-            constraints.make_height_tight(height);
-        }
+        let constraints = nodes
+            .constraints
+            .clamp_min(self.min_width, self.min_height)
+            .tighten_if(self.width, self.height);
 
         if constraints == Constraints::ZERO {
             return Ok(Size::ZERO);
@@ -46,25 +33,7 @@ impl Layout for BorderLayout {
 
         nodes.next(|mut node| {
             // Shrink the constraint for the child to fit inside the border
-            let mut constraints = constraints;
-            constraints.max_width = match constraints.max_width.checked_sub(border_size.width) {
-                Some(w) => w,
-                None => return Err(Error::InsufficientSpaceAvailble),
-            };
-
-            constraints.max_height = match constraints.max_height.checked_sub(border_size.height) {
-                Some(h) => h,
-                None => return Err(Error::InsufficientSpaceAvailble),
-            };
-
-            if constraints.min_width > constraints.max_width {
-                constraints.min_width = constraints.max_width;
-            }
-
-            if constraints.min_height > constraints.max_height {
-                constraints.min_height = constraints.max_height;
-            }
-
+            let constraints = constraints.shrink_by(border_size);
             if constraints.max_width == 0 || constraints.max_height == 0 {
                 return Err(Error::InsufficientSpaceAvailble);
             }
@@ -54,7 +54,11 @@ pub fn layout(nodes: &mut LayoutNodes<'_, '_, '_>, axis: Axis) -> Result<Size> {
 
     let factors = expansions
         .iter()
-        .map(|w| w.to_ref::<Expand>().factor.value_or(DEFAULT_FACTOR))
+        .map(|w| {
+            w.try_to::<Expand>().map_or(DEFAULT_FACTOR, |expand| {
+                expand.factor.value_or(DEFAULT_FACTOR)
+            })
+        })
         .collect::<Vec<_>>();
 
     let mut size = Size::ZERO;
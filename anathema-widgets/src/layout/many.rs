@@ -34,6 +34,14 @@ impl SizeMod {
         }
     }
 
+    // Only grow the size across the axis
+    fn apply_cross(&mut self, size: Size) {
+        match self.axis {
+            Axis::Vertical => self.inner.width = self.inner.width.max(size.width),
+            Axis::Horizontal => self.inner.height = self.inner.height.max(size.height),
+        }
+    }
+
     fn no_space_left(&self) -> bool {
         match self.axis {
             Axis::Horizontal => self.inner.width >= self.max_size.width,
@@ -142,6 +150,11 @@ impl Layout for Many {
             let mut widget_size = node.layout(widget_constraints)?;
 
             if self.offset.skip(&mut widget_size) {
+                // A sticky widget that is scrolled past can still be pinned
+                // to the start of a viewport, so it needs the space across
+                if node.sticky().is_some() {
+                    used_size.apply_cross(widget_size);
+                }
                 return Ok(());
             }
 
@@ -15,8 +15,12 @@ use anathema_values::{
 };
 use unicode_width::UnicodeWidthChar;
 
+/// Prevents a line break on either side of it, e.g `a-\u{2060}b`
+pub const WORD_JOINER: char = '\u{2060}';
+
+// Non-breaking spaces are whitespace, but never break a line
 fn word_break(c: char) -> bool {
-    c == '-' || c.is_whitespace()
+    c == '-' || c.is_whitespace() && !matches!(c, '\u{a0}' | '\u{2007}' | '\u{202f}')
 }
 
 #[derive(Debug)]
@@ -51,6 +55,11 @@ impl LineSegment {
         &s[self.start..self.end]
     }
 
+    /// Byte offset of the segment in the text
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
     fn new(start: usize, byte_count: usize, index: usize, width: usize) -> Self {
         Self {
             start,
@@ -213,6 +222,8 @@ pub struct TextLayout {
     squash: bool,
     slice_index: usize,
     wrap: Wrap,
+    // Don't break lines in the text that is processed
+    nowrap: bool,
 }
 
 impl TextLayout {
@@ -225,6 +236,7 @@ impl TextLayout {
             squash,
             slice_index: 0,
             wrap,
+            nowrap: false,
         }
     }
 
@@ -232,6 +244,12 @@ impl TextLayout {
         &self.lines
     }
 
+    /// Keep the text processed from now on from being broken across lines,
+    /// as if every space and hyphen in it was non-breaking.
+    pub fn set_nowrap(&mut self, nowrap: bool) {
+        self.nowrap = nowrap;
+    }
+
     pub fn reset(&mut self, max_size: Size, squash: bool) {
         self.max_size = max_size;
         self.lines.clear();
@@ -242,13 +260,21 @@ impl TextLayout {
     }
 
     fn process_word_wrap(&mut self, s: &str) -> ProcessOutput {
-        for (i, c) in s.char_indices() {
+        let mut chars = s.char_indices().peekable();
+        let mut joined = false;
+        while let Some((i, c)) = chars.next() {
             let width = c.width().unwrap_or(0);
 
+            // A word joiner on either side keeps the char from breaking the line
+            let next_joins = chars.peek().is_some_and(|&(_, next)| next == WORD_JOINER);
+            let breaks = word_break(c) && !self.nowrap && !joined && !next_joins;
+            let squash = breaks && c.is_whitespace() && self.squash;
+            joined = c == WORD_JOINER;
+
             if width + self.current_width > self.max_size.width {
                 // Squash = remove whitespace that would otherwise
                 // be trailing the last character on the left
-                let line = if c.is_whitespace() && self.squash {
+                let line = if squash {
                     self.tree.drain(Drain::All)
                 } else {
                     self.tree.drain(Drain::Left)
@@ -262,7 +288,7 @@ impl TextLayout {
                     return ProcessOutput::InsufficientSpaceAvailble;
                 }
 
-                if c.is_whitespace() && self.squash {
+                if squash {
                     continue;
                 }
             }
@@ -275,7 +301,7 @@ impl TextLayout {
                     self.lines.push(line);
                     self.tree.focus = Focus::Left;
                 }
-                _ if word_break(c) => {
+                _ if breaks => {
                     self.tree.set_middle(
                         LineSegment::new(i, c.len_utf8(), self.slice_index, width),
                         c.is_whitespace(),
@@ -351,7 +377,8 @@ pub enum ProcessOutput {
 /// Word wrapping strategy
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 pub enum Wrap {
-    /// Normal word wrapping. This will break text on hyphen and whitespace.
+    /// Normal word wrapping. This will break text on hyphen and whitespace,
+    /// except for non-breaking spaces and next to a [`WORD_JOINER`].
     /// Trailing whitespace is consumed if it would cause a line break.
     #[default]
     Normal,
@@ -417,3 +444,88 @@ impl TryFrom<ValueRef<'_>> for TextAlignment {
         Ok(wrap)
     }
 }
+
+/// Where a text that is too wide is cut, see [`truncate`].
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum TruncateMode {
+    /// Wrap the text instead of truncating it
+    #[default]
+    None,
+    /// Keep the end of the text: `…ject/file.rs`
+    Start,
+    /// Keep the start and the end of the text: `/home/…/file.rs`
+    Middle,
+    /// Keep the start of the text: `/home/user/…`
+    End,
+}
+
+impl_dyn_value!(TruncateMode);
+
+impl TryFrom<ValueRef<'_>> for TruncateMode {
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> Result<Self, Self::Error> {
+        let mode = match value {
+            ValueRef::Str("start") => Self::Start,
+            ValueRef::Str("middle") => Self::Middle,
+            ValueRef::Str("end") => Self::End,
+            _ => Self::None,
+        };
+        Ok(mode)
+    }
+}
+
+const ELLIPSIS: char = '…';
+
+/// Replace the characters that don't fit within the width by an ellipsis.
+/// Every line of the text is truncated on its own.
+/// Returns `None` if the text fits, or if the mode is [`TruncateMode::None`].
+pub fn truncate(text: &str, width: usize, mode: TruncateMode) -> Option<String> {
+    let too_wide = |line: &str| line.chars().map(|c| c.width().unwrap_or(0)).sum::<usize>() > width;
+    if mode == TruncateMode::None || !text.split('\n').any(too_wide) {
+        return None;
+    }
+
+    let lines = text
+        .split('\n')
+        .map(|line| match too_wide(line) {
+            true => truncate_line(line, width, mode),
+            false => line.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    Some(lines.join("\n"))
+}
+
+fn truncate_line(line: &str, width: usize, mode: TruncateMode) -> String {
+    // Take chars until the width is used up
+    fn take(chars: impl Iterator<Item = char>, width: usize) -> Vec<char> {
+        let mut used = 0;
+        chars
+            .take_while(|c| {
+                used += c.width().unwrap_or(0);
+                used <= width
+            })
+            .collect()
+    }
+
+    let Some(available) = width.checked_sub(1) else {
+        return String::new();
+    };
+
+    // The end of the text gets the extra cell, as that's where the file name is
+    let (head, tail) = match mode {
+        TruncateMode::None | TruncateMode::End => (available, 0),
+        TruncateMode::Start => (0, available),
+        TruncateMode::Middle => (available / 2, available - available / 2),
+    };
+
+    let head = take(line.chars(), head);
+    let mut tail = take(line.chars().rev(), tail);
+    tail.reverse();
+
+    head.into_iter()
+        .chain(std::iter::once(ELLIPSIS))
+        .chain(tail)
+        .collect()
+}
@@ -1,18 +1,37 @@
 pub mod layout;
 
-#[cfg(feature = "testing")]
+#[cfg(any(test, feature = "testing"))]
 pub mod testing;
 
 mod alignment;
+mod backdrop;
+mod bigtext;
 mod border;
+mod boundary;
+mod breadcrumbs;
+mod button;
+mod chart;
+mod context_menu;
 mod expand;
+mod field_error;
+mod heatmap;
 mod hstack;
+mod input;
+mod menu;
 mod position;
+mod progress;
+mod rule;
+mod scatter;
+mod search_bar;
+mod segmented;
+mod slider;
 mod spacer;
+mod split;
 mod stack;
 mod text;
 mod viewport;
 mod vstack;
+mod windows;
 mod zstack;
 
 use anathema_widget_core::error::Result;
@@ -22,14 +41,33 @@ use anathema_widget_core::Factory;
 //   - Export widgets -
 // -----------------------------------------------------------------------------
 pub use crate::alignment::Alignment;
+pub use crate::backdrop::Backdrop;
+pub use crate::bigtext::{BigText, Font};
 pub use crate::border::{Border, BorderStyle, Sides};
+pub use crate::boundary::Boundary;
+pub use crate::breadcrumbs::Breadcrumbs;
+pub use crate::button::{Button, ButtonState};
+pub use crate::chart::{Chart, Series, SeriesKind};
+pub use crate::context_menu::ContextMenu;
 pub use crate::expand::Expand;
+pub use crate::field_error::FieldError;
+pub use crate::heatmap::Heatmap;
 pub use crate::hstack::HStack;
+pub use crate::input::Input;
+pub use crate::menu::{Menu, MenuBar, MenuItem};
 pub use crate::position::Position;
+pub use crate::progress::Progress;
+pub use crate::rule::{LineStyle, Rule, Ruler};
+pub use crate::scatter::Scatter;
+pub use crate::search_bar::SearchBar;
+pub use crate::segmented::{Segment, Segmented};
+pub use crate::slider::{Slider, Stepper};
 pub use crate::spacer::Spacer;
-pub use crate::text::{Text, TextSpan};
+pub use crate::split::Split;
+pub use crate::text::{Highlight, SourceRange, Text, TextSpan};
 pub use crate::viewport::Viewport;
 pub use crate::vstack::VStack;
+pub use crate::windows::{Window, Windows};
 pub use crate::zstack::ZStack;
 
 // -----------------------------------------------------------------------------
@@ -37,14 +75,32 @@ pub use crate::zstack::ZStack;
 // -----------------------------------------------------------------------------
 mod factories {
     pub(super) use crate::alignment::AlignmentFactory;
+    pub(super) use crate::backdrop::BackdropFactory;
+    pub(super) use crate::bigtext::BigTextFactory;
     pub(super) use crate::border::BorderFactory;
+    pub(super) use crate::boundary::BoundaryFactory;
+    pub(super) use crate::breadcrumbs::BreadcrumbsFactory;
+    pub(super) use crate::button::ButtonFactory;
+    pub(super) use crate::chart::{ChartFactory, SeriesFactory};
     pub(super) use crate::expand::ExpandFactory;
+    pub(super) use crate::field_error::FieldErrorFactory;
+    pub(super) use crate::heatmap::HeatmapFactory;
     pub(super) use crate::hstack::HStackFactory;
+    pub(super) use crate::input::InputFactory;
+    pub(super) use crate::menu::{MenuBarFactory, MenuFactory, MenuItemFactory};
     pub(super) use crate::position::PositionFactory;
+    pub(super) use crate::progress::ProgressFactory;
+    pub(super) use crate::rule::{RuleFactory, RulerFactory};
+    pub(super) use crate::scatter::ScatterFactory;
+    pub(super) use crate::search_bar::SearchBarFactory;
+    pub(super) use crate::segmented::{SegmentFactory, SegmentedFactory};
+    pub(super) use crate::slider::{SliderFactory, StepperFactory};
     pub(super) use crate::spacer::SpacerFactory;
+    pub(super) use crate::split::SplitFactory;
     pub(super) use crate::text::{SpanFactory, TextFactory};
     pub(super) use crate::viewport::ViewportFactory;
     pub(super) use crate::vstack::VStackFactory;
+    pub(super) use crate::windows::{WindowFactory, WindowsFactory};
     pub(super) use crate::zstack::ZStackFactory;
 }
 
@@ -52,14 +108,39 @@ mod factories {
 pub fn register_default_widgets() -> Result<()> {
     let results = [
         Factory::register("alignment".to_string(), factories::AlignmentFactory),
+        Factory::register("backdrop".to_string(), factories::BackdropFactory),
+        Factory::register("bigtext".to_string(), factories::BigTextFactory),
         Factory::register("border".to_string(), factories::BorderFactory),
+        Factory::register("boundary".to_string(), factories::BoundaryFactory),
+        Factory::register("breadcrumbs".to_string(), factories::BreadcrumbsFactory),
+        Factory::register("button".to_string(), factories::ButtonFactory),
+        Factory::register("chart".to_string(), factories::ChartFactory),
         Factory::register("expand".to_string(), factories::ExpandFactory),
+        Factory::register("fielderror".to_string(), factories::FieldErrorFactory),
+        Factory::register("heatmap".to_string(), factories::HeatmapFactory),
         Factory::register("hstack".to_string(), factories::HStackFactory),
+        Factory::register("input".to_string(), factories::InputFactory),
+        Factory::register("menu".to_string(), factories::MenuFactory),
+        Factory::register("menubar".to_string(), factories::MenuBarFactory),
+        Factory::register("menuitem".to_string(), factories::MenuItemFactory),
         Factory::register("position".to_string(), factories::PositionFactory),
+        Factory::register("progress".to_string(), factories::ProgressFactory),
+        Factory::register("rule".to_string(), factories::RuleFactory),
+        Factory::register("ruler".to_string(), factories::RulerFactory),
+        Factory::register("scatter".to_string(), factories::ScatterFactory),
+        Factory::register("searchbar".to_string(), factories::SearchBarFactory),
+        Factory::register("segment".to_string(), factories::SegmentFactory),
+        Factory::register("segmented".to_string(), factories::SegmentedFactory),
+        Factory::register("series".to_string(), factories::SeriesFactory),
+        Factory::register("slider".to_string(), factories::SliderFactory),
         Factory::register("spacer".to_string(), factories::SpacerFactory),
         Factory::register("span".to_string(), factories::SpanFactory),
+        Factory::register("split".to_string(), factories::SplitFactory),
+        Factory::register("stepper".to_string(), factories::StepperFactory),
         Factory::register("text".to_string(), factories::TextFactory),
         Factory::register("vstack".to_string(), factories::VStackFactory),
+        Factory::register("window".to_string(), factories::WindowFactory),
+        Factory::register("windows".to_string(), factories::WindowsFactory),
         Factory::register("zstack".to_string(), factories::ZStackFactory),
         Factory::register("viewport".to_string(), factories::ViewportFactory),
     ];
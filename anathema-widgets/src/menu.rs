@@ -0,0 +1,835 @@
+use anathema_render::{Size, Style};
+use anathema_values::{Context, NodeId, Symbol, Value};
+use anathema_widget_core::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::layout::Constraints;
+use anathema_widget_core::overlay::Overlay;
+use anathema_widget_core::{
+    send_action, AnyWidget, Event, EventResult, FactoryContext, KeyCode, KeyModifiers, LayoutNodes,
+    LocalPos, MouseButton, Nodes, Pos, Widget, WidgetFactory, WidgetStyle,
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const SUBMENU_MARKER: char = '›';
+
+// -----------------------------------------------------------------------------
+//   - Label -
+// -----------------------------------------------------------------------------
+/// A label with an optional mnemonic, marked with a `&` before the character: `&File`.
+/// Use `&&` for a literal `&`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Label {
+    pub(crate) text: String,
+    // Index of the mnemonic character in the text
+    mnemonic: Option<usize>,
+}
+
+impl Label {
+    pub(crate) fn parse(label: &str) -> Self {
+        let mut text = String::with_capacity(label.len());
+        let mut mnemonic = None;
+        let mut chars = label.chars();
+
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('&', Some('&')) => {
+                    chars.next();
+                    text.push('&');
+                }
+                ('&', Some(_)) if mnemonic.is_none() => mnemonic = Some(text.chars().count()),
+                _ => text.push(c),
+            }
+        }
+
+        Self { text, mnemonic }
+    }
+
+    fn width(&self) -> usize {
+        self.text.width()
+    }
+
+    // The mnemonic, in lower case
+    fn mnemonic(&self) -> Option<char> {
+        let c = self.text.chars().nth(self.mnemonic?)?;
+        c.to_lowercase().next()
+    }
+
+    // The characters of the label and their style, the mnemonic is underlined
+    fn chars(&self, style: Style) -> impl Iterator<Item = (char, Style)> + '_ {
+        let mut underlined = style;
+        underlined.set_underlined(true);
+
+        self.text
+            .chars()
+            .enumerate()
+            .map(move |(i, c)| match self.mnemonic == Some(i) {
+                true => (c, underlined),
+                false => (c, style),
+            })
+    }
+}
+
+fn matches_mnemonic(label: &Label, c: char) -> bool {
+    label.mnemonic().is_some() && label.mnemonic() == c.to_lowercase().next()
+}
+
+// -----------------------------------------------------------------------------
+//   - Menu entries -
+// -----------------------------------------------------------------------------
+#[derive(Debug, Clone, PartialEq)]
+enum EntryKind {
+    // An item without an action is disabled
+    Action(Option<Symbol>),
+    Submenu(Vec<Entry>),
+}
+
+// An entry of a menu, collected from the `menuitem` and `menu` widgets during layout
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    label: Label,
+    kind: EntryKind,
+}
+
+impl Entry {
+    fn entries(&self) -> &[Entry] {
+        match &self.kind {
+            EntryKind::Submenu(entries) => entries,
+            EntryKind::Action(_) => &[],
+        }
+    }
+}
+
+// Lay out the children and collect the menu items and submenus
+fn entries(nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Vec<Entry>> {
+    nodes.for_each(|mut node| {
+        node.layout(Constraints::ZERO)?;
+        Ok(())
+    })?;
+
+    let entries = nodes
+        .filter(|_| true)
+        .filter_map(|node| {
+            if let Some(item) = node.try_to_ref::<MenuItem>() {
+                return Some(item.entry());
+            }
+            node.try_to_ref::<Menu>().map(Menu::entry)
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+// The size of a dropdown, including the border and a space on each side
+fn dropdown_size(entries: &[Entry]) -> Size {
+    let width = entries
+        .iter()
+        .map(|entry| match entry.kind {
+            EntryKind::Submenu(_) => entry.label.width() + 2,
+            EntryKind::Action(_) => entry.label.width(),
+        })
+        .max()
+        .unwrap_or(0);
+
+    Size::new(width + 4, entries.len() + 2)
+}
+
+// -----------------------------------------------------------------------------
+//   - Menu item -
+// -----------------------------------------------------------------------------
+/// An item of a [`Menu`], running the `action` when chosen.
+/// An item without an action is disabled.
+///
+/// Also used by [`Breadcrumbs`](crate::Breadcrumbs).
+///
+/// ```text
+/// menuitem [action: "file.save"] "&Save"
+/// ```
+#[derive(Debug)]
+pub struct MenuItem {
+    /// The label, see [`MenuBar`] for mnemonics
+    pub label: Value<String>,
+    pub action: Value<String>,
+}
+
+impl MenuItem {
+    /// Widget name.
+    pub const KIND: &'static str = "MenuItem";
+
+    fn entry(&self) -> Entry {
+        Entry {
+            label: Label::parse(self.label.value_ref().map_or("", String::as_str)),
+            kind: EntryKind::Action(self.action.value_ref().map(|a| Symbol::new(a))),
+        }
+    }
+
+    pub(crate) fn crumb(&self) -> (String, Option<Symbol>) {
+        let Entry { label, kind } = self.entry();
+        match kind {
+            EntryKind::Action(action) => (label.text, action),
+            EntryKind::Submenu(_) => (label.text, None),
+        }
+    }
+}
+
+impl Widget for MenuItem {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.label.resolve(context, node_id);
+        self.action.resolve(context, node_id);
+    }
+
+    // Menu items are painted by the menu bar
+    fn layout(&mut self, _: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        Ok(Size::ZERO)
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, _: PaintCtx<'_, WithSize>) {}
+}
+
+pub(crate) struct MenuItemFactory;
+
+impl WidgetFactory for MenuItemFactory {
+    fn make(&self, mut ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = MenuItem {
+            label: ctx.text.take(),
+            action: ctx.get("action"),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Menu -
+// -----------------------------------------------------------------------------
+/// A menu of a [`MenuBar`], or a submenu when it's inside another menu.
+///
+/// ```text
+/// menu [title: "&Export"]
+///     menuitem [action: "export.pdf"] "&PDF"
+/// ```
+#[derive(Debug)]
+pub struct Menu {
+    /// The title, see [`MenuBar`] for mnemonics
+    pub title: Value<String>,
+    entries: Vec<Entry>,
+}
+
+impl Menu {
+    /// Widget name.
+    pub const KIND: &'static str = "Menu";
+
+    fn entry(&self) -> Entry {
+        Entry {
+            label: Label::parse(self.title.value_ref().map_or("", String::as_str)),
+            kind: EntryKind::Submenu(self.entries.clone()),
+        }
+    }
+}
+
+impl Widget for Menu {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.title.resolve(context, node_id);
+    }
+
+    // Menus are painted by the menu bar
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        self.entries = entries(nodes)?;
+        Ok(Size::ZERO)
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, _: PaintCtx<'_, WithSize>) {}
+}
+
+pub(crate) struct MenuFactory;
+
+impl WidgetFactory for MenuFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Menu {
+            title: ctx.get("title"),
+            entries: vec![],
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Menu bar -
+// -----------------------------------------------------------------------------
+/// A row of menus, where each menu opens a dropdown on top of the other widgets.
+///
+/// Titles and labels can have a mnemonic, marked with a `&` before the character
+/// (`&File`, use `&&` for a literal `&`).
+/// The mnemonic is underlined, `alt` and the mnemonic of a menu opens the menu,
+/// and the mnemonic of an item in an open dropdown chooses the item.
+///
+/// Choosing an item sends its action to the views as an [`Event::Action`],
+/// the same as a key binding would.
+///
+/// Keys while a menu is open:
+/// * `up` / `down`: select an item
+/// * `left` / `right`: close or open a submenu, or move to the previous or next menu
+/// * `enter`: choose the selected item
+/// * `esc`: close the submenu, or the menu
+///
+/// The `menubar.open` action opens the first menu.
+///
+/// ```text
+/// menubar
+///     menu [title: "&File"]
+///         menuitem [action: "file.open"] "&Open"
+///         menuitem [action: "file.save"] "&Save"
+///         menu [title: "&Export"]
+///             menuitem [action: "export.pdf"] "&PDF"
+///     menu [title: "&Help"]
+///         menuitem [action: "help.about"] "&About"
+/// ```
+///
+/// ```text
+///  File  Help
+/// ┌──────────┐
+/// │ Open     │
+/// │ Save     │
+/// │ Export › │
+/// └──────────┘
+/// ```
+#[derive(Debug)]
+pub struct MenuBar {
+    /// The style of the menu bar and the dropdowns
+    pub style: WidgetStyle,
+    menus: Vec<Entry>,
+    // The open menu, followed by the selected item of each open dropdown
+    path: Vec<usize>,
+    pos: Pos,
+}
+
+impl MenuBar {
+    /// Widget name.
+    pub const KIND: &'static str = "MenuBar";
+
+    pub fn is_open(&self) -> bool {
+        !self.path.is_empty()
+    }
+
+    /// Open a menu by index
+    pub fn open(&mut self, menu: usize) {
+        if menu < self.menus.len() {
+            self.path = vec![menu, 0];
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.path.clear();
+    }
+
+    // The offset and width of each title
+    fn titles(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.menus.iter().scan(0, |x, menu| {
+            let title = (*x, menu.label.width() + 2);
+            *x += title.1;
+            Some(title)
+        })
+    }
+
+    // The entries, the screen position and the size of each open dropdown
+    fn dropdowns(&self) -> Vec<(&[Entry], Pos, Size)> {
+        let mut dropdowns = vec![];
+        let Some(&menu) = self.path.first() else {
+            return dropdowns;
+        };
+
+        let x = self.titles().nth(menu).map_or(0, |(x, _)| x);
+        let mut pos = Pos::new(self.pos.x + x as i32, self.pos.y + 1);
+        let mut entries = self.menus[menu].entries();
+
+        for &selected in &self.path[1..] {
+            let size = dropdown_size(entries);
+            dropdowns.push((entries, pos, size));
+            pos = Pos::new(pos.x + size.width as i32, pos.y + 1 + selected as i32);
+            entries = match entries.get(selected) {
+                Some(entry) => entry.entries(),
+                None => break,
+            };
+        }
+
+        dropdowns
+    }
+
+    // The entries of the innermost dropdown
+    fn current(&self) -> &[Entry] {
+        let Some(&menu) = self.path.first() else {
+            return &[];
+        };
+
+        let mut entries = self.menus[menu].entries();
+        for &selected in &self.path[1..self.path.len() - 1] {
+            entries = entries.get(selected).map_or(&[], Entry::entries);
+        }
+        entries
+    }
+
+    fn selected(&self) -> Option<&Entry> {
+        self.current().get(*self.path.last()?)
+    }
+
+    // Keep the path valid as the menus change
+    fn fit_path(&mut self) {
+        if self
+            .path
+            .first()
+            .is_some_and(|&menu| menu >= self.menus.len())
+        {
+            self.close();
+        }
+
+        let Some(&menu) = self.path.first() else {
+            return;
+        };
+
+        let mut entries = self.menus[menu].entries();
+        for level in 1..self.path.len() {
+            let selected = self.path[level].min(entries.len().saturating_sub(1));
+            self.path[level] = selected;
+            match entries.get(selected) {
+                Some(Entry {
+                    kind: EntryKind::Submenu(submenu),
+                    ..
+                }) => entries = submenu,
+                _ => {
+                    self.path.truncate(level + 1);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        let len = self.current().len();
+        if let Some(selected) = self.path.last_mut() {
+            *selected = index.min(len.saturating_sub(1));
+        }
+    }
+
+    // Choose an entry of the innermost dropdown:
+    // open a submenu, or run the action and close the menu
+    fn choose(&mut self, index: usize) {
+        self.select(index);
+        match self.selected().map(|entry| &entry.kind) {
+            Some(EntryKind::Submenu(_)) => self.path.push(0),
+            Some(EntryKind::Action(Some(action))) => {
+                send_action(*action);
+                self.close();
+            }
+            // Disabled
+            Some(EntryKind::Action(None)) | None => {}
+        }
+    }
+
+    // Move to the previous or next menu
+    fn cycle(&mut self, forward: bool) {
+        let len = self.menus.len();
+        let menu = self.path[0];
+        match forward {
+            true => self.open((menu + 1) % len),
+            false => self.open((menu + len - 1) % len),
+        }
+    }
+
+    // The menu with the title at the screen position
+    fn title_at(&self, x: u16, y: u16) -> Option<usize> {
+        if y as i32 != self.pos.y {
+            return None;
+        }
+        let x = x as i32 - self.pos.x;
+        self.titles()
+            .position(|(offset, width)| (offset as i32..(offset + width) as i32).contains(&x))
+    }
+
+    // The level of the dropdown, and the index of the entry at the screen position
+    fn entry_at(&self, x: u16, y: u16) -> Option<(usize, usize)> {
+        let (x, y) = (x as i32, y as i32);
+        self.dropdowns()
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(level, (entries, pos, size))| {
+                let inside = (pos.x + 1..pos.x + size.width as i32 - 1).contains(&x)
+                    && (pos.y + 1..pos.y + 1 + entries.len() as i32).contains(&y);
+                inside.then(|| (level + 1, (y - pos.y - 1) as usize))
+            })
+    }
+
+    fn key_press(&mut self, code: KeyCode, modifiers: KeyModifiers) -> EventResult {
+        if let (KeyCode::Char(c), true) = (code, modifiers.contains(KeyModifiers::ALT)) {
+            return match self
+                .menus
+                .iter()
+                .position(|m| matches_mnemonic(&m.label, c))
+            {
+                Some(menu) => {
+                    self.open(menu);
+                    EventResult::Consumed
+                }
+                None => EventResult::Ignored,
+            };
+        }
+
+        if !self.is_open() {
+            return EventResult::Ignored;
+        }
+
+        let selected = *self.path.last().unwrap_or(&0);
+        let submenu = matches!(
+            self.selected().map(|entry| &entry.kind),
+            Some(EntryKind::Submenu(_))
+        );
+
+        match code {
+            KeyCode::Esc if self.path.len() > 2 => drop(self.path.pop()),
+            KeyCode::Esc => self.close(),
+            KeyCode::Up => self.select(selected.saturating_sub(1)),
+            KeyCode::Down => self.select(selected + 1),
+            KeyCode::Left if self.path.len() > 2 => drop(self.path.pop()),
+            KeyCode::Left => self.cycle(false),
+            KeyCode::Right if submenu => self.path.push(0),
+            KeyCode::Right => self.cycle(true),
+            KeyCode::Enter => self.choose(selected),
+            KeyCode::Char(c) => {
+                if let Some(index) = self
+                    .current()
+                    .iter()
+                    .position(|entry| matches_mnemonic(&entry.label, c))
+                {
+                    self.choose(index);
+                }
+            }
+            _ => {}
+        }
+
+        // The menu has the keyboard while it's open
+        EventResult::Consumed
+    }
+
+    fn mouse_down(&mut self, x: u16, y: u16) -> EventResult {
+        if let Some(menu) = self.title_at(x, y) {
+            match self.path.first() == Some(&menu) {
+                true => self.close(),
+                false => self.open(menu),
+            }
+            return EventResult::Consumed;
+        }
+
+        if !self.is_open() {
+            return EventResult::Ignored;
+        }
+
+        match self.entry_at(x, y) {
+            Some((level, index)) => {
+                self.path.truncate(level + 1);
+                self.choose(index);
+            }
+            // Clicking away closes the menu
+            None => self.close(),
+        }
+
+        EventResult::Consumed
+    }
+
+    fn mouse_move(&mut self, x: u16, y: u16) -> EventResult {
+        let Some((level, index)) = self.entry_at(x, y) else {
+            return EventResult::Ignored;
+        };
+
+        if self.path.len() == level + 1 && self.path[level] == index {
+            return EventResult::Ignored;
+        }
+
+        self.path.truncate(level + 1);
+        self.select(index);
+        EventResult::Consumed
+    }
+
+    fn paint_dropdown(entries: &[Entry], selected: usize, pos: Pos, size: Size, style: Style) {
+        let mut overlay = Overlay::new(pos, size);
+        let Size { width, height } = size;
+        let inner = "─".repeat(width - 2);
+
+        overlay.print(&format!("┌{inner}┐"), style, LocalPos::ZERO);
+        overlay.print(&format!("└{inner}┘"), style, LocalPos::new(0, height - 1));
+
+        for (i, entry) in entries.iter().enumerate() {
+            let y = i + 1;
+            let mut style = style;
+            style.set_inverse(i == selected);
+            if let EntryKind::Action(None) = entry.kind {
+                style.set_dim(true);
+            }
+
+            overlay.put('│', Style::new(), LocalPos::new(0, y));
+            overlay.print(&" ".repeat(width - 2), style, LocalPos::new(1, y));
+            overlay.put('│', Style::new(), LocalPos::new(width - 1, y));
+
+            let mut x = 2;
+            for (c, style) in entry.label.chars(style) {
+                overlay.put(c, style, LocalPos::new(x, y));
+                x += c.width().unwrap_or(0);
+            }
+
+            if let EntryKind::Submenu(_) = entry.kind {
+                overlay.put(SUBMENU_MARKER, style, LocalPos::new(width - 3, y));
+            }
+        }
+
+        overlay.show();
+    }
+}
+
+impl Widget for MenuBar {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.style.resolve(context, node_id);
+    }
+
+    // The menu bar is a single line filling the width
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        self.menus = entries(nodes)?
+            .into_iter()
+            .filter(|entry| matches!(entry.kind, EntryKind::Submenu(_)))
+            .collect();
+        self.fit_path();
+
+        let width = nodes.constraints.max_width;
+        Ok(Size::new(width, nodes.constraints.max_height.min(1)))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, ctx: PositionCtx) {
+        self.pos = ctx.pos;
+    }
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let style = self.style.style();
+        let titles = self.titles().collect::<Vec<_>>();
+
+        for (i, (menu, (x, _))) in self.menus.iter().zip(titles).enumerate() {
+            let mut style = style;
+            style.set_inverse(self.path.first() == Some(&i));
+
+            let mut pos = ctx.put(' ', style, LocalPos::new(x, 0));
+            for (c, style) in menu.label.chars(style) {
+                pos = pos.and_then(|pos| ctx.put(c, style, pos));
+            }
+            if let Some(pos) = pos {
+                ctx.put(' ', style, pos);
+            }
+        }
+
+        let dropdowns = self.dropdowns();
+        for (level, (entries, pos, size)) in dropdowns.into_iter().enumerate() {
+            Self::paint_dropdown(entries, self.path[level + 1], pos, size, style);
+        }
+    }
+
+    fn on_event(&mut self, event: &Event, _: EventCtx<'_>) -> EventResult {
+        match *event {
+            Event::KeyPress(code, modifiers, _) => self.key_press(code, modifiers),
+            Event::MouseDown(x, y, MouseButton::Left, ..) => self.mouse_down(x, y),
+            Event::MouseMove(x, y, _) if self.is_open() => self.mouse_move(x, y),
+            Event::Action(action) if action == "menubar.open" && !self.menus.is_empty() => {
+                self.open(0);
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+pub(crate) struct MenuBarFactory;
+
+impl WidgetFactory for MenuBarFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = MenuBar {
+            style: ctx.style(),
+            menus: vec![],
+            path: vec![],
+            pos: Pos::ZERO,
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::drain_actions;
+    use anathema_widget_core::expressions::Expression;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use super::*;
+    use crate::testing::{alt, click, key, test_widget, test_widget_events};
+
+    fn item(label: &str, action: &str) -> Expression {
+        expression(
+            "menuitem",
+            Some(label.into()),
+            [("action".to_string(), action.into())],
+            [],
+        )
+    }
+
+    fn menu<const N: usize>(title: &str, items: [Expression; N]) -> Expression {
+        expression("menu", None, [("title".to_string(), title.into())], items)
+    }
+
+    fn menubar() -> Expression {
+        expression(
+            "menubar",
+            None,
+            [],
+            [
+                menu(
+                    "&File",
+                    [
+                        item("&Open", "file.open"),
+                        item("&Save", "file.save"),
+                        menu("E&xport", [item("&PDF", "export.pdf")]),
+                    ],
+                ),
+                menu("&Help", [item("&About", "help.about")]),
+            ],
+        )
+    }
+
+    #[test]
+    fn parse_mnemonics() {
+        let label = Label::parse("&File");
+        assert_eq!(label.text, "File");
+        assert_eq!(label.mnemonic(), Some('f'));
+
+        let label = Label::parse("Save && E&xit");
+        assert_eq!(label.text, "Save & Exit");
+        assert_eq!(label.mnemonic(), Some('x'));
+
+        assert_eq!(Label::parse("Plain").mnemonic(), None);
+    }
+
+    #[test]
+    fn closed_menu_bar() {
+        test_widget(
+            menubar(),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║ File  Help    ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn open_with_mnemonic() {
+        test_widget_events(
+            menubar(),
+            &[
+                alt('f'),
+                key(KeyCode::Down),
+                key(KeyCode::Down),
+                key(KeyCode::Right),
+            ],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══════════╗
+            ║ File  Help             ║
+            ║┌──────────┐            ║
+            ║│ Open     │            ║
+            ║│ Save     │            ║
+            ║│ Export › │┌─────┐     ║
+            ║└──────────┘│ PDF │     ║
+            ║            └─────┘     ║
+            ╚════════════════════════╝
+            "#,
+            ),
+        );
+        assert!(drain_actions().is_empty());
+    }
+
+    #[test]
+    fn choose_items() {
+        // Keyboard
+        test_widget_events(
+            menubar(),
+            &[alt('f'), key(KeyCode::Char('x')), key(KeyCode::Enter)],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║ File  Help    ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+        assert_eq!(drain_actions(), vec![Symbol::new("export.pdf")]);
+
+        // Mouse: open the help menu, then choose "About"
+        test_widget_events(
+            menubar(),
+            &[click(7, 0), click(10, 2)],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║ File  Help    ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+        assert_eq!(drain_actions(), vec![Symbol::new("help.about")]);
+    }
+
+    #[test]
+    fn close_menu() {
+        test_widget_events(
+            menubar(),
+            &[alt('f'), key(KeyCode::Right), key(KeyCode::Esc)],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║ File  Help    ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+
+        // Clicking away
+        test_widget_events(
+            menubar(),
+            &[alt('h'), click(14, 1)],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║ File  Help    ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+        assert!(drain_actions().is_empty());
+    }
+}
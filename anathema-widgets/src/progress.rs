@@ -0,0 +1,198 @@
+use anathema_render::Size;
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::{
+    AnyWidget, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget, WidgetFactory, WidgetStyle,
+};
+
+const DEFAULT_PROGRESS_WIDTH: usize = 20;
+const DEFAULT_PROGRESS_RANGE: (f64, f64) = (0.0, 100.0);
+const FULL: char = '█';
+// A cell filled by one to seven eighths, from the left
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// A horizontal bar filled from the left by how far the `value` is
+/// from `min` to `max`.
+///
+/// The last cell of the bar is partially filled, in eighths of a cell,
+/// so the bar moves smoothly. Terminals or fonts without the partial
+/// block characters can set `whole-cells` to only fill entire cells.
+///
+/// The bar is as wide as the space available to it, or 20 cells
+/// if the space is unbounded.
+///
+/// Attributes:
+/// * `value`: the progress
+/// * `min`, `max`: the range of the progress, defaults to `0` to `100`
+/// * `whole-cells`: don't partially fill the last cell
+///
+/// ```text
+/// progress [value: downloaded, max: total, foreground: "green"]
+/// ```
+///
+/// ```text
+/// ██████████▋
+/// ```
+#[derive(Debug)]
+pub struct Progress {
+    pub value: Value<f64>,
+    pub min: Value<f64>,
+    pub max: Value<f64>,
+    pub whole_cells: Value<bool>,
+    /// The style of the bar
+    pub style: WidgetStyle,
+    width: usize,
+}
+
+impl Progress {
+    /// Widget name.
+    pub const KIND: &'static str = "Progress";
+
+    /// How far the value is from the min to the max, from `0` to `1`
+    pub fn fraction(&self) -> f64 {
+        let min = self.min.value_or(DEFAULT_PROGRESS_RANGE.0);
+        let max = self.max.value_or(DEFAULT_PROGRESS_RANGE.1);
+        let value = self.value.value_or(min);
+        match max > min {
+            true => ((value - min) / (max - min)).clamp(0.0, 1.0),
+            false => 0.0,
+        }
+    }
+
+    // The filled cells, and the partially filled cell after them
+    fn bar(&self) -> (usize, Option<char>) {
+        let filled = self.fraction() * self.width as f64;
+        if self.whole_cells.value_or_default() {
+            return (filled.round() as usize, None);
+        }
+
+        let eighths = (filled * 8.0).round() as usize;
+        let partial = (eighths % 8).checked_sub(1).map(|i| EIGHTHS[i]);
+        (eighths / 8, partial)
+    }
+}
+
+impl Widget for Progress {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.value.resolve(context, node_id);
+        self.min.resolve(context, node_id);
+        self.max.resolve(context, node_id);
+        self.whole_cells.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        self.width = match constraints.is_width_unbounded() {
+            true => DEFAULT_PROGRESS_WIDTH,
+            false => constraints.max_width,
+        };
+
+        Ok(Size::new(self.width, constraints.max_height.min(1)))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let style = self.style.style();
+        let (filled, partial) = self.bar();
+
+        for x in 0..self.width {
+            let c = match x.cmp(&filled) {
+                std::cmp::Ordering::Less => FULL,
+                std::cmp::Ordering::Equal => partial.unwrap_or(' '),
+                std::cmp::Ordering::Greater => ' ',
+            };
+            ctx.put(c, style, LocalPos::new(x, 0));
+        }
+    }
+}
+
+pub(crate) struct ProgressFactory;
+
+impl WidgetFactory for ProgressFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Progress {
+            value: ctx.get("value"),
+            min: ctx.get("min"),
+            max: ctx.get("max"),
+            whole_cells: ctx.get("whole-cells"),
+            style: ctx.style(),
+            width: 0,
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_values::ValueExpr;
+    use anathema_widget_core::expressions::Expression;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use crate::testing::test_widget;
+
+    fn progress(attributes: impl IntoIterator<Item = (&'static str, ValueExpr)>) -> Expression {
+        let attributes = attributes
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value));
+        expression("progress", None, attributes, [])
+    }
+
+    #[test]
+    fn partial_cell() {
+        test_widget(
+            progress([("value", 45.into())]),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══╗
+            ║███████▎        ║
+            ╚════════════════╝
+            "#,
+            ),
+        );
+
+        test_widget(
+            progress([("value", 3.into()), ("min", 1.into()), ("max", 5.into())]),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══╗
+            ║████████        ║
+            ╚════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn whole_cells() {
+        test_widget(
+            progress([("value", 45.into()), ("whole-cells", true.into())]),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══╗
+            ║███████         ║
+            ╚════════════════╝
+            "#,
+            ),
+        );
+
+        // The value is clamped to the range
+        test_widget(
+            progress([("value", 200.into())]),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [══╗
+            ║████████████████║
+            ╚════════════════╝
+            "#,
+            ),
+        );
+    }
+}
@@ -0,0 +1,509 @@
+use std::fmt;
+
+use anathema_render::Size;
+use anathema_values::{
+    impl_dyn_value, Context, DynValue, Immediate, NodeId, Value, ValueExpr, ValueRef,
+};
+use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::layout::Axis;
+use anathema_widget_core::{
+    Align, AnyWidget, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget, WidgetFactory,
+    WidgetStyle,
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// The line before a label aligned to the start of a rule
+const LEAD: usize = 2;
+// The length of a ruler without constraints
+const DEFAULT_LENGTH: usize = 40;
+const DEFAULT_STEP: usize = 10;
+
+/// The characters of a [`Rule`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineStyle {
+    /// `─` / `│`
+    #[default]
+    Thin,
+    /// `━` / `┃`
+    Thick,
+    /// `═` / `║`
+    Double,
+    /// The first character of any other string, e.g `"*"`
+    Custom(char),
+}
+
+impl LineStyle {
+    fn char(&self, axis: Axis) -> char {
+        match (self, axis) {
+            (Self::Thin, Axis::Horizontal) => '─',
+            (Self::Thin, Axis::Vertical) => '│',
+            (Self::Thick, Axis::Horizontal) => '━',
+            (Self::Thick, Axis::Vertical) => '┃',
+            (Self::Double, Axis::Horizontal) => '═',
+            (Self::Double, Axis::Vertical) => '║',
+            (Self::Custom(c), _) => *c,
+        }
+    }
+}
+
+impl_dyn_value!(LineStyle);
+
+impl TryFrom<ValueRef<'_>> for LineStyle {
+    type Error = ();
+
+    fn try_from(value: ValueRef<'_>) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ValueRef::Str("thin") => Ok(Self::Thin),
+            ValueRef::Str("thick") => Ok(Self::Thick),
+            ValueRef::Str("double") => Ok(Self::Double),
+            ValueRef::Str(s) => s.chars().next().map(Self::Custom).ok_or(()),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for LineStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Thin => write!(f, "thin"),
+            Self::Thick => write!(f, "thick"),
+            Self::Double => write!(f, "double"),
+            Self::Custom(c) => write!(f, "{c}"),
+        }
+    }
+}
+
+fn main_axis(size: Size, axis: Axis) -> usize {
+    match axis {
+        Axis::Horizontal => size.width,
+        Axis::Vertical => size.height,
+    }
+}
+
+/// A horizontal or vertical line with an optional label,
+/// to separate the children of a stack.
+///
+/// The rule fills the available space along the axis.
+///
+/// Attributes:
+/// * `axis`: `"horizontal"` (default) or `"vertical"`
+/// * `line-style`: `"thin"` (default), `"thick"`, `"double"`,
+///   or a string where the first character is used for the line
+/// * `align`: the position of the label, `"left"` (default), `"centre"` or `"right"`
+///
+/// ```text
+/// vstack
+///     text "Inbox"
+///     rule [align: "centre"] "Yesterday"
+///     text "Archive"
+/// ```
+///
+/// ```text
+/// Inbox
+/// ── Yesterday ──
+/// Archive
+/// ```
+#[derive(Debug)]
+pub struct Rule {
+    /// The label
+    pub text: Value<String>,
+    /// Horizontal or vertical
+    pub axis: Value<Axis>,
+    /// The characters of the line
+    pub line_style: Value<LineStyle>,
+    /// The position of the label
+    pub align: Value<Align>,
+    /// The style of the line and the label
+    pub style: WidgetStyle,
+}
+
+impl Rule {
+    /// Widget name.
+    pub const KIND: &'static str = "Rule";
+
+    fn axis(&self) -> Axis {
+        self.axis.value_or(Axis::Horizontal)
+    }
+
+    // The position of the label, including the space on each side
+    fn label_start(&self, len: usize) -> usize {
+        let label = self.text.str().width() + 2;
+        match self.align.value() {
+            Some(Align::Centre | Align::Top | Align::Bottom) => len.saturating_sub(label) / 2,
+            Some(Align::Right | Align::TopRight | Align::BottomRight) => {
+                len.saturating_sub(label + LEAD)
+            }
+            _ => LEAD,
+        }
+    }
+}
+
+impl Widget for Rule {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.text.resolve(context, node_id);
+        self.axis.resolve(context, node_id);
+        self.line_style.resolve(context, node_id);
+        self.align.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        let label = match self.text.str().is_empty() {
+            true => 0,
+            false => self.text.str().width() + 2,
+        };
+        let min = (label + LEAD * 2).max(1);
+
+        let size = match self.axis() {
+            Axis::Horizontal => match constraints.is_width_unbounded() {
+                true => Size::new(min, 1),
+                false => Size::new(constraints.max_width, 1),
+            },
+            Axis::Vertical => match constraints.is_height_unbounded() {
+                true => Size::new(1, min),
+                false => Size::new(1, constraints.max_height),
+            },
+        };
+
+        Ok(Size::new(
+            size.width.min(constraints.max_width),
+            size.height.min(constraints.max_height),
+        ))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let axis = self.axis();
+        let style = self.style.style();
+        let line = self.line_style.value_or_default().char(axis);
+        let len = main_axis(ctx.local_size, axis);
+        let pos = |i: usize| match axis {
+            Axis::Horizontal => LocalPos::new(i, 0),
+            Axis::Vertical => LocalPos::new(0, i),
+        };
+
+        for i in 0..len {
+            ctx.put(line, style, pos(i));
+        }
+
+        let label = self.text.str();
+        if label.is_empty() {
+            return;
+        }
+
+        let start = self.label_start(len);
+        match axis {
+            Axis::Horizontal => {
+                ctx.print(&format!(" {label} "), style, pos(start));
+            }
+            // One character per line, leaving out wide characters
+            Axis::Vertical => {
+                let chars = label.chars().filter(|c| c.width() == Some(1));
+                for (i, c) in [' '].into_iter().chain(chars).chain([' ']).enumerate() {
+                    ctx.put(c, style, pos(start + i));
+                }
+            }
+        }
+    }
+}
+
+pub(crate) struct RuleFactory;
+
+impl WidgetFactory for RuleFactory {
+    fn make(&self, mut ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Rule {
+            axis: ctx.get("axis"),
+            line_style: ctx.get("line-style"),
+            align: ctx.get("align"),
+            style: ctx.style(),
+            text: ctx.text.take(),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+/// A numbered guide of the columns (or lines) next to it,
+/// to line things up while building a layout.
+///
+/// The ruler fills the available space along the axis.
+/// Every `step` columns there is a number and a tick, with a smaller tick halfway.
+///
+/// Attributes:
+/// * `axis`: `"horizontal"` (default) or `"vertical"`
+/// * `start`: the number of the first column or line, defaults to `0`
+/// * `step`: the columns or lines between the numbers, defaults to `10`
+///
+/// ```text
+/// ruler [start: 1]
+/// ```
+///
+/// ```text
+///          10        20
+/// ────┴────┼────┴────┼─
+/// ```
+#[derive(Debug)]
+pub struct Ruler {
+    /// Horizontal or vertical
+    pub axis: Value<Axis>,
+    /// The number of the first column or line
+    pub start: Value<usize>,
+    /// The columns or lines between the numbers
+    pub step: Value<usize>,
+    /// The style of the ruler
+    pub style: WidgetStyle,
+}
+
+impl Ruler {
+    /// Widget name.
+    pub const KIND: &'static str = "Ruler";
+
+    fn axis(&self) -> Axis {
+        self.axis.value_or(Axis::Horizontal)
+    }
+
+    fn step(&self) -> usize {
+        self.step.value_or(DEFAULT_STEP).max(1)
+    }
+
+    // A full tick for every step and a half tick halfway
+    fn tick(&self, n: usize) -> Tick {
+        let step = self.step();
+        match n % step {
+            0 => Tick::Full,
+            m if step.is_multiple_of(2) && m == step / 2 => Tick::Half,
+            _ => Tick::None,
+        }
+    }
+}
+
+enum Tick {
+    Full,
+    Half,
+    None,
+}
+
+impl Widget for Ruler {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.axis.resolve(context, node_id);
+        self.start.resolve(context, node_id);
+        self.step.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        let start = self.start.value_or_default();
+
+        let size = match self.axis() {
+            Axis::Horizontal => match constraints.is_width_unbounded() {
+                true => Size::new(DEFAULT_LENGTH, 2),
+                false => Size::new(constraints.max_width, 2),
+            },
+            Axis::Vertical => {
+                let height = match constraints.is_height_unbounded() {
+                    true => DEFAULT_LENGTH,
+                    false => constraints.max_height,
+                };
+                let last = start + height.saturating_sub(1);
+                Size::new(last.to_string().len() + 1, height)
+            }
+        };
+
+        Ok(Size::new(
+            size.width.min(constraints.max_width),
+            size.height.min(constraints.max_height),
+        ))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let style = self.style.style();
+        let start = self.start.value_or_default();
+
+        match self.axis() {
+            Axis::Horizontal => {
+                // Numbers that don't fit before the next number are left out
+                let mut free = 0;
+                for x in 0..ctx.local_size.width {
+                    let n = start + x;
+                    let tick = self.tick(n);
+                    let c = match tick {
+                        Tick::Full => '┼',
+                        Tick::Half => '┴',
+                        Tick::None => '─',
+                    };
+                    ctx.put(c, style, LocalPos::new(x, 1));
+
+                    if matches!(tick, Tick::Full) && x >= free {
+                        let number = n.to_string();
+                        free = x + number.len() + 1;
+                        ctx.print(&number, style, LocalPos::new(x, 0));
+                    }
+                }
+            }
+            Axis::Vertical => {
+                let digits = ctx.local_size.width.saturating_sub(1);
+                for y in 0..ctx.local_size.height {
+                    let n = start + y;
+                    let tick = self.tick(n);
+                    let c = match tick {
+                        Tick::Full => '┼',
+                        Tick::Half => '┤',
+                        Tick::None => '│',
+                    };
+                    ctx.put(c, style, LocalPos::new(digits, y));
+
+                    if matches!(tick, Tick::Full) {
+                        let number = n.to_string();
+                        let x = digits.saturating_sub(number.len());
+                        ctx.print(&number, style, LocalPos::new(x, y));
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) struct RulerFactory;
+
+impl WidgetFactory for RulerFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Ruler {
+            axis: ctx.get("axis"),
+            start: ctx.get("start"),
+            step: ctx.get("step"),
+            style: ctx.style(),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use crate::testing::test_widget;
+
+    #[test]
+    fn rule_with_label() {
+        let vstack = expression(
+            "vstack",
+            None,
+            [],
+            [
+                expression("rule", Some("left".into()), [], []),
+                expression(
+                    "rule",
+                    Some("mid".into()),
+                    [("align".into(), "centre".into())],
+                    [],
+                ),
+                expression(
+                    "rule",
+                    Some("right".into()),
+                    [
+                        ("align".into(), "right".into()),
+                        ("line-style".into(), "double".into()),
+                    ],
+                    [],
+                ),
+                expression("rule", None, [("line-style".into(), "*".into())], []),
+            ],
+        );
+
+        test_widget(
+            vstack,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║── left ───────║
+            ║───── mid ─────║
+            ║══════ right ══║
+            ║***************║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn vertical_rule() {
+        test_widget(
+            expression(
+                "rule",
+                Some("ab".into()),
+                [("axis".into(), "vertical".into())],
+                [],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║│              ║
+            ║│              ║
+            ║               ║
+            ║a              ║
+            ║b              ║
+            ║               ║
+            ║│              ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn horizontal_ruler() {
+        test_widget(
+            expression("ruler", None, [("step".into(), 4.into())], []),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║0   4   8   12 ║
+            ║┼─┴─┼─┴─┼─┴─┼─┴║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn vertical_ruler() {
+        test_widget(
+            expression(
+                "ruler",
+                None,
+                [
+                    ("axis".into(), "vertical".into()),
+                    ("start".into(), 8.into()),
+                    ("step".into(), 2.into()),
+                ],
+                [],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║ 8┼            ║
+            ║  ┤            ║
+            ║10┼            ║
+            ║  ┤            ║
+            ║12┼            ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use anathema_render::Size;
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::{
+    AnyWidget, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget, WidgetFactory, WidgetStyle,
+};
+
+const DEFAULT_WIDTH: usize = 40;
+const DEFAULT_HEIGHT: usize = 10;
+const BRAILLE: u32 = 0x2800;
+// The bit of each dot in a braille character, by column and row
+const DOTS: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// Plot points with braille dots, where each cell holds two by four dots.
+///
+/// The plot fills the space available to it, and defaults to 40 by 10 cells
+/// if the space is unbounded.
+///
+/// Attributes:
+/// * `x`, `y`: lists of numbers, the coordinates of the points.
+///   Without `x` the points are spread evenly by their index
+/// * `x-min`, `x-max`, `min`, `max`: the range of each axis,
+///   defaults to the smallest and the largest value.
+///   Points outside of the range are not drawn
+///
+/// ```text
+/// scatter [x: latency, y: size, foreground: "green"]
+/// ```
+#[derive(Debug)]
+pub struct Scatter {
+    pub x: Value<Vec<f64>>,
+    pub y: Value<Vec<f64>>,
+    pub x_min: Value<f64>,
+    pub x_max: Value<f64>,
+    pub min: Value<f64>,
+    pub max: Value<f64>,
+    pub style: WidgetStyle,
+}
+
+impl Scatter {
+    /// Widget name.
+    pub const KIND: &'static str = "Scatter";
+
+    fn points(&self) -> Vec<(f64, f64)> {
+        let ys = self.y.value_ref().map_or(&[][..], Vec::as_slice);
+        match self.x.value_ref() {
+            Some(xs) => xs.iter().copied().zip(ys.iter().copied()).collect(),
+            None => ys.iter().enumerate().map(|(i, y)| (i as f64, *y)).collect(),
+        }
+    }
+}
+
+fn range(
+    values: impl Iterator<Item = f64> + Clone,
+    min: &Value<f64>,
+    max: &Value<f64>,
+) -> (f64, f64) {
+    let min = min
+        .value()
+        .unwrap_or_else(|| values.clone().fold(f64::INFINITY, f64::min));
+    let max = max
+        .value()
+        .unwrap_or_else(|| values.fold(f64::NEG_INFINITY, f64::max));
+    (min, max)
+}
+
+// The dot of a value on an axis of `len` dots.
+// A range without a length puts every value in the middle.
+fn scale(value: f64, (min, max): (f64, f64), len: usize) -> Option<usize> {
+    if !(min..=max).contains(&value) {
+        return None;
+    }
+
+    match max > min {
+        true => Some(((value - min) / (max - min) * (len - 1) as f64).round() as usize),
+        false => Some(len / 2),
+    }
+}
+
+impl Widget for Scatter {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.x.resolve(context, node_id);
+        self.y.resolve(context, node_id);
+        self.x_min.resolve(context, node_id);
+        self.x_max.resolve(context, node_id);
+        self.min.resolve(context, node_id);
+        self.max.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        let width = match constraints.is_width_unbounded() {
+            true => DEFAULT_WIDTH,
+            false => constraints.max_width,
+        };
+        let height = match constraints.is_height_unbounded() {
+            true => DEFAULT_HEIGHT,
+            false => constraints.max_height,
+        };
+
+        Ok(Size::new(width, height))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let Size { width, height } = ctx.local_size;
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let points = self.points();
+        let x_range = range(points.iter().map(|p| p.0), &self.x_min, &self.x_max);
+        let y_range = range(points.iter().map(|p| p.1), &self.min, &self.max);
+        let (dots_x, dots_y) = (width * 2, height * 4);
+
+        let mut cells = vec![0u8; width * height];
+        for (x, y) in points {
+            let (Some(x), Some(y)) = (scale(x, x_range, dots_x), scale(y, y_range, dots_y)) else {
+                continue;
+            };
+            // The y axis goes up
+            let y = dots_y - 1 - y;
+            cells[y / 4 * width + x / 2] |= DOTS[x % 2][y % 4];
+        }
+
+        let style = self.style.style();
+        for (i, dots) in cells.into_iter().enumerate() {
+            if dots == 0 {
+                continue;
+            }
+            let c = char::from_u32(BRAILLE + dots as u32).unwrap_or(' ');
+            ctx.put(c, style, LocalPos::new(i % width, i / width));
+        }
+    }
+}
+
+pub(crate) struct ScatterFactory;
+
+impl WidgetFactory for ScatterFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Scatter {
+            x: ctx.get("x"),
+            y: ctx.get("y"),
+            x_min: ctx.get("x-min"),
+            x_max: ctx.get("x-max"),
+            min: ctx.get("min"),
+            max: ctx.get("max"),
+            style: ctx.style(),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_values::testing::{list, unum};
+    use anathema_values::ValueExpr;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use crate::testing::test_widget;
+
+    fn numbers(values: &[u64]) -> ValueExpr {
+        *list(values.iter().map(|v| *unum(*v)))
+    }
+
+    #[test]
+    fn plot_points() {
+        let scatter = expression(
+            "scatter",
+            None,
+            [
+                ("x".to_string(), numbers(&[0, 1, 2, 3])),
+                ("y".to_string(), numbers(&[0, 1, 2, 3])),
+            ],
+            [],
+        );
+
+        test_widget(
+            scatter,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║⡀    ⠄   ⠐    ⠈║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn points_outside_of_the_range() {
+        let scatter = expression(
+            "scatter",
+            None,
+            [
+                ("y".to_string(), numbers(&[5, 50, 5])),
+                ("max".to_string(), 10.into()),
+            ],
+            [],
+        );
+
+        test_widget(
+            scatter,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [╗
+            ║⡀            ⢀║
+            ╚══════════════╝
+            "#,
+            ),
+        );
+    }
+}
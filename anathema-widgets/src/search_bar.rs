@@ -0,0 +1,280 @@
+use std::cell::RefCell;
+use std::ops::Range;
+
+use anathema_render::{Size, Style};
+use anathema_values::search::{Match, Search};
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::{
+    send_change, AnyWidget, Event, EventResult, FactoryContext, LayoutNodes, LocalPos, Nodes,
+    Widget, WidgetFactory, WidgetStyle,
+};
+use unicode_width::UnicodeWidthStr;
+
+// The search of the last search bar that was updated,
+// highlighted by the texts with a `search-item`
+thread_local! {
+    static ACTIVE: RefCell<Option<Search>> = const { RefCell::new(None) };
+}
+
+/// Make the search the active search
+pub(crate) fn set_active(search: Option<Search>) {
+    ACTIVE.with_borrow_mut(|active| *active = search);
+}
+
+/// The occurrences of the active search in the text of an item of the source,
+/// and the occurrence that is the current match, if any.
+pub(crate) fn occurrences(item: usize, text: &str) -> (Vec<Range<usize>>, Option<Range<usize>>) {
+    ACTIVE.with_borrow(|search| {
+        let Some(search) = search else {
+            return (vec![], None);
+        };
+
+        let current = search
+            .current_match()
+            .filter(|found| found.item == item)
+            .map(|found| found.range.clone());
+        (anathema_values::search::find(search.query(), text), current)
+    })
+}
+
+/// An incremental search of the texts of the `source` (e.g the lines of a log),
+/// showing the `query` and which match is the current match out of how many.
+///
+/// The `search.next` and `search.previous` actions move to the next
+/// and previous match, wrapping around at the ends, and send an [`Event::Change`]
+/// with the `action` and the index of the text of the match,
+/// which the view uses to scroll it into view, e.g with the `ensure-visible`
+/// attribute of a `viewport`.
+///
+/// Texts with a `search-item` attribute, the index of the text in the source,
+/// paint the occurrences of the query in bold and the current match inverted.
+///
+/// Attributes:
+/// * `query`: the text to search for, matched ignoring case unless it has upper case characters
+/// * `source`: a list of texts
+/// * `action`: the name of the change
+///
+/// ```text
+/// vstack
+///     searchbar [query: query, source: lines, action: "match"]
+///     viewport [ensure-visible: current_line]
+///         for line in lines
+///             text [search-item: loop] line
+/// ```
+///
+/// ```text
+/// error                    2/5
+/// ```
+#[derive(Debug)]
+pub struct SearchBar {
+    pub query: Value<String>,
+    pub source: Value<Vec<String>>,
+    pub action: Value<String>,
+    /// The style of the search bar
+    pub style: WidgetStyle,
+    search: Search,
+}
+
+impl SearchBar {
+    /// Widget name.
+    pub const KIND: &'static str = "SearchBar";
+
+    /// The matches of the query, and the current match
+    pub fn search(&self) -> &Search {
+        &self.search
+    }
+
+    // Search the source, and make it the active search
+    fn sync(&mut self) {
+        let query = self.query.value_ref().map_or("", String::as_str);
+        let source = self.source.value_ref().map_or(&[][..], Vec::as_slice);
+        self.search.update(query, source);
+        self.activate();
+    }
+
+    fn activate(&self) {
+        set_active(Some(self.search.clone()));
+    }
+
+    // Send the item of the current match
+    fn moved(&self, found: Option<Match>) -> EventResult {
+        let Some(found) = found else {
+            return EventResult::Ignored;
+        };
+
+        self.activate();
+        if let Some(action) = self.action.value_ref() {
+            send_change(action.as_str(), found.item as f64);
+        }
+        EventResult::Consumed
+    }
+
+    fn status(&self) -> String {
+        match (self.search.query(), self.search.current()) {
+            ("", _) => String::new(),
+            (_, None) => "no matches".to_string(),
+            (_, Some(current)) => format!("{}/{}", current + 1, self.search.count()),
+        }
+    }
+}
+
+impl Widget for SearchBar {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.query.resolve(context, node_id);
+        self.source.resolve(context, node_id);
+        self.action.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+        self.sync();
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        Ok(Size::new(
+            constraints.max_width,
+            constraints.max_height.min(1),
+        ))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let style = self.style.style();
+        ctx.print(self.search.query(), style, LocalPos::ZERO);
+
+        // The status is right aligned, and painted over a query that is too long
+        let status = self.status();
+        let width = ctx.local_size.width;
+        let x = width.saturating_sub(status.width());
+        let mut status_style = Style::new();
+        status_style.set_dim(true);
+        status_style.merge(style);
+        ctx.print(
+            &format!(" {status}"),
+            status_style,
+            LocalPos::new(x.saturating_sub(1), 0),
+        );
+    }
+
+    fn on_event(&mut self, event: &Event, _: EventCtx<'_>) -> EventResult {
+        match event {
+            Event::Action(action) if action == "search.next" => {
+                let found = self.search.next().cloned();
+                self.moved(found)
+            }
+            Event::Action(action) if action == "search.previous" => {
+                let found = self.search.previous().cloned();
+                self.moved(found)
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn on_remove(&mut self) {
+        ACTIVE.with_borrow_mut(|active| {
+            if active.as_ref() == Some(&self.search) {
+                *active = None;
+            }
+        });
+    }
+}
+
+pub(crate) struct SearchBarFactory;
+
+impl WidgetFactory for SearchBarFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let mut widget = SearchBar {
+            query: ctx.get("query"),
+            source: ctx.get("source"),
+            action: ctx.get("action"),
+            style: ctx.style(),
+            search: Search::default(),
+        };
+        widget.sync();
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_values::testing::{list, strlit};
+    use anathema_values::{Symbol, ValueExpr};
+    use anathema_widget_core::drain_changes;
+    use anathema_widget_core::expressions::Expression;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use super::*;
+    use crate::testing::{test_widget, test_widget_events};
+
+    fn search_bar(query: &str) -> Expression {
+        let lines = ["error: disk", "ok", "warning", "error: network"];
+        expression(
+            "searchbar",
+            None,
+            [
+                ("query".to_string(), ValueExpr::from(query)),
+                ("source".to_string(), *list(lines.map(|line| *strlit(line)))),
+                ("action".to_string(), ValueExpr::from("match")),
+            ],
+            [],
+        )
+    }
+
+    #[test]
+    fn count_matches() {
+        test_widget(
+            search_bar("ERROR"),
+            FakeTerm::from_str(
+                r#"
+                ╔═] Fake term [══════════╗
+                ║ERROR         no matches║
+                ╚════════════════════════╝
+                "#,
+            ),
+        );
+
+        test_widget(
+            search_bar("r"),
+            FakeTerm::from_str(
+                r#"
+                ╔═] Fake term [══════════╗
+                ║r                    1/8║
+                ╚════════════════════════╝
+                "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn next_and_previous_match() {
+        let next = Event::Action(Symbol::new("search.next"));
+        let previous = Event::Action(Symbol::new("search.previous"));
+        test_widget_events(
+            search_bar("error"),
+            &[next.clone(), next, previous.clone(), previous],
+            FakeTerm::from_str(
+                r#"
+                ╔═] Fake term [══════════╗
+                ║error                1/2║
+                ╚════════════════════════╝
+                "#,
+            ),
+        );
+
+        let changes = drain_changes()
+            .into_iter()
+            .map(|(action, item)| {
+                assert_eq!(action, Symbol::new("match"));
+                item
+            })
+            .collect::<Vec<_>>();
+        // Wrapping around in both directions
+        assert_eq!(changes, vec![3.0, 0.0, 3.0, 0.0]);
+    }
+}
@@ -0,0 +1,367 @@
+use anathema_render::{Size, Style};
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::layout::Constraints;
+use anathema_widget_core::{
+    send_change, AnyWidget, Event, EventResult, FactoryContext, KeyCode, LayoutNodes, LocalPos,
+    MouseButton, Nodes, Widget, WidgetFactory, WidgetStyle,
+};
+use unicode_width::UnicodeWidthStr;
+
+const SEPARATOR: char = '│';
+
+// -----------------------------------------------------------------------------
+//   - Segment -
+// -----------------------------------------------------------------------------
+/// An option of a [`Segmented`] control.
+/// The `value` is compared to the value of the control, and defaults to the label.
+///
+/// ```text
+/// segment [value: "daily", foreground: green] "Daily"
+/// ```
+#[derive(Debug)]
+pub struct Segment {
+    pub label: Value<String>,
+    pub value: Value<String>,
+    /// The style of the segment, on top of the style of the control
+    pub style: WidgetStyle,
+}
+
+impl Segment {
+    /// Widget name.
+    pub const KIND: &'static str = "Segment";
+
+    fn label(&self) -> &str {
+        self.label.value_ref().map_or("", String::as_str)
+    }
+
+    fn value(&self) -> &str {
+        self.value
+            .value_ref()
+            .map_or_else(|| self.label(), String::as_str)
+    }
+}
+
+impl Widget for Segment {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.label.resolve(context, node_id);
+        self.value.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    // Segments are painted by the segmented control
+    fn layout(&mut self, _: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        Ok(Size::ZERO)
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, _: PaintCtx<'_, WithSize>) {}
+}
+
+pub(crate) struct SegmentFactory;
+
+impl WidgetFactory for SegmentFactory {
+    fn make(&self, mut ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Segment {
+            label: ctx.text.take(),
+            value: ctx.get("value"),
+            style: ctx.style(),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Segmented -
+// -----------------------------------------------------------------------------
+#[derive(Debug)]
+struct Entry {
+    label: String,
+    value: String,
+    style: Style,
+}
+
+/// A row of mutually exclusive `segment`s, where the segment matching the `value`
+/// is selected. The selected segment is painted inverted.
+///
+/// The left and right arrow keys select the previous and next segment,
+/// wrapping around at the ends, and clicking a segment selects it.
+///
+/// Selecting a segment sends an [`Event::Change`] with the `action`
+/// and the index of the segment, which the view uses to update the bound state.
+///
+/// Attributes:
+/// * `value`: the value of the selected segment
+/// * `action`: the name of the change
+///
+/// ```text
+/// segmented [value: interval, action: "interval"]
+///     segment [value: "hourly"] "Hourly"
+///     segment [value: "daily"] "Daily"
+///     segment [value: "weekly"] "Weekly"
+/// ```
+///
+/// ```text
+///  Hourly │ Daily │ Weekly
+/// ```
+#[derive(Debug)]
+pub struct Segmented {
+    pub value: Value<String>,
+    pub action: Value<String>,
+    /// The style of the control
+    pub style: WidgetStyle,
+    segments: Vec<Entry>,
+    selected: Option<usize>,
+    // The bound value that `selected` was last set from
+    bound: Option<String>,
+}
+
+impl Segmented {
+    /// Widget name.
+    pub const KIND: &'static str = "Segmented";
+
+    /// The index of the selected segment
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    // Select the segment matching the value if the bound value changed
+    fn sync(&mut self) {
+        let bound = self.value.value_ref();
+        if bound != self.bound.as_ref() {
+            self.bound = bound.cloned();
+            self.selected = self
+                .segments
+                .iter()
+                .position(|segment| Some(&segment.value) == bound);
+        }
+        self.selected = self.selected.filter(|index| *index < self.segments.len());
+    }
+
+    // Select a segment, and send the change if the selection changed
+    fn select(&mut self, index: usize) -> EventResult {
+        if self.selected != Some(index) {
+            self.selected = Some(index);
+            if let Some(action) = self.action.value_ref() {
+                send_change(action.as_str(), index as f64);
+            }
+        }
+        EventResult::Consumed
+    }
+
+    // The offset and width of each segment, including the padding
+    fn offsets(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut x = 0;
+        self.segments.iter().map(move |segment| {
+            let offset = x;
+            let width = segment.label.width() + 2;
+            x += width + 1;
+            (offset, width)
+        })
+    }
+
+    fn width(&self) -> usize {
+        self.offsets().last().map_or(0, |(x, width)| x + width)
+    }
+}
+
+impl Widget for Segmented {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.value.resolve(context, node_id);
+        self.action.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        nodes.for_each(|mut node| {
+            node.layout(Constraints::ZERO)?;
+            Ok(())
+        })?;
+
+        self.segments = nodes
+            .filter(|_| true)
+            .filter_map(|node| {
+                let segment = node.try_to_ref::<Segment>()?;
+                Some(Entry {
+                    label: segment.label().to_string(),
+                    value: segment.value().to_string(),
+                    style: segment.style.style(),
+                })
+            })
+            .collect();
+        self.sync();
+
+        let height = match self.segments.is_empty() {
+            true => 0,
+            false => nodes.constraints.max_height.min(1),
+        };
+        Ok(Size::new(
+            self.width().min(nodes.constraints.max_width),
+            height,
+        ))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let control_style = self.style.style();
+
+        for (index, (x, _)) in self.offsets().enumerate() {
+            let segment = &self.segments[index];
+            let mut style = segment.style;
+            style.merge(control_style);
+            style.set_inverse(self.selected == Some(index));
+
+            if index > 0 {
+                ctx.put(SEPARATOR, control_style, LocalPos::new(x - 1, 0));
+            }
+            ctx.print(&format!(" {} ", segment.label), style, LocalPos::new(x, 0));
+        }
+    }
+
+    fn on_event(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult {
+        let last = match self.segments.len() {
+            0 => return EventResult::Ignored,
+            len => len - 1,
+        };
+
+        match *event {
+            Event::KeyPress(KeyCode::Right, ..) => match self.selected {
+                Some(index) if index < last => self.select(index + 1),
+                _ => self.select(0),
+            },
+            Event::KeyPress(KeyCode::Left, ..) => match self.selected {
+                Some(index) if index > 0 => self.select(index - 1),
+                _ => self.select(last),
+            },
+            Event::MouseDown(x, y, MouseButton::Left, ..) => {
+                let Some(pos) = ctx.local_pos(x, y) else {
+                    return EventResult::Ignored;
+                };
+                let index = self
+                    .offsets()
+                    .position(|(x, width)| (x..x + width).contains(&pos.x));
+                match index {
+                    Some(index) => self.select(index),
+                    // The separator between two segments
+                    None => EventResult::Ignored,
+                }
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+pub(crate) struct SegmentedFactory;
+
+impl WidgetFactory for SegmentedFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Segmented {
+            value: ctx.get("value"),
+            action: ctx.get("action"),
+            style: ctx.style(),
+            segments: vec![],
+            selected: None,
+            bound: None,
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_values::{Symbol, ValueExpr};
+    use anathema_widget_core::expressions::Expression;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+    use anathema_widget_core::{drain_changes, KeyModifiers};
+
+    use anathema_render::{Attributes, ScreenPos};
+
+    use super::*;
+    use crate::testing::{key, test_widget, test_widget_events};
+
+    fn segmented() -> Expression {
+        let segment = |label: &str| {
+            let value = ("value".to_string(), ValueExpr::from(label.to_lowercase()));
+            expression("segment", Some(label.into()), [value], [])
+        };
+
+        expression(
+            "segmented",
+            None,
+            [
+                ("value".to_string(), ValueExpr::from("daily")),
+                ("action".to_string(), ValueExpr::from("interval")),
+            ],
+            [segment("Hourly"), segment("Daily"), segment("Weekly")],
+        )
+    }
+
+    fn expected() -> FakeTerm {
+        FakeTerm::from_str(
+            r#"
+            ╔═] Fake term [══════════════╗
+            ║ Hourly │ Daily │ Weekly    ║
+            ╚════════════════════════════╝
+            "#,
+        )
+    }
+
+    #[test]
+    fn select_bound_value() {
+        let term = test_widget(segmented(), expected());
+        let inverse = |x| {
+            let (_, style) = term.screen().get(ScreenPos::new(x, 0)).unwrap();
+            style.attributes.contains(Attributes::INVERSE)
+        };
+        assert!(!inverse(1));
+        assert!(inverse(10));
+        assert!(!inverse(16));
+    }
+
+    #[test]
+    fn cycle_with_keys() {
+        test_widget_events(
+            segmented(),
+            &[key(KeyCode::Right), key(KeyCode::Right), key(KeyCode::Left)],
+            expected(),
+        );
+        let changes = drain_changes()
+            .into_iter()
+            .map(|(action, index)| {
+                assert_eq!(action, Symbol::new("interval"));
+                index
+            })
+            .collect::<Vec<_>>();
+        // From daily to weekly, wrapping around to hourly, and back to weekly
+        assert_eq!(changes, vec![2.0, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn click_segment() {
+        let click = |x| Event::MouseDown(x, 0, MouseButton::Left, KeyModifiers::NONE, 1);
+        test_widget_events(
+            segmented(),
+            // The separator, the selected segment and the first segment
+            &[click(8), click(10), click(1)],
+            expected(),
+        );
+        let changes = drain_changes()
+            .into_iter()
+            .map(|(_, index)| index)
+            .collect::<Vec<_>>();
+        assert_eq!(changes, vec![0.0]);
+    }
+}
@@ -0,0 +1,461 @@
+use anathema_render::Size;
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::{
+    send_change, AnyWidget, Event, EventResult, FactoryContext, KeyCode, LayoutNodes, LocalPos,
+    MouseButton, Nodes, Widget, WidgetFactory, WidgetStyle,
+};
+use unicode_width::UnicodeWidthStr;
+
+const DEFAULT_STEP: f64 = 1.0;
+const DEFAULT_SLIDER_WIDTH: usize = 20;
+const DEFAULT_SLIDER_RANGE: (f64, f64) = (0.0, 100.0);
+const HANDLE: char = '●';
+const TRACK: char = '─';
+const TRACK_FILLED: char = '━';
+
+// A number bound to a value, kept within the range and rounded to the step.
+//
+// Widgets can't change the state, so the number keeps its own value.
+// The value is replaced when the bound value changes.
+#[derive(Debug)]
+struct Number {
+    value: Value<f64>,
+    min: Value<f64>,
+    max: Value<f64>,
+    step: Value<f64>,
+    action: Value<String>,
+    default_range: (f64, f64),
+    current: f64,
+    // The bound value that `current` was last set from
+    bound: Option<f64>,
+}
+
+impl Number {
+    fn new(ctx: &FactoryContext<'_>, default_range: (f64, f64)) -> Self {
+        let mut number = Self {
+            value: ctx.get("value"),
+            min: ctx.get("min"),
+            max: ctx.get("max"),
+            step: ctx.get("step"),
+            action: ctx.get("action"),
+            default_range,
+            current: 0.0,
+            bound: None,
+        };
+        number.sync();
+        number
+    }
+
+    // Replace the value if the bound value changed
+    fn sync(&mut self) {
+        let bound = self.value.value();
+        if bound != self.bound {
+            self.bound = bound;
+            self.current = self.round(bound.unwrap_or(self.current));
+        }
+    }
+
+    fn resolve(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.value.resolve(context, node_id);
+        self.min.resolve(context, node_id);
+        self.max.resolve(context, node_id);
+        self.step.resolve(context, node_id);
+        self.action.resolve(context, node_id);
+        self.sync();
+    }
+
+    fn range(&self) -> (f64, f64) {
+        let min = self.min.value_or(self.default_range.0);
+        let max = self.max.value_or(self.default_range.1).max(min);
+        (min, max)
+    }
+
+    fn step(&self) -> f64 {
+        self.step
+            .value()
+            .filter(|step| *step > 0.0)
+            .unwrap_or(DEFAULT_STEP)
+    }
+
+    // The number of decimals of the step, e.g `0.25` has two decimals
+    fn decimals(&self) -> usize {
+        let step = self.step().to_string();
+        step.split_once('.')
+            .map_or(0, |(_, decimals)| decimals.len())
+    }
+
+    fn round(&self, value: f64) -> f64 {
+        let (min, max) = self.range();
+        let step = self.step();
+        let offset = match min.is_finite() {
+            true => min,
+            false => 0.0,
+        };
+        let value = ((value - offset) / step).round() * step + offset;
+
+        // Remove the rounding errors of the step, e.g `0.30000000000000004`
+        let precision = 10f64.powi(self.decimals() as i32);
+        ((value * precision).round() / precision).clamp(min, max)
+    }
+
+    // Set the value, and send the change if the value changed
+    fn set(&mut self, value: f64) -> EventResult {
+        let value = self.round(value);
+        if value != self.current {
+            self.current = value;
+            if let Some(action) = self.action.value_ref() {
+                send_change(action.as_str(), value);
+            }
+        }
+        EventResult::Consumed
+    }
+
+    fn text(&self) -> String {
+        format!("{:.*}", self.decimals(), self.current)
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Stepper -
+// -----------------------------------------------------------------------------
+/// A number that is changed by a step at a time, with `+` and `-` (or up and down),
+/// or by clicking the buttons on either side.
+///
+/// Changing the number sends an [`Event::Change`] with the `action`
+/// and the new value, which the view uses to update the bound state.
+///
+/// Attributes:
+/// * `value`: the number
+/// * `min`, `max`: the range of the number, unbounded by default
+/// * `step`: the size of a step, defaults to `1`
+/// * `action`: the name of the change
+///
+/// ```text
+/// stepper [value: quantity, min: 1, max: 10, action: "quantity"]
+/// ```
+///
+/// ```text
+/// - 3 +
+/// ```
+#[derive(Debug)]
+pub struct Stepper {
+    /// The style of the stepper
+    pub style: WidgetStyle,
+    number: Number,
+}
+
+impl Stepper {
+    /// Widget name.
+    pub const KIND: &'static str = "Stepper";
+
+    /// The current value
+    pub fn value(&self) -> f64 {
+        self.number.current
+    }
+
+    fn width(&self) -> usize {
+        self.number.text().width() + 4
+    }
+}
+
+impl Widget for Stepper {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.number.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        Ok(Size::new(
+            self.width().min(constraints.max_width),
+            constraints.max_height.min(1),
+        ))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let text = format!("- {} +", self.number.text());
+        ctx.print(&text, self.style.style(), LocalPos::ZERO);
+    }
+
+    fn on_event(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult {
+        let step = self.number.step();
+        let current = self.number.current;
+
+        match *event {
+            Event::KeyPress(KeyCode::Char('+' | '=') | KeyCode::Up, ..) => {
+                self.number.set(current + step)
+            }
+            Event::KeyPress(KeyCode::Char('-') | KeyCode::Down, ..) => {
+                self.number.set(current - step)
+            }
+            Event::MouseDown(x, y, MouseButton::Left, ..) => match ctx.local_pos(x, y) {
+                Some(pos) if pos.x == 0 => self.number.set(current - step),
+                Some(pos) if pos.x + 1 == self.width() => self.number.set(current + step),
+                _ => EventResult::Ignored,
+            },
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+pub(crate) struct StepperFactory;
+
+impl WidgetFactory for StepperFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Stepper {
+            number: Number::new(&ctx, (f64::NEG_INFINITY, f64::INFINITY)),
+            style: ctx.style(),
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Slider -
+// -----------------------------------------------------------------------------
+/// A horizontal track with a handle, moved with the left and right arrow keys
+/// (home and end for the ends of the track), or by clicking or dragging the track.
+///
+/// Moving the handle sends an [`Event::Change`] with the `action`
+/// and the new value, which the view uses to update the bound state.
+///
+/// The slider is as wide as the space available to it, or 20 cells
+/// if the space is unbounded.
+///
+/// Attributes:
+/// * `value`: the number
+/// * `min`, `max`: the range of the number, defaults to `0` to `100`
+/// * `step`: the size of a step, defaults to `1`
+/// * `action`: the name of the change
+///
+/// ```text
+/// slider [value: volume, max: 10, action: "volume"]
+/// ```
+///
+/// ```text
+/// ━━━━━━━━━━━●────────
+/// ```
+#[derive(Debug)]
+pub struct Slider {
+    /// The style of the slider
+    pub style: WidgetStyle,
+    number: Number,
+    width: usize,
+    dragging: bool,
+}
+
+impl Slider {
+    /// Widget name.
+    pub const KIND: &'static str = "Slider";
+
+    /// The current value
+    pub fn value(&self) -> f64 {
+        self.number.current
+    }
+
+    // The position of the handle on the track
+    fn handle(&self) -> usize {
+        let (min, max) = self.number.range();
+        match max > min {
+            true => {
+                let t = (self.number.current - min) / (max - min);
+                (t * self.width.saturating_sub(1) as f64).round() as usize
+            }
+            false => 0,
+        }
+    }
+
+    // The value at a position on the track
+    fn value_at(&self, x: i32) -> f64 {
+        let (min, max) = self.number.range();
+        let last = self.width.saturating_sub(1).max(1) as f64;
+        let t = (x as f64 / last).clamp(0.0, 1.0);
+        min + t * (max - min)
+    }
+}
+
+impl Widget for Slider {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.number.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        self.width = match constraints.is_width_unbounded() {
+            true => DEFAULT_SLIDER_WIDTH,
+            false => constraints.max_width,
+        };
+
+        Ok(Size::new(self.width, constraints.max_height.min(1)))
+    }
+
+    fn position(&mut self, _: &mut Nodes<'_>, _: PositionCtx) {}
+
+    fn paint(&mut self, _: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let style = self.style.style();
+        let handle = self.handle();
+
+        for x in 0..self.width {
+            let c = match x.cmp(&handle) {
+                std::cmp::Ordering::Less => TRACK_FILLED,
+                std::cmp::Ordering::Equal => HANDLE,
+                std::cmp::Ordering::Greater => TRACK,
+            };
+            ctx.put(c, style, LocalPos::new(x, 0));
+        }
+    }
+
+    fn on_event(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult {
+        let step = self.number.step();
+        let current = self.number.current;
+        let (min, max) = self.number.range();
+
+        match *event {
+            Event::KeyPress(KeyCode::Right, ..) => self.number.set(current + step),
+            Event::KeyPress(KeyCode::Left, ..) => self.number.set(current - step),
+            Event::KeyPress(KeyCode::Home, ..) => self.number.set(min),
+            Event::KeyPress(KeyCode::End, ..) => self.number.set(max),
+            Event::MouseDown(x, y, MouseButton::Left, ..) => match ctx.local_pos(x, y) {
+                Some(pos) => {
+                    self.dragging = true;
+                    self.number.set(self.value_at(pos.x as i32))
+                }
+                None => EventResult::Ignored,
+            },
+            Event::MouseDrag(x, _, MouseButton::Left, _) if self.dragging => {
+                self.number.set(self.value_at(x as i32 - ctx.pos.x))
+            }
+            Event::MouseUp(_, _, MouseButton::Left, _) if self.dragging => {
+                self.dragging = false;
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+pub(crate) struct SliderFactory;
+
+impl WidgetFactory for SliderFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Slider {
+            number: Number::new(&ctx, DEFAULT_SLIDER_RANGE),
+            style: ctx.style(),
+            width: 0,
+            dragging: false,
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_values::{Symbol, ValueExpr};
+    use anathema_widget_core::expressions::Expression;
+    use anathema_widget_core::testing::FakeTerm;
+    use anathema_widget_core::{drain_changes, KeyModifiers};
+
+    use super::*;
+    use crate::testing::{key, test_widget, test_widget_events, widget};
+
+    fn stepper(attributes: impl IntoIterator<Item = (&'static str, ValueExpr)>) -> Expression {
+        let action = ("action", ValueExpr::from("count"));
+        widget("stepper", attributes.into_iter().chain([action]))
+    }
+
+    #[test]
+    fn step_with_keys() {
+        test_widget_events(
+            stepper([("value", 3.into()), ("max", 5.into())]),
+            &[key(KeyCode::Char('+')), key(KeyCode::Up), key(KeyCode::Up)],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║- 5 +          ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+        // The last step was past the max
+        let changes = drain_changes();
+        assert_eq!(
+            changes,
+            vec![(Symbol::new("count"), 4.0), (Symbol::new("count"), 5.0)]
+        );
+    }
+
+    #[test]
+    fn click_buttons_with_fractions() {
+        let click = |x| Event::MouseDown(x, 0, MouseButton::Left, KeyModifiers::NONE, 1);
+        test_widget_events(
+            stepper([("step", ValueExpr::from(0.1))]),
+            // Plus, plus, plus and minus
+            &[click(6), click(6), click(6), click(0)],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║- 0.2 +        ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+        drain_changes();
+    }
+
+    #[test]
+    fn slider() {
+        test_widget(
+            widget("slider", [("value", 50.into())]),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [════╗
+            ║━━━━━━━━━●────────║
+            ╚══════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn drag_slider() {
+        let slider = widget("slider", [("max", 10.into()), ("action", "volume".into())]);
+
+        test_widget_events(
+            slider,
+            &[
+                Event::MouseDown(2, 0, MouseButton::Left, KeyModifiers::NONE, 1),
+                Event::MouseDrag(30, 0, MouseButton::Left, KeyModifiers::NONE),
+                Event::MouseUp(30, 0, MouseButton::Left, KeyModifiers::NONE),
+                key(KeyCode::Left),
+            ],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═════╗
+            ║━━━━━━━━━━━━━━━━●──║
+            ╚═══════════════════╝
+            "#,
+            ),
+        );
+
+        let values = drain_changes()
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![1.0, 10.0, 9.0]);
+    }
+}
@@ -0,0 +1,302 @@
+use anathema_render::Size;
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::layout::{Axis, Constraints};
+use anathema_widget_core::{
+    AnyWidget, FactoryContext, LayoutNodes, LocalPos, Nodes, Pos, Widget, WidgetFactory,
+    WidgetStyle,
+};
+
+const DEFAULT_RATIO: f64 = 0.5;
+
+/// Split the available space between two children, separated by a divider.
+///
+/// The `ratio` (a float between `0.0` and `1.0`) is the share of the space given to the
+/// first child. The `axis` decides if the children are placed side by side (`horz`, default)
+/// or on top of each other (`vert`).
+///
+/// The split fills all the space available to it.
+///
+/// ```text
+/// split [ratio: ratio]
+///     text "left"
+///     text "right"
+/// ```
+/// output:
+/// ```text
+/// left│right
+/// ```
+///
+/// To resize the split, bind the `ratio` to state and update it from a view,
+/// using [`Split::divider_contains`] and [`Split::ratio_at`] for mouse events
+/// or [`Split::ratio_step`] for keyboard bindings.
+#[derive(Debug)]
+pub struct Split {
+    /// Place the children along this axis
+    pub axis: Value<Axis>,
+    /// The share of the space given to the first child
+    pub ratio: Value<f64>,
+    /// The style of the divider
+    pub style: WidgetStyle,
+    pos: Pos,
+    size: Size,
+    // Size of the first child along the axis,
+    // which is also the offset of the divider.
+    first: usize,
+}
+
+impl Split {
+    /// Widget name.
+    pub const KIND: &'static str = "Split";
+
+    fn axis(&self) -> Axis {
+        self.axis.value_ref().copied().unwrap_or(Axis::Horizontal)
+    }
+
+    fn ratio(&self) -> f64 {
+        self.ratio.value_or(DEFAULT_RATIO).clamp(0.0, 1.0)
+    }
+
+    // The size along the axis, and the size across the axis
+    fn main_cross(&self, size: Size) -> (usize, usize) {
+        match self.axis() {
+            Axis::Horizontal => (size.width, size.height),
+            Axis::Vertical => (size.height, size.width),
+        }
+    }
+
+    // Space available to the children, excluding the divider
+    fn available(&self) -> usize {
+        self.main_cross(self.size).0.saturating_sub(1)
+    }
+
+    /// Returns true if the screen position is on the divider
+    pub fn divider_contains(&self, x: u16, y: u16) -> bool {
+        let (main, cross) = match self.axis() {
+            Axis::Horizontal => (x as i32 - self.pos.x, y as i32 - self.pos.y),
+            Axis::Vertical => (y as i32 - self.pos.y, x as i32 - self.pos.x),
+        };
+
+        let (_, cross_size) = self.main_cross(self.size);
+        main == self.first as i32 && cross >= 0 && (cross as usize) < cross_size
+    }
+
+    /// The ratio that would place the divider at the screen position
+    pub fn ratio_at(&self, x: u16, y: u16) -> f64 {
+        let offset = match self.axis() {
+            Axis::Horizontal => x as i32 - self.pos.x,
+            Axis::Vertical => y as i32 - self.pos.y,
+        };
+
+        let available = self.available();
+        if available == 0 {
+            return self.ratio();
+        }
+
+        (offset as f64 / available as f64).clamp(0.0, 1.0)
+    }
+
+    /// The ratio that would move the divider by a number of cells
+    pub fn ratio_step(&self, cells: i32) -> f64 {
+        let available = self.available();
+        if available == 0 {
+            return self.ratio();
+        }
+
+        let first = (self.first as i32 + cells).clamp(0, available as i32);
+        first as f64 / available as f64
+    }
+}
+
+impl Widget for Split {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.axis.resolve(context, node_id);
+        self.ratio.resolve(context, node_id);
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        let axis = self.axis();
+
+        let (max_main, max_cross) = match axis {
+            Axis::Horizontal => (constraints.max_width, constraints.max_height),
+            Axis::Vertical => (constraints.max_height, constraints.max_width),
+        };
+
+        let available = max_main.saturating_sub(1);
+        self.first = (available as f64 * self.ratio()).round() as usize;
+        let sizes = [self.first, available - self.first];
+
+        let mut cross = 0;
+        let mut index = 0;
+        nodes.for_each(|mut node| {
+            // Only the first two children are part of the split
+            let main = sizes.get(index).copied().unwrap_or(0);
+            index += 1;
+
+            let child_constraints = match axis {
+                Axis::Horizontal => Constraints::new(main, max_cross).tighten_if(Some(main), None),
+                Axis::Vertical => Constraints::new(max_cross, main).tighten_if(None, Some(main)),
+            };
+
+            let size = node.layout(child_constraints)?;
+            cross = cross.max(match axis {
+                Axis::Horizontal => size.height,
+                Axis::Vertical => size.width,
+            });
+            Ok(())
+        })?;
+
+        // Fill the space across the axis, unless it's unbounded
+        if max_cross != usize::MAX {
+            cross = max_cross;
+        }
+
+        self.size = match axis {
+            Axis::Horizontal => Size::new(max_main, cross),
+            Axis::Vertical => Size::new(cross, max_main),
+        };
+
+        Ok(self.size)
+    }
+
+    fn position(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx) {
+        self.pos = ctx.pos;
+        let mut offset = 0;
+
+        for (widget, children) in children.iter_mut().take(2) {
+            let pos = match self.axis() {
+                Axis::Horizontal => Pos::new(ctx.pos.x + offset, ctx.pos.y),
+                Axis::Vertical => Pos::new(ctx.pos.x, ctx.pos.y + offset),
+            };
+            widget.position(children, pos);
+
+            // Skip the divider
+            offset += self.first as i32 + 1;
+        }
+    }
+
+    fn paint(&mut self, children: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        for (widget, children) in children.iter_mut().take(2) {
+            let ctx = ctx.to_unsized();
+            widget.paint(children, ctx);
+        }
+
+        let style = self.style.style();
+        let (_, cross) = self.main_cross(ctx.local_size);
+        for i in 0..cross {
+            let (c, pos) = match self.axis() {
+                Axis::Horizontal => ('│', LocalPos::new(self.first, i)),
+                Axis::Vertical => ('─', LocalPos::new(i, self.first)),
+            };
+            ctx.put(c, style, pos);
+        }
+    }
+}
+
+pub(crate) struct SplitFactory;
+
+impl WidgetFactory for SplitFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Split {
+            axis: ctx.get("axis"),
+            ratio: ctx.get("ratio"),
+            style: ctx.style(),
+            pos: Pos::ZERO,
+            size: Size::ZERO,
+            first: 0,
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use super::*;
+    use crate::testing::test_widget;
+
+    #[test]
+    fn hit_test_divider() {
+        let split = Split {
+            axis: Value::Empty,
+            ratio: Value::Empty,
+            style: WidgetStyle::default(),
+            pos: Pos::new(2, 1),
+            size: Size::new(11, 4),
+            first: 5,
+        };
+
+        assert!(split.divider_contains(7, 1));
+        assert!(split.divider_contains(7, 4));
+        assert!(!split.divider_contains(7, 5));
+        assert!(!split.divider_contains(6, 2));
+
+        assert_eq!(split.ratio_at(4, 0), 0.2);
+        assert_eq!(split.ratio_at(0, 0), 0.0);
+        assert_eq!(split.ratio_at(20, 0), 1.0);
+        assert_eq!(split.ratio_step(-1), 0.4);
+        assert_eq!(split.ratio_step(100), 1.0);
+    }
+
+    #[test]
+    fn horizontal_split() {
+        let split = expression(
+            "split",
+            None,
+            [("ratio".to_string(), 0.25.into())],
+            [
+                expression("text", Some("left".into()), [], []),
+                expression("text", Some("right".into()), [], []),
+            ],
+        );
+
+        test_widget(
+            split,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║left│right     ║
+            ║    │          ║
+            ║    │          ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn vertical_split() {
+        let split = expression(
+            "split",
+            None,
+            [("axis".to_string(), "vert".into())],
+            [
+                expression("text", Some("top".into()), [], []),
+                expression("text", Some("bottom".into()), [], []),
+            ],
+        );
+
+        test_widget(
+            split,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║top            ║
+            ║               ║
+            ║───────────────║
+            ║bottom         ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+}
@@ -3,7 +3,7 @@ use anathema_values::{Context, NodeId, Value};
 use anathema_widget_core::contexts::PositionCtx;
 use anathema_widget_core::error::Result;
 use anathema_widget_core::layout::{Direction, Layout};
-use anathema_widget_core::{Axis, LayoutNodes, Nodes};
+use anathema_widget_core::{Axis, CrossAlign, LayoutNodes, Nodes, Pos};
 
 use crate::layout::horizontal::Horizontal;
 use crate::layout::vertical::Vertical;
@@ -46,6 +46,9 @@ pub struct Stack {
     /// The minimum height. This will force the minimum constrained height to expand to
     /// this value.
     pub min_height: Value<usize>,
+    /// Align the children on the cross axis.
+    /// Only horizontal stacks can align the children on their baseline.
+    pub align: Value<CrossAlign>,
     axis: Axis,
 }
 
@@ -57,6 +60,7 @@ impl Stack {
             height,
             min_width: Value::Empty,
             min_height: Value::Empty,
+            align: Value::Empty,
             axis,
         }
     }
@@ -68,36 +72,69 @@ impl Stack {
         self.min_width.resolve(context, node_id);
         self.height.resolve(context, node_id);
         self.min_height.resolve(context, node_id);
+        self.align.resolve(context, node_id);
+    }
+
+    fn align_baseline(&self) -> bool {
+        self.axis == Axis::Horizontal && self.align.value_or_default() == CrossAlign::Baseline
     }
 
     pub(crate) fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
-        if let Some(width) = self.width.value() {
-            nodes.constraints.max_width = nodes.constraints.max_width.min(width);
-            nodes.constraints.min_width = nodes.constraints.max_width.min(width);
-        }
+        nodes.constraints = nodes
+            .constraints
+            .tighten_if(self.width.value(), self.height.value())
+            .clamp_min(self.min_width.value(), self.min_height.value());
 
-        if let Some(height) = self.height.value() {
-            nodes.constraints.max_height = nodes.constraints.max_height.min(height);
-            nodes.constraints.min_height = nodes.constraints.max_height.min(height);
-        }
+        let mut size = match self.axis {
+            Axis::Vertical => Vertical::new(Direction::Forwards).layout(nodes)?,
+            Axis::Horizontal => Horizontal::new(Direction::Forwards).layout(nodes)?,
+        };
 
-        if let Some(min_width) = self.min_width.value() {
-            nodes.constraints.min_width = nodes.constraints.min_width.max(min_width);
-        }
-        if let Some(min_height) = self.min_height.value() {
-            nodes.constraints.min_height = nodes.constraints.min_height.max(min_height);
+        // Children are moved down to line up with the lowest baseline,
+        // which can make the stack taller
+        if self.align_baseline() {
+            let mut children = nodes
+                .filter(|_| true)
+                .map(|mut node| (node.baseline(), node.size.height))
+                .collect::<Vec<_>>();
+            let baseline = children.iter().filter_map(|(b, _)| *b).max().unwrap_or(0);
+            let height = children
+                .drain(..)
+                .map(|(b, height)| height + b.map(|b| baseline - b).unwrap_or(0))
+                .max()
+                .unwrap_or(0);
+            size.height = size.height.max(height).min(nodes.constraints.max_height);
         }
 
-        match self.axis {
-            Axis::Vertical => Vertical::new(Direction::Forwards).layout(nodes),
-            Axis::Horizontal => Horizontal::new(Direction::Forwards).layout(nodes),
+        Ok(size)
+    }
+
+    pub(crate) fn baseline(&self, children: &mut Nodes<'_>) -> Option<usize> {
+        match self.align_baseline() {
+            true => children
+                .iter_mut()
+                .filter_map(|(widget, children)| widget.baseline(children))
+                .max(),
+            false => {
+                let (widget, children) = children.first_mut()?;
+                widget.baseline(children)
+            }
         }
     }
 
     pub(crate) fn position(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx) {
+        let baseline = match self.align_baseline() {
+            true => self.baseline(children),
+            false => None,
+        };
+
         let mut pos = ctx.pos;
         for (widget, children) in children.iter_mut() {
-            widget.position(children, pos);
+            let offset = match (baseline, widget.baseline(children)) {
+                (Some(baseline), Some(own)) => (baseline - own) as i32,
+                _ => 0,
+            };
+            widget.position(children, pos + Pos::new(0, offset));
             match self.axis {
                 Axis::Vertical => pos.y += widget.size.height as i32,
                 Axis::Horizontal => pos.x += widget.size.width as i32,
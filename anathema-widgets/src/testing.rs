@@ -1,7 +1,54 @@
+use anathema_values::ValueExpr;
 use anathema_widget_core::expressions::Expression;
-use anathema_widget_core::testing::{test_widget as core_test_widget, FakeTerm};
+use anathema_widget_core::testing::{
+    expression, test_widget as core_test_widget, test_widget_events as core_test_widget_events,
+    test_widget_with as core_test_widget_with, FakeTerm,
+};
+use anathema_widget_core::{
+    Event, KeyCode, KeyEventState, KeyModifiers, MouseButton, WidgetContainer,
+};
 
-pub fn test_widget(expr: Expression, expected: FakeTerm) {
+pub fn test_widget(expr: Expression, expected: FakeTerm) -> FakeTerm {
     let _ = crate::register_default_widgets();
-    core_test_widget(expr, expected);
+    core_test_widget(expr, expected)
+}
+
+pub fn test_widget_events(expr: Expression, events: &[Event], expected: FakeTerm) -> FakeTerm {
+    let _ = crate::register_default_widgets();
+    core_test_widget_events(expr, events, expected)
+}
+
+pub fn test_widget_with(
+    expr: Expression,
+    f: impl FnOnce(&mut WidgetContainer<'_>),
+    expected: FakeTerm,
+) -> FakeTerm {
+    let _ = crate::register_default_widgets();
+    core_test_widget_with(expr, f, expected)
+}
+
+/// A widget without text or children, e.g `widget("slider", [("value", 50.into())])`
+pub fn widget(
+    ident: &str,
+    attributes: impl IntoIterator<Item = (&'static str, ValueExpr)>,
+) -> Expression {
+    let attributes = attributes
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value));
+    expression(ident, None, attributes, [])
+}
+
+/// A key press without modifiers
+pub fn key(code: KeyCode) -> Event {
+    Event::KeyPress(code, KeyModifiers::NONE, KeyEventState::NONE)
+}
+
+/// A key press with the alt modifier
+pub fn alt(c: char) -> Event {
+    Event::KeyPress(KeyCode::Char(c), KeyModifiers::ALT, KeyEventState::NONE)
+}
+
+/// A left click
+pub fn click(x: u16, y: u16) -> Event {
+    Event::MouseDown(x, y, MouseButton::Left, KeyModifiers::NONE, 1)
 }
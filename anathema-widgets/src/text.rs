@@ -1,12 +1,22 @@
-use anathema_render::Size;
-use anathema_values::{Context, NodeId, Value};
-use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
+use std::ops::Range;
+use std::sync::Arc;
+
+use anathema_render::{Color, HighlightStyle, Size, Style};
+use anathema_values::fuzzy::fuzzy_match;
+use anathema_values::spans::Spans;
+use anathema_values::{Context, DynValue, NodeId, Value};
+use anathema_widget_core::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
 use anathema_widget_core::error::{Error, Result};
 use anathema_widget_core::{
-    AnyWidget, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget, WidgetFactory, WidgetStyle,
+    AnyWidget, Event, EventResult, FactoryContext, LayoutNodes, LocalPos, Nodes, Widget,
+    WidgetFactory, WidgetStyle,
 };
+use unicode_width::UnicodeWidthChar;
 
-use crate::layout::text::{Line, ProcessOutput, TextAlignment, TextLayout, Wrap};
+use crate::layout::text::{
+    truncate, Line, ProcessOutput, TextAlignment, TextLayout, TruncateMode, Wrap,
+};
+use crate::search_bar;
 
 // -----------------------------------------------------------------------------
 //     - Text -
@@ -18,6 +28,69 @@ use crate::layout::text::{Line, ProcessOutput, TextAlignment, TextLayout, Wrap};
 /// * foreground
 /// * text-align
 /// * wrap
+/// * highlight
+/// * highlight-foreground
+/// * mask
+/// * truncate-mode
+/// * search-item
+/// * nowrap
+/// ```
+///
+/// The characters fuzzy matching the `highlight` query are painted in bold,
+/// using the `highlight-foreground` color if one is set.
+/// This is useful together with `fuzzy`:
+/// ```ignore
+/// for item in fuzzy(items, query)
+///     text [highlight: query, highlight-foreground: #ff0] item
+/// ```
+///
+/// With a `search-item`, the index of the text in the source of a
+/// [`SearchBar`](crate::SearchBar), the occurrences of the query
+/// are highlighted the same way, and the current match is painted inverted:
+/// ```ignore
+/// for line in lines
+///     text [search-item: loop] line
+/// ```
+///
+/// Text with `nowrap` is never broken across lines, e.g a size and its unit,
+/// the same as if every space and hyphen in it was non-breaking.
+/// Non-breaking spaces (`\u{a0}`) and word joiners (`\u{2060}`) keep
+/// parts of any text together:
+/// ```ignore
+/// text "Downloaded "
+///     span [nowrap: true] "{{ size }} MB"
+/// ```
+///
+/// Spans can be nested, a nested span inherits the style and link
+/// of its parent span unless it sets its own:
+/// ```ignore
+/// text "See "
+///     span [foreground: blue, link: "https://example.com"] "the "
+///         span [bold: true] "docs"
+/// ```
+///
+/// With a `mask` every character of the text is painted as the mask character,
+/// for passwords and other secrets. The `text.reveal` action toggles between
+/// the mask and the text. A selection of a masked text
+/// (see [`Text::selected_text`]) only contains the mask characters:
+/// ```ignore
+/// text [mask: "*"] password
+/// ```
+/// The text of the spans is not masked.
+///
+/// With a `truncate-mode` of `start`, `middle` or `end`, every line that is wider
+/// than the available width is cut with an ellipsis instead of being wrapped,
+/// e.g `/home/…/project/file.rs` with `middle`. A selection of a truncated text
+/// contains the ellipsis. The text of the spans is not truncated.
+/// ```ignore
+/// text [truncate-mode: "middle"] path
+/// ```
+///
+/// A text returned by a span function is painted with the colors of the function,
+/// see [`anathema_values::spans`]:
+/// ```ignore
+/// text colorize(sparkline, green, yellow, red)
+/// text [bold: true] gradient(title, #ff00ff, #00ffff)
 /// ```
 ///
 /// Note: Spans, unlike other widgets, does not require a widget id
@@ -33,50 +106,221 @@ pub struct Text {
     pub text_alignment: Value<TextAlignment>,
     /// Text
     pub text: Value<String>,
+    /// The colors of the text, if the text is returned by a span function
+    pub spans: Value<Spans>,
     /// Text style
     pub style: WidgetStyle,
     /// Squash empty lines containing a singular whitespace char
     pub squash: Value<bool>,
+    /// Highlight the characters matching this query
+    pub highlight: Highlight,
+    /// Paint every character of the text as the first character of the mask
+    pub mask: Value<String>,
+    /// Cut the lines that don't fit with an ellipsis instead of wrapping them
+    pub truncate_mode: Value<TruncateMode>,
+    /// The index of the text in the source of the search bar
+    pub search_item: Value<usize>,
+    /// Don't break the text across lines
+    pub nowrap: Value<bool>,
 
     layout: TextLayout,
+    revealed: bool,
+    // The text with every character replaced by the mask
+    masked: Option<String>,
+    // The shown text with the lines that don't fit cut
+    truncated: Option<String>,
 }
 
 impl Text {
     pub const KIND: &'static str = "Text";
 
+    // The text as it is shown: truncated, and masked unless it's revealed
+    fn shown(&self) -> &str {
+        self.truncated
+            .as_deref()
+            .or(self.masked.as_deref())
+            .unwrap_or(self.text.str())
+    }
+
+    fn apply_mask(&mut self) {
+        let mask = self.mask.str().chars().next();
+        self.masked = match (mask, self.revealed) {
+            (Some(mask), false) => Some(
+                self.text
+                    .str()
+                    .chars()
+                    .map(|c| if c == '\n' { c } else { mask })
+                    .collect(),
+            ),
+            _ => None,
+        };
+    }
+
+    /// Map a selection of cells, in local coordinates, back to the source strings.
+    /// The selection goes from `from` to `to` (inclusive) in reading order,
+    /// like selecting text in a terminal, so it can span wrapped lines.
+    ///
+    /// The children are the spans of the text.
+    pub fn source_ranges(
+        &self,
+        children: &mut Nodes<'_>,
+        from: LocalPos,
+        to: LocalPos,
+    ) -> Vec<SourceRange> {
+        let sources = self.sources(children);
+        self.select(&sources, from, to)
+    }
+
+    /// The characters inside a selection of cells, see [`Text::source_ranges`].
+    ///
+    /// Lines that were wrapped are joined, while new lines in the
+    /// source strings are kept.
+    pub fn selected_text(&self, children: &mut Nodes<'_>, from: LocalPos, to: LocalPos) -> String {
+        let sources = self.sources(children);
+        let ranges = self.select(&sources, from, to);
+
+        let mut text = String::new();
+        let mut previous: Option<&SourceRange> = None;
+        for range in &ranges {
+            if let Some(previous) = previous {
+                let newlines = skipped(&sources, previous, range).matches('\n').count();
+                text.extend(std::iter::repeat_n('\n', newlines));
+            }
+            text.push_str(&sources[range.index][range.range.clone()]);
+            previous = Some(range);
+        }
+        text
+    }
+
+    // The text followed by the text of all the spans
+    fn sources<'a>(&'a self, children: &'a mut Nodes<'_>) -> Vec<&'a str> {
+        let mut runs = vec![];
+        flatten_spans(children, Style::new(), None, &mut runs);
+        [self.shown()]
+            .into_iter()
+            .chain(runs.into_iter().map(|run| run.span.text.str()))
+            .collect()
+    }
+
+    fn select(&self, sources: &[&str], from: LocalPos, to: LocalPos) -> Vec<SourceRange> {
+        let mut ranges: Vec<SourceRange> = vec![];
+        let lines = self.layout.lines();
+
+        let count = (to.y + 1).saturating_sub(from.y);
+        for (y, line) in lines.iter().enumerate().skip(from.y).take(count) {
+            let first = if y == from.y { from.x } else { 0 };
+            let last = if y == to.y { to.x } else { usize::MAX };
+            let mut x = self.line_offset(line);
+
+            for segment in &line.segments {
+                let text = segment.slice(sources[segment.index]);
+                for (offset, c) in text.char_indices() {
+                    let width = c.width().unwrap_or(0);
+                    let selected = x <= last && x + width.max(1) > first;
+                    x += width;
+                    if !selected {
+                        continue;
+                    }
+
+                    let start = segment.start() + offset;
+                    let end = start + c.len_utf8();
+                    match ranges.last_mut() {
+                        Some(range) if range.index == segment.index && range.range.end == start => {
+                            range.range.end = end
+                        }
+                        _ => ranges.push(SourceRange {
+                            index: segment.index,
+                            range: start..end,
+                        }),
+                    }
+                }
+            }
+        }
+
+        ranges
+    }
+
+    // The x position of the first character on the line
+    fn line_offset(&self, line: &Line) -> usize {
+        let max_width = self.layout.size().width;
+        match self.text_alignment.value_or_default() {
+            TextAlignment::Left => 0,
+            TextAlignment::Centre => max_width / 2 - line.width / 2,
+            TextAlignment::Right => max_width - line.width,
+        }
+    }
+
     fn paint_line(
         &self,
         line: &Line,
-        children: &[&TextSpan],
+        runs: &[Run<'_>],
+        highlights: &[Vec<Range<usize>>],
+        current: Option<&Range<usize>>,
         y: usize,
         ctx: &mut PaintCtx<'_, WithSize>,
     ) {
-        let mut pos = LocalPos::new(0, y);
-
-        let max_width = self.layout.size().width;
-        match self.text_alignment.value_or_default() {
-            TextAlignment::Left => {}
-            TextAlignment::Centre => pos.x = max_width / 2 - line.width / 2,
-            TextAlignment::Right => pos.x = max_width - line.width,
-        }
+        let mut pos = LocalPos::new(self.line_offset(line), y);
 
         for segment in &line.segments {
-            let (text, style) = match segment.index {
-                0 => (self.text.str(), self.style.style()),
+            let (text, style, highlight, link, spans) = match segment.index {
+                0 => (
+                    self.shown(),
+                    self.style.style(),
+                    &self.highlight,
+                    None,
+                    self.spans
+                        .value_ref()
+                        .filter(|_| self.masked.is_none() && self.truncated.is_none()),
+                ),
                 i => {
-                    let child = children[i - 1];
-                    let text = child.text.str();
-                    let style = child.style.style();
-                    (text, style)
+                    let run = &runs[i - 1];
+                    let text = run.span.text.str();
+                    (
+                        text,
+                        run.style,
+                        &run.span.highlight,
+                        run.link.as_ref(),
+                        None,
+                    )
                 }
             };
 
+            let ranges = &highlights[segment.index];
             let text = segment.slice(text);
-            let Some(new_pos) = ctx.print(text, style, pos) else {
+            if ranges.is_empty() && spans.is_none() {
+                let new_pos = match link {
+                    Some(link) => ctx.print_link(text, style, link, pos),
+                    None => ctx.print(text, style, pos),
+                };
+                let Some(new_pos) = new_pos else {
+                    continue;
+                };
+                pos = new_pos;
                 continue;
-            };
+            }
 
-            pos = new_pos;
+            for (offset, c) in text.char_indices() {
+                let offset = segment.start() + offset;
+                let mut style = style;
+                if let Some(color) = spans.and_then(|spans| spans.foreground_at(offset)) {
+                    style.set_fg(color);
+                }
+                if ranges.iter().any(|range| range.contains(&offset)) {
+                    style = highlight.style(style);
+                }
+                if segment.index == 0 && current.is_some_and(|range| range.contains(&offset)) {
+                    let size = Size::new(c.width().unwrap_or(0).max(1), 1);
+                    ctx.highlight(pos, size, HighlightStyle::Inverse);
+                }
+                let new_pos = match link {
+                    Some(link) => ctx.put_link(c, style, link, pos),
+                    None => ctx.put(c, style, pos),
+                };
+                let Some(new_pos) = new_pos else {
+                    break;
+                };
+                pos = new_pos;
+            }
         }
     }
 }
@@ -90,8 +334,14 @@ impl Widget for Text {
         self.word_wrap.resolve(context, node_id);
         self.text_alignment.resolve(context, node_id);
         self.text.resolve(context, node_id);
+        self.spans.resolve(context, node_id);
         self.style.resolve(context, node_id);
         self.squash.resolve(context, node_id);
+        self.highlight.resolve(context, node_id);
+        self.mask.resolve(context, node_id);
+        self.truncate_mode.resolve(context, node_id);
+        self.search_item.resolve(context, node_id);
+        self.nowrap.resolve(context, node_id);
     }
 
     fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
@@ -101,21 +351,22 @@ impl Widget for Text {
             self.squash.value_or(true),
         );
 
-        self.layout.process(self.text.str());
-
-        let _ = nodes.for_each(|mut span| {
-            // Ignore any widget that isn't a span
-            if span.kind() != TextSpan::KIND {
-                return Ok(());
-            }
-
-            let inner_span = span.to_mut::<TextSpan>();
+        self.apply_mask();
+        self.truncated = None;
+        self.truncated = truncate(
+            self.shown(),
+            constraints.max_width,
+            self.truncate_mode.value_or_default(),
+        );
+        let text = (self.truncated.as_deref())
+            .or(self.masked.as_deref())
+            .unwrap_or(self.text.str());
+        let nowrap = self.nowrap.value_or_default();
+        self.layout.set_nowrap(nowrap);
+        self.layout.process(text);
 
-            match self.layout.process(inner_span.text.str()) {
-                ProcessOutput::Done => Ok(()),
-                ProcessOutput::InsufficientSpaceAvailble => Err(Error::InsufficientSpaceAvailble),
-            }
-        });
+        let _ = layout_spans(&mut self.layout, nodes, nowrap);
+        self.layout.set_nowrap(false);
 
         self.layout.finish();
 
@@ -124,13 +375,34 @@ impl Widget for Text {
     }
 
     fn paint<'ctx>(&mut self, children: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
-        let children = children
-            .iter_mut()
-            .map(|(c, _)| c.to_ref::<TextSpan>())
+        let mut runs = vec![];
+        flatten_spans(children, Style::new(), None, &mut runs);
+        let mut ranges = self.highlight.ranges(self.shown());
+        let mut current = None;
+        if let Some(item) = self.search_item.value().filter(|_| self.masked.is_none()) {
+            let (occurrences, found) = search_bar::occurrences(item, self.shown());
+            ranges.extend(occurrences);
+            current = found;
+        }
+
+        let highlights = [ranges]
+            .into_iter()
+            .chain(
+                runs.iter()
+                    .map(|run| run.span.highlight.ranges(run.span.text.str())),
+            )
             .collect::<Vec<_>>();
+
         let lines = self.layout.lines();
         for (y, line) in lines.iter().enumerate() {
-            self.paint_line(line, children.as_slice(), y, &mut ctx);
+            self.paint_line(
+                line,
+                runs.as_slice(),
+                &highlights,
+                current.as_ref(),
+                y,
+                &mut ctx,
+            );
         }
     }
 
@@ -138,15 +410,176 @@ impl Widget for Text {
         // NOTE: there is no need to position text as the text
         // is printed from the context position
     }
+
+    fn baseline(&self, _: &mut Nodes<'_>) -> Option<usize> {
+        match self.layout.size().height {
+            0 => None,
+            _ => Some(0),
+        }
+    }
+
+    fn on_event(&mut self, event: &Event, _: EventCtx<'_>) -> EventResult {
+        match event {
+            Event::Action(action) if action == "text.reveal" && !self.mask.str().is_empty() => {
+                self.revealed = !self.revealed;
+                EventResult::Consumed
+            }
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+/// A byte range in one of the source strings of a [`Text`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceRange {
+    /// The source string: `0` is the text itself,
+    /// followed by the spans (including nested spans) in order
+    pub index: usize,
+    /// Byte range in the source string
+    pub range: Range<usize>,
+}
+
+// The text between two ranges that was not selected,
+// e.g a new line or whitespace consumed by word wrapping.
+fn skipped(sources: &[&str], from: &SourceRange, to: &SourceRange) -> String {
+    if from.index == to.index {
+        return sources[from.index][from.range.end..to.range.start].to_string();
+    }
+
+    let mut text = sources[from.index][from.range.end..].to_string();
+    sources[from.index + 1..to.index]
+        .iter()
+        .for_each(|s| text.push_str(s));
+    text.push_str(&sources[to.index][..to.range.start]);
+    text
+}
+
+// Process the text of the spans, and the spans nested inside them,
+// in the same order as `flatten_spans`.
+// Spans inherit `nowrap` from their parent.
+fn layout_spans(
+    layout: &mut TextLayout,
+    nodes: &mut LayoutNodes<'_, '_, '_>,
+    parent_nowrap: bool,
+) -> Result<()> {
+    let constraints = nodes.constraints;
+    nodes.for_each(|mut span| {
+        // Ignore any widget that isn't a span
+        let Ok(inner_span) = span.try_to_mut::<TextSpan>() else {
+            return Ok(());
+        };
+
+        let nowrap = inner_span.nowrap.value_or(parent_nowrap);
+        layout.set_nowrap(nowrap);
+        if let ProcessOutput::InsufficientSpaceAvailble = layout.process(inner_span.text.str()) {
+            return Err(Error::InsufficientSpaceAvailble);
+        }
+
+        layout_spans(layout, &mut span.children(constraints), nowrap)
+    })
+}
+
+// A span with the style and link of its parent spans applied
+struct Run<'a> {
+    span: &'a TextSpan,
+    style: Style,
+    link: Option<Arc<str>>,
+}
+
+// Flatten nested spans into a list of runs
+fn flatten_spans<'a>(
+    nodes: &'a mut Nodes<'_>,
+    parent_style: Style,
+    parent_link: Option<&Arc<str>>,
+    runs: &mut Vec<Run<'a>>,
+) {
+    for (span, children) in nodes.iter_mut() {
+        let Ok(span) = span.try_to::<TextSpan>() else {
+            continue;
+        };
+
+        let mut style = span.style.style();
+        style.merge(parent_style);
+        let link = match span.link.value_ref() {
+            Some(link) => Some(Arc::from(link.as_str())),
+            None => parent_link.cloned(),
+        };
+
+        runs.push(Run {
+            span,
+            style,
+            link: link.clone(),
+        });
+        flatten_spans(children, style, link.as_ref(), runs);
+    }
+}
+
+/// Highlight the characters of a text that fuzzy match a query
+#[derive(Debug)]
+pub struct Highlight {
+    /// The query
+    pub query: Value<String>,
+    /// Foreground color of the matching characters
+    pub foreground: Value<Color>,
+}
+
+impl Highlight {
+    fn new(ctx: &FactoryContext<'_>) -> Self {
+        Self {
+            query: ctx.get("highlight"),
+            foreground: ctx.get("highlight-foreground"),
+        }
+    }
+
+    fn resolve(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.query.resolve(context, node_id);
+        self.foreground.resolve(context, node_id);
+    }
+
+    // Byte ranges of the text matching the query
+    fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        match self.query.value_ref() {
+            Some(query) if !query.is_empty() => fuzzy_match(query, text)
+                .map(|found| found.byte_ranges(text))
+                .unwrap_or_default(),
+            _ => vec![],
+        }
+    }
+
+    fn style(&self, mut style: Style) -> Style {
+        style.set_bold(true);
+        if let Some(color) = self.foreground.value_ref() {
+            style.set_fg(*color);
+        }
+        style
+    }
 }
 
 /// Represents a chunk of text with its own style
+/// ```ignore
+/// Attributes:
+/// * background
+/// * foreground
+/// * highlight
+/// * highlight-foreground
+/// * link
+/// * nowrap
+/// ```
+///
+/// A span with a `link` is rendered as a hyperlink (OSC 8) in terminals that support it.
+/// Spans nested inside a span inherit the style and link of the parent.
 #[derive(Debug)]
 pub struct TextSpan {
     /// The text
     pub text: Value<String>,
     /// Style for the text
     pub style: WidgetStyle,
+    /// Highlight the characters matching this query
+    pub highlight: Highlight,
+    /// URL the text links to
+    pub link: Value<String>,
+    /// Don't break the text across lines, defaults to the parent span or text
+    pub nowrap: Value<bool>,
 }
 
 impl TextSpan {
@@ -161,6 +594,9 @@ impl Widget for TextSpan {
     fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
         self.text.resolve(context, node_id);
         self.style.resolve(context, node_id);
+        self.highlight.resolve(context, node_id);
+        self.link.resolve(context, node_id);
+        self.nowrap.resolve(context, node_id);
     }
 
     fn layout(&mut self, _nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
@@ -182,12 +618,25 @@ pub(crate) struct TextFactory;
 impl WidgetFactory for TextFactory {
     fn make(&self, mut ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
         let word_wrap = ctx.get("wrap");
+        let spans = match &ctx.text {
+            Value::Dyn { expr, .. } => Spans::init_value(ctx.ctx, &ctx.node_id, expr),
+            _ => Value::Empty,
+        };
         let widget = Text {
             text_alignment: ctx.get("text-align"),
             squash: ctx.get("squash"),
             style: ctx.style(),
+            highlight: Highlight::new(&ctx),
+            mask: ctx.get("mask"),
+            truncate_mode: ctx.get("truncate-mode"),
+            search_item: ctx.get("search-item"),
+            nowrap: ctx.get("nowrap"),
             layout: TextLayout::new(Size::ZERO, false, word_wrap.value_or_default()),
+            revealed: false,
+            masked: None,
+            truncated: None,
             text: ctx.text.take(),
+            spans,
             word_wrap,
         };
 
@@ -202,6 +651,9 @@ impl WidgetFactory for SpanFactory {
         let widget = TextSpan {
             text: ctx.text.take(),
             style: ctx.style(),
+            highlight: Highlight::new(&ctx),
+            link: ctx.get("link"),
+            nowrap: ctx.get("nowrap"),
         };
 
         Ok(Box::new(widget))
@@ -210,10 +662,377 @@ impl WidgetFactory for SpanFactory {
 
 #[cfg(test)]
 mod test {
-    use anathema_values::ValueExpr;
+    use anathema_render::{Attributes, ScreenPos};
+    use anathema_values::{Symbol, ValueExpr};
     use anathema_widget_core::testing::{expression, FakeTerm};
 
-    use crate::testing::test_widget;
+    use super::*;
+    use crate::testing::{test_widget, test_widget_events};
+
+    #[test]
+    fn highlight_ranges() {
+        let highlight = Highlight {
+            query: Value::Static("hw".into()),
+            foreground: Value::Static(Color::Red),
+        };
+        assert_eq!(highlight.ranges("hello world"), vec![0..1, 6..7]);
+        assert!(highlight.ranges("goodbye").is_empty());
+
+        let style = highlight.style(Style::new());
+        assert_eq!(style.fg, Some(Color::Red));
+    }
+
+    fn text(sources: &[&str], width: usize) -> Text {
+        let mut text = Text {
+            word_wrap: Value::Empty,
+            text_alignment: Value::Empty,
+            text: Value::Static(sources[0].into()),
+            spans: Value::Empty,
+            style: WidgetStyle::default(),
+            squash: Value::Empty,
+            highlight: Highlight {
+                query: Value::Empty,
+                foreground: Value::Empty,
+            },
+            mask: Value::Empty,
+            truncate_mode: Value::Empty,
+            search_item: Value::Empty,
+            nowrap: Value::Empty,
+            layout: TextLayout::new(Size::new(width, 10), true, Wrap::Normal),
+            revealed: false,
+            masked: None,
+            truncated: None,
+        };
+        sources.iter().for_each(|s| {
+            text.layout.process(s);
+        });
+        text.layout.finish();
+        text
+    }
+
+    fn selected(
+        sources: &[&str],
+        width: usize,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> String {
+        let text = text(sources, width);
+        let ranges = text.select(
+            sources,
+            LocalPos::new(from.0, from.1),
+            LocalPos::new(to.0, to.1),
+        );
+        ranges
+            .iter()
+            .map(|range| &sources[range.index][range.range.clone()])
+            .collect()
+    }
+
+    #[test]
+    fn select_across_wrapped_lines() {
+        // hello how are
+        // you
+        let text = text(&["hello how are you"], 16);
+        let ranges = text.select(
+            &["hello how are you"],
+            LocalPos::new(6, 0),
+            LocalPos::new(1, 1),
+        );
+        assert_eq!(
+            ranges,
+            vec![SourceRange {
+                index: 0,
+                range: 6..16
+            }]
+        );
+    }
+
+    #[test]
+    fn select_spans() {
+        let sources = ["one", "two", " three"];
+        assert_eq!(selected(&sources, 20, (2, 0), (7, 0)), "etwo t");
+
+        let text = text(&sources, 20);
+        let ranges = text.select(&sources, LocalPos::new(2, 0), LocalPos::new(7, 0));
+        let indices = ranges.iter().map(|range| range.index).collect::<Vec<_>>();
+        assert_eq!(indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn select_wide_chars() {
+        // A selection starting in the middle of a wide char includes the char
+        assert_eq!(selected(&["a💖b"], 10, (2, 0), (3, 0)), "💖b");
+    }
+
+    #[test]
+    fn select_keeps_new_lines() {
+        let sources = ["hello\nworld"];
+        let text = text(&sources, 20);
+        let ranges = text.select(&sources, LocalPos::new(3, 0), LocalPos::new(1, 1));
+        assert_eq!(ranges[0].range, 3..5);
+        assert_eq!(ranges[1].range, 6..8);
+        assert_eq!(skipped(&sources, &ranges[0], &ranges[1]), "\n");
+    }
+
+    #[test]
+    fn highlighted_text() {
+        test_widget(
+            expression(
+                "text",
+                Some("hello world".into()),
+                [("highlight".into(), ValueExpr::from("hw"))],
+                [expression(
+                    "span",
+                    Some(" again".into()),
+                    [("highlight".into(), ValueExpr::from("ag"))],
+                    [],
+                )],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═════╗
+            ║hello world again  ║
+            ║                   ║
+            ╚═══════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn masked_text() {
+        test_widget(
+            expression(
+                "text",
+                Some("secret".into()),
+                [("mask".into(), ValueExpr::from("*"))],
+                [expression("span", Some(" shown".into()), [], [])],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═════╗
+            ║****** shown       ║
+            ╚═══════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn reveal_masked_text() {
+        let reveal = Event::Action(Symbol::new("text.reveal"));
+        let text = || {
+            expression(
+                "text",
+                Some("secret".into()),
+                [("mask".into(), ValueExpr::from("*"))],
+                [],
+            )
+        };
+
+        test_widget_events(
+            text(),
+            &[reveal.clone(), reveal.clone()],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═════╗
+            ║******             ║
+            ╚═══════════════════╝
+            "#,
+            ),
+        );
+
+        test_widget_events(
+            text(),
+            &[reveal],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═════╗
+            ║secret             ║
+            ╚═══════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn select_masked_text() {
+        let mut text = text(&["secret"], 20);
+        text.mask = Value::Static("•".into());
+        text.apply_mask();
+
+        let masked = text.shown().to_string();
+        text.layout.reset(Size::new(20, 10), true);
+        text.layout.process(&masked);
+        text.layout.finish();
+
+        let sources = [text.shown()];
+        let ranges = text.select(&sources, LocalPos::new(0, 0), LocalPos::new(20, 0));
+        assert_eq!(&sources[0][ranges[0].range.clone()], "••••••");
+    }
+
+    #[test]
+    fn nested_spans_with_links() {
+        let term = test_widget(
+            expression(
+                "text",
+                Some("see ".into()),
+                [],
+                [expression(
+                    "span",
+                    Some("the ".into()),
+                    [
+                        ("foreground".into(), ValueExpr::from("red")),
+                        ("link".into(), ValueExpr::from("https://example.com")),
+                    ],
+                    [expression(
+                        "span",
+                        Some("docs".into()),
+                        [("bold".into(), ValueExpr::from(true))],
+                        [],
+                    )],
+                )],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═════╗
+            ║see the docs       ║
+            ║                   ║
+            ╚═══════════════════╝
+            "#,
+            ),
+        );
+
+        let screen = term.screen();
+        assert!(screen.link(ScreenPos::new(0, 0)).is_none());
+        let (_, style) = screen.get(ScreenPos::new(4, 0)).unwrap();
+        assert_eq!(style.fg, Some(Color::Red));
+        assert_eq!(
+            &**screen.link(ScreenPos::new(4, 0)).unwrap(),
+            "https://example.com"
+        );
+
+        // The nested span inherits both the colour and the link
+        let (_, style) = screen.get(ScreenPos::new(8, 0)).unwrap();
+        assert_eq!(style.fg, Some(Color::Red));
+        assert!(style.attributes.contains(Attributes::BOLD));
+        assert_eq!(
+            &**screen.link(ScreenPos::new(11, 0)).unwrap(),
+            "https://example.com"
+        );
+    }
+
+    #[test]
+    fn truncate_lines() {
+        let path = "/home/user/project/file.rs";
+        assert_eq!(truncate(path, 30, TruncateMode::Middle), None);
+        assert_eq!(truncate(path, 10, TruncateMode::None), None);
+        assert_eq!(
+            truncate(path, 15, TruncateMode::Middle).unwrap(),
+            "/home/u…file.rs"
+        );
+        assert_eq!(truncate(path, 8, TruncateMode::Start).unwrap(), "…file.rs");
+        assert_eq!(truncate(path, 8, TruncateMode::End).unwrap(), "/home/u…");
+        assert_eq!(truncate(path, 0, TruncateMode::End).unwrap(), "");
+
+        // Wide characters are never split
+        assert_eq!(truncate("ab💖cd", 4, TruncateMode::End).unwrap(), "ab…");
+        assert_eq!(
+            truncate("ab\nabcdef", 4, TruncateMode::End).unwrap(),
+            "ab\nabc…"
+        );
+    }
+
+    #[test]
+    fn truncate_text() {
+        test_widget(
+            expression(
+                "text",
+                Some("/home/user/project/file.rs".into()),
+                [("truncate-mode".into(), ValueExpr::from("middle"))],
+                [],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═════╗
+            ║/home/use…t/file.rs║
+            ║                   ║
+            ╚═══════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn colorize_text() {
+        let text = anathema_values::testing::call(
+            "colorize",
+            vec![
+                ValueExpr::from("▁x█").into(),
+                ValueExpr::from("green").into(),
+                ValueExpr::from("red").into(),
+            ],
+        );
+        let term = test_widget(
+            expression(
+                "text",
+                Some(*text),
+                [("highlight".into(), ValueExpr::from("x"))],
+                [],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═════╗
+            ║▁x█                ║
+            ╚═══════════════════╝
+            "#,
+            ),
+        );
+
+        let screen = term.screen();
+        let fg = |x| screen.get(ScreenPos::new(x, 0)).unwrap().1.fg;
+        assert_eq!(fg(0), Some(Color::Green));
+        assert_eq!(fg(2), Some(Color::Red));
+
+        // The highlight is painted over the colors
+        let (_, style) = screen.get(ScreenPos::new(1, 0)).unwrap();
+        assert!(style.attributes.contains(Attributes::BOLD));
+    }
+
+    #[test]
+    fn search_matches() {
+        let mut search = anathema_values::search::Search::default();
+        search.update("ab", &["ab ab", "ab"]);
+        search.next();
+        search_bar::set_active(Some(search));
+
+        let term = test_widget(
+            expression(
+                "text",
+                Some("ab ab".into()),
+                [("search-item".into(), ValueExpr::from(0))],
+                [],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═════╗
+            ║ab ab              ║
+            ╚═══════════════════╝
+            "#,
+            ),
+        );
+
+        let screen = term.screen();
+        let attributes = |x| screen.get(ScreenPos::new(x, 0)).unwrap().1.attributes;
+        assert!(attributes(0).contains(Attributes::BOLD));
+        assert!(!attributes(0).contains(Attributes::INVERSE));
+        // The current match is the second occurrence
+        assert!(attributes(3).contains(Attributes::BOLD | Attributes::INVERSE));
+        assert!(attributes(4).contains(Attributes::INVERSE));
+        assert!(!attributes(2).contains(Attributes::INVERSE));
+
+        search_bar::set_active(None);
+    }
 
     #[test]
     fn word_wrap_excessive_space() {
@@ -234,6 +1053,44 @@ mod test {
         );
     }
 
+    #[test]
+    fn keep_words_together() {
+        // A non-breaking space and a word joiner after the hyphen
+        test_widget(
+            expression("text", Some("size: 10\u{a0}MB, a-\u{2060}b".into()), [], []),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║size: 10 MB,   ║
+            ║a-b            ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+
+        test_widget(
+            expression(
+                "text",
+                Some("file size: ".into()),
+                [],
+                [expression(
+                    "span",
+                    Some("10 MB".into()),
+                    [("nowrap".into(), ValueExpr::from(true))],
+                    [],
+                )],
+            ),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║file size:     ║
+            ║10 MB          ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
     #[test]
     fn word_wrap() {
         test_widget(
@@ -1,13 +1,41 @@
 use anathema_render::Size;
 use anathema_values::{Context, NodeId, Value};
 use anathema_widget_core::contexts::{PaintCtx, PositionCtx, WithSize};
-use anathema_widget_core::error::Result;
+use anathema_widget_core::error::{Error, Result};
 use anathema_widget_core::layout::{Axis, Direction, Layout};
-use anathema_widget_core::{AnyWidget, FactoryContext, LayoutNodes, Nodes, Widget, WidgetFactory};
+use anathema_widget_core::{
+    AnyWidget, FactoryContext, LayoutNodes, Nodes, Pos, Widget, WidgetFactory,
+};
 
 use crate::layout::many::Many;
+use crate::{Expand, Spacer};
 
 /// A viewport where the children can be rendered with an offset.
+///
+/// Children with the `sticky` attribute stay pinned to the start of the
+/// viewport once they are scrolled past, until the next sticky child
+/// pushes them out (e.g table headers or date separators):
+///
+/// ```text
+/// viewport [offset: offset]
+///     text [sticky: "top"] "Today"
+///     for message in today
+///         text message
+///     text [sticky: "top"] "Yesterday"
+///     ...
+/// ```
+///
+/// A forward viewport can scroll a child into view, adjusting the offset
+/// just enough for the child to be fully visible: either the child at the
+/// index of the `ensure-visible` attribute (e.g the selected item of a list),
+/// or a child found with [`Viewport::scroll_to_node`].
+/// The adjusted offset is kept until the `offset` attribute changes.
+///
+/// ```text
+/// viewport [ensure-visible: selected_index]
+///     for item in items
+///         text item
+/// ```
 #[derive(Debug)]
 pub struct Viewport {
     /// Line / cell offset
@@ -21,11 +49,21 @@ pub struct Viewport {
     pub direction: Value<Direction>,
     /// Vertical or horizontal
     pub axis: Value<Axis>,
+    /// The index of a child to keep fully visible
+    pub ensure_visible: Value<usize>,
+    // The index of the sticky child that is pinned to the start
+    pinned: Option<usize>,
+    // The node to scroll into view on the next layout
+    scroll_to: Option<NodeId>,
+    // The offset after scrolling a child into view
+    scrolled: Option<i32>,
 }
 
 impl Viewport {
     pub fn offset(&self) -> i32 {
-        let mut offset = self.offset.value_or_default();
+        let mut offset = self
+            .scrolled
+            .unwrap_or_else(|| self.offset.value_or_default());
 
         if self.clamp.value_or(false) && offset < 0 {
             offset = 0;
@@ -33,6 +71,117 @@ impl Viewport {
 
         offset
     }
+
+    /// Scroll the child containing the node into view on the next layout.
+    ///
+    /// ```
+    /// # use anathema_values::NodeId;
+    /// # use anathema_widget_core::Nodes;
+    /// # use anathema_widgets::Viewport;
+    /// fn show(nodes: &mut Nodes<'_>, node_id: &NodeId) {
+    ///     if let Some(viewport) = nodes.query().by_tag("viewport").first() {
+    ///         viewport.to_mut::<Viewport>().scroll_to_node(node_id);
+    ///     }
+    /// }
+    /// ```
+    pub fn scroll_to_node(&mut self, node_id: &NodeId) {
+        self.scroll_to = Some(node_id.clone());
+    }
+
+    // The start and length of the child to scroll into view, along the axis.
+    // The children up to the target are laid out to measure them.
+    fn find_target(
+        &mut self,
+        nodes: &mut LayoutNodes<'_, '_, '_>,
+        axis: Axis,
+    ) -> Result<Option<(i32, i32)>> {
+        let scroll_to = self.scroll_to.take();
+        let index = self.ensure_visible.value();
+        if scroll_to.is_none() && index.is_none() {
+            return Ok(None);
+        }
+
+        let mut constraints = nodes.constraints;
+        match axis {
+            Axis::Horizontal => constraints.unbound_width(),
+            Axis::Vertical => constraints.unbound_height(),
+        }
+
+        let mut start = 0;
+        let mut target = None;
+        let mut current = 0;
+        let res = nodes.for_each(|mut node| {
+            if [Spacer::KIND, Expand::KIND].contains(&node.kind()) {
+                return Ok(());
+            }
+
+            let size = node.layout(constraints)?;
+            let len = match axis {
+                Axis::Horizontal => size.width as i32,
+                Axis::Vertical => size.height as i32,
+            };
+
+            let found = match &scroll_to {
+                Some(node_id) => node.node_id.contains(node_id.as_slice()),
+                None => index == Some(current),
+            };
+            if found {
+                target = Some((start, len));
+                return Err(Error::InsufficientSpaceAvailble);
+            }
+
+            start += len;
+            current += 1;
+            Ok(())
+        });
+
+        match res {
+            Ok(()) | Err(Error::InsufficientSpaceAvailble) => {}
+            Err(e) => return Err(e),
+        }
+
+        nodes.reset();
+        Ok(target)
+    }
+
+    // Pin the last sticky child that was scrolled past to the start,
+    // unless the next sticky child pushes it out.
+    fn pin_sticky(&mut self, children: &mut Nodes<'_>, axis: Axis, start: Pos) {
+        let along = |pos: Pos, size: Size| match axis {
+            Axis::Horizontal => (pos.x, size.width as i32),
+            Axis::Vertical => (pos.y, size.height as i32),
+        };
+        let (start, _) = along(start, Size::ZERO);
+
+        let mut pinned = None;
+        let mut end = start;
+        for (index, (widget, _)) in children.iter_mut().enumerate() {
+            if widget.sticky().is_none() {
+                continue;
+            }
+
+            let (pos, len) = along(widget.pos, widget.size);
+            if pos > start {
+                if let Some((_, len)) = pinned {
+                    end = end.min(pos - len);
+                }
+                break;
+            }
+            pinned = Some((index, len));
+        }
+
+        self.pinned = pinned.map(|(index, _)| index);
+        let Some((widget, children)) = self.pinned.and_then(|i| children.iter_mut().nth(i)) else {
+            return;
+        };
+
+        let mut pos = widget.pos;
+        match axis {
+            Axis::Horizontal => pos.x = end,
+            Axis::Vertical => pos.y = end,
+        }
+        widget.position(children, pos);
+    }
 }
 
 impl Widget for Viewport {
@@ -41,12 +190,27 @@ impl Widget for Viewport {
     }
 
     fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
-        let mut many = Many::new(
-            self.direction.value_or_default(),
-            self.axis.value_or(Axis::Vertical),
-            self.offset(),
-            true,
-        );
+        let direction = self.direction.value_or_default();
+        let axis = self.axis.value_or(Axis::Vertical);
+
+        if let Direction::Forwards = direction {
+            if let Some((start, len)) = self.find_target(nodes, axis)? {
+                let visible = match axis {
+                    Axis::Horizontal => nodes.constraints.max_width,
+                    Axis::Vertical => nodes.constraints.max_height,
+                }
+                .min(i32::MAX as usize) as i32;
+
+                let offset = self.offset();
+                if start < offset {
+                    self.scrolled = Some(start);
+                } else if start + len > offset + visible {
+                    self.scrolled = Some(start + len - visible);
+                }
+            }
+        }
+
+        let mut many = Many::new(direction, axis, self.offset(), true);
 
         many.layout(nodes)
     }
@@ -54,8 +218,15 @@ impl Widget for Viewport {
     fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
         self.direction.resolve(context, node_id);
         self.axis.resolve(context, node_id);
-        self.offset.resolve(context, node_id);
+        self.ensure_visible.resolve(context, node_id);
         self.clamp.resolve(context, node_id);
+
+        // Scrolling the viewport through the state overrides the adjusted offset
+        let offset = self.offset.value();
+        self.offset.resolve(context, node_id);
+        if self.offset.value() != offset {
+            self.scrolled = None;
+        }
     }
 
     fn position<'tpl>(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx) {
@@ -129,11 +300,30 @@ impl Widget for Viewport {
                 widget.position(children, pos);
             }
         }
+
+        self.pinned = None;
+        if let Direction::Forwards = direction {
+            self.pin_sticky(children, axis, ctx.pos);
+        }
     }
 
     fn paint(&mut self, children: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
         let region = ctx.create_region();
-        for (widget, children) in children.iter_mut() {
+        for (index, (widget, children)) in children.iter_mut().enumerate() {
+            if self.pinned == Some(index) {
+                continue;
+            }
+            let mut ctx = ctx.to_unsized();
+            ctx.set_region(&region);
+            widget.paint(children, ctx);
+        }
+
+        // The pinned child covers the children scrolling under it
+        if let Some((widget, children)) = self.pinned.and_then(|i| children.iter_mut().nth(i)) {
+            let mut erase_ctx = ctx.to_unsized();
+            erase_ctx.set_region(&region);
+            erase_ctx.into_sized(widget.size, widget.pos).erase();
+
             let mut ctx = ctx.to_unsized();
             ctx.set_region(&region);
             widget.paint(children, ctx);
@@ -150,6 +340,10 @@ impl WidgetFactory for ViewportFactory {
             axis: ctx.get("axis"),
             offset: ctx.get("offset"),
             clamp: ctx.get("clamp"),
+            ensure_visible: ctx.get("ensure-visible"),
+            pinned: None,
+            scroll_to: None,
+            scrolled: None,
         };
 
         Ok(Box::new(widget))
@@ -161,7 +355,8 @@ mod test {
     use anathema_widget_core::expressions::Expression;
     use anathema_widget_core::testing::{expression, FakeTerm};
 
-    use crate::testing::test_widget;
+    use super::*;
+    use crate::testing::{test_widget, test_widget_with};
 
     fn children(count: usize) -> Vec<Expression> {
         (0..count)
@@ -339,4 +534,127 @@ mod test {
             ),
         );
     }
+
+    fn text(text: &str) -> Expression {
+        expression("text", Some(text.into()), [], [])
+    }
+
+    fn sticky(child: Expression) -> Expression {
+        expression("border", None, [("sticky".into(), "top".into())], [child])
+    }
+
+    #[test]
+    fn sticky_header() {
+        let mut body = vec![expression(
+            "text",
+            Some("header".into()),
+            [("sticky".into(), "top".into())],
+            [],
+        )];
+        body.extend((0..10).map(|i| text(&i.to_string())));
+
+        let viewport = expression("viewport", None, [("offset".into(), 2.into())], body);
+        test_widget(
+            viewport,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║header         ║
+            ║2              ║
+            ║3              ║
+            ║4              ║
+            ║5              ║
+            ║6              ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn sticky_pushed_out_by_next_sticky() {
+        let body = vec![
+            sticky(text("A")),
+            text("a1"),
+            text("a2"),
+            text("a3"),
+            sticky(text("B")),
+            text("b1"),
+            text("b2"),
+        ];
+
+        let viewport = expression("viewport", None, [("offset".into(), 4.into())], body);
+        test_widget(
+            viewport,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║│A│            ║
+            ║└─┘            ║
+            ║┌─┐            ║
+            ║│B│            ║
+            ║└─┘            ║
+            ║b1             ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn ensure_visible() {
+        let viewport = expression(
+            "viewport",
+            None,
+            [("ensure-visible".into(), 4.into())],
+            children(10),
+        );
+        test_widget(
+            viewport,
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║┌─┐            ║
+            ║│3│            ║
+            ║└─┘            ║
+            ║┌─┐            ║
+            ║│4│            ║
+            ║└─┘            ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn scroll_to_node() {
+        let viewport = expression(
+            "viewport",
+            None,
+            [("offset".into(), 27.into())],
+            children(10),
+        );
+
+        // Scroll back to the text inside the third border
+        test_widget_with(
+            viewport,
+            |widget| {
+                widget
+                    .to_mut::<Viewport>()
+                    .scroll_to_node(&vec![0, 2, 0].into())
+            },
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║┌─┐            ║
+            ║│2│            ║
+            ║└─┘            ║
+            ║┌─┐            ║
+            ║│3│            ║
+            ║└─┘            ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
 }
@@ -60,6 +60,10 @@ impl Widget for VStack {
     fn position<'tpl>(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx) {
         self.0.position(children, ctx)
     }
+
+    fn baseline(&self, children: &mut Nodes<'_>) -> Option<usize> {
+        self.0.baseline(children)
+    }
 }
 
 pub(crate) struct VStackFactory;
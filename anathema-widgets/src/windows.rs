@@ -0,0 +1,676 @@
+use anathema_render::{Size, Style};
+use anathema_values::{Context, NodeId, Value};
+use anathema_widget_core::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
+use anathema_widget_core::error::Result;
+use anathema_widget_core::layout::Constraints;
+use anathema_widget_core::{
+    AnyWidget, Event, EventResult, FactoryContext, KeyCode, KeyModifiers, LayoutNodes, LocalPos,
+    MouseButton, Nodes, Pos, Widget, WidgetFactory, WidgetStyle,
+};
+use unicode_width::UnicodeWidthChar;
+
+use crate::border::{
+    BORDER_EDGE_BOTTOM, BORDER_EDGE_BOTTOM_LEFT, BORDER_EDGE_BOTTOM_RIGHT, BORDER_EDGE_LEFT,
+    BORDER_EDGE_RIGHT, BORDER_EDGE_TOP, BORDER_EDGE_TOP_LEFT, BORDER_EDGE_TOP_RIGHT,
+    DEFAULT_SLIM_EDGES, DEFAULT_THICK_EDGES,
+};
+
+const DEFAULT_WIDTH: usize = 30;
+const DEFAULT_HEIGHT: usize = 10;
+// Room for the corners, the close button and a character of the title
+const MIN_WIDTH: usize = 7;
+const MIN_HEIGHT: usize = 3;
+const CLOSE_BUTTON: &str = "[x]";
+
+// -----------------------------------------------------------------------------
+//   - Window -
+// -----------------------------------------------------------------------------
+/// A window inside a [`Windows`] widget: a frame with a title and a close button
+/// around the children.
+///
+/// The `x`, `y`, `width` and `height` are the initial position and size of the window,
+/// once the window is shown it's moved and resized by the `windows` widget.
+///
+/// The focused window is drawn with a thick frame.
+///
+/// ```text
+/// ┌ Logs ───[x]┐
+/// │hello world │
+/// └────────────┘
+/// ```
+#[derive(Debug)]
+pub struct Window {
+    /// The title in the top of the frame
+    pub title: Value<String>,
+    /// Initial offset from the left of the `windows` widget
+    pub x: Value<usize>,
+    /// Initial offset from the top of the `windows` widget
+    pub y: Value<usize>,
+    /// Initial width, including the frame
+    pub width: Value<usize>,
+    /// Initial height, including the frame
+    pub height: Value<usize>,
+    /// The style of the frame
+    pub style: WidgetStyle,
+    focused: bool,
+}
+
+impl Window {
+    /// Widget name.
+    pub const KIND: &'static str = "Window";
+
+    /// Returns true if this is the top most window
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn frame(&self) -> Frame {
+        Frame {
+            x: self.x.value_or_default() as i32,
+            y: self.y.value_or_default() as i32,
+            width: self.width.value_or(DEFAULT_WIDTH).max(MIN_WIDTH),
+            height: self.height.value_or(DEFAULT_HEIGHT).max(MIN_HEIGHT),
+            open: true,
+            maximized: false,
+        }
+    }
+}
+
+impl Widget for Window {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn update(&mut self, context: &Context<'_, '_>, node_id: &NodeId) {
+        self.title.resolve(context, node_id);
+        self.x.resolve(context, node_id);
+        self.y.resolve(context, node_id);
+        self.width.resolve(context, node_id);
+        self.height.resolve(context, node_id);
+        self.style.resolve(context, node_id);
+    }
+
+    // A window takes all the space given to it by the `windows` widget
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let size = Size::new(nodes.constraints.max_width, nodes.constraints.max_height);
+        let inner = Constraints::new(size.width.saturating_sub(2), size.height.saturating_sub(2));
+        nodes.for_each(|mut node| {
+            node.layout(inner)?;
+            Ok(())
+        })?;
+        Ok(size)
+    }
+
+    fn position(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx) {
+        for (widget, children) in children.iter_mut() {
+            widget.position(children, Pos::new(ctx.pos.x + 1, ctx.pos.y + 1));
+        }
+    }
+
+    fn paint(&mut self, children: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let Size { width, height } = ctx.local_size;
+        if width < 2 || height < 2 {
+            return;
+        }
+
+        // Clear the window so the windows below it don't show through
+        for y in 0..height {
+            for x in 0..width {
+                ctx.put(' ', Style::reset(), LocalPos::new(x, y));
+            }
+        }
+
+        let edges = match self.focused {
+            true => DEFAULT_THICK_EDGES,
+            false => DEFAULT_SLIM_EDGES,
+        };
+        let style = self.style.style();
+        let (right, bottom) = (width - 1, height - 1);
+
+        for x in 1..right {
+            ctx.put(edges[BORDER_EDGE_TOP], style, LocalPos::new(x, 0));
+            ctx.put(edges[BORDER_EDGE_BOTTOM], style, LocalPos::new(x, bottom));
+        }
+        for y in 1..bottom {
+            ctx.put(edges[BORDER_EDGE_LEFT], style, LocalPos::new(0, y));
+            ctx.put(edges[BORDER_EDGE_RIGHT], style, LocalPos::new(right, y));
+        }
+        ctx.put(edges[BORDER_EDGE_TOP_LEFT], style, LocalPos::ZERO);
+        ctx.put(edges[BORDER_EDGE_TOP_RIGHT], style, LocalPos::new(right, 0));
+        ctx.put(
+            edges[BORDER_EDGE_BOTTOM_LEFT],
+            style,
+            LocalPos::new(0, bottom),
+        );
+        ctx.put(
+            edges[BORDER_EDGE_BOTTOM_RIGHT],
+            style,
+            LocalPos::new(right, bottom),
+        );
+
+        // The title, with a space on each side, cut short before the close button
+        let close = width.saturating_sub(CLOSE_BUTTON.len() + 1);
+        if let Some(title) = self.title.value_ref() {
+            let mut x = 1;
+            for c in std::iter::once(' ').chain(title.chars()).chain([' ']) {
+                let w = c.width().unwrap_or(0);
+                if x + w > close {
+                    break;
+                }
+                ctx.put(c, style, LocalPos::new(x, 0));
+                x += w;
+            }
+        }
+        if width >= MIN_WIDTH {
+            ctx.print(CLOSE_BUTTON, style, LocalPos::new(close, 0));
+        }
+
+        for (widget, children) in children.iter_mut() {
+            let ctx = ctx.to_unsized();
+            widget.paint(children, ctx);
+        }
+    }
+}
+
+pub(crate) struct WindowFactory;
+
+impl WidgetFactory for WindowFactory {
+    fn make(&self, ctx: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Window {
+            title: ctx.get("title"),
+            x: ctx.get("x"),
+            y: ctx.get("y"),
+            width: ctx.get("width"),
+            height: ctx.get("height"),
+            style: ctx.style(),
+            focused: false,
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+// -----------------------------------------------------------------------------
+//   - Windows -
+// -----------------------------------------------------------------------------
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Frame {
+    x: i32,
+    y: i32,
+    width: usize,
+    height: usize,
+    open: bool,
+    maximized: bool,
+}
+
+impl Frame {
+    // Keep the window inside the area
+    fn fit(&mut self, area: Size) {
+        self.width = self.width.min(area.width);
+        self.height = self.height.min(area.height);
+        self.x = self.x.clamp(0, (area.width - self.width) as i32);
+        self.y = self.y.clamp(0, (area.height - self.height) as i32);
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Drag {
+    // The offset from the top left corner of the window to the mouse
+    Move { index: usize, dx: i32, dy: i32 },
+    Resize { index: usize },
+}
+
+/// Host movable and resizable [`Window`]s inside the area of the widget.
+///
+/// Clicking a window brings it to the front and gives it focus.
+/// Windows are moved by dragging the title bar, resized by dragging the bottom right corner
+/// and closed with the close button.
+///
+/// Keyboard shortcuts (or the same actions from the [`Keymap`](anathema_widget_core::keymap::Keymap)):
+/// * `alt+n` (`windows.next`): focus the next window
+/// * `alt+m` (`windows.maximize`): maximise, or restore, the focused window
+/// * `alt+w` (`windows.close`): close the focused window
+///
+/// The `windows` widget fills all the space available to it.
+///
+/// ```text
+/// windows
+///     window [title: "Logs", x: 2, y: 1, width: 40, height: 10]
+///         text "..."
+///     window [title: "Editor", x: 10, y: 4]
+///         text "..."
+/// ```
+#[derive(Debug)]
+pub struct Windows {
+    // One frame per window, in the order of the children
+    frames: Vec<Frame>,
+    // The stacking order of the windows, the last one is on top
+    order: Vec<usize>,
+    drag: Option<Drag>,
+    size: Size,
+}
+
+impl Windows {
+    /// Widget name.
+    pub const KIND: &'static str = "Windows";
+
+    /// The index of the focused window: the top most open window
+    pub fn focused(&self) -> Option<usize> {
+        self.order
+            .iter()
+            .rev()
+            .copied()
+            .find(|&index| self.frames[index].open)
+    }
+
+    /// Bring the window to the front
+    pub fn focus(&mut self, index: usize) {
+        if index < self.frames.len() {
+            self.order.retain(|&i| i != index);
+            self.order.push(index);
+        }
+    }
+
+    /// Focus the bottom most open window.
+    /// Repeatedly calling this cycles through all the windows.
+    pub fn focus_next(&mut self) {
+        let next = self
+            .order
+            .iter()
+            .copied()
+            .find(|&index| self.frames[index].open);
+
+        if let Some(index) = next {
+            self.focus(index);
+        }
+    }
+
+    /// Maximise the window, or restore it if it's already maximised
+    pub fn toggle_maximized(&mut self, index: usize) {
+        if let Some(frame) = self.frames.get_mut(index) {
+            frame.maximized = !frame.maximized;
+            self.drag = None;
+        }
+    }
+
+    pub fn close(&mut self, index: usize) {
+        if let Some(frame) = self.frames.get_mut(index) {
+            frame.open = false;
+            self.drag = None;
+        }
+    }
+
+    /// Open a closed window and bring it to the front
+    pub fn open(&mut self, index: usize) {
+        if let Some(frame) = self.frames.get_mut(index) {
+            frame.open = true;
+            self.focus(index);
+        }
+    }
+
+    pub fn is_open(&self, index: usize) -> bool {
+        self.frames.get(index).is_some_and(|frame| frame.open)
+    }
+
+    // The position (relative to the widget) and the size of the window
+    fn rect(&self, index: usize) -> (Pos, Size) {
+        let frame = &self.frames[index];
+        match frame.maximized {
+            true => (Pos::ZERO, self.size),
+            false => (
+                Pos::new(frame.x, frame.y),
+                Size::new(frame.width, frame.height),
+            ),
+        }
+    }
+
+    // The top most window at the position
+    fn window_at(&self, pos: LocalPos) -> Option<usize> {
+        let (x, y) = (pos.x as i32, pos.y as i32);
+        self.order.iter().rev().copied().find(|&index| {
+            let (origin, size) = self.rect(index);
+            self.frames[index].open
+                && (origin.x..origin.x + size.width as i32).contains(&x)
+                && (origin.y..origin.y + size.height as i32).contains(&y)
+        })
+    }
+
+    fn mouse_down(&mut self, pos: LocalPos) -> EventResult {
+        let Some(index) = self.window_at(pos) else {
+            return EventResult::Ignored;
+        };
+
+        self.focus(index);
+        let (origin, size) = self.rect(index);
+        let (x, y) = (pos.x as i32 - origin.x, pos.y as i32 - origin.y);
+        // The close button is only painted on windows that are wide enough
+        let close = size.width.saturating_sub(CLOSE_BUTTON.len() + 1) as i32;
+        let on_close =
+            size.width >= MIN_WIDTH && (close..close + CLOSE_BUTTON.len() as i32).contains(&x);
+        let maximized = self.frames[index].maximized;
+
+        if y == 0 && on_close {
+            self.close(index);
+        } else if y == 0 && !maximized {
+            self.drag = Some(Drag::Move {
+                index,
+                dx: x,
+                dy: y,
+            });
+        } else if x == size.width as i32 - 1 && y == size.height as i32 - 1 && !maximized {
+            self.drag = Some(Drag::Resize { index });
+        }
+
+        EventResult::Consumed
+    }
+
+    // The position is relative to the widget, and can be outside of it
+    fn mouse_drag(&mut self, x: i32, y: i32) -> EventResult {
+        let Some(drag) = self.drag else {
+            return EventResult::Ignored;
+        };
+
+        let area = self.size;
+        match drag {
+            Drag::Move { index, dx, dy } => {
+                let frame = &mut self.frames[index];
+                frame.x = x - dx;
+                frame.y = y - dy;
+                frame.fit(area);
+            }
+            Drag::Resize { index } => {
+                let frame = &mut self.frames[index];
+                frame.width = (x - frame.x + 1).max(MIN_WIDTH as i32) as usize;
+                frame.height = (y - frame.y + 1).max(MIN_HEIGHT as i32) as usize;
+                frame.width = frame.width.min(area.width.saturating_sub(frame.x as usize));
+                frame.height = frame
+                    .height
+                    .min(area.height.saturating_sub(frame.y as usize));
+            }
+        }
+
+        EventResult::Consumed
+    }
+
+    fn action(&mut self, action: &str) -> EventResult {
+        match (action, self.focused()) {
+            ("windows.next", _) => self.focus_next(),
+            ("windows.maximize", Some(index)) => self.toggle_maximized(index),
+            ("windows.close", Some(index)) => self.close(index),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed
+    }
+}
+
+impl Widget for Windows {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
+        let constraints = nodes.constraints;
+        self.size = Size::new(constraints.max_width, constraints.max_height);
+
+        let mut index = 0;
+        nodes.for_each(|mut node| {
+            if index == self.frames.len() {
+                let frame = match node.try_to_ref::<Window>() {
+                    Some(window) => window.frame(),
+                    None => Frame {
+                        x: 0,
+                        y: 0,
+                        width: DEFAULT_WIDTH,
+                        height: DEFAULT_HEIGHT,
+                        open: true,
+                        maximized: false,
+                    },
+                };
+                self.frames.push(frame);
+                self.order.push(index);
+            }
+
+            self.frames[index].fit(self.size);
+            let size = match self.frames[index].open {
+                true => self.rect(index).1,
+                false => Size::ZERO,
+            };
+
+            let mut constraints = Constraints::new(size.width, size.height);
+            constraints.make_width_tight(size.width);
+            constraints.make_height_tight(size.height);
+            node.layout(constraints)?;
+
+            index += 1;
+            Ok(())
+        })?;
+
+        // Windows that are no longer part of the tree
+        if index < self.frames.len() {
+            self.frames.truncate(index);
+            self.order.retain(|&i| i < index);
+            self.drag = None;
+        }
+
+        Ok(self.size)
+    }
+
+    fn position(&mut self, children: &mut Nodes<'_>, ctx: PositionCtx) {
+        for (index, (widget, children)) in children.iter_mut().enumerate() {
+            let offset = match index < self.frames.len() {
+                true => self.rect(index).0,
+                false => Pos::ZERO,
+            };
+            widget.position(children, ctx.pos + offset);
+        }
+    }
+
+    // Paint the windows from the bottom to the top
+    fn paint(&mut self, children: &mut Nodes<'_>, mut ctx: PaintCtx<'_, WithSize>) {
+        let focused = self.focused();
+        let mut windows = children.iter_mut().collect::<Vec<_>>();
+        for &index in &self.order {
+            if !self.frames[index].open {
+                continue;
+            }
+
+            if let Some((widget, children)) = windows.get_mut(index) {
+                if let Ok(window) = widget.try_to_mut::<Window>() {
+                    window.focused = focused == Some(index);
+                }
+                let ctx = ctx.to_unsized();
+                widget.paint(children, ctx);
+            }
+        }
+    }
+
+    fn on_event(&mut self, event: &Event, ctx: EventCtx<'_>) -> EventResult {
+        match event {
+            Event::MouseDown(x, y, MouseButton::Left, ..) => match ctx.local_pos(*x, *y) {
+                Some(pos) => self.mouse_down(pos),
+                None => EventResult::Ignored,
+            },
+            Event::MouseDrag(x, y, MouseButton::Left, _) => {
+                self.mouse_drag(*x as i32 - ctx.pos.x, *y as i32 - ctx.pos.y)
+            }
+            Event::MouseUp(_, _, MouseButton::Left, _) if self.drag.is_some() => {
+                self.drag = None;
+                EventResult::Consumed
+            }
+            Event::KeyPress(KeyCode::Char(c), modifiers, _)
+                if modifiers.contains(KeyModifiers::ALT) =>
+            {
+                match c {
+                    'n' => self.action("windows.next"),
+                    'm' => self.action("windows.maximize"),
+                    'w' => self.action("windows.close"),
+                    _ => EventResult::Ignored,
+                }
+            }
+            Event::Action(action) => self.action(action.as_str()),
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+pub(crate) struct WindowsFactory;
+
+impl WidgetFactory for WindowsFactory {
+    fn make(&self, _: FactoryContext<'_>) -> Result<Box<dyn AnyWidget>> {
+        let widget = Windows {
+            frames: vec![],
+            order: vec![],
+            drag: None,
+            size: Size::ZERO,
+        };
+
+        Ok(Box::new(widget))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anathema_widget_core::expressions::Expression;
+    use anathema_widget_core::testing::{expression, FakeTerm};
+
+    use super::*;
+    use crate::testing::{alt, click, test_widget, test_widget_events};
+
+    fn window(title: &str, x: usize, y: usize, text: &str) -> Expression {
+        expression(
+            "window",
+            None,
+            [
+                ("title".to_string(), title.into()),
+                ("x".to_string(), x.into()),
+                ("y".to_string(), y.into()),
+                ("width".to_string(), 10.into()),
+                ("height".to_string(), 3.into()),
+            ],
+            [expression("text", Some(text.into()), [], [])],
+        )
+    }
+
+    fn windows() -> Expression {
+        expression(
+            "windows",
+            None,
+            [],
+            [window("one", 0, 0, "first"), window("two", 3, 1, "second")],
+        )
+    }
+
+    fn drag(x: u16, y: u16) -> Event {
+        Event::MouseDrag(x, y, MouseButton::Left, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn overlapping_windows() {
+        test_widget(
+            windows(),
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║┌ one [x]┐     ║
+            ║│fi╔ two [x]╗  ║
+            ║└──║second  ║  ║
+            ║   ╚════════╝  ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn click_to_focus() {
+        test_widget_events(
+            windows(),
+            &[click(1, 1)],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║╔ one [x]╗     ║
+            ║║first   ║x]┐  ║
+            ║╚════════╝  │  ║
+            ║   └────────┘  ║
+            ║               ║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn move_and_close() {
+        // Drag the second window by the title, then close the first window
+        test_widget_events(
+            windows(),
+            &[click(4, 1), drag(6, 3), click(7, 0)],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═╗
+            ║               ║
+            ║               ║
+            ║     ╔ two [x]╗║
+            ║     ║second  ║║
+            ║     ╚════════╝║
+            ╚═══════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn keyboard_shortcuts() {
+        // Focus the first window and maximise it
+        test_widget_events(
+            windows(),
+            &[alt('n'), alt('m')],
+            FakeTerm::from_str(
+                r#"
+            ╔═] Fake term [═══╗
+            ║╔ one ═══════[x]╗║
+            ║║first          ║║
+            ║║               ║║
+            ║╚═══════════════╝║
+            ╚═════════════════╝
+            "#,
+            ),
+        );
+    }
+
+    #[test]
+    fn area_smaller_than_window() {
+        let frame = Frame {
+            x: 5,
+            y: 2,
+            width: 10,
+            height: 3,
+            open: true,
+            maximized: false,
+        };
+        let mut windows = Windows {
+            frames: vec![frame],
+            order: vec![0],
+            drag: None,
+            size: Size::new(20, 10),
+        };
+
+        // Grab the bottom right corner, then shrink the area below the window
+        windows.mouse_down(LocalPos::new(14, 4));
+        windows.size = Size::new(4, 2);
+        windows.mouse_drag(3, 1);
+        assert_eq!((windows.frames[0].width, windows.frames[0].height), (0, 0));
+
+        // A window too narrow for the close button
+        windows.drag = None;
+        windows.frames[0] = frame;
+        windows.frames[0].fit(Size::new(2, 1));
+        assert_eq!(
+            windows.mouse_down(LocalPos::new(1, 0)),
+            EventResult::Consumed
+        );
+        assert!(windows.frames[0].open);
+    }
+}
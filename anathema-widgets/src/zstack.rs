@@ -79,18 +79,10 @@ impl Widget for ZStack {
     }
 
     fn layout(&mut self, nodes: &mut LayoutNodes<'_, '_, '_>) -> Result<Size> {
-        if let Some(min_width) = self.min_width.value() {
-            nodes.constraints.min_width = nodes.constraints.min_width.max(min_width);
-        }
-        if let Some(min_height) = self.min_height.value() {
-            nodes.constraints.min_height = nodes.constraints.min_height.max(min_height);
-        }
-        if let Some(width) = self.width.value() {
-            nodes.constraints.make_width_tight(width);
-        }
-        if let Some(height) = self.height.value() {
-            nodes.constraints.make_height_tight(height);
-        }
+        nodes.constraints = nodes
+            .constraints
+            .clamp_min(self.min_width.value(), self.min_height.value())
+            .tighten_if(self.width.value(), self.height.value());
 
         Stacked.layout(nodes)
     }
@@ -0,0 +1,69 @@
+// -----------------------------------------------------------------------------
+//   - Benchmark helpers -
+//   Every benchmark warms up before it's measured, and is measured as a
+//   number of samples, reporting the median with the fastest and
+//   slowest sample.
+// -----------------------------------------------------------------------------
+// Not every benchmark uses every helper
+#![allow(dead_code)]
+
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
+
+/// How long to run a benchmark before measuring it
+pub const WARMUP: Duration = Duration::from_millis(500);
+
+/// The number of samples taken of every benchmark
+pub const SAMPLES: u32 = 50;
+
+/// Run `f` until the warm up is over
+pub fn warm_up(mut f: impl FnMut()) {
+    let start = Instant::now();
+    while start.elapsed() < WARMUP {
+        f();
+    }
+}
+
+/// Warm up, then time `SAMPLES` samples of `iterations` calls to `f`,
+/// and print the time per call.
+pub fn bench(name: &str, iterations: u32, mut f: impl FnMut()) {
+    warm_up(&mut f);
+
+    let samples = (0..SAMPLES)
+        .map(|_| {
+            let now = Instant::now();
+            for _ in 0..iterations {
+                f();
+            }
+            now.elapsed() / iterations
+        })
+        .collect();
+
+    println!("{name:<20} {}", Summary::new(samples));
+}
+
+/// The median, fastest and slowest of a number of samples
+#[derive(Debug, Copy, Clone)]
+pub struct Summary {
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Summary {
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+        samples.sort();
+        Self {
+            median: samples[samples.len() / 2],
+            min: samples[0],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>10?} [{:?} .. {:?}]", self.median, self.min, self.max)
+    }
+}
@@ -0,0 +1,131 @@
+// -----------------------------------------------------------------------------
+//   - Frame benchmarks -
+//   Layout, position and paint representative templates, without a terminal.
+//
+//   Run with `cargo bench --bench frame`
+// -----------------------------------------------------------------------------
+use std::hint::black_box;
+use std::time::Instant;
+
+use anathema::core::contexts::PaintCtx;
+use anathema::core::layout::Constraints;
+use anathema::core::nodes::make_it_so;
+use anathema::core::{LayoutNodes, Pos};
+use anathema::render::{Screen, Size};
+use anathema::values::Context;
+use anathema::vm::Templates;
+use anathema::widgets::register_default_widgets;
+use common::{warm_up, Summary};
+
+mod common;
+
+const WIDTH: usize = 200;
+const HEIGHT: usize = 100;
+// Samples of every phase of a frame
+const ITERATIONS: u32 = 200;
+
+fn large_list() -> String {
+    let items = (0..1000)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "
+vstack
+    for item in [{items}]
+        border
+            text \"item: \" item
+"
+    )
+}
+
+fn deep_nesting() -> String {
+    let mut template = String::new();
+    for depth in 0..50 {
+        template.push_str(&"    ".repeat(depth));
+        template.push_str("border\n");
+    }
+    template.push_str(&"    ".repeat(50));
+    template.push_str("text \"deep\"\n");
+    template
+}
+
+fn heavy_text() -> String {
+    let text = "lorem ipsum dolor sit amet 💖 ".repeat(500);
+    format!(
+        "
+vstack
+    text \"{text}\"
+    text [wrap: \"word\"] \"{text}\"
+"
+    )
+}
+
+fn bench(name: &str, template: String) {
+    let mut templates = Templates::new(template, ());
+    templates.compile().unwrap();
+    let expressions = templates.expressions();
+
+    let size = Size::new(WIDTH, HEIGHT);
+    let constraints = Constraints::new(Some(size.width), Some(size.height));
+    let mut screen = Screen::new(size);
+    let mut nodes = make_it_so(expressions);
+
+    // Layout, position and paint a frame, returning the time spent on each
+    let mut frame = || {
+        nodes.reset_cache();
+
+        let now = Instant::now();
+        let context = Context::root(&());
+        let mut layout_nodes = LayoutNodes::new(&mut nodes, constraints, &context);
+        layout_nodes
+            .for_each(|mut node| {
+                black_box(node.layout(constraints)?);
+                Ok(())
+            })
+            .unwrap();
+        let layout = now.elapsed();
+
+        let now = Instant::now();
+        for (widget, children) in nodes.iter_mut() {
+            widget.position(children, Pos::ZERO);
+        }
+        let position = now.elapsed();
+
+        let now = Instant::now();
+        for (widget, children) in nodes.iter_mut() {
+            widget.paint(children, PaintCtx::new(&mut screen, None));
+        }
+        let paint = now.elapsed();
+
+        screen.erase();
+        (layout, position, paint)
+    };
+
+    warm_up(|| {
+        black_box(frame());
+    });
+
+    let mut layout = vec![];
+    let mut position = vec![];
+    let mut paint = vec![];
+    for _ in 0..ITERATIONS {
+        let (frame_layout, frame_position, frame_paint) = frame();
+        layout.push(frame_layout);
+        position.push(frame_position);
+        paint.push(frame_paint);
+    }
+
+    println!("{name} ({} widgets)", nodes.count());
+    println!("    layout:   {}", Summary::new(layout));
+    println!("    position: {}", Summary::new(position));
+    println!("    paint:    {}", Summary::new(paint));
+}
+
+fn main() {
+    register_default_widgets().unwrap();
+
+    bench("large list", large_list());
+    bench("deep nesting", deep_nesting());
+    bench("heavy text", heavy_text());
+}
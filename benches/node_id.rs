@@ -0,0 +1,43 @@
+// -----------------------------------------------------------------------------
+//   - Node id benchmarks -
+//   Compare cloning and comparing node ids on deep trees
+//   with the previous `Vec<usize>` representation.
+//
+//   Run with `cargo bench --bench node_id`
+// -----------------------------------------------------------------------------
+use std::hint::black_box;
+
+use anathema::values::NodeId;
+use common::bench;
+
+mod common;
+
+const DEPTH: usize = 64;
+// Calls per sample
+const ITERATIONS: u32 = 20_000;
+
+fn main() {
+    let node_id = (1..DEPTH).fold(NodeId::new(0), |id, i| id.child(i));
+    let vec_id = node_id.as_slice().to_vec();
+    let descendant = node_id.child(DEPTH);
+
+    bench("clone (arc)", ITERATIONS, || {
+        black_box(black_box(&node_id).clone());
+    });
+
+    bench("clone (vec)", ITERATIONS, || {
+        black_box(black_box(&vec_id).clone());
+    });
+
+    bench("child", ITERATIONS, || {
+        black_box(black_box(&node_id).child(1));
+    });
+
+    bench("parent", ITERATIONS, || {
+        black_box(black_box(&node_id).parent());
+    });
+
+    bench("contains", ITERATIONS, || {
+        black_box(black_box(&node_id).contains(descendant.as_slice()));
+    });
+}
@@ -0,0 +1,58 @@
+// -----------------------------------------------------------------------------
+//   - Symbol benchmarks -
+//   Compare resolving symbols through the thread local copy of the interner
+//   with taking the lock of the global interner every time.
+//
+//   Run with `cargo bench --bench symbol`
+// -----------------------------------------------------------------------------
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::Mutex;
+
+use anathema::values::Symbol;
+use common::bench;
+
+mod common;
+
+// Calls per sample
+const ITERATIONS: u32 = 20_000;
+
+fn main() {
+    let symbol = Symbol::new("background");
+    let locked = Mutex::new(HashMap::from([("background", symbol)]));
+    let strings = Mutex::new(vec!["background"]);
+
+    bench("get (lock)", ITERATIONS, || {
+        black_box(locked.lock().unwrap().get(black_box("background")).copied());
+    });
+
+    bench("get", ITERATIONS, || {
+        black_box(Symbol::get(black_box("background")));
+    });
+
+    bench("get missing (lock)", ITERATIONS, || {
+        black_box(
+            locked
+                .lock()
+                .unwrap()
+                .get(black_box("border-style"))
+                .copied(),
+        );
+    });
+
+    bench("get missing", ITERATIONS, || {
+        black_box(Symbol::get(black_box("border-style")));
+    });
+
+    bench("as_str (lock)", ITERATIONS, || {
+        black_box(strings.lock().unwrap()[0]);
+    });
+
+    bench("as_str", ITERATIONS, || {
+        black_box(black_box(symbol).as_str());
+    });
+
+    bench("spread", ITERATIONS, || {
+        black_box(Symbol::SPREAD);
+    });
+}
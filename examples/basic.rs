@@ -4,8 +4,7 @@
 // -----------------------------------------------------------------------------
 use std::fs::read_to_string;
 
-use anathema::runtime::Runtime;
-use anathema::vm::Templates;
+use anathema::prelude::*;
 
 fn main() {
     // Step one: Load and compile templates
@@ -4,10 +4,7 @@
 // -----------------------------------------------------------------------------
 use std::fs::read_to_string;
 
-use anathema::core::{Event, KeyCode, Nodes, View};
-use anathema::runtime::Runtime;
-use anathema::values::{List, State, StateValue};
-use anathema::vm::Templates;
+use anathema::prelude::*;
 
 #[derive(Debug, State)]
 struct RootState {
@@ -21,7 +18,10 @@ struct RootView {
 
 impl View for RootView {
     fn on_event(&mut self, event: Event, _nodes: &mut Nodes<'_>) {
-        if let Event::KeyPress(code, ..) = event {
+        if let Event::Paste(text) = event {
+            // Insert the pasted text as a single change
+            self.state.input.push_str(&text);
+        } else if let Event::KeyPress(code, ..) = event {
             match code {
                 KeyCode::Char(c) => self.state.input.push(c),
                 KeyCode::Backspace => drop(self.state.input.pop()),
@@ -1,5 +1,6 @@
 pub use {
     anathema_compiler as compiler,   // compiler
+    anathema_macros as macros,       // macros
     anathema_render as render,       // render
     anathema_runtime as runtime,     // runtime
     anathema_value_derive as derive, // derive
@@ -8,3 +9,28 @@ pub use {
     anathema_widget_core as core,    // core
     anathema_widgets as widgets,     // wigets
 };
+
+/// Everything needed to build an application:
+///
+/// ```no_run
+/// use anathema::prelude::*;
+///
+/// let mut templates = Templates::new(template!("text 'hello world'").into(), ());
+/// templates.compile().unwrap();
+/// let runtime = Runtime::new(templates.expressions()).unwrap();
+/// runtime.run().unwrap();
+/// ```
+pub mod prelude {
+    pub use anathema_macros::template;
+    pub use anathema_render::Color;
+    pub use anathema_runtime::{request_redraw, QuitAction, QuitHandle, Runtime};
+    pub use anathema_values::{Context, List, Map, NodeId, State, StateValue, Value, ValueRef};
+    pub use anathema_vm::{Templates, ViewTemplates};
+    pub use anathema_widget_core::contexts::{EventCtx, PaintCtx, PositionCtx, WithSize};
+    pub use anathema_widget_core::error::{Error, Result};
+    pub use anathema_widget_core::layout::Constraints;
+    pub use anathema_widget_core::{
+        AnyWidget, Event, EventResult, Factory, FactoryContext, KeyCode, KeyModifiers, LayoutNodes,
+        MouseButton, Nodes, View, Widget, WidgetFactory, WidgetStyle,
+    };
+}